@@ -10,11 +10,12 @@ mod new_vote_plan;
 mod new_vote_tally;
 mod show;
 mod sign;
-mod weighted_pool_ids;
+pub(crate) mod weighted_pool_ids;
 
 pub(crate) use self::sign::{
     committee_encrypted_vote_tally_sign, committee_vote_plan_sign, committee_vote_tally_sign,
-    pool_owner_sign, stake_delegation_account_binding_sign, update_proposal_sign, update_vote_sign,
+    pool_owner_sign, sign_certificate, stake_delegation_account_binding_sign, update_proposal_sign,
+    update_vote_sign,
 };
 
 use crate::jcli_lib::utils::{
@@ -268,7 +269,7 @@ fn read_cert_or_signed_cert(input: Option<&Path>) -> Result<interfaces::Certific
     }
 }
 
-fn read_cert(input: Option<&Path>) -> Result<interfaces::Certificate, Error> {
+pub(crate) fn read_cert(input: Option<&Path>) -> Result<interfaces::Certificate, Error> {
     use std::str::FromStr as _;
 
     let cert_str = read_input(input)?;
@@ -293,7 +294,7 @@ where
     write_output(output, cert)
 }
 
-fn write_signed_cert(
+pub(crate) fn write_signed_cert(
     output: Option<&Path>,
     signedcert: interfaces::SignedCertificate,
 ) -> Result<(), Error> {
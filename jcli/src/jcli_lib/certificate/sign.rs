@@ -1,5 +1,5 @@
 use crate::jcli_lib::{
-    certificate::{read_cert, read_input, write_signed_cert, Error},
+    certificate::{read_cert, write_signed_cert, Error},
     utils::key_parser::{self, parse_ed25519_secret_key},
 };
 use chain_crypto::{Ed25519, PublicKey};
@@ -46,99 +46,108 @@ impl Sign {
         let keys_str: Result<Vec<String>, Error> = self
             .signing_keys
             .iter()
-            .map(|sk| read_input(Some(sk.as_ref())))
+            .map(|sk| key_parser::read_key_line(&Some(sk)).map_err(Error::from))
             .collect();
         let keys_str = keys_str?;
 
-        let signedcert = match cert.into() {
-            Certificate::StakeDelegation(s) => {
-                let txbuilder = Transaction::block0_payload_builder(&s);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| stake_delegation_account_binding_sign(s, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-            Certificate::PoolRegistration(s) => {
-                let sclone = s.clone();
-                let txbuilder = Transaction::block0_payload_builder(&s);
-                pool_owner_sign(s, Some(&sclone), &keys_str, txbuilder, |c, a| {
-                    SignedCertificate::PoolRegistration(c, PoolSignature::Owners(a))
-                })?
-            }
-            Certificate::PoolRetirement(s) => {
-                let txbuilder = Transaction::block0_payload_builder(&s);
-                pool_owner_sign(s, None, &keys_str, txbuilder, |c, a| {
-                    SignedCertificate::PoolRetirement(c, PoolSignature::Owners(a))
-                })?
-            }
-            Certificate::PoolUpdate(s) => {
-                let txbuilder = Transaction::block0_payload_builder(&s);
-                pool_owner_sign(s, None, &keys_str, txbuilder, |c, a| {
-                    SignedCertificate::PoolUpdate(c, PoolSignature::Owners(a))
-                })?
-            }
-            Certificate::VoteTally(vt) => {
-                let txbuilder = Transaction::block0_payload_builder(&vt);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| committee_vote_tally_sign(vt, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-            Certificate::EncryptedVoteTally(vt) => {
-                let txbuilder = Transaction::block0_payload_builder(&vt);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| committee_encrypted_vote_tally_sign(vt, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-            Certificate::OwnerStakeDelegation(_) => {
-                return Err(Error::OwnerStakeDelegationDoesntNeedSignature)
-            }
-            Certificate::VotePlan(vp) => {
-                let txbuilder = Transaction::block0_payload_builder(&vp);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| committee_vote_plan_sign(vp, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-            Certificate::VoteCast(_) => return Err(Error::VoteCastDoesntNeedSignature),
-            Certificate::UpdateProposal(up) => {
-                let txbuilder = Transaction::block0_payload_builder(&up);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| update_proposal_sign(up, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-            Certificate::UpdateVote(uv) => {
-                let txbuilder = Transaction::block0_payload_builder(&uv);
-                keys_str
-                    .len()
-                    .eq(&1)
-                    .then(|| update_vote_sign(uv, &keys_str[0], txbuilder))
-                    .ok_or(Error::ExpectingOnlyOneSigningKey {
-                        got: keys_str.len(),
-                    })??
-            }
-        };
+        let signedcert = sign_certificate(cert, &keys_str)?;
         write_signed_cert(self.output.as_deref(), signedcert.into())
     }
 }
 
+/// sign the certificate with the given keys, as required by its kind
+pub(crate) fn sign_certificate(
+    cert: interfaces::Certificate,
+    keys_str: &[String],
+) -> Result<SignedCertificate, Error> {
+    let signedcert = match cert.into() {
+        Certificate::StakeDelegation(s) => {
+            let txbuilder = Transaction::block0_payload_builder(&s);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| stake_delegation_account_binding_sign(s, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+        Certificate::PoolRegistration(s) => {
+            let sclone = s.clone();
+            let txbuilder = Transaction::block0_payload_builder(&s);
+            pool_owner_sign(s, Some(&sclone), &keys_str, txbuilder, |c, a| {
+                SignedCertificate::PoolRegistration(c, PoolSignature::Owners(a))
+            })?
+        }
+        Certificate::PoolRetirement(s) => {
+            let txbuilder = Transaction::block0_payload_builder(&s);
+            pool_owner_sign(s, None, &keys_str, txbuilder, |c, a| {
+                SignedCertificate::PoolRetirement(c, PoolSignature::Owners(a))
+            })?
+        }
+        Certificate::PoolUpdate(s) => {
+            let txbuilder = Transaction::block0_payload_builder(&s);
+            pool_owner_sign(s, None, &keys_str, txbuilder, |c, a| {
+                SignedCertificate::PoolUpdate(c, PoolSignature::Owners(a))
+            })?
+        }
+        Certificate::VoteTally(vt) => {
+            let txbuilder = Transaction::block0_payload_builder(&vt);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| committee_vote_tally_sign(vt, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+        Certificate::EncryptedVoteTally(vt) => {
+            let txbuilder = Transaction::block0_payload_builder(&vt);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| committee_encrypted_vote_tally_sign(vt, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+        Certificate::OwnerStakeDelegation(_) => {
+            return Err(Error::OwnerStakeDelegationDoesntNeedSignature)
+        }
+        Certificate::VotePlan(vp) => {
+            let txbuilder = Transaction::block0_payload_builder(&vp);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| committee_vote_plan_sign(vp, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+        Certificate::VoteCast(_) => return Err(Error::VoteCastDoesntNeedSignature),
+        Certificate::UpdateProposal(up) => {
+            let txbuilder = Transaction::block0_payload_builder(&up);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| update_proposal_sign(up, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+        Certificate::UpdateVote(uv) => {
+            let txbuilder = Transaction::block0_payload_builder(&uv);
+            keys_str
+                .len()
+                .eq(&1)
+                .then(|| update_vote_sign(uv, &keys_str[0], txbuilder))
+                .ok_or(Error::ExpectingOnlyOneSigningKey {
+                    got: keys_str.len(),
+                })??
+        }
+    };
+    Ok(signedcert)
+}
+
 pub(crate) fn committee_vote_tally_sign(
     vote_tally: VoteTally,
     key_str: &str,
@@ -1,5 +1,7 @@
 use crate::jcli_lib::utils::io;
+use crate::jcli_lib::utils::key_parser;
 use crate::jcli_lib::utils::output_file::{self, OutputFile};
+use crate::jcli_lib::utils::passphrase;
 use bech32::{self, u5, FromBase32};
 use chain_crypto::{
     bech32::{Bech32, Error as Bech32Error},
@@ -9,6 +11,11 @@ use chain_crypto::{
 };
 use ed25519_bip32::{DerivationError, DerivationScheme};
 use hex::FromHexError;
+use jormungandr_lib::crypto::{
+    kes,
+    key::SigningKey,
+    password::{self, EncryptedSecret, Kdf},
+};
 use rand::{rngs::OsRng, SeedableRng};
 use rand_chacha::ChaChaRng;
 use std::{
@@ -49,6 +56,18 @@ pub enum Error {
     },
     #[error("signature verification failed")]
     SignatureVerification,
+    #[error(transparent)]
+    KeyFile(#[from] key_parser::Error),
+    #[error(transparent)]
+    Passphrase(#[from] passphrase::Error),
+    #[error("could not encrypt or decrypt the secret key")]
+    Encryption(#[from] password::Error),
+    #[error("input is not an encrypted key file")]
+    NotEncrypted(#[source] serde_yaml::Error),
+    #[error("could not write the encrypted key")]
+    EncryptedKeyFormat(#[source] serde_yaml::Error),
+    #[error("encrypted input does not hold text")]
+    EncryptedKeyMalformed,
     #[error("failed to derive from BIP32 public key")]
     Derivation(#[from] DerivationError),
     #[error("ed25519bip32 key expected, signature bech32 has invalid HRP: '{actual_hrp}', expected: '{public_hrp}' or '{private_hrp}'")]
@@ -76,6 +95,13 @@ pub enum Key {
     Verify(Verify),
     /// derive a child key from a ed25519bip32 parent key
     Derive(Derive),
+    /// encrypt a private key, or a node secret file, under a passphrase
+    Encrypt(Encrypt),
+    /// decrypt a file encrypted with `encrypt`
+    Decrypt(Decrypt),
+    /// get the current period of a sumed25519_12 (KES) private key and the
+    /// number of periods it can still be evolved to
+    KesPeriod(KesPeriod),
 }
 
 #[derive(StructOpt, Debug)]
@@ -125,6 +151,13 @@ pub struct Generate {
     /// are not sure.
     #[structopt(long = "seed", short = "s", name = "SEED", parse(try_from_str))]
     seed: Option<Seed>,
+
+    /// encrypt the generated key under a passphrase
+    #[structopt(long = "encrypt")]
+    encrypt: bool,
+
+    #[structopt(flatten)]
+    encryption: EncryptionArgs,
 }
 
 #[derive(StructOpt, Debug)]
@@ -188,6 +221,67 @@ pub struct Derive {
     child_key: OutputFile,
 }
 
+#[derive(StructOpt, Debug)]
+pub struct Encrypt {
+    /// the private key, or the node secret file, to encrypt
+    ///
+    /// if no value passed, the input will be read from the standard
+    /// input. An already encrypted input is re-encrypted under the new
+    /// passphrase
+    #[structopt(long = "input")]
+    input_key: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    encryption: EncryptionArgs,
+
+    #[structopt(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct Decrypt {
+    /// the encrypted private key or node secret file
+    ///
+    /// if no value passed, the encrypted input will be read from the
+    /// standard input
+    #[structopt(long = "input")]
+    input_key: Option<PathBuf>,
+
+    /// read the passphrase from the given file instead of the
+    /// `JCLI_PASSPHRASE` environment variable or a prompt
+    #[structopt(long = "passphrase-file")]
+    passphrase_file: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct KesPeriod {
+    /// the sumed25519_12 private key to inspect
+    ///
+    /// if no value passed, the private key will be read from the
+    /// standard input
+    #[structopt(long = "input")]
+    input_key: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(StructOpt, Debug)]
+pub struct EncryptionArgs {
+    /// read the new passphrase from the given file instead of the
+    /// `JCLI_PASSPHRASE` environment variable or a prompt
+    #[structopt(long = "passphrase-file")]
+    passphrase_file: Option<PathBuf>,
+
+    /// log2 of the scrypt cost parameter N deriving the encryption key from
+    /// the passphrase. The derivation uses 2^(kdf-log-n + 10) bytes of memory
+    #[structopt(long = "kdf-log-n", default_value = "15")]
+    kdf_log_n: u8,
+}
+
 arg_enum! {
     #[derive(StructOpt, Debug)]
     pub enum GenPrivKeyType {
@@ -209,6 +303,9 @@ impl Key {
             Key::Sign(args) => args.exec(),
             Key::Verify(args) => args.exec(),
             Key::Derive(args) => args.exec(),
+            Key::Encrypt(args) => args.exec(),
+            Key::Decrypt(args) => args.exec(),
+            Key::KesPeriod(args) => args.exec(),
         }
     }
 }
@@ -224,8 +321,13 @@ impl Generate {
                 gen_priv_key::<RistrettoGroup2HashDh>(self.seed)?
             }
         };
+        let priv_key = if self.encrypt {
+            self.encryption.encrypt(&priv_key_bech32)?
+        } else {
+            priv_key_bech32
+        };
         let mut output = self.output_file.open()?;
-        writeln!(output, "{}", priv_key_bech32)?;
+        writeln!(output, "{}", priv_key)?;
         Ok(())
     }
 }
@@ -369,6 +471,25 @@ impl Verify {
     }
 }
 
+impl KesPeriod {
+    fn exec(self) -> Result<(), Error> {
+        let (hrp, data, _variant) = read_bech32(&self.input_key)?;
+        if hrp != SumEd25519_12::SECRET_BECH32_HRP {
+            return Err(Error::UnknownBech32PrivKeyHrp { hrp });
+        }
+        let bytes = Vec::<u8>::from_base32(&data).map_err(Bech32Error::from)?;
+        let key = SigningKey::from(SecretKey::<SumEd25519_12>::from_binary(&bytes)?);
+        let mut output = self.output_file.open()?;
+        writeln!(output, "period: {}", kes::period(&key))?;
+        writeln!(
+            output,
+            "remaining_periods: {}",
+            kes::remaining_periods(&key)
+        )?;
+        Ok(())
+    }
+}
+
 impl Derive {
     fn exec(self) -> Result<(), Error> {
         let (phrp, pdata, _variant) = read_bech32(&self.parent_key)?;
@@ -402,6 +523,64 @@ impl Derive {
     }
 }
 
+impl Encrypt {
+    fn exec(self) -> Result<(), Error> {
+        let secret = key_parser::read_secret_file(&self.input_key)?;
+        let secret = secret.trim_end();
+        // node secret files are encrypted as they are, but a lone key
+        // must be a private one
+        if let Ok((hrp, _, _)) = bech32::decode(secret) {
+            match hrp.as_ref() {
+                Ed25519::SECRET_BECH32_HRP
+                | Ed25519Bip32::SECRET_BECH32_HRP
+                | Ed25519Extended::SECRET_BECH32_HRP
+                | SumEd25519_12::SECRET_BECH32_HRP
+                | RistrettoGroup2HashDh::SECRET_BECH32_HRP => Ok(()),
+                _ => Err(Error::UnknownBech32PrivKeyHrp { hrp }),
+            }?;
+        }
+        let encrypted = self.encryption.encrypt(secret)?;
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", encrypted)?;
+        Ok(())
+    }
+}
+
+impl Decrypt {
+    fn exec(self) -> Result<(), Error> {
+        let mut content = String::new();
+        io::open_file_read(&self.input_key)?.read_to_string(&mut content)?;
+        let encrypted: EncryptedSecret =
+            serde_yaml::from_str(&content).map_err(Error::NotEncrypted)?;
+        let passphrase = passphrase::read(self.passphrase_file.as_deref(), "Key passphrase: ")?;
+        let key = String::from_utf8(encrypted.decrypt(passphrase.as_bytes())?)
+            .map_err(|_| Error::EncryptedKeyMalformed)?;
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", key)?;
+        Ok(())
+    }
+}
+
+impl EncryptionArgs {
+    /// encrypt the secret, returning the content of the encrypted file
+    fn encrypt(&self, secret: &str) -> Result<String, Error> {
+        let passphrase =
+            passphrase::read_new(self.passphrase_file.as_deref(), "New key passphrase: ")?;
+        let kdf = Kdf::Scrypt {
+            log_n: self.kdf_log_n,
+            r: password::DEFAULT_R,
+            p: password::DEFAULT_P,
+        };
+        let encrypted = EncryptedSecret::encrypt_with(
+            &mut OsRng,
+            kdf,
+            passphrase.as_bytes(),
+            secret.as_bytes(),
+        )?;
+        serde_yaml::to_string(&encrypted).map_err(Error::EncryptedKeyFormat)
+    }
+}
+
 fn read_hex<P: AsRef<Path>>(path: &Option<P>) -> Result<Vec<u8>, Error> {
     hex::decode(io::read_line(path)?).map_err(Into::into)
 }
@@ -409,7 +588,7 @@ fn read_hex<P: AsRef<Path>>(path: &Option<P>) -> Result<Vec<u8>, Error> {
 fn read_bech32<'a>(
     path: impl Into<Option<&'a PathBuf>>,
 ) -> Result<(String, Vec<bech32::u5>, bech32::Variant), Error> {
-    let line = io::read_line(&path.into())?;
+    let line = key_parser::read_key_line(&path.into())?;
     Ok(bech32::decode(&line).map_err(Bech32Error::from)?)
 }
 
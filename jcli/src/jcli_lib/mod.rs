@@ -4,9 +4,12 @@ pub mod block;
 pub mod certificate;
 pub mod debug;
 pub mod key;
+pub mod offline;
 pub mod rest;
+pub mod snapshot;
 pub mod transaction;
 pub mod vote;
+pub mod wallet;
 
 pub mod utils;
 
@@ -55,6 +58,12 @@ pub enum JCliCommand {
     Utils(utils::Utils),
     /// Vote related operations
     Votes(vote::Vote),
+    /// Snapshot archives to fast sync a node
+    Snapshot(snapshot::Snapshot),
+    /// HD wallet backed by a local encrypted keystore
+    Wallet(wallet::Wallet),
+    /// Sign transactions and certificates on an air-gapped machine
+    Offline(offline::Offline),
 }
 
 impl JCli {
@@ -87,6 +96,9 @@ impl JCliCommand {
             AutoCompletion(auto_completion) => auto_completion.exec::<Self>()?,
             Utils(utils) => utils.exec()?,
             Votes(vote) => vote.exec()?,
+            Snapshot(snapshot) => snapshot.exec()?,
+            Wallet(wallet) => wallet.exec()?,
+            Offline(offline) => offline.exec()?,
         };
         Ok(())
     }
@@ -0,0 +1,233 @@
+use crate::jcli_lib::{
+    offline::{ChunkInput, ChunkOutput, Error},
+    transaction::staging::Staging,
+    utils::io,
+};
+use bech32::{FromBase32 as _, ToBase32 as _};
+use chain_impl_mockchain::key::Hash as Blake2b256;
+use jormungandr_lib::{crypto::hash::Hash, interfaces};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead as _, Write as _},
+    path::Path,
+};
+
+const HRP: &str = "offline";
+const FORMAT_VERSION: u8 = 1;
+/// version, part number, number of parts and payload checksum
+const HEADER_SIZE: usize = 7;
+
+/// what travels between the online and the offline machine
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Payload {
+    /// a finalized transaction, with what the offline machine needs to
+    /// make its witnesses; or the signed transaction on the way back
+    Transaction {
+        block0_hash: Hash,
+        /// spending counter of each input, `None` for the UTxO inputs
+        spending_counters: Vec<Option<u32>>,
+        staging: Staging,
+    },
+    Certificate(interfaces::Certificate),
+    SignedCertificate(interfaces::SignedCertificate),
+}
+
+impl Payload {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Payload::Transaction { .. } => "a transaction",
+            Payload::Certificate(_) => "a certificate",
+            Payload::SignedCertificate(_) => "a signed certificate",
+        }
+    }
+}
+
+/// Split the payload into Bech32 chunks of at most `chunk_size` payload
+/// bytes. Every chunk carries its position and a checksum of the whole
+/// payload so they can be scanned in any order.
+pub fn encode(payload: &Payload, chunk_size: usize) -> Result<Vec<String>, Error> {
+    if chunk_size == 0 {
+        return Err(Error::ChunkSizeInvalid);
+    }
+    let bytes = bincode::serialize(payload).map_err(Error::PayloadEncodingFailed)?;
+    let checksum = checksum(&bytes);
+    let parts: Vec<&[u8]> = bytes.chunks(chunk_size).collect();
+    if parts.len() > u8::MAX as usize {
+        return Err(Error::TooManyChunks {
+            chunks: parts.len(),
+        });
+    }
+
+    parts
+        .iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let mut data = Vec::with_capacity(HEADER_SIZE + part.len());
+            data.push(FORMAT_VERSION);
+            data.push(index as u8);
+            data.push(parts.len() as u8);
+            data.extend_from_slice(&checksum);
+            data.extend_from_slice(part);
+            Ok(bech32::encode(
+                HRP,
+                data.to_base32(),
+                bech32::Variant::Bech32m,
+            )?)
+        })
+        .collect()
+}
+
+/// Reassemble a payload from its chunks, given in any order. The same
+/// chunk may be given more than once.
+pub fn decode<S: AsRef<str>>(chunks: &[S]) -> Result<Payload, Error> {
+    let mut parts = BTreeMap::new();
+    let mut expected = None;
+    for chunk in chunks {
+        let (hrp, data, _variant) = bech32::decode(chunk.as_ref().trim())?;
+        if hrp != HRP {
+            return Err(Error::ChunkHrpInvalid {
+                expected: HRP,
+                actual: hrp,
+            });
+        }
+        let data = Vec::<u8>::from_base32(&data)?;
+        if data.len() < HEADER_SIZE {
+            return Err(Error::ChunkTooShort);
+        }
+        if data[0] != FORMAT_VERSION {
+            return Err(Error::ChunkVersionUnsupported(data[0]));
+        }
+        let header = (data[2], [data[3], data[4], data[5], data[6]]);
+        if *expected.get_or_insert(header) != header {
+            return Err(Error::ChunkMismatch);
+        }
+        parts.insert(data[1], data[HEADER_SIZE..].to_vec());
+    }
+
+    let (count, checksum) = expected.ok_or(Error::NoChunks)?;
+    let missing: Vec<u8> = (0..count)
+        .filter(|index| !parts.contains_key(index))
+        .collect();
+    if !missing.is_empty() {
+        return Err(Error::MissingChunks(missing));
+    }
+    if parts.len() != count as usize {
+        return Err(Error::ChunkMismatch);
+    }
+
+    let bytes: Vec<u8> = parts.into_iter().flat_map(|(_, part)| part).collect();
+    if self::checksum(&bytes) != checksum {
+        return Err(Error::ChecksumMismatch);
+    }
+    bincode::deserialize(&bytes).map_err(Error::PayloadMalformed)
+}
+
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    let hash = Blake2b256::hash_bytes(bytes);
+    let mut checksum = [0; 4];
+    checksum.copy_from_slice(&hash.as_ref()[..4]);
+    checksum
+}
+
+impl ChunkInput {
+    /// read the chunks, one per line, from the files or the standard input
+    pub fn load(&self) -> Result<Payload, Error> {
+        let mut chunks = Vec::new();
+        if self.files.is_empty() {
+            read_chunks(&None::<&Path>, &mut chunks)?;
+        }
+        for file in &self.files {
+            read_chunks(&Some(file), &mut chunks)?;
+        }
+        decode(&chunks)
+    }
+
+    pub fn is_stdin(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+fn read_chunks<P: AsRef<Path>>(path: &Option<P>, chunks: &mut Vec<String>) -> Result<(), Error> {
+    let read_failed = |source| Error::ChunkReadFailed {
+        source,
+        path: io::path_to_path_buf(path),
+    };
+    for line in io::open_file_read(path).map_err(read_failed)?.lines() {
+        let line = line.map_err(read_failed)?;
+        if !line.trim().is_empty() {
+            chunks.push(line);
+        }
+    }
+    Ok(())
+}
+
+impl ChunkOutput {
+    pub fn store(&self, payload: &Payload) -> Result<(), Error> {
+        let write_failed = |source| Error::ChunkWriteFailed {
+            source,
+            path: io::path_to_path_buf(&self.output),
+        };
+        let chunks = encode(payload, self.chunk_size)?;
+        let mut writer = io::open_file_write(&self.output).map_err(write_failed)?;
+        for chunk in chunks {
+            writeln!(writer, "{}", chunk).map_err(write_failed)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::Transaction {
+            block0_hash: Hash::from([7; 32]),
+            spending_counters: vec![Some(3), None],
+            staging: Staging::new(),
+        }
+    }
+
+    #[test]
+    fn chunks_round_trip_in_any_order() {
+        let mut chunks = encode(&payload(), 8).unwrap();
+        assert!(chunks.len() > 1);
+        chunks.reverse();
+        chunks.push(chunks[0].clone());
+
+        match decode(&chunks).unwrap() {
+            Payload::Transaction {
+                block0_hash,
+                spending_counters,
+                ..
+            } => {
+                assert_eq!(block0_hash, Hash::from([7; 32]));
+                assert_eq!(spending_counters, vec![Some(3), None]);
+            }
+            other => panic!("unexpected payload {:?}", other),
+        }
+    }
+
+    #[test]
+    fn missing_chunks_are_reported() {
+        let mut chunks = encode(&payload(), 8).unwrap();
+        chunks.remove(1);
+        assert!(matches!(
+            decode(&chunks),
+            Err(Error::MissingChunks(missing)) if missing == vec![1]
+        ));
+    }
+
+    #[test]
+    fn chunks_of_other_payloads_are_rejected() {
+        let mut chunks = encode(&payload(), 8).unwrap();
+        let other = Payload::Transaction {
+            block0_hash: Hash::from([8; 32]),
+            spending_counters: Vec::new(),
+            staging: Staging::new(),
+        };
+        chunks[0] = encode(&other, 8).unwrap().remove(0);
+        assert!(matches!(decode(&chunks), Err(Error::ChunkMismatch)));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::jcli_lib::{
+    certificate::read_cert,
+    offline::{ChunkOutput, Error, Payload},
+    transaction::{common, staging::StagingKind},
+};
+use jormungandr_lib::{crypto::hash::Hash, interfaces::TransactionInputType};
+use std::{path::PathBuf, str::FromStr};
+use structopt::StructOpt;
+use thiserror::Error;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Export {
+    /// a finalized transaction, to witness and seal offline
+    Transaction(ExportTransaction),
+    /// a certificate, to sign offline
+    Certificate(ExportCertificate),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ExportTransaction {
+    #[structopt(flatten)]
+    pub common: common::CommonTransaction,
+
+    /// the hash of the block0, the first block of the blockchain
+    #[structopt(long = "genesis-block-hash")]
+    pub genesis_block_hash: Hash,
+
+    /// the spending counter of the account input at the given index, as
+    /// `INDEX:COUNTER`. Mandatory for every account input
+    #[structopt(long = "account-spending-counter", number_of_values = 1)]
+    pub spending_counters: Vec<InputSpendingCounter>,
+
+    #[structopt(flatten)]
+    pub output: ChunkOutput,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ExportCertificate {
+    /// get the certificate to sign from the given file. If no file
+    /// provided, it will be read from the standard input
+    #[structopt(short = "c", long = "certificate")]
+    pub input: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    pub output: ChunkOutput,
+}
+
+/// the spending counter of an account input, given as `INDEX:COUNTER`
+pub struct InputSpendingCounter {
+    pub index: usize,
+    pub counter: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum InputSpendingCounterParseError {
+    #[error("expected INDEX:COUNTER")]
+    MissingSeparator,
+    #[error("invalid number")]
+    Number(#[from] std::num::ParseIntError),
+}
+
+impl FromStr for InputSpendingCounter {
+    type Err = InputSpendingCounterParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(2, ':');
+        let index = split.next().unwrap_or_default().parse()?;
+        let counter = split
+            .next()
+            .ok_or(InputSpendingCounterParseError::MissingSeparator)?
+            .parse()?;
+        Ok(InputSpendingCounter { index, counter })
+    }
+}
+
+impl Export {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Export::Transaction(export) => export.exec(),
+            Export::Certificate(export) => export.exec(),
+        }
+    }
+}
+
+impl ExportTransaction {
+    fn exec(self) -> Result<(), Error> {
+        let staging = self.common.load()?;
+        if staging.kind() != StagingKind::Finalizing || staging.witness_count() != 0 {
+            return Err(Error::TransactionNotFinalized {
+                kind: staging.kind(),
+                witnesses: staging.witness_count(),
+            });
+        }
+
+        let spending_counters = staging
+            .inputs()
+            .iter()
+            .enumerate()
+            .map(|(index, input)| match input.input {
+                TransactionInputType::Account(_) => self
+                    .spending_counters
+                    .iter()
+                    .rev()
+                    .find(|counter| counter.index == index)
+                    .map(|counter| Some(counter.counter))
+                    .ok_or(Error::SpendingCounterMissing { index }),
+                TransactionInputType::Utxo(..) => Ok(None),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let payload = Payload::Transaction {
+            block0_hash: self.genesis_block_hash,
+            spending_counters,
+            staging,
+        };
+        self.output.store(&payload)
+    }
+}
+
+impl ExportCertificate {
+    fn exec(self) -> Result<(), Error> {
+        let certificate = read_cert(self.input.as_deref())?;
+        self.output.store(&Payload::Certificate(certificate))
+    }
+}
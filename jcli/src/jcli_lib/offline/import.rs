@@ -0,0 +1,44 @@
+use crate::jcli_lib::{
+    certificate::write_signed_cert,
+    offline::{ChunkInput, Error, Payload},
+    transaction::common,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Import {
+    #[structopt(flatten)]
+    pub input: ChunkInput,
+
+    #[structopt(flatten)]
+    pub common: common::CommonTransaction,
+
+    /// write a signed certificate into the given file. If no file
+    /// provided it will be written into the standard output
+    #[structopt(long = "certificate-output")]
+    pub certificate_output: Option<PathBuf>,
+}
+
+impl Import {
+    pub fn exec(self) -> Result<(), Error> {
+        match self.input.load()? {
+            Payload::Transaction { staging, .. } => {
+                // fails unless the transaction is completely signed
+                staging.fragment()?;
+                self.common.store(&staging)?;
+            }
+            Payload::SignedCertificate(certificate) => {
+                write_signed_cert(self.certificate_output.as_deref(), certificate)?
+            }
+            other => {
+                return Err(Error::UnexpectedPayload {
+                    expected: "a signed transaction or a signed certificate",
+                    actual: other.name(),
+                })
+            }
+        }
+        Ok(())
+    }
+}
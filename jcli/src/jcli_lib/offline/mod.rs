@@ -0,0 +1,161 @@
+mod chunks;
+mod export;
+mod import;
+mod sign;
+mod summary;
+
+use crate::jcli_lib::{
+    certificate,
+    transaction::{self, staging::StagingKind},
+    utils::key_parser,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use thiserror::Error;
+
+pub use self::chunks::Payload;
+
+/// Move transactions and certificates to and from an air-gapped machine,
+/// as text chunks fit for QR codes or copy and paste
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Offline {
+    /// encode a finalized transaction or a certificate to sign offline
+    Export(export::Export),
+    /// decode a payload and display its content
+    Inspect(Inspect),
+    /// display a payload, sign it with the offline keys and encode the
+    /// signed result
+    Sign(sign::Sign),
+    /// decode a signed payload into a staging transaction or a signed
+    /// certificate
+    Import(import::Import),
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not read chunks from '{path}'")]
+    ChunkReadFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("could not write chunks to '{path}'")]
+    ChunkWriteFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("could not parse Bech32 chunk")]
+    ChunkBech32Malformed(#[from] bech32::Error),
+    #[error("invalid chunk prefix, expected '{expected}', found '{actual}'")]
+    ChunkHrpInvalid {
+        expected: &'static str,
+        actual: String,
+    },
+    #[error("chunk is too short to hold its header")]
+    ChunkTooShort,
+    #[error("unsupported chunk format version {0}")]
+    ChunkVersionUnsupported(u8),
+    #[error("the chunks are not parts of the same payload")]
+    ChunkMismatch,
+    #[error("no chunk given")]
+    NoChunks,
+    #[error("chunks {0:?} are missing")]
+    MissingChunks(Vec<u8>),
+    #[error("chunk size must be at least 1 byte")]
+    ChunkSizeInvalid,
+    #[error(
+        "the payload needs {chunks} chunks, at most 255 are supported, use a larger --chunk-size"
+    )]
+    TooManyChunks { chunks: usize },
+    #[error("payload checksum mismatch, the chunks are corrupted")]
+    ChecksumMismatch,
+    #[error("could not encode the payload")]
+    PayloadEncodingFailed(#[source] bincode::Error),
+    #[error("could not decode the payload")]
+    PayloadMalformed(#[source] bincode::Error),
+    #[error("expected {expected}, found {actual}")]
+    UnexpectedPayload {
+        expected: &'static str,
+        actual: &'static str,
+    },
+
+    #[error(transparent)]
+    Transaction(#[from] transaction::Error),
+    #[error(transparent)]
+    Certificate(#[from] certificate::Error),
+    #[error("could not read key file")]
+    Key(#[from] key_parser::Error),
+
+    #[error("expected a finalized transaction without witnesses, found a {kind} transaction with {witnesses} witnesses")]
+    TransactionNotFinalized { kind: StagingKind, witnesses: usize },
+    #[error(
+        "the spending counter of account input {index} is missing, use --account-spending-counter"
+    )]
+    SpendingCounterMissing { index: usize },
+    #[error("the transaction has {inputs} inputs but {keys} keys were given")]
+    InputKeyCountMismatch { inputs: usize, keys: usize },
+    #[error("the key given for input {index} does not own the account")]
+    InputKeyMismatch { index: usize },
+    #[error("the certificate of the transaction needs to be signed, use --auth-key")]
+    AuthKeysMissing,
+    #[error("no signing keys specified (use -k or --key to specify)")]
+    NoSigningKeys,
+    #[error("the chunks are read from the standard input, give them as files or use --yes")]
+    ConfirmationNeedsChunkFiles,
+    #[error("canceled by user")]
+    CancelByUser,
+    #[error("error requesting user input")]
+    UserInputError(#[source] std::io::Error),
+}
+
+/// where to read the chunks of a payload from
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ChunkInput {
+    /// files holding the chunks, one chunk per line, in any order. If
+    /// omitted, the chunks are read from the standard input
+    #[structopt(name = "CHUNKS")]
+    pub files: Vec<PathBuf>,
+}
+
+/// where and how to write the chunks of a payload
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct ChunkOutput {
+    /// write the chunks into the given file, one chunk per line. If
+    /// omitted, they are written to the standard output
+    #[structopt(long = "output")]
+    pub output: Option<PathBuf>,
+
+    /// maximum number of payload bytes in a chunk. The default fits a QR
+    /// code comfortably
+    #[structopt(long = "chunk-size", default_value = "256")]
+    pub chunk_size: usize,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Inspect {
+    #[structopt(flatten)]
+    pub input: ChunkInput,
+}
+
+impl Offline {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Offline::Export(export) => export.exec(),
+            Offline::Inspect(inspect) => inspect.exec(),
+            Offline::Sign(sign) => sign.exec(),
+            Offline::Import(import) => import.exec(),
+        }
+    }
+}
+
+impl Inspect {
+    fn exec(self) -> Result<(), Error> {
+        let payload = self.input.load()?;
+        summary::print(&payload)
+    }
+}
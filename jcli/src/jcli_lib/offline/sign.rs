@@ -0,0 +1,155 @@
+use crate::jcli_lib::{
+    certificate::sign_certificate,
+    offline::{summary, ChunkInput, ChunkOutput, Error, Payload},
+    transaction::{
+        mk_witness::{make_witness, WitnessType},
+        staging::{Staging, StagingKind},
+    },
+    utils::{io::ask_yes_or_no, key_parser},
+};
+use chain_impl_mockchain::{
+    account::{Identifier, SpendingCounter},
+    transaction::UnspecifiedAccountIdentifier,
+};
+use jormungandr_lib::{crypto::hash::Hash, interfaces::TransactionInputType};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Sign {
+    #[structopt(flatten)]
+    pub input: ChunkInput,
+
+    /// path to the file with a signing key. For a transaction, give one key
+    /// per input, in the order of the inputs. For a certificate, give the
+    /// keys `certificate sign` expects
+    #[structopt(short = "k", long = "key", number_of_values = 1)]
+    pub signing_keys: Vec<PathBuf>,
+
+    /// path to the file with a key authenticating the certificate of the
+    /// transaction, when it needs one
+    #[structopt(long = "auth-key", number_of_values = 1)]
+    pub auth_keys: Vec<PathBuf>,
+
+    /// do not ask for confirmation after displaying the payload
+    #[structopt(long = "yes")]
+    pub yes: bool,
+
+    #[structopt(flatten)]
+    pub output: ChunkOutput,
+}
+
+impl Sign {
+    pub fn exec(self) -> Result<(), Error> {
+        if !self.yes && self.input.is_stdin() {
+            return Err(Error::ConfirmationNeedsChunkFiles);
+        }
+        if self.signing_keys.is_empty() {
+            return Err(Error::NoSigningKeys);
+        }
+
+        let payload = self.input.load()?;
+        summary::print(&payload)?;
+        if !self.yes && !ask_yes_or_no(true).map_err(Error::UserInputError)? {
+            return Err(Error::CancelByUser);
+        }
+
+        let signed = match payload {
+            Payload::Transaction {
+                block0_hash,
+                spending_counters,
+                staging,
+            } => {
+                let staging = self.sign_transaction(block0_hash, &spending_counters, staging)?;
+                Payload::Transaction {
+                    block0_hash,
+                    spending_counters,
+                    staging,
+                }
+            }
+            Payload::Certificate(certificate) => {
+                let keys = read_keys(&self.signing_keys)?;
+                Payload::SignedCertificate(sign_certificate(certificate, &keys)?.into())
+            }
+            other => {
+                return Err(Error::UnexpectedPayload {
+                    expected: "a transaction or a certificate",
+                    actual: other.name(),
+                })
+            }
+        };
+        self.output.store(&signed)
+    }
+
+    fn sign_transaction(
+        &self,
+        block0_hash: Hash,
+        spending_counters: &[Option<u32>],
+        mut staging: Staging,
+    ) -> Result<Staging, Error> {
+        if staging.kind() != StagingKind::Finalizing || staging.witness_count() != 0 {
+            return Err(Error::TransactionNotFinalized {
+                kind: staging.kind(),
+                witnesses: staging.witness_count(),
+            });
+        }
+        if self.signing_keys.len() != staging.inputs().len() {
+            return Err(Error::InputKeyCountMismatch {
+                inputs: staging.inputs().len(),
+                keys: self.signing_keys.len(),
+            });
+        }
+
+        let block0_hash = block0_hash.into_hash();
+        let sign_data_hash = staging.transaction_sign_data_hash()?;
+        let inputs = staging.inputs().to_vec();
+        for (index, (input, key)) in inputs.iter().zip(&self.signing_keys).enumerate() {
+            let secret_key = key_parser::read_ed25519_secret_key_from_file(&Some(key))?;
+            let witness = match input.input {
+                TransactionInputType::Account(account) => {
+                    let owner = UnspecifiedAccountIdentifier::from(account).to_single_account();
+                    if owner != Some(Identifier::from(secret_key.to_public())) {
+                        return Err(Error::InputKeyMismatch { index });
+                    }
+                    let counter = spending_counters
+                        .get(index)
+                        .copied()
+                        .flatten()
+                        .ok_or(Error::SpendingCounterMissing { index })?;
+                    make_witness(
+                        &WitnessType::Account,
+                        &block0_hash,
+                        &sign_data_hash,
+                        Some(SpendingCounter::from(counter)),
+                        &secret_key,
+                    )?
+                }
+                TransactionInputType::Utxo(..) => make_witness(
+                    &WitnessType::UTxO,
+                    &block0_hash,
+                    &sign_data_hash,
+                    None,
+                    &secret_key,
+                )?,
+            };
+            staging.add_witness(witness)?;
+        }
+        staging.seal()?;
+
+        if staging.need_auth() {
+            if self.auth_keys.is_empty() {
+                return Err(Error::AuthKeysMissing);
+            }
+            staging.set_auth(&read_keys(&self.auth_keys)?)?;
+        }
+        Ok(staging)
+    }
+}
+
+fn read_keys(paths: &[PathBuf]) -> Result<Vec<String>, Error> {
+    paths
+        .iter()
+        .map(|path| key_parser::read_key_line(&Some(path)).map_err(Error::from))
+        .collect()
+}
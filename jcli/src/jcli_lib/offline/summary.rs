@@ -0,0 +1,110 @@
+use crate::jcli_lib::{
+    offline::{Error, Payload},
+    transaction::{staging::Staging, Error as TxError},
+};
+use chain_impl_mockchain::{certificate::Certificate, transaction::UnspecifiedAccountIdentifier};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{self, AccountIdentifier, TransactionInputType},
+};
+
+/// Display everything the signer commits to: the blockchain, the values
+/// moved, the fees and the certificate.
+pub fn print(payload: &Payload) -> Result<(), Error> {
+    match payload {
+        Payload::Transaction {
+            block0_hash,
+            spending_counters,
+            staging,
+        } => print_transaction(block0_hash, spending_counters, staging),
+        Payload::Certificate(certificate) => {
+            println!("certificate to sign:");
+            print_certificate(certificate);
+            Ok(())
+        }
+        Payload::SignedCertificate(certificate) => {
+            println!("signed certificate:");
+            print_certificate(&certificate.clone().strip_auth());
+            Ok(())
+        }
+    }
+}
+
+fn print_transaction(
+    block0_hash: &Hash,
+    spending_counters: &[Option<u32>],
+    staging: &Staging,
+) -> Result<(), Error> {
+    println!("transaction ({}):", staging.staging_kind_name());
+    println!("  block0 hash: {}", block0_hash);
+    if let Some(valid_until) = staging.valid_until() {
+        println!("  valid until: {}", valid_until);
+    }
+    println!("inputs:");
+    for (index, input) in staging.inputs().iter().enumerate() {
+        match input.input {
+            TransactionInputType::Account(account) => {
+                let account = AccountIdentifier::from(UnspecifiedAccountIdentifier::from(account));
+                let counter = spending_counters
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .map(|counter| counter.to_string())
+                    .unwrap_or_else(|| "unknown".to_owned());
+                println!(
+                    "  {}: {} from account {} (spending counter {})",
+                    index, input.value, account, counter
+                );
+            }
+            TransactionInputType::Utxo(fragment_id, output_index) => println!(
+                "  {}: {} from output {}:{}",
+                index,
+                input.value,
+                Hash::from(fragment_id),
+                output_index
+            ),
+        }
+    }
+    println!("outputs:");
+    for (index, output) in staging.outputs().iter().enumerate() {
+        println!("  {}: {} to {}", index, output.value(), output.address());
+    }
+
+    let total_input = staging.total_input().map_err(TxError::from)?;
+    let total_output = staging.total_output().map_err(TxError::from)?;
+    let fees = (total_input - total_output).map_err(TxError::from)?;
+    println!("total input: {}", total_input);
+    println!("total output: {}", total_output);
+    println!("fees: {}", fees);
+
+    if let Some(certificate) = staging.certificate() {
+        println!("certificate:");
+        print_certificate(certificate);
+        if staging.need_auth() {
+            println!("  needs to be signed with --auth-key");
+        }
+    }
+    Ok(())
+}
+
+fn print_certificate(certificate: &interfaces::Certificate) {
+    let kind = match &certificate.0 {
+        Certificate::StakeDelegation(_) => "stake delegation".to_owned(),
+        Certificate::OwnerStakeDelegation(_) => "owner stake delegation".to_owned(),
+        Certificate::PoolRegistration(registration) => {
+            format!("stake pool registration of {}", registration.to_id())
+        }
+        Certificate::PoolRetirement(retirement) => {
+            format!("stake pool retirement of {}", retirement.pool_id)
+        }
+        Certificate::PoolUpdate(_) => "stake pool update".to_owned(),
+        Certificate::VotePlan(vote_plan) => format!("vote plan {}", vote_plan.to_id()),
+        Certificate::VoteCast(_) => "vote cast".to_owned(),
+        Certificate::VoteTally(_) => "vote tally".to_owned(),
+        Certificate::EncryptedVoteTally(_) => "encrypted vote tally".to_owned(),
+        Certificate::UpdateProposal(_) => "update proposal".to_owned(),
+        Certificate::UpdateVote(_) => "update vote".to_owned(),
+    };
+    println!("  kind: {}", kind);
+    println!("  {}", certificate);
+}
@@ -118,6 +118,10 @@ impl RestClient {
         self.make_request_builder(address_segments, |client, url| client.post(url))
     }
 
+    pub fn put(self, address_segments: &[&str]) -> RestRequestBuilder {
+        self.make_request_builder(address_segments, |client, url| client.put(url))
+    }
+
     pub fn delete(self, address_segments: &[&str]) -> RestRequestBuilder {
         self.make_request_builder(address_segments, |client, url| client.delete(url))
     }
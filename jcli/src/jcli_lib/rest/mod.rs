@@ -5,6 +5,7 @@ pub mod v1;
 use crate::jcli_lib::utils::{io::ReadYamlError, output_format};
 pub use config::RestArgs;
 use hex::FromHexError;
+use reqwest::StatusCode;
 use structopt::StructOpt;
 use thiserror::Error;
 
@@ -36,6 +37,17 @@ pub enum Error {
     SerdeError(#[from] serde_json::Error),
 }
 
+impl Error {
+    /// whether the node answered that the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::RequestError(config::Error::InvalidParams(e))
+                if e.status() == Some(StatusCode::NOT_FOUND)
+        )
+    }
+}
+
 impl From<ReadYamlError> for Error {
     fn from(error: ReadYamlError) -> Self {
         match error {
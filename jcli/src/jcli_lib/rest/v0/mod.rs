@@ -12,7 +12,7 @@ mod stake;
 mod stake_pool;
 mod stake_pools;
 mod tip;
-mod utxo;
+pub mod utxo;
 mod vote;
 
 use crate::jcli_lib::rest::Error;
@@ -1,5 +1,6 @@
 use crate::jcli_lib::rest::{Error, RestArgs};
 use crate::jcli_lib::utils::OutputFormat;
+use jormungandr_lib::interfaces::TransactionOutput;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -49,3 +50,21 @@ impl Utxo {
         Ok(())
     }
 }
+
+/// `None` if the output does not exist or has already been spent
+pub fn request_utxo(
+    args: RestArgs,
+    fragment_id: &str,
+    output_index: u8,
+) -> Result<Option<TransactionOutput>, Error> {
+    let response = args
+        .client()?
+        .get(&["v0", "utxo", fragment_id, &output_index.to_string()])
+        .execute()
+        .map_err(Error::from);
+    match response {
+        Ok(response) => Ok(Some(response.json()?)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::jcli_lib::{
+    rest::{Error, RestArgs},
+    utils::{io, OutputFormat},
+};
+use chain_core::property::Deserialize;
+use chain_impl_mockchain::fragment::Fragment;
+use jormungandr_lib::interfaces::{FragmentsBatch, FragmentsSimulation};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Fragments {
+    /// apply fragments to the ledger of the node tip without adding them to
+    /// the mempool. Prints the fee, the expiry validity, the ledger error and
+    /// the balance changes of each fragment
+    Simulate {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// do not apply the fragments following the first rejected one
+        #[structopt(long)]
+        fail_fast: bool,
+        /// files containing a hex-encoded fragment each, simulated in the
+        /// given order. If not provided, a fragment will be read from stdin.
+        #[structopt(short, long, number_of_values = 1)]
+        file: Vec<PathBuf>,
+    },
+}
+
+impl Fragments {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Fragments::Simulate {
+                args,
+                output_format,
+                fail_fast,
+                file,
+            } => {
+                let fragments = if file.is_empty() {
+                    vec![read_fragment(&None)?]
+                } else {
+                    file.into_iter()
+                        .map(|file| read_fragment(&Some(file)))
+                        .collect::<Result<_, _>>()?
+                };
+                let simulation = simulate_fragments(args, fragments, fail_fast)?;
+                let formatted = output_format.format_json(serde_json::to_value(&simulation)?)?;
+                println!("{}", formatted);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn read_fragment(file: &Option<PathBuf>) -> Result<Fragment, Error> {
+    let msg_hex = io::read_line(file)?;
+    let msg_bin = hex::decode(&msg_hex)?;
+    Fragment::deserialize(msg_bin.as_slice()).map_err(Error::InputFragmentMalformed)
+}
+
+pub fn simulate_fragments(
+    args: RestArgs,
+    fragments: Vec<Fragment>,
+    fail_fast: bool,
+) -> Result<FragmentsSimulation, Error> {
+    args.client()?
+        .post(&["v1", "fragments", "simulate"])
+        .json(&FragmentsBatch {
+            fail_fast,
+            fragments,
+        })
+        .execute()?
+        .json()
+}
@@ -0,0 +1,103 @@
+use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
+use jormungandr_lib::interfaces::{BlockDate, LeadershipRehearsalRequest};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Leadership {
+    /// Build, sign and validate the block of a slot the node is elected for,
+    /// without sending it to the network, and report the time each stage
+    /// took against the slot deadlines
+    Rehearse {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// the slot to rehearse, as `epoch.slot`. Defaults to the first slot
+        /// after the tip the node is elected for
+        #[structopt(long)]
+        date: Option<BlockDate>,
+    },
+    /// Get the period of the KES keys the node signs the blocks of its
+    /// stake pools with and when they expire
+    Kes {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+    /// Get the BFT leaders and the stake pools the node runs as
+    Leaders {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+    /// Get the leadership logs of the node
+    Logs {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// only get the logs of this leader of the node: the public key of a
+        /// BFT leader or the id of a stake pool
+        #[structopt(long)]
+        leader: Option<String>,
+    },
+}
+
+impl Leadership {
+    pub fn exec(self) -> Result<(), Error> {
+        let (output_format, response) = match self {
+            Leadership::Rehearse {
+                args,
+                output_format,
+                date,
+            } => {
+                let response = args
+                    .client()?
+                    .post(&["v1", "leadership", "rehearsal"])
+                    .json(&LeadershipRehearsalRequest { date })
+                    .execute()?
+                    .json()?;
+                (output_format, response)
+            }
+            Leadership::Kes {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v1", "leadership", "kes"])
+                    .execute()?
+                    .json()?;
+                (output_format, response)
+            }
+            Leadership::Leaders {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v1", "leadership", "leaders"])
+                    .execute()?
+                    .json()?;
+                (output_format, response)
+            }
+            Leadership::Logs {
+                args,
+                output_format,
+                leader,
+            } => {
+                let mut path = vec!["v1", "leadership", "logs"];
+                path.extend(leader.as_deref());
+                let response = args.client()?.get(&path).execute()?.json()?;
+                (output_format, response)
+            }
+        };
+        let formatted = output_format.format_json(response)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
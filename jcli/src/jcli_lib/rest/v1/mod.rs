@@ -1,3 +1,7 @@
+pub mod fragments;
+mod leadership;
+mod runtime_settings;
+mod services;
 mod vote;
 
 use crate::jcli_lib::rest::Error;
@@ -6,12 +10,23 @@ use structopt::StructOpt;
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 pub enum V1 {
+    Fragments(fragments::Fragments),
+    /// Rehearse block production and inspect the KES key of the node
+    Leadership(leadership::Leadership),
+    /// Inspect and change the settings of the running node
+    RuntimeSettings(runtime_settings::RuntimeSettings),
+    /// Inspect the internal services of the node
+    Services(services::Services),
     Vote(vote::Vote),
 }
 
 impl V1 {
     pub fn exec(self) -> Result<(), Error> {
         match self {
+            V1::Fragments(fragments) => fragments.exec(),
+            V1::Leadership(leadership) => leadership.exec(),
+            V1::RuntimeSettings(settings) => settings.exec(),
+            V1::Services(services) => services.exec(),
             V1::Vote(vote) => vote.exec(),
         }
     }
@@ -0,0 +1,94 @@
+use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum RuntimeSettings {
+    /// Get the value of a runtime setting, or of all of them.
+    /// Settings without value use the node configuration
+    Get {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// name of the setting, for example `log.level`
+        name: Option<String>,
+    },
+    /// Set the value of a runtime setting. The value is kept across restarts
+    /// of the node and overrides the node configuration
+    Set {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// name of the setting, for example `log.level`
+        name: String,
+        value: String,
+    },
+    /// Remove the value of a runtime setting, the node configuration applies
+    /// again
+    Remove {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+        /// name of the setting, for example `log.level`
+        name: String,
+    },
+}
+
+impl RuntimeSettings {
+    pub fn exec(self) -> Result<(), Error> {
+        let (output_format, response) = match self {
+            RuntimeSettings::Get {
+                args,
+                output_format,
+                name,
+            } => {
+                let response = match name {
+                    Some(name) => args
+                        .client()?
+                        .get(&["v1", "runtime-settings", &name])
+                        .execute()?
+                        .json()?,
+                    None => args
+                        .client()?
+                        .get(&["v1", "runtime-settings"])
+                        .execute()?
+                        .json()?,
+                };
+                (output_format, response)
+            }
+            RuntimeSettings::Set {
+                args,
+                output_format,
+                name,
+                value,
+            } => {
+                let response = args
+                    .client()?
+                    .put(&["v1", "runtime-settings", &name])
+                    .json(&value)
+                    .execute()?
+                    .json()?;
+                (output_format, response)
+            }
+            RuntimeSettings::Remove {
+                args,
+                output_format,
+                name,
+            } => {
+                let response = args
+                    .client()?
+                    .delete(&["v1", "runtime-settings", &name])
+                    .execute()?
+                    .json()?;
+                (output_format, response)
+            }
+        };
+        let formatted = output_format.format_json(response)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Services {
+    /// Get the restart policy, state and restart count of the internal
+    /// services of the node
+    Get {
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
+}
+
+impl Services {
+    pub fn exec(self) -> Result<(), Error> {
+        let Services::Get {
+            args,
+            output_format,
+        } = self;
+        let response = args.client()?.get(&["v1", "services"]).execute()?.json()?;
+        let formatted = output_format.format_json(response)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
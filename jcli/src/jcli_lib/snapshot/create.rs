@@ -0,0 +1,198 @@
+use crate::jcli_lib::{
+    rest::{self, RestArgs},
+    snapshot::{write_manifest, Error},
+};
+use chain_core::property::Deserialize as _;
+use chain_impl_mockchain::block::{Block, Header};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{
+        read_snapshot_block, read_snapshot_ledger, snapshot_blocks_digest, write_snapshot_block,
+        SettingsDto, SnapshotBlocks, SnapshotLedgers, SnapshotManifest, SnapshotTip,
+        SNAPSHOT_BLOCKS_FILE, SNAPSHOT_LEDGERS_FILE,
+    },
+};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write as _};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// number of blocks requested from the node at once
+const BLOCKS_PER_REQUEST: usize = 100;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Create {
+    #[structopt(flatten)]
+    args: RestArgs,
+
+    /// the last epoch to include in the snapshot. The snapshot ends with the
+    /// last block of this epoch, which must be completed. Default is the
+    /// epoch preceding the epoch of the node's tip.
+    #[structopt(long)]
+    epoch: Option<u32>,
+
+    /// directory to write the snapshot archive to
+    #[structopt(short, long, parse(from_os_str))]
+    output: PathBuf,
+}
+
+impl Create {
+    pub fn exec(self) -> Result<(), Error> {
+        let Create {
+            args,
+            epoch,
+            output,
+        } = self;
+
+        let block0 = Hash::from_hex(&get_settings(&args)?.block0_hash)?;
+
+        let tip = get_tip(&args)?;
+        let tip = get_block_bytes(&args, tip.trim())?;
+        let tip_epoch = Block::deserialize(tip.as_ref())
+            .map_err(Error::BlockMalformed)?
+            .header()
+            .block_date()
+            .epoch;
+        let epoch = match epoch {
+            Some(epoch) if epoch < tip_epoch => epoch,
+            Some(epoch) => return Err(Error::EpochNotCompleted { epoch, tip_epoch }),
+            None => tip_epoch.checked_sub(1).ok_or(Error::NoEpochCompleted)?,
+        };
+
+        fs::create_dir_all(&output).map_err(|source| Error::Io {
+            path: output.clone(),
+            source,
+        })?;
+        let blocks_path = output.join(SNAPSHOT_BLOCKS_FILE);
+        let io_error = |source| Error::Io {
+            path: blocks_path.clone(),
+            source,
+        };
+        let mut writer = BufWriter::new(File::create(&blocks_path).map_err(io_error)?);
+
+        let mut last: Option<Header> = None;
+        let mut count = 0;
+        let mut from = block0.to_hex();
+        'blocks: loop {
+            let blocks = get_snapshot_blocks(&args, &from)?;
+            let mut reader = blocks.as_slice();
+            if reader.is_empty() {
+                break;
+            }
+            while let Some(bytes) =
+                read_snapshot_block(&mut reader).map_err(Error::BlockMalformed)?
+            {
+                let block = Block::deserialize(bytes.as_ref()).map_err(Error::BlockMalformed)?;
+                if block.header().block_date().epoch > epoch {
+                    break 'blocks;
+                }
+                write_snapshot_block(&mut writer, &bytes).map_err(io_error)?;
+                count += 1;
+                from = Hash::from_hash(block.header().hash()).to_hex();
+                last = Some(block.header().clone());
+            }
+        }
+        writer.flush().map_err(io_error)?;
+        drop(writer);
+
+        let last = last.ok_or(Error::NoBlocks(epoch))?;
+        let (digest, size) = snapshot_blocks_digest(File::open(&blocks_path).map_err(io_error)?)
+            .map_err(io_error)?;
+
+        let tip_hash = Hash::from_hash(last.hash());
+        let ledgers = get_snapshot_ledgers(&args, &tip_hash.to_hex())?;
+        let mut boundaries = Vec::new();
+        let mut reader = ledgers.as_slice();
+        while let Some((boundary, _)) =
+            read_snapshot_ledger(&mut reader).map_err(Error::LedgersMalformed)?
+        {
+            boundaries.push(boundary);
+        }
+        if boundaries.last() != Some(&tip_hash) {
+            return Err(Error::LedgersMissingTip(tip_hash));
+        }
+        let ledgers_path = output.join(SNAPSHOT_LEDGERS_FILE);
+        fs::write(&ledgers_path, &ledgers).map_err(|source| Error::Io {
+            path: ledgers_path,
+            source,
+        })?;
+        let (ledgers_digest, ledgers_size) =
+            snapshot_blocks_digest(ledgers.as_slice()).map_err(Error::LedgersMalformed)?;
+
+        let manifest = SnapshotManifest::new(
+            block0,
+            SnapshotTip {
+                hash: tip_hash,
+                chain_length: last.chain_length().into(),
+                date: last.block_date().into(),
+            },
+            SnapshotBlocks {
+                count,
+                size,
+                digest,
+            },
+            SnapshotLedgers {
+                boundaries,
+                size: ledgers_size,
+                digest: ledgers_digest,
+            },
+        );
+        write_manifest(&output, &manifest)?;
+
+        println!("{}", manifest.tip.hash);
+        Ok(())
+    }
+}
+
+fn get_settings(args: &RestArgs) -> Result<SettingsDto, rest::Error> {
+    Ok(args
+        .clone()
+        .client()?
+        .get(&["v0", "settings"])
+        .execute()?
+        .json()?)
+}
+
+fn get_tip(args: &RestArgs) -> Result<String, rest::Error> {
+    Ok(args
+        .clone()
+        .client()?
+        .get(&["v0", "tip"])
+        .execute()?
+        .text()?)
+}
+
+fn get_block_bytes(args: &RestArgs, block_id: &str) -> Result<Vec<u8>, rest::Error> {
+    Ok(args
+        .clone()
+        .client()?
+        .get(&["v0", "block", block_id])
+        .execute()?
+        .bytes()?
+        .to_vec())
+}
+
+/// the blocks following `block_id`, in the format of the blocks file
+fn get_snapshot_blocks(args: &RestArgs, block_id: &str) -> Result<Vec<u8>, rest::Error> {
+    Ok(args
+        .clone()
+        .client()?
+        .get(&["v0", "snapshot", "blocks", block_id])
+        .query(&[("count", BLOCKS_PER_REQUEST)])
+        .execute()?
+        .bytes()?
+        .to_vec())
+}
+
+/// the ledger states at the epoch boundaries up to `block_id`, in the format
+/// of the ledgers file
+fn get_snapshot_ledgers(args: &RestArgs, block_id: &str) -> Result<Vec<u8>, rest::Error> {
+    Ok(args
+        .clone()
+        .client()?
+        .get(&["v0", "snapshot", "ledgers", block_id])
+        .execute()?
+        .bytes()?
+        .to_vec())
+}
@@ -0,0 +1,40 @@
+use crate::jcli_lib::snapshot::{read_manifest, Error};
+use jormungandr_lib::interfaces::{
+    snapshot_blocks_digest, SNAPSHOT_BLOCKS_FILE, SNAPSHOT_LEDGERS_FILE,
+};
+use std::fs::File;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Info {
+    /// directory of the snapshot archive
+    #[structopt(parse(from_os_str))]
+    snapshot: PathBuf,
+}
+
+impl Info {
+    pub fn exec(self) -> Result<(), Error> {
+        let manifest = read_manifest(&self.snapshot)?;
+
+        let path = self.snapshot.join(SNAPSHOT_BLOCKS_FILE);
+        let (digest, size) = File::open(&path)
+            .and_then(snapshot_blocks_digest)
+            .map_err(|source| Error::Io { path, source })?;
+        if digest != manifest.blocks.digest || size != manifest.blocks.size {
+            return Err(Error::DigestMismatch);
+        }
+
+        let path = self.snapshot.join(SNAPSHOT_LEDGERS_FILE);
+        let (digest, size) = File::open(&path)
+            .and_then(snapshot_blocks_digest)
+            .map_err(|source| Error::Io { path, source })?;
+        if digest != manifest.ledgers.digest || size != manifest.ledgers.size {
+            return Err(Error::LedgersDigestMismatch);
+        }
+
+        println!("{}", serde_yaml::to_string(&manifest)?);
+        Ok(())
+    }
+}
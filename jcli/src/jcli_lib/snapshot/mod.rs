@@ -0,0 +1,84 @@
+mod create;
+mod info;
+mod sign;
+
+use crate::jcli_lib::rest;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{SnapshotManifest, SNAPSHOT_MANIFEST_FILE},
+};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use thiserror::Error;
+
+/// Create and sign snapshot archives used to fast sync a node
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Snapshot {
+    /// create a snapshot archive from the storage of a running node
+    Create(create::Create),
+    /// add a signature to the manifest of a snapshot archive
+    Sign(sign::Sign),
+    /// display the manifest of a snapshot archive and check its blocks and
+    /// ledgers files
+    Info(info::Info),
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Rest(#[from] rest::Error),
+    #[error("cannot access snapshot file '{path}'")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("snapshot manifest is malformed")]
+    Manifest(#[from] serde_yaml::Error),
+    #[error("node returned a malformed block")]
+    BlockMalformed(#[source] std::io::Error),
+    #[error("node returned a malformed block hash")]
+    HashMalformed(#[from] chain_crypto::hash::Error),
+    #[error("epoch {epoch} is not completed yet, the node tip is in epoch {tip_epoch}")]
+    EpochNotCompleted { epoch: u32, tip_epoch: u32 },
+    #[error("no epoch has been completed yet, the node tip is still in epoch 0")]
+    NoEpochCompleted,
+    #[error("no block has been created up to epoch {0}")]
+    NoBlocks(u32),
+    #[error("could not read the signing key")]
+    SigningKey(#[source] std::io::Error),
+    #[error("signing key is not a valid ed25519 secret key")]
+    SigningKeyMalformed(#[from] chain_crypto::bech32::Error),
+    #[error("blocks file does not match the digest of the manifest")]
+    DigestMismatch,
+    #[error("ledgers file does not match the digest of the manifest")]
+    LedgersDigestMismatch,
+    #[error("node returned malformed ledger states")]
+    LedgersMalformed(#[source] std::io::Error),
+    #[error("node did not return the ledger state of the snapshot tip {0}")]
+    LedgersMissingTip(Hash),
+}
+
+impl Snapshot {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Snapshot::Create(create) => create.exec(),
+            Snapshot::Sign(sign) => sign.exec(),
+            Snapshot::Info(info) => info.exec(),
+        }
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<SnapshotManifest, Error> {
+    let path = dir.join(SNAPSHOT_MANIFEST_FILE);
+    let file = File::open(&path).map_err(|source| Error::Io { path, source })?;
+    Ok(serde_yaml::from_reader(file)?)
+}
+
+fn write_manifest(dir: &Path, manifest: &SnapshotManifest) -> Result<(), Error> {
+    let path = dir.join(SNAPSHOT_MANIFEST_FILE);
+    let file = File::create(&path).map_err(|source| Error::Io { path, source })?;
+    Ok(serde_yaml::to_writer(file, manifest)?)
+}
@@ -0,0 +1,35 @@
+use crate::jcli_lib::{
+    snapshot::{read_manifest, write_manifest, Error},
+    utils::io,
+};
+use chain_crypto::Ed25519;
+use jormungandr_lib::crypto::key::SigningKey;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Sign {
+    /// path to the file with the bech32 ed25519 secret key of a BFT leader or
+    /// of a committee member
+    #[structopt(short = "k", long = "key", parse(from_os_str))]
+    signing_key: PathBuf,
+
+    /// directory of the snapshot archive
+    #[structopt(parse(from_os_str))]
+    snapshot: PathBuf,
+}
+
+impl Sign {
+    pub fn exec(self) -> Result<(), Error> {
+        let key = io::read_line(&Some(&self.signing_key)).map_err(Error::SigningKey)?;
+        let key = SigningKey::<Ed25519>::from_bech32_str(&key)?;
+
+        let mut manifest = read_manifest(&self.snapshot)?;
+        manifest.sign(&key);
+        write_manifest(&self.snapshot, &manifest)?;
+
+        println!("{}", key.identifier());
+        Ok(())
+    }
+}
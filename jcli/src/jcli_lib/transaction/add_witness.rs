@@ -5,7 +5,7 @@ use crate::jcli_lib::{
 use bech32::{self, FromBase32 as _};
 use chain_core::mempack::{ReadBuf, Readable as _};
 use chain_impl_mockchain::transaction::Witness;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -21,46 +21,45 @@ impl AddWitness {
     pub fn exec(self) -> Result<(), Error> {
         let mut transaction = self.common.load()?;
 
-        let witness = self.witness()?;
+        let witness = read_witness(&self.witness)?;
 
         transaction.add_witness(witness)?;
 
         self.common.store(&transaction)?;
         Ok(())
     }
+}
 
-    fn witness(&self) -> Result<Witness, Error> {
-        const HRP: &str = "witness";
+/// read a witness from a file in the bech32 format written by
+/// `make-witness`
+pub(crate) fn read_witness(path: &Path) -> Result<Witness, Error> {
+    const HRP: &str = "witness";
 
-        let bech32_str =
-            io::read_line(&Some(&self.witness)).map_err(|source| Error::WitnessFileReadFailed {
-                source,
-                path: self.witness.clone(),
-            })?;
+    let bech32_str = io::read_line(&Some(path)).map_err(|source| Error::WitnessFileReadFailed {
+        source,
+        path: path.to_path_buf(),
+    })?;
 
-        let (hrp, data, _variant) = bech32::decode(bech32_str.trim()).map_err(|source| {
-            Error::WitnessFileBech32Malformed {
-                source,
-                path: self.witness.clone(),
-            }
+    let (hrp, data, _variant) =
+        bech32::decode(bech32_str.trim()).map_err(|source| Error::WitnessFileBech32Malformed {
+            source,
+            path: path.to_path_buf(),
         })?;
-        if hrp != HRP {
-            return Err(Error::WitnessFileBech32HrpInvalid {
-                expected: HRP,
-                actual: hrp,
-                path: self.witness.clone(),
-            });
-        }
-        let bytes =
-            Vec::from_base32(&data).map_err(|source| Error::WitnessFileBech32Malformed {
-                source,
-                path: self.witness.clone(),
-            })?;
-        Witness::read(&mut ReadBuf::from(&bytes)).map_err(|source| {
-            Error::WitnessFileDeserializationFailed {
-                source,
-                path: self.witness.clone(),
-            }
-        })
+    if hrp != HRP {
+        return Err(Error::WitnessFileBech32HrpInvalid {
+            expected: HRP,
+            actual: hrp,
+            path: path.to_path_buf(),
+        });
     }
+    let bytes = Vec::from_base32(&data).map_err(|source| Error::WitnessFileBech32Malformed {
+        source,
+        path: path.to_path_buf(),
+    })?;
+    Witness::read(&mut ReadBuf::from(&bytes)).map_err(|source| {
+        Error::WitnessFileDeserializationFailed {
+            source,
+            path: path.to_path_buf(),
+        }
+    })
 }
@@ -1,6 +1,6 @@
 use crate::jcli_lib::{
-    certificate::read_input,
     transaction::{common, Error},
+    utils::key_parser,
 };
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -26,9 +26,7 @@ impl Auth {
         let keys_str: Result<Vec<String>, Error> = self
             .signing_keys
             .iter()
-            .map(|sk| {
-                read_input(Some(sk.as_ref())).map_err(|e| Error::CertificateError { error: e })
-            })
+            .map(|sk| key_parser::read_key_line(&Some(sk)).map_err(Error::from))
             .collect();
         let keys_str = keys_str?;
 
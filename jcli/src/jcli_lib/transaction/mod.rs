@@ -4,15 +4,17 @@ mod add_input;
 pub mod add_output;
 mod add_witness;
 mod auth;
-mod common;
+pub(crate) mod common;
 pub mod finalize;
 mod info;
-mod mk_witness;
+pub(crate) mod mk_witness;
 pub mod new;
+mod partial;
 mod seal;
 mod set_expiry_date;
 mod simplified;
-mod staging;
+mod simulate;
+pub(crate) mod staging;
 
 use self::staging::StagingKind;
 use crate::jcli_lib::{
@@ -22,6 +24,7 @@ use crate::jcli_lib::{
 use crate::{block, rest, utils};
 use chain_core::property::Serialize as _;
 use chain_impl_mockchain as chain;
+use jormungandr_lib::interfaces::PartialTransactionError;
 use std::path::PathBuf;
 use structopt::StructOpt;
 use thiserror::Error;
@@ -69,6 +72,12 @@ pub enum Transaction {
     ToMessage(common::CommonTransaction),
     /// send a transaction from one account to another (simplified method)
     MakeTransaction(simplified::MakeTransaction),
+    /// exchange a transaction signed by several parties
+    Partial(partial::Partial),
+    /// apply the given 'sealed' transaction to the ledger of a node tip,
+    /// without posting it, to check its fee, its expiry date and whether
+    /// the ledger would accept it
+    Simulate(simulate::Simulate),
 }
 
 type StaticStr = &'static str;
@@ -224,6 +233,33 @@ pub enum Error {
 
     #[error("cannot finalize the payload without a validity end date set")]
     CannotFinalizeWithoutValidUntil,
+
+    #[error("could not open partial transaction file '{path}'")]
+    PartialFileOpenFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("could not parse partial transaction file '{path}'")]
+    PartialFileMalformed {
+        #[source]
+        source: serde_yaml::Error,
+        path: PathBuf,
+    },
+    #[error("could not write partial transaction file '{path}'")]
+    PartialFileWriteFailed {
+        #[source]
+        source: serde_yaml::Error,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    PartialTransaction(#[from] PartialTransactionError),
+    #[error("the address owning UTxO input {index} is missing, use --input-address")]
+    PartialInputAddressMissing { index: usize },
+    #[error("the address given for input {index} is not the address of its account")]
+    PartialInputAddressMismatch { index: usize },
+    #[error("the partial transaction content does not match its sign data hash")]
+    PartialSignDataHashMismatch,
 }
 
 /*
@@ -254,6 +290,8 @@ impl Transaction {
             Transaction::ToMessage(common) => display_message(common),
             Transaction::MakeTransaction(send) => send.exec(),
             Transaction::SetExpiryDate(set_expiry_date) => set_expiry_date.exec(),
+            Transaction::Partial(partial) => partial.exec(),
+            Transaction::Simulate(simulate) => simulate.exec(),
         }
     }
 }
@@ -0,0 +1,306 @@
+use crate::jcli_lib::{
+    transaction::{
+        add_witness::read_witness,
+        common,
+        staging::{self, Staging},
+        Error,
+    },
+    utils::io,
+};
+use chain_addr::Discrimination;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{Address, InputPointer, PartialTransaction},
+};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use structopt::StructOpt;
+use thiserror::Error;
+
+/// Build a transaction with several parties: each party checks and signs
+/// its own copy of a partial transaction, and the copies are merged back
+/// before the transaction is sealed.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Partial {
+    /// describe a finalized staging transaction as a partial transaction
+    Export(Export),
+    /// add the witness of one input to a partial transaction
+    AddWitness(AddWitness),
+    /// merge the witnesses of several copies of a partial transaction
+    Merge(Merge),
+    /// display a partial transaction and the witnesses it still needs
+    Status(Status),
+    /// turn a complete partial transaction back into a staging transaction,
+    /// ready to be sealed
+    Import(Import),
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct PartialFile {
+    /// the partial transaction file. If omitted, the partial transaction is
+    /// read from the standard input
+    #[structopt(long = "partial")]
+    pub partial_file: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Export {
+    #[structopt(flatten)]
+    pub common: common::CommonTransaction,
+
+    /// the hash of the block0, the first block of the blockchain
+    #[structopt(long = "genesis-block-hash")]
+    pub genesis_block_hash: Hash,
+
+    /// the address owning the input at the given index, as `INDEX:ADDRESS`.
+    /// Mandatory for UTxO inputs, account addresses are derived from the
+    /// account when omitted
+    #[structopt(long = "input-address", number_of_values = 1)]
+    pub input_addresses: Vec<InputAddress>,
+
+    /// use the testing discrimination for the derived account addresses
+    #[structopt(long = "testing")]
+    pub testing: bool,
+
+    /// the prefix of the derived account addresses
+    #[structopt(long = "prefix", default_value = "ca")]
+    pub prefix: String,
+
+    /// the file to write the partial transaction in. If omitted, it is
+    /// written to the standard output
+    #[structopt(long = "output")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct AddWitness {
+    #[structopt(flatten)]
+    pub partial: PartialFile,
+
+    /// index of the signed input
+    #[structopt(name = "INDEX")]
+    pub index: usize,
+
+    /// the witness file, as written by `make-witness`
+    #[structopt(name = "WITNESS")]
+    pub witness: PathBuf,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Merge {
+    /// the copies of the partial transaction to merge
+    #[structopt(name = "FILES", required = true, min_values = 1)]
+    pub files: Vec<PathBuf>,
+
+    /// the file to write the merged partial transaction in. If omitted, it
+    /// is written to the standard output
+    #[structopt(long = "output")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Status {
+    #[structopt(flatten)]
+    pub partial: PartialFile,
+}
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Import {
+    #[structopt(flatten)]
+    pub partial: PartialFile,
+
+    #[structopt(flatten)]
+    pub common: common::CommonTransaction,
+}
+
+/// the address owning an input, given as `INDEX:ADDRESS`
+pub struct InputAddress {
+    pub index: usize,
+    pub address: Address,
+}
+
+#[derive(Debug, Error)]
+pub enum InputAddressParseError {
+    #[error("expected INDEX:ADDRESS")]
+    MissingSeparator,
+    #[error("invalid input index")]
+    Index(#[from] std::num::ParseIntError),
+    #[error("invalid address")]
+    Address(#[from] chain_addr::Error),
+}
+
+impl FromStr for InputAddress {
+    type Err = InputAddressParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(2, ':');
+        let index = split.next().unwrap_or_default().parse()?;
+        let address = split
+            .next()
+            .ok_or(InputAddressParseError::MissingSeparator)?
+            .parse()?;
+        Ok(InputAddress { index, address })
+    }
+}
+
+impl Partial {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Partial::Export(export) => export.exec(),
+            Partial::AddWitness(add_witness) => add_witness.exec(),
+            Partial::Merge(merge) => merge.exec(),
+            Partial::Status(status) => status.exec(),
+            Partial::Import(import) => import.exec(),
+        }
+    }
+}
+
+impl PartialFile {
+    pub fn load(&self) -> Result<PartialTransaction, Error> {
+        load(&self.partial_file)
+    }
+
+    pub fn store(&self, partial: &PartialTransaction) -> Result<(), Error> {
+        store(&self.partial_file, partial)
+    }
+}
+
+impl Export {
+    fn exec(self) -> Result<(), Error> {
+        let staging = self.common.load()?;
+        let discrimination = if self.testing {
+            Discrimination::Test
+        } else {
+            Discrimination::Production
+        };
+
+        let mut addresses = Vec::with_capacity(staging.inputs().len());
+        for (index, input) in staging.inputs().iter().enumerate() {
+            let given = self
+                .input_addresses
+                .iter()
+                .rev()
+                .find(|input_address| input_address.index == index)
+                .map(|input_address| input_address.address.clone());
+            let address = match (staging::input_pointer(&input.input), given) {
+                (InputPointer::Account { account }, Some(address)) => {
+                    let derived = account.into_address(address.1.discrimination(), &address.0);
+                    if derived != address {
+                        return Err(Error::PartialInputAddressMismatch { index });
+                    }
+                    address
+                }
+                (InputPointer::Account { account }, None) => {
+                    account.into_address(discrimination, &self.prefix)
+                }
+                (InputPointer::Utxo { .. }, Some(address)) => address,
+                (InputPointer::Utxo { .. }, None) => {
+                    return Err(Error::PartialInputAddressMissing { index })
+                }
+            };
+            addresses.push(address);
+        }
+
+        let partial = staging.to_partial(self.genesis_block_hash, addresses)?;
+        store(&self.output, &partial)
+    }
+}
+
+impl AddWitness {
+    fn exec(self) -> Result<(), Error> {
+        let mut partial = self.partial.load()?;
+        let witness = read_witness(&self.witness)?;
+        partial.set_witness(self.index, witness.into())?;
+        self.partial.store(&partial)
+    }
+}
+
+impl Merge {
+    fn exec(self) -> Result<(), Error> {
+        let mut files = self.files.iter();
+        let mut merged = load(&files.next().map(PathBuf::as_path))?;
+        for file in files {
+            merged.merge(&load(&Some(file))?)?;
+        }
+        store(&self.output, &merged)
+    }
+}
+
+impl Status {
+    fn exec(self) -> Result<(), Error> {
+        let partial = self.partial.load()?;
+        println!("sign data hash: {}", partial.sign_data_hash);
+        println!("block0 hash: {}", partial.block0_hash);
+        println!("valid until: {}", partial.valid_until);
+        println!("inputs:");
+        for (index, input) in partial.inputs.iter().enumerate() {
+            let pointer = match &input.pointer {
+                InputPointer::Account { account } => format!("account {}", account),
+                InputPointer::Utxo {
+                    fragment_id,
+                    output_index,
+                } => format!("output {}:{}", fragment_id, output_index),
+            };
+            let state = if input.witness.is_some() {
+                "signed"
+            } else {
+                "missing witness"
+            };
+            println!(
+                "  {}: {} from {} ({}), {}",
+                index, input.value, input.address, pointer, state
+            );
+        }
+        println!("outputs:");
+        for output in &partial.outputs {
+            println!("  {} to {}", output.value(), output.address());
+        }
+        if let Some(certificate) = &partial.certificate {
+            println!("certificate: {}", certificate);
+        }
+        println!("missing witnesses: {}", partial.missing_witnesses().len());
+        Ok(())
+    }
+}
+
+impl Import {
+    fn exec(self) -> Result<(), Error> {
+        let partial = self.partial.load()?;
+        let staging = Staging::from_partial(&partial)?;
+        self.common.store(&staging)
+    }
+}
+
+fn load<P: AsRef<Path>>(path: &Option<P>) -> Result<PartialTransaction, Error> {
+    let reader = io::open_file_read(path).map_err(|source| Error::PartialFileOpenFailed {
+        source,
+        path: io::path_to_path_buf(path),
+    })?;
+    let partial: PartialTransaction =
+        serde_yaml::from_reader(reader).map_err(|source| Error::PartialFileMalformed {
+            source,
+            path: io::path_to_path_buf(path),
+        })?;
+    partial.check_version()?;
+    Ok(partial)
+}
+
+fn store<P: AsRef<Path>>(path: &Option<P>, partial: &PartialTransaction) -> Result<(), Error> {
+    let writer = io::open_file_write(path).map_err(|source| Error::PartialFileOpenFailed {
+        source,
+        path: io::path_to_path_buf(path),
+    })?;
+    serde_yaml::to_writer(writer, partial).map_err(|source| Error::PartialFileWriteFailed {
+        source,
+        path: io::path_to_path_buf(path),
+    })
+}
@@ -0,0 +1,30 @@
+use crate::jcli_lib::{
+    rest::{self, v1::fragments::simulate_fragments, RestArgs},
+    transaction::{common, Error},
+    utils::OutputFormat,
+};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Simulate {
+    #[structopt(flatten)]
+    common: common::CommonTransaction,
+
+    #[structopt(flatten)]
+    rest_args: RestArgs,
+
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl Simulate {
+    pub fn exec(self) -> Result<(), Error> {
+        let fragment = self.common.load()?.fragment()?;
+        let simulation = simulate_fragments(self.rest_args, vec![fragment], false)?;
+        let json = serde_json::to_value(&simulation).map_err(rest::Error::from)?;
+        let formatted = self.output_format.format_json(json)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@ use crate::jcli_lib::{
     utils::io,
 };
 use chain_addr::{Address, Kind};
-use chain_impl_mockchain::transaction::UnspecifiedAccountIdentifier;
+use chain_impl_mockchain::transaction::{AccountIdentifier, UnspecifiedAccountIdentifier};
 use chain_impl_mockchain::{
     self as chain,
     certificate::{Certificate, CertificatePayload, PoolSignature, SignedCertificate},
@@ -20,7 +20,10 @@ use chain_impl_mockchain::{
     },
     value::{Value, ValueError},
 };
-use jormungandr_lib::interfaces;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{self, InputPointer, PartialInput, PartialTransaction},
+};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -633,6 +636,85 @@ impl Staging {
     }
     */
 
+    /// Describe a finalized transaction in the partial transaction
+    /// interchange format. `addresses` gives the address owning each input,
+    /// the witnesses added so far are kept.
+    pub fn to_partial(
+        &self,
+        block0_hash: Hash,
+        addresses: Vec<interfaces::Address>,
+    ) -> Result<PartialTransaction, Error> {
+        let sign_data_hash = self.transaction_sign_data_hash()?;
+        let inputs = self
+            .inputs
+            .iter()
+            .zip(addresses)
+            .enumerate()
+            .map(|(index, (input, address))| PartialInput {
+                pointer: input_pointer(&input.input),
+                value: input.value,
+                address,
+                witness: self.witnesses.get(index).cloned(),
+            })
+            .collect();
+
+        Ok(PartialTransaction {
+            version: interfaces::PARTIAL_TRANSACTION_VERSION,
+            block0_hash,
+            sign_data_hash: hash_from_bytes(sign_data_hash.as_ref()),
+            valid_until: self
+                .valid_until
+                .ok_or(Error::CannotFinalizeWithoutValidUntil)?,
+            inputs,
+            outputs: self.outputs.clone(),
+            certificate: self.extra.clone(),
+        })
+    }
+
+    /// Rebuild a finalized transaction, with all its witnesses, from a
+    /// complete partial transaction. It is then ready to be sealed.
+    pub fn from_partial(partial: &PartialTransaction) -> Result<Self, Error> {
+        partial.check_version()?;
+        let witnesses = partial.witnesses()?;
+        let mut staging = Staging {
+            kind: StagingKind::Finalizing,
+            inputs: partial
+                .inputs
+                .iter()
+                .map(|input| interfaces::TransactionInput {
+                    input: input_type(&input.pointer),
+                    value: input.value,
+                })
+                .collect(),
+            outputs: partial.outputs.clone(),
+            valid_until: Some(partial.valid_until),
+            witnesses: Vec::new(),
+            extra: partial.certificate.clone(),
+            extra_authed: None,
+        };
+
+        // the witnesses sign the transaction content, not the hash the
+        // partial transaction claims
+        let sign_data_hash = staging.transaction_sign_data_hash()?;
+        if hash_from_bytes(sign_data_hash.as_ref()) != partial.sign_data_hash {
+            return Err(Error::PartialSignDataHashMismatch);
+        }
+        staging.witnesses = witnesses;
+        Ok(staging)
+    }
+
+    pub fn kind(&self) -> StagingKind {
+        self.kind
+    }
+
+    pub fn valid_until(&self) -> Option<interfaces::BlockDate> {
+        self.valid_until
+    }
+
+    pub fn certificate(&self) -> Option<&interfaces::Certificate> {
+        self.extra.as_ref()
+    }
+
     pub fn inputs(&self) -> &[interfaces::TransactionInput] {
         &self.inputs
     }
@@ -676,6 +758,45 @@ impl Staging {
     }
 }
 
+pub(crate) fn input_pointer(input: &interfaces::TransactionInputType) -> InputPointer {
+    match input {
+        interfaces::TransactionInputType::Account(id) => InputPointer::Account {
+            account: UnspecifiedAccountIdentifier::from(*id).into(),
+        },
+        interfaces::TransactionInputType::Utxo(fragment_id, output_index) => InputPointer::Utxo {
+            fragment_id: Hash::from(*fragment_id),
+            output_index: *output_index,
+        },
+    }
+}
+
+fn input_type(pointer: &InputPointer) -> interfaces::TransactionInputType {
+    match pointer {
+        InputPointer::Account { account } => {
+            let account: AccountIdentifier = account.clone().into();
+            let id = match account {
+                AccountIdentifier::Single(id) => {
+                    UnspecifiedAccountIdentifier::from_single_account(id)
+                }
+                AccountIdentifier::Multi(id) => {
+                    UnspecifiedAccountIdentifier::from_multi_account(id)
+                }
+            };
+            interfaces::TransactionInputType::Account(id.into())
+        }
+        InputPointer::Utxo {
+            fragment_id,
+            output_index,
+        } => interfaces::TransactionInputType::Utxo(fragment_id.into_hash().into(), *output_index),
+    }
+}
+
+fn hash_from_bytes(bytes: &[u8]) -> Hash {
+    let mut hash = [0; 32];
+    hash.copy_from_slice(bytes);
+    Hash::from(hash)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -716,4 +837,27 @@ mod tests {
             incorrect_stage
         );
     }
+
+    #[test]
+    pub fn test_partial_transaction_round_trip() {
+        let mut staging = Staging::new();
+        staging.kind = StagingKind::Finalizing;
+        staging.valid_until = Some(interfaces::BlockDate::new(10, 0));
+        let utxo = interfaces::TransactionInputType::Utxo([1; 32], 2);
+        assert_eq!(input_type(&input_pointer(&utxo)), utxo);
+
+        let block0_hash = jormungandr_lib::crypto::hash::Hash::from([0; 32]);
+        let mut partial = staging.to_partial(block0_hash, Vec::new()).unwrap();
+        let imported = Staging::from_partial(&partial).unwrap();
+        assert_eq!(
+            imported.transaction_sign_data_hash().unwrap(),
+            staging.transaction_sign_data_hash().unwrap()
+        );
+
+        partial.valid_until = interfaces::BlockDate::new(11, 0);
+        assert!(matches!(
+            Staging::from_partial(&partial),
+            Err(Error::PartialSignDataHashMismatch)
+        ));
+    }
 }
@@ -1,7 +1,9 @@
-use super::io;
+use super::{io, passphrase};
 use chain_crypto::bech32::{self, Bech32};
 use chain_crypto::{AsymmetricKey, AsymmetricPublicKey, PublicKey, SecretKey};
 use chain_impl_mockchain::key::EitherEd25519SecretKey;
+use jormungandr_lib::crypto::password::{self, EncryptedSecret};
+use std::io::Read as _;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -23,6 +25,16 @@ pub enum Error {
     SecretKeyMalformed(#[from] bech32::Error),
     #[error("error requesting user input")]
     UserInputError(#[from] std::io::Error),
+    #[error(transparent)]
+    Passphrase(#[from] passphrase::Error),
+    #[error("could not decrypt secret key file '{path}'")]
+    SecretKeyFileDecryptionFailed {
+        #[source]
+        source: password::Error,
+        path: PathBuf,
+    },
+    #[error("encrypted secret file '{path}' does not hold text")]
+    EncryptedSecretFileMalformed { path: PathBuf },
 }
 
 pub fn parse_pub_key<A: AsymmetricPublicKey>(
@@ -37,17 +49,58 @@ where
     SecretKey<A>: Bech32,
     P: AsRef<Path>,
 {
-    let bech32_str: String =
-        io::read_line(path).map_err(|source| Error::SecretKeyFileReadFailed {
-            source,
-            path: io::path_to_path_buf(path),
-        })?;
+    let bech32_str = read_key_line(path)?;
     SecretKey::try_from_bech32_str(&bech32_str).map_err(|source| Error::SecretKeyFileMalformed {
         source,
         path: io::path_to_path_buf(path),
     })
 }
 
+/// Read the content of a secret file, or of the standard input if no path
+/// is given. Files encrypted with `jcli key encrypt` are decrypted, with
+/// the passphrase from the `JCLI_PASSPHRASE` environment variable or a
+/// prompt.
+pub fn read_secret_file<P: AsRef<Path>>(path: &Option<P>) -> Result<String, Error> {
+    let mut content = String::new();
+    io::open_file_read(path)
+        .and_then(|mut reader| reader.read_to_string(&mut content))
+        .map_err(|source| Error::SecretKeyFileReadFailed {
+            source,
+            path: io::path_to_path_buf(path),
+        })?;
+
+    match serde_yaml::from_str::<EncryptedSecret>(&content) {
+        Ok(encrypted) => decrypt(&encrypted, &io::path_to_path_buf(path)),
+        Err(_) => Ok(content),
+    }
+}
+
+/// read the key held in the first line of a key file, decrypting it
+/// first if needed
+pub fn read_key_line<P: AsRef<Path>>(path: &Option<P>) -> Result<String, Error> {
+    let content = read_secret_file(path)?;
+    Ok(content
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .to_string())
+}
+
+fn decrypt(encrypted: &EncryptedSecret, path: &Path) -> Result<String, Error> {
+    let prompt = format!("Passphrase for '{}': ", path.display());
+    let passphrase = passphrase::read(None, &prompt)?;
+    let bytes = encrypted.decrypt(passphrase.as_bytes()).map_err(|source| {
+        Error::SecretKeyFileDecryptionFailed {
+            source,
+            path: path.to_path_buf(),
+        }
+    })?;
+    String::from_utf8(bytes).map_err(|_| Error::EncryptedSecretFileMalformed {
+        path: path.to_path_buf(),
+    })
+}
+
 pub fn read_secret_key(secret_key_path: Option<PathBuf>) -> Result<EitherEd25519SecretKey, Error> {
     match secret_key_path {
         Some(path) => read_ed25519_secret_key_from_file(&Some(path)),
@@ -62,11 +115,7 @@ pub fn read_secret_key(secret_key_path: Option<PathBuf>) -> Result<EitherEd25519
 pub fn read_ed25519_secret_key_from_file<P: AsRef<Path>>(
     path: &Option<P>,
 ) -> Result<EitherEd25519SecretKey, Error> {
-    let bech32_str: String =
-        io::read_line(path).map_err(|source| Error::SecretKeyFileReadFailed {
-            source,
-            path: io::path_to_path_buf(path),
-        })?;
+    let bech32_str = read_key_line(path)?;
 
     match SecretKey::try_from_bech32_str(&bech32_str) {
         Ok(sk) => Ok(EitherEd25519SecretKey::Extended(sk)),
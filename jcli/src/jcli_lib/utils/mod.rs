@@ -3,6 +3,7 @@ pub mod io;
 pub mod key_parser;
 pub mod output_file;
 pub mod output_format;
+pub mod passphrase;
 pub mod vote;
 
 pub use self::account_id::AccountId;
@@ -0,0 +1,44 @@
+use super::io;
+use std::path::Path;
+use thiserror::Error;
+
+/// environment variable holding the passphrase, for non interactive use
+pub const PASSPHRASE_ENV: &str = "JCLI_PASSPHRASE";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("could not read the passphrase")]
+    Io(#[from] std::io::Error),
+    #[error("passphrases do not match")]
+    Mismatch,
+}
+
+/// Get a passphrase from the given file, from the `JCLI_PASSPHRASE`
+/// environment variable, or by prompting for it, in that order.
+pub fn read(file: Option<&Path>, prompt: &str) -> Result<String, Error> {
+    if let Some(passphrase) = from_file_or_env(file)? {
+        return Ok(passphrase);
+    }
+    Ok(rpassword::prompt_password_stdout(prompt)?)
+}
+
+/// Get a new passphrase, from the same sources as [`read`]. The user is
+/// asked twice when prompting.
+pub fn read_new(file: Option<&Path>, prompt: &str) -> Result<String, Error> {
+    if let Some(passphrase) = from_file_or_env(file)? {
+        return Ok(passphrase);
+    }
+    let passphrase = rpassword::prompt_password_stdout(prompt)?;
+    let confirmation = rpassword::prompt_password_stdout("Repeat the passphrase: ")?;
+    if passphrase != confirmation {
+        return Err(Error::Mismatch);
+    }
+    Ok(passphrase)
+}
+
+fn from_file_or_env(file: Option<&Path>) -> Result<Option<String>, Error> {
+    match file {
+        Some(path) => Ok(Some(io::read_line(&Some(path))?)),
+        None => Ok(std::env::var(PASSPHRASE_ENV).ok()),
+    }
+}
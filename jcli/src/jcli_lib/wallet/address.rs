@@ -0,0 +1,52 @@
+use crate::jcli_lib::wallet::{Error, WalletArgs};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Address {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    /// derive a new account, at the given index or after the highest
+    /// derived index, and print its address
+    #[structopt(long)]
+    new: bool,
+
+    /// index of the account to derive with `--new`
+    #[structopt(long, requires = "new")]
+    index: Option<u32>,
+
+    /// print the UTxO addresses instead of the account addresses
+    #[structopt(long)]
+    utxo: bool,
+}
+
+impl Address {
+    pub fn exec(self) -> Result<(), Error> {
+        let mut keystore = self.wallet.load()?;
+
+        let indices = if self.new {
+            let root_key = keystore.unlock(&self.wallet.passphrase()?)?;
+            let index = self.index.unwrap_or_else(|| keystore.next_index());
+            keystore.add_account(&root_key, index)?;
+            self.wallet.store(&keystore)?;
+            vec![index]
+        } else {
+            keystore
+                .accounts
+                .iter()
+                .map(|account| account.index)
+                .collect()
+        };
+
+        for index in indices {
+            let account = keystore.account(index)?;
+            let address = match (self.utxo, account.public_key()) {
+                (true, Some(public_key)) => keystore.utxo_address(public_key.clone()),
+                _ => account.address.clone(),
+            };
+            println!("{}: {}", index, address);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::jcli_lib::{
+    utils::OutputFormat,
+    wallet::{Error, WalletArgs},
+};
+use chain_impl_mockchain::value::Value;
+use serde_json::json;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Balance {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl Balance {
+    pub fn exec(self) -> Result<(), Error> {
+        let keystore = self.wallet.load()?;
+
+        let accounts: Vec<_> = keystore
+            .accounts
+            .iter()
+            .map(|account| {
+                json!({
+                    "index": account.index,
+                    "address": account.address,
+                    "value": account.value,
+                    "counter": account.counter,
+                })
+            })
+            .collect();
+        let utxos: Vec<_> = keystore
+            .utxos
+            .iter()
+            .map(|utxo| {
+                json!({
+                    "index": utxo.index,
+                    "fragment_id": utxo.fragment_id,
+                    "output_index": utxo.output_index,
+                    "value": utxo.value,
+                })
+            })
+            .collect();
+        let total = Value::sum(
+            keystore
+                .accounts
+                .iter()
+                .map(|account| account.value.into())
+                .chain(keystore.utxos.iter().map(|utxo| utxo.value.into())),
+        )?;
+
+        let balance = json!({
+            "block0_hash": keystore.block0_hash(),
+            "total": total.0,
+            "accounts": accounts,
+            "utxos": utxos,
+        });
+        let formatted = self.output_format.format_json(balance)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
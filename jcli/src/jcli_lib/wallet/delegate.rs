@@ -0,0 +1,35 @@
+use crate::jcli_lib::{
+    certificate::weighted_pool_ids::WeightedPoolIds,
+    wallet::{
+        transaction::{self, TransactionArgs},
+        Error,
+    },
+};
+use chain_impl_mockchain::certificate::{Certificate, OwnerStakeDelegation};
+use std::convert::TryInto;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Delegate {
+    /// index of the account delegating its stake
+    #[structopt(long, default_value = "0")]
+    account: u32,
+
+    #[structopt(flatten)]
+    pool_ids: WeightedPoolIds,
+
+    #[structopt(flatten)]
+    transaction: TransactionArgs,
+}
+
+impl Delegate {
+    pub fn exec(self) -> Result<(), Error> {
+        let delegation = (&self.pool_ids).try_into()?;
+        let certificate = Certificate::OwnerStakeDelegation(OwnerStakeDelegation { delegation });
+        let account = self.account;
+        transaction::submit(self.transaction, |keystore, fees| {
+            transaction::certificate_draft(keystore, account, certificate, fees)
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use crate::jcli_lib::{
+    utils::io,
+    wallet::{keystore::RootKey, Error, Keystore, WalletArgs},
+};
+use bech32::FromBase32;
+use chain_addr::Discrimination;
+use chain_crypto::{bech32::Error as Bech32Error, AsymmetricKey, Ed25519Bip32};
+use rand::{rngs::OsRng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Init {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    /// import the given bech32 ed25519bip32 secret key as the root key
+    /// of the wallet, instead of generating a new one
+    #[structopt(long)]
+    root_key: Option<PathBuf>,
+
+    /// the wallet addresses are for testing networks
+    #[structopt(long)]
+    testing: bool,
+
+    /// number of accounts to derive from the root key
+    #[structopt(long, default_value = "1")]
+    accounts: u32,
+}
+
+impl Init {
+    pub fn exec(self) -> Result<(), Error> {
+        let root_key = match &self.root_key {
+            Some(path) => read_root_key(path)?,
+            None => RootKey::generate(ChaChaRng::from_rng(OsRng)?),
+        };
+        let discrimination = if self.testing {
+            Discrimination::Test
+        } else {
+            Discrimination::Production
+        };
+
+        let passphrase = self.wallet.new_passphrase()?;
+        let mut rng = ChaChaRng::from_rng(OsRng)?;
+        let mut keystore = Keystore::new(&mut rng, &passphrase, discrimination, &root_key)?;
+        for index in 0..self.accounts {
+            let account = keystore.add_account(&root_key, index)?;
+            println!("{}", account.address);
+        }
+        keystore.create(&self.wallet.path)
+    }
+}
+
+fn read_root_key(path: &Path) -> Result<RootKey, Error> {
+    let line = io::read_line(&Some(path)).map_err(Error::RootKeyRead)?;
+    let (hrp, data, _variant) = bech32::decode(&line).map_err(Bech32Error::from)?;
+    if hrp != Ed25519Bip32::SECRET_BECH32_HRP {
+        return Err(Error::RootKeyHrp {
+            actual: hrp,
+            expected: Ed25519Bip32::SECRET_BECH32_HRP,
+        });
+    }
+    let bytes = Vec::<u8>::from_base32(&data).map_err(Bech32Error::from)?;
+    RootKey::from_binary(&bytes)
+}
@@ -0,0 +1,312 @@
+use crate::jcli_lib::wallet::Error;
+use chain_addr::{Discrimination, Kind};
+use chain_crypto::{AsymmetricKey, Ed25519, Ed25519Bip32, Ed25519Extended, PublicKey, SecretKey};
+use chain_impl_mockchain::key::EitherEd25519SecretKey;
+use ed25519_bip32::{DerivationScheme, XPrv};
+use jormungandr_lib::{
+    crypto::{hash::Hash, password::EncryptedSecret},
+    interfaces::{self, DiscriminationDef},
+};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// The wallet file.
+///
+/// Only the root key is secret, and kept encrypted. The addresses and the
+/// state of the accounts and outputs, as last seen on the blockchain, are
+/// readable without the passphrase.
+#[derive(Serialize, Deserialize)]
+pub struct Keystore {
+    version: u32,
+    #[serde(with = "DiscriminationDef")]
+    discrimination: Discrimination,
+    /// set on the first synchronization with a node
+    #[serde(default)]
+    block0_hash: Option<Hash>,
+    root_key: EncryptedSecret,
+    #[serde(default)]
+    pub accounts: Vec<AccountEntry>,
+    #[serde(default)]
+    pub utxos: Vec<UtxoEntry>,
+}
+
+/// a key derived from the root key, used both as an account and as a
+/// UTxO address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountEntry {
+    pub index: u32,
+    pub address: interfaces::Address,
+    pub value: interfaces::Value,
+    pub counter: u32,
+}
+
+/// an unspent output sent to the UTxO address of one of the accounts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtxoEntry {
+    pub index: u32,
+    pub fragment_id: Hash,
+    pub output_index: u8,
+    pub value: interfaces::Value,
+}
+
+/// the decrypted root key of the wallet
+pub struct RootKey(XPrv);
+
+impl RootKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: R) -> Self {
+        RootKey(Ed25519Bip32::generate(rng))
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(RootKey(Ed25519Bip32::secret_from_binary(bytes)?))
+    }
+
+    /// the key at the given index, derived the same way as
+    /// `jcli key derive` does
+    pub fn derive(&self, index: u32) -> Result<EitherEd25519SecretKey, Error> {
+        let child = self.0.derive(DerivationScheme::V2, index);
+        // the first 64 bytes of an extended private key are the ed25519
+        // extended secret, the last 32 are the chain code
+        let secret = SecretKey::<Ed25519Extended>::from_binary(&child.as_ref()[..64])?;
+        Ok(EitherEd25519SecretKey::Extended(secret))
+    }
+}
+
+impl Keystore {
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        passphrase: &str,
+        discrimination: Discrimination,
+        root_key: &RootKey,
+    ) -> Result<Self, Error> {
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            discrimination,
+            block0_hash: None,
+            root_key: EncryptedSecret::encrypt(rng, passphrase.as_bytes(), root_key.0.as_ref())?,
+            accounts: Vec::new(),
+            utxos: Vec::new(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file = File::open(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let keystore: Self = serde_yaml::from_reader(file).map_err(|source| Error::Malformed {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if keystore.version != KEYSTORE_VERSION {
+            return Err(Error::UnsupportedVersion(keystore.version));
+        }
+        Ok(keystore)
+    }
+
+    pub fn store(&self, path: &Path) -> Result<(), Error> {
+        let file = File::create(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_yaml::to_writer(file, self).map_err(|source| Error::Malformed {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// store a new keystore, failing if the file already exists
+    pub fn create(&self, path: &Path) -> Result<(), Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|source| match source.kind() {
+                std::io::ErrorKind::AlreadyExists => Error::AlreadyExists(path.to_path_buf()),
+                _ => Error::Io {
+                    path: path.to_path_buf(),
+                    source,
+                },
+            })?;
+        serde_yaml::to_writer(file, self).map_err(|source| Error::Malformed {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<RootKey, Error> {
+        let bytes = self.root_key.decrypt(passphrase.as_bytes())?;
+        RootKey::from_binary(&bytes)
+    }
+
+    pub fn discrimination(&self) -> Discrimination {
+        self.discrimination
+    }
+
+    pub fn block0_hash(&self) -> Option<Hash> {
+        self.block0_hash
+    }
+
+    pub fn set_block0_hash(&mut self, block0_hash: Hash) {
+        self.block0_hash = Some(block0_hash);
+    }
+
+    /// derive the account at `index` and add it to the wallet
+    pub fn add_account(&mut self, root_key: &RootKey, index: u32) -> Result<&AccountEntry, Error> {
+        if self.account(index).is_ok() {
+            return Err(Error::AccountExists(index));
+        }
+        let address = self.account_address(root_key.derive(index)?.to_public());
+        self.accounts.push(AccountEntry {
+            index,
+            address,
+            value: 0u64.into(),
+            counter: 0,
+        });
+        self.accounts.sort_by_key(|account| account.index);
+        self.account(index)
+    }
+
+    /// the index following the highest derived index
+    pub fn next_index(&self) -> u32 {
+        self.accounts
+            .iter()
+            .map(|account| account.index + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn account(&self, index: u32) -> Result<&AccountEntry, Error> {
+        self.accounts
+            .iter()
+            .find(|account| account.index == index)
+            .ok_or(Error::UnknownAccount(index))
+    }
+
+    pub fn account_mut(&mut self, index: u32) -> Result<&mut AccountEntry, Error> {
+        self.accounts
+            .iter_mut()
+            .find(|account| account.index == index)
+            .ok_or(Error::UnknownAccount(index))
+    }
+
+    /// the index of the account owning the given public key
+    pub fn index_of(&self, public_key: &PublicKey<Ed25519>) -> Option<u32> {
+        self.accounts
+            .iter()
+            .find(|account| account.public_key() == Some(public_key))
+            .map(|account| account.index)
+    }
+
+    pub fn account_address(&self, public_key: PublicKey<Ed25519>) -> interfaces::Address {
+        chain_addr::Address(self.discrimination, Kind::Account(public_key)).into()
+    }
+
+    pub fn utxo_address(&self, public_key: PublicKey<Ed25519>) -> interfaces::Address {
+        chain_addr::Address(self.discrimination, Kind::Single(public_key)).into()
+    }
+}
+
+impl AccountEntry {
+    pub fn public_key(&self) -> Option<&PublicKey<Ed25519>> {
+        match self.address.1.kind() {
+            Kind::Account(public_key) => Some(public_key),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::NamedTempFile;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    fn keystore(root_key: &RootKey) -> Keystore {
+        let mut rng = ChaChaRng::seed_from_u64(1);
+        Keystore::new(&mut rng, "passphrase", Discrimination::Test, root_key).unwrap()
+    }
+
+    #[test]
+    fn unlock_with_the_passphrase_only() {
+        let root_key = RootKey::generate(ChaChaRng::seed_from_u64(0));
+        let keystore = keystore(&root_key);
+
+        let unlocked = keystore.unlock("passphrase").unwrap();
+        assert_eq!(unlocked.0.as_ref(), root_key.0.as_ref());
+        assert!(matches!(keystore.unlock("wrong"), Err(Error::Unlock(_))));
+    }
+
+    #[test]
+    fn store_and_load_roundtrip() {
+        let file = NamedTempFile::new("wallet.yaml").unwrap();
+        let root_key = RootKey::generate(ChaChaRng::seed_from_u64(0));
+        let mut keystore = keystore(&root_key);
+        keystore.set_block0_hash(Hash::from([1; 32]));
+        keystore.add_account(&root_key, 0).unwrap();
+        keystore.account_mut(0).unwrap().value = 42u64.into();
+        keystore.utxos.push(UtxoEntry {
+            index: 0,
+            fragment_id: Hash::from([2; 32]),
+            output_index: 1,
+            value: 7u64.into(),
+        });
+        keystore.create(file.path()).unwrap();
+
+        let loaded = Keystore::load(file.path()).unwrap();
+        assert_eq!(loaded.block0_hash(), Some(Hash::from([1; 32])));
+        assert_eq!(loaded.discrimination(), Discrimination::Test);
+        assert_eq!(
+            loaded.account(0).unwrap().address,
+            keystore.accounts[0].address
+        );
+        assert_eq!(loaded.account(0).unwrap().value, 42u64.into());
+        assert_eq!(loaded.utxos, keystore.utxos);
+        assert_eq!(
+            loaded.unlock("passphrase").unwrap().0.as_ref(),
+            root_key.0.as_ref()
+        );
+    }
+
+    #[test]
+    fn create_does_not_overwrite() {
+        let file = NamedTempFile::new("wallet.yaml").unwrap();
+        let root_key = RootKey::generate(ChaChaRng::seed_from_u64(0));
+        let keystore = keystore(&root_key);
+        keystore.create(file.path()).unwrap();
+        assert!(matches!(
+            keystore.create(file.path()),
+            Err(Error::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn accounts_are_derived_from_the_root_key() {
+        let root_key = RootKey::generate(ChaChaRng::seed_from_u64(0));
+        let mut keystore = keystore(&root_key);
+        assert_eq!(keystore.next_index(), 0);
+
+        keystore.add_account(&root_key, 3).unwrap();
+        keystore.add_account(&root_key, 1).unwrap();
+        assert!(matches!(
+            keystore.add_account(&root_key, 1),
+            Err(Error::AccountExists(1))
+        ));
+        assert_eq!(keystore.next_index(), 4);
+        let indices: Vec<u32> = keystore.accounts.iter().map(|a| a.index).collect();
+        assert_eq!(indices, vec![1, 3]);
+
+        let public_key = root_key.derive(3).unwrap().to_public();
+        assert_eq!(keystore.index_of(&public_key), Some(3));
+        assert_eq!(
+            keystore.account(3).unwrap().address,
+            keystore.account_address(public_key)
+        );
+        assert!(matches!(keystore.account(2), Err(Error::UnknownAccount(2))));
+    }
+}
@@ -0,0 +1,170 @@
+mod address;
+mod balance;
+mod delegate;
+mod init;
+mod keystore;
+mod send;
+mod sync;
+mod transaction;
+mod utxo;
+mod vote;
+
+use crate::jcli_lib::{
+    certificate, rest, transaction as tx,
+    utils::{account_id, output_format, passphrase},
+};
+use chain_impl_mockchain::value::{Value, ValueError};
+use jormungandr_lib::{crypto::hash::Hash, crypto::password};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use thiserror::Error;
+
+pub use self::keystore::Keystore;
+
+/// HD wallet backed by a local encrypted keystore
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Wallet {
+    /// create a new wallet keystore, from a new or an existing
+    /// ed25519bip32 root key
+    Init(init::Init),
+    /// list the addresses of the wallet, or derive a new one
+    Address(address::Address),
+    /// update the balances, spending counters and UTxOs from a node
+    Sync(sync::Sync),
+    /// display the funds of the wallet, as of the last synchronization
+    Balance(balance::Balance),
+    /// track an unspent output sent to one of the wallet UTxO addresses
+    AddUtxo(utxo::AddUtxo),
+    /// send funds to an address
+    Send(send::Send),
+    /// delegate the stake of a wallet account to stake pools
+    Delegate(delegate::Delegate),
+    /// cast a vote from a wallet account
+    Vote(vote::Vote),
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Rest(#[from] rest::Error),
+    #[error(transparent)]
+    Transaction(#[from] tx::Error),
+    #[error(transparent)]
+    Certificate(#[from] certificate::Error),
+    #[error("cannot access wallet file '{path}'")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("wallet file '{path}' is malformed")]
+    Malformed {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    #[error("wallet file '{0}' already exists")]
+    AlreadyExists(PathBuf),
+    #[error("unsupported wallet file version {0}")]
+    UnsupportedVersion(u32),
+    #[error(transparent)]
+    Passphrase(#[from] passphrase::Error),
+    #[error("could not unlock the wallet")]
+    Unlock(#[from] password::Error),
+    #[error("could not read the root key")]
+    RootKeyRead(#[source] std::io::Error),
+    #[error("root key is not a valid bech32 string")]
+    RootKeyMalformed(#[from] chain_crypto::bech32::Error),
+    #[error("root key has HRP '{actual}', expected an ed25519bip32 secret key ('{expected}')")]
+    RootKeyHrp {
+        actual: String,
+        expected: &'static str,
+    },
+    #[error("invalid root key")]
+    RootKey(#[from] chain_crypto::SecretKeyError),
+    #[error("error while using random source")]
+    Rand(#[from] rand::Error),
+    #[error("invalid block0 hash returned by the node")]
+    Block0Hash(#[from] chain_crypto::hash::Error),
+    #[error("wallet is not synchronized, run `jcli wallet sync` first")]
+    NotSynced,
+    #[error("wallet belongs to the blockchain {expected}, the node runs {actual}")]
+    Block0Mismatch { expected: Hash, actual: Hash },
+    #[error("wallet uses {expected:?} addresses, the node runs {actual:?}")]
+    DiscriminationMismatch {
+        expected: chain_addr::Discrimination,
+        actual: chain_addr::Discrimination,
+    },
+    #[error(transparent)]
+    AccountId(#[from] account_id::Error),
+    #[error("no account with index {0} in the wallet")]
+    UnknownAccount(u32),
+    #[error("account {0} is already derived")]
+    AccountExists(u32),
+    #[error("output {fragment_id}:{output_index} does not exist or is already spent")]
+    UtxoNotFound { fragment_id: Hash, output_index: u8 },
+    #[error("output {fragment_id}:{output_index} is not sent to an address of the wallet")]
+    UtxoNotOwned { fragment_id: Hash, output_index: u8 },
+    #[error("not enough funds: {needed} needed, {available} available")]
+    NotEnoughFunds { needed: Value, available: Value },
+    #[error("invalid value")]
+    Value(#[from] ValueError),
+    #[error("could not read the election key")]
+    ElectionKeyRead(#[source] std::io::Error),
+    #[error("invalid election key")]
+    ElectionKey(#[source] chain_crypto::bech32::Error),
+    #[error("formatting output failed")]
+    OutputFormat(#[from] output_format::Error),
+    #[error("cancelled by user")]
+    CancelByUser,
+}
+
+impl Wallet {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Wallet::Init(init) => init.exec(),
+            Wallet::Address(address) => address.exec(),
+            Wallet::Sync(sync) => sync.exec(),
+            Wallet::Balance(balance) => balance.exec(),
+            Wallet::AddUtxo(add_utxo) => add_utxo.exec(),
+            Wallet::Send(send) => send.exec(),
+            Wallet::Delegate(delegate) => delegate.exec(),
+            Wallet::Vote(vote) => vote.exec(),
+        }
+    }
+}
+
+/// the keystore file and how to get its passphrase
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct WalletArgs {
+    /// path to the wallet keystore file
+    #[structopt(long = "wallet", env = "JCLI_WALLET")]
+    pub path: PathBuf,
+
+    /// read the passphrase from the given file instead of the
+    /// `JCLI_PASSPHRASE` environment variable or a prompt
+    #[structopt(long)]
+    pub passphrase_file: Option<PathBuf>,
+}
+
+impl WalletArgs {
+    pub fn load(&self) -> Result<Keystore, Error> {
+        Keystore::load(&self.path)
+    }
+
+    pub fn store(&self, keystore: &Keystore) -> Result<(), Error> {
+        keystore.store(&self.path)
+    }
+
+    pub fn passphrase(&self) -> Result<String, Error> {
+        passphrase::read(self.passphrase_file.as_deref(), "Wallet passphrase: ").map_err(Into::into)
+    }
+
+    /// ask for a new passphrase, twice when prompting
+    pub fn new_passphrase(&self) -> Result<String, Error> {
+        passphrase::read_new(self.passphrase_file.as_deref(), "New wallet passphrase: ")
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::jcli_lib::wallet::{
+    transaction::{self, Draft, TransactionArgs},
+    Error,
+};
+use chain_impl_mockchain::{fee::FeeAlgorithm, transaction::Output, value::Value};
+use jormungandr_lib::interfaces;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Send {
+    /// the address to send the funds to
+    #[structopt(name = "ADDRESS")]
+    receiver: interfaces::Address,
+
+    /// the value to send, the fees are paid on top of it
+    #[structopt(name = "VALUE")]
+    value: interfaces::Value,
+
+    #[structopt(flatten)]
+    transaction: TransactionArgs,
+}
+
+impl Send {
+    pub fn exec(self) -> Result<(), Error> {
+        let output = Output {
+            address: self.receiver.into(),
+            value: self.value.into(),
+        };
+        transaction::submit(self.transaction, |keystore, fees| {
+            let target: Value = output.value;
+            let inputs = transaction::select_inputs(keystore, target, |inputs, change| {
+                fees.calculate(None, inputs, 1 + change as u8)
+            })?;
+            Ok(Draft {
+                inputs,
+                outputs: vec![output],
+                certificate: None,
+            })
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use crate::jcli_lib::{
+    rest::{
+        v0::{account, settings, utxo},
+        RestArgs,
+    },
+    utils::AccountId,
+    wallet::{Error, Keystore, WalletArgs},
+};
+use jormungandr_lib::crypto::hash::Hash;
+use jormungandr_lib::interfaces::SettingsDto;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Sync {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    #[structopt(flatten)]
+    rest_args: RestArgs,
+}
+
+impl Sync {
+    pub fn exec(self) -> Result<(), Error> {
+        let mut keystore = self.wallet.load()?;
+        sync(&mut keystore, &self.rest_args)?;
+        self.wallet.store(&keystore)?;
+
+        for account in &keystore.accounts {
+            println!(
+                "{}: {} (counter {})",
+                account.index, account.value, account.counter
+            );
+        }
+        for utxo in &keystore.utxos {
+            println!(
+                "{}: {}:{} {}",
+                utxo.index, utxo.fragment_id, utxo.output_index, utxo.value
+            );
+        }
+        Ok(())
+    }
+}
+
+/// check the wallet belongs to the blockchain of the node, recording the
+/// block0 hash on the first synchronization
+pub fn check_settings(keystore: &mut Keystore, settings: &SettingsDto) -> Result<(), Error> {
+    if settings.discrimination != keystore.discrimination() {
+        return Err(Error::DiscriminationMismatch {
+            expected: keystore.discrimination(),
+            actual: settings.discrimination,
+        });
+    }
+    let block0_hash = Hash::from_str(&settings.block0_hash)?;
+    match keystore.block0_hash() {
+        Some(expected) if expected != block0_hash => Err(Error::Block0Mismatch {
+            expected,
+            actual: block0_hash,
+        }),
+        Some(_) => Ok(()),
+        None => {
+            keystore.set_block0_hash(block0_hash);
+            Ok(())
+        }
+    }
+}
+
+/// refresh the balances and spending counters of the accounts, and forget
+/// the outputs that have been spent
+pub fn sync(keystore: &mut Keystore, rest_args: &RestArgs) -> Result<SettingsDto, Error> {
+    let settings = settings::request_settings(rest_args.clone())?;
+    check_settings(keystore, &settings)?;
+
+    for entry in keystore.accounts.iter_mut() {
+        let account_id = AccountId::try_from_str(&entry.address.to_string())?;
+        match account::request_account_information(rest_args.clone(), account_id) {
+            Ok(state) => {
+                entry.value = *state.value();
+                entry.counter = state.counters().first().copied().unwrap_or(0);
+            }
+            // the account does not exist until it receives funds
+            Err(e) if e.is_not_found() => {
+                entry.value = 0u64.into();
+                entry.counter = 0;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut unspent = Vec::with_capacity(keystore.utxos.len());
+    for entry in keystore.utxos.drain(..) {
+        let output = utxo::request_utxo(
+            rest_args.clone(),
+            &entry.fragment_id.to_string(),
+            entry.output_index,
+        )?;
+        if output.is_some() {
+            unspent.push(entry);
+        }
+    }
+    keystore.utxos = unspent;
+
+    Ok(settings)
+}
@@ -0,0 +1,437 @@
+use crate::jcli_lib::{
+    rest::{v0::message::post_fragment, RestArgs},
+    transaction::{
+        mk_witness::{make_witness, WitnessType},
+        staging::Staging,
+        Error as TxError,
+    },
+    utils::io::ask_yes_or_no,
+    wallet::{
+        keystore::{Keystore, RootKey, UtxoEntry},
+        sync, Error, WalletArgs,
+    },
+};
+use chain_core::property::Serialize as _;
+use chain_impl_mockchain::{
+    account::SpendingCounter,
+    certificate::{Certificate, CertificatePayload},
+    fee::{FeeAlgorithm, LinearFee},
+    transaction::{Output, OutputPolicy},
+    value::Value,
+};
+use jormungandr_lib::{
+    interfaces::{self, BlockDate, SettingsDto},
+    time::SystemTime,
+};
+use std::cmp::Reverse;
+use structopt::StructOpt;
+
+/// options shared by the commands issuing a transaction
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TransactionArgs {
+    #[structopt(flatten)]
+    pub wallet: WalletArgs,
+
+    /// the date after which the transaction cannot be included in a block
+    /// anymore. By default, the furthest date the blockchain settings allow
+    #[structopt(long)]
+    pub valid_until: Option<BlockDate>,
+
+    /// do not ask for confirmation before sending the transaction
+    #[structopt(long)]
+    pub force: bool,
+
+    /// print the signed fragment in hex instead of sending it to the node
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    #[structopt(flatten)]
+    pub rest_args: RestArgs,
+}
+
+/// an input of a wallet transaction
+#[derive(Debug, Clone)]
+pub enum Input {
+    /// the given value from the account at the given index
+    Account {
+        index: u32,
+        value: Value,
+    },
+    Utxo(UtxoEntry),
+}
+
+/// what a transaction does, before it is balanced and signed
+pub struct Draft {
+    pub inputs: Vec<Input>,
+    pub outputs: Vec<Output<chain_addr::Address>>,
+    pub certificate: Option<Certificate>,
+}
+
+impl Input {
+    fn value(&self) -> Value {
+        match self {
+            Input::Account { value, .. } => *value,
+            Input::Utxo(utxo) => utxo.value.into(),
+        }
+    }
+}
+
+/// Pick the inputs paying for `target` and the fees, largest first.
+///
+/// `fee` gives the fees of the transaction for a number of inputs, with or
+/// without a change output. Outputs are always spent entirely, but the last
+/// account input only provides what is missing, which spares the change
+/// output. When the last input is an output, the excess goes to a change
+/// output and the inputs must also pay for its fees.
+pub fn select_inputs<F>(keystore: &Keystore, target: Value, fee: F) -> Result<Vec<Input>, Error>
+where
+    F: Fn(u8, bool) -> Value,
+{
+    let mut candidates: Vec<Input> = keystore
+        .utxos
+        .iter()
+        .cloned()
+        .map(Input::Utxo)
+        .chain(
+            keystore
+                .accounts
+                .iter()
+                .filter(|account| account.value > 0u64.into())
+                .map(|account| Input::Account {
+                    index: account.index,
+                    value: account.value.into(),
+                }),
+        )
+        .collect();
+    candidates.sort_by_key(|input| Reverse(input.value()));
+
+    let mut selected = Vec::new();
+    let mut total = Value::zero();
+    for candidate in candidates.into_iter().take(u8::MAX as usize) {
+        total = (total + candidate.value())?;
+        selected.push(candidate);
+        let inputs = selected.len() as u8;
+        let exact = (target + fee(inputs, false))?;
+        if total < exact {
+            continue;
+        }
+        let excess = (total - exact)?;
+        match selected.last_mut() {
+            Some(Input::Account { value, .. }) => {
+                *value = (*value - excess)?;
+                return Ok(selected);
+            }
+            _ if excess == Value::zero() => return Ok(selected),
+            _ => {
+                // the change output must get something once its own fees
+                // are paid, otherwise keep adding inputs
+                if total > (target + fee(inputs, true))? {
+                    return Ok(selected);
+                }
+            }
+        }
+    }
+
+    let inputs = selected.len().max(1) as u8;
+    let needed = match selected.last() {
+        Some(Input::Utxo(_)) if total >= (target + fee(inputs, false))? => {
+            (target + fee(inputs, true))?
+        }
+        _ => (target + fee(inputs, false))?,
+    };
+    Err(Error::NotEnoughFunds {
+        needed,
+        available: total,
+    })
+}
+
+/// the draft of a transaction carrying a certificate paid by a single
+/// account, as required for delegation and vote casting
+pub fn certificate_draft(
+    keystore: &Keystore,
+    index: u32,
+    certificate: Certificate,
+    fees: &LinearFee,
+) -> Result<Draft, Error> {
+    let account = keystore.account(index)?;
+    let payload = CertificatePayload::from(&certificate);
+    let fee = fees.calculate(Some(payload.as_slice()), 1, 0);
+    let available: Value = account.value.into();
+    if available < fee {
+        return Err(Error::NotEnoughFunds {
+            needed: fee,
+            available,
+        });
+    }
+    Ok(Draft {
+        inputs: vec![Input::Account { index, value: fee }],
+        outputs: Vec::new(),
+        certificate: Some(certificate),
+    })
+}
+
+/// Synchronize the wallet, build the transaction, sign it and send it to
+/// the node. The spent outputs and the spending counters of the accounts
+/// are updated in the keystore once the node accepted the fragment.
+pub fn submit<F>(args: TransactionArgs, build: F) -> Result<(), Error>
+where
+    F: FnOnce(&Keystore, &LinearFee) -> Result<Draft, Error>,
+{
+    let mut keystore = args.wallet.load()?;
+    let settings = sync::sync(&mut keystore, &args.rest_args)?;
+    let draft = build(&keystore, &settings.fees)?;
+
+    let valid_until = args
+        .valid_until
+        .unwrap_or_else(|| default_valid_until(&settings));
+    let root_key = keystore.unlock(&args.wallet.passphrase()?)?;
+    let staging = sign(&keystore, &root_key, &settings, valid_until, &draft)?;
+
+    print_summary(&keystore, &draft, &staging, &settings.fees)?;
+    if !args.force && !ask_yes_or_no(true).map_err(TxError::from)? {
+        return Err(Error::CancelByUser);
+    }
+
+    let fragment = staging.fragment()?;
+    if args.dry_run {
+        let bytes = fragment
+            .serialize_as_vec()
+            .map_err(TxError::MessageSerializationFailed)?;
+        println!("{}", hex::encode(&bytes));
+        return args.wallet.store(&keystore);
+    }
+
+    let fragment_id = post_fragment(args.rest_args, fragment)?;
+    println!("{}", fragment_id);
+    apply(&mut keystore, &draft)?;
+    args.wallet.store(&keystore)
+}
+
+fn sign(
+    keystore: &Keystore,
+    root_key: &RootKey,
+    settings: &SettingsDto,
+    valid_until: BlockDate,
+    draft: &Draft,
+) -> Result<Staging, Error> {
+    let block0_hash = keystore.block0_hash().ok_or(Error::NotSynced)?.into_hash();
+    let change = keystore
+        .accounts
+        .first()
+        .ok_or(Error::UnknownAccount(0))?
+        .address
+        .clone();
+
+    let mut staging = Staging::new();
+    for input in &draft.inputs {
+        match input {
+            Input::Account { index, value } => {
+                let account = keystore.account(*index)?;
+                staging.add_account(account.address.clone(), (*value).into())?;
+            }
+            Input::Utxo(utxo) => staging.add_input(interfaces::TransactionInput {
+                input: interfaces::TransactionInputType::Utxo(
+                    utxo.fragment_id.into_hash().into(),
+                    utxo.output_index,
+                ),
+                value: utxo.value,
+            })?,
+        }
+    }
+    for output in &draft.outputs {
+        staging.add_output(output.clone())?;
+    }
+    if let Some(certificate) = &draft.certificate {
+        staging.set_extra(certificate.clone())?;
+    }
+    staging.set_expiry_date(valid_until)?;
+    staging.balance_inputs_outputs(&settings.fees, OutputPolicy::One(change.into()))?;
+
+    let sign_data_hash = staging.transaction_sign_data_hash()?;
+    for input in &draft.inputs {
+        let witness = match input {
+            Input::Account { index, .. } => make_witness(
+                &WitnessType::Account,
+                &block0_hash,
+                &sign_data_hash,
+                Some(SpendingCounter::from(keystore.account(*index)?.counter)),
+                &root_key.derive(*index)?,
+            )?,
+            Input::Utxo(utxo) => make_witness(
+                &WitnessType::UTxO,
+                &block0_hash,
+                &sign_data_hash,
+                None,
+                &root_key.derive(utxo.index)?,
+            )?,
+        };
+        staging.add_witness(witness)?;
+    }
+    staging.seal()?;
+    Ok(staging)
+}
+
+fn print_summary(
+    keystore: &Keystore,
+    draft: &Draft,
+    staging: &Staging,
+    fees: &LinearFee,
+) -> Result<(), Error> {
+    println!("inputs:");
+    for input in &draft.inputs {
+        match input {
+            Input::Account { index, value } => {
+                println!(
+                    "  {} (account {})",
+                    value,
+                    keystore.account(*index)?.address
+                )
+            }
+            Input::Utxo(utxo) => println!(
+                "  {} (output {}:{})",
+                utxo.value, utxo.fragment_id, utxo.output_index
+            ),
+        }
+    }
+    println!("outputs:");
+    for (position, output) in staging.outputs().iter().enumerate() {
+        let kind = if position < draft.outputs.len() {
+            ""
+        } else {
+            " (change)"
+        };
+        println!("  {} to {}{}", output.value(), output.address(), kind);
+    }
+    println!("fees: {}", staging.fees(fees));
+    Ok(())
+}
+
+/// record the effects of a sent transaction in the keystore
+fn apply(keystore: &mut Keystore, draft: &Draft) -> Result<(), Error> {
+    for input in &draft.inputs {
+        match input {
+            Input::Account { index, value } => {
+                let account = keystore.account_mut(*index)?;
+                let balance: Value = account.value.into();
+                account.value = Value(balance.0.saturating_sub(value.0)).into();
+                account.counter += 1;
+            }
+            Input::Utxo(utxo) => keystore.utxos.retain(|entry| entry != utxo),
+        }
+    }
+    Ok(())
+}
+
+/// the furthest expiry date the blockchain accepts, from the current slot
+fn default_valid_until(settings: &SettingsDto) -> BlockDate {
+    let now = settings
+        .curr_slot_start_time
+        .unwrap_or_else(SystemTime::now);
+    let elapsed = now
+        .duration_since(settings.block0_time)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let slots_per_epoch = u64::from(settings.slots_per_epoch.max(1));
+    let slot = elapsed / settings.slot_duration.max(1);
+    let epoch = slot / slots_per_epoch + u64::from(settings.tx_max_expiry_epochs);
+    BlockDate::new(epoch as u32, (slot % slots_per_epoch) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_addr::Discrimination;
+    use jormungandr_lib::crypto::hash::Hash;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    /// 10 + 5 per input and output
+    fn fee(inputs: u8, change: bool) -> Value {
+        LinearFee::new(10, 5, 0).calculate(None, inputs, 1 + change as u8)
+    }
+
+    fn keystore(accounts: &[u64], utxos: &[u64]) -> Keystore {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let root_key = RootKey::generate(&mut rng);
+        let mut keystore =
+            Keystore::new(&mut rng, "passphrase", Discrimination::Test, &root_key).unwrap();
+        for (index, value) in accounts.iter().enumerate() {
+            keystore.add_account(&root_key, index as u32).unwrap();
+            keystore.account_mut(index as u32).unwrap().value = (*value).into();
+        }
+        for (output_index, value) in utxos.iter().enumerate() {
+            keystore.utxos.push(UtxoEntry {
+                index: 0,
+                fragment_id: Hash::from([output_index as u8; 32]),
+                output_index: output_index as u8,
+                value: (*value).into(),
+            });
+        }
+        keystore
+    }
+
+    fn values(inputs: &[Input]) -> Vec<u64> {
+        inputs.iter().map(|input| input.value().0).collect()
+    }
+
+    #[test]
+    fn exact_output_needs_no_change() {
+        // 50 + 10 + 5 * 2
+        let keystore = keystore(&[], &[70, 20]);
+        let inputs = select_inputs(&keystore, Value(50), fee).unwrap();
+        assert_eq!(values(&inputs), vec![70]);
+    }
+
+    #[test]
+    fn change_output_fees_are_paid() {
+        let keystore = keystore(&[], &[100]);
+        let inputs = select_inputs(&keystore, Value(50), fee).unwrap();
+        assert_eq!(values(&inputs), vec![100]);
+    }
+
+    #[test]
+    fn excess_too_small_for_the_change_output_adds_an_input() {
+        // 72 covers 50 + 20 but not the 25 of the fees with a change output
+        let keystore = keystore(&[], &[72, 40]);
+        let inputs = select_inputs(&keystore, Value(50), fee).unwrap();
+        assert_eq!(values(&inputs), vec![72, 40]);
+    }
+
+    #[test]
+    fn excess_too_small_for_the_change_output_without_other_input() {
+        let keystore = keystore(&[], &[72]);
+        match select_inputs(&keystore, Value(50), fee) {
+            Err(Error::NotEnoughFunds { needed, available }) => {
+                assert_eq!(needed, Value(75));
+                assert_eq!(available, Value(72));
+            }
+            other => panic!("unexpected selection: {:?}", other.map(|i| values(&i))),
+        }
+    }
+
+    #[test]
+    fn last_account_input_only_provides_what_is_missing() {
+        let keystore = keystore(&[1000], &[40]);
+        let inputs = select_inputs(&keystore, Value(50), fee).unwrap();
+        // 50 + 10 + 5 * 2
+        assert_eq!(values(&inputs), vec![70]);
+
+        let keystore = keystore(&[90], &[100]);
+        let inputs = select_inputs(&keystore, Value(150), fee).unwrap();
+        // the output is spent entirely, the account pays the rest
+        assert_eq!(values(&inputs), vec![100, 75]);
+    }
+
+    #[test]
+    fn not_enough_funds() {
+        let keystore = keystore(&[20], &[30]);
+        match select_inputs(&keystore, Value(50), fee) {
+            Err(Error::NotEnoughFunds { needed, available }) => {
+                assert_eq!(needed, Value(75));
+                assert_eq!(available, Value(50));
+            }
+            other => panic!("unexpected selection: {:?}", other.map(|i| values(&i))),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::jcli_lib::{
+    rest::{
+        v0::{settings, utxo},
+        RestArgs,
+    },
+    wallet::{keystore::UtxoEntry, sync::check_settings, Error, WalletArgs},
+};
+use chain_addr::Kind;
+use jormungandr_lib::crypto::hash::Hash;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct AddUtxo {
+    #[structopt(flatten)]
+    wallet: WalletArgs,
+
+    /// hex-encoded ID of the transaction fragment
+    fragment_id: Hash,
+
+    /// index of the transaction output
+    output_index: u8,
+
+    #[structopt(flatten)]
+    rest_args: RestArgs,
+}
+
+impl AddUtxo {
+    pub fn exec(self) -> Result<(), Error> {
+        let mut keystore = self.wallet.load()?;
+        let settings = settings::request_settings(self.rest_args.clone())?;
+        check_settings(&mut keystore, &settings)?;
+
+        let output = utxo::request_utxo(
+            self.rest_args,
+            &self.fragment_id.to_string(),
+            self.output_index,
+        )?
+        .ok_or(Error::UtxoNotFound {
+            fragment_id: self.fragment_id,
+            output_index: self.output_index,
+        })?;
+
+        let index = match output.address().1.kind() {
+            Kind::Single(public_key) | Kind::Group(public_key, _) => keystore.index_of(public_key),
+            _ => None,
+        }
+        .ok_or(Error::UtxoNotOwned {
+            fragment_id: self.fragment_id,
+            output_index: self.output_index,
+        })?;
+
+        let entry = UtxoEntry {
+            index,
+            fragment_id: self.fragment_id,
+            output_index: self.output_index,
+            value: *output.value(),
+        };
+        if !keystore.utxos.contains(&entry) {
+            keystore.utxos.push(entry);
+        }
+        self.wallet.store(&keystore)
+    }
+}
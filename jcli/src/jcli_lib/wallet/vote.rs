@@ -0,0 +1,81 @@
+use crate::jcli_lib::{
+    utils::io,
+    wallet::{
+        transaction::{self, TransactionArgs},
+        Error,
+    },
+};
+use chain_crypto::bech32::Bech32;
+use chain_impl_mockchain::{
+    certificate::{Certificate, VoteCast, VotePlanId},
+    vote::{Choice, Payload},
+};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Vote {
+    /// index of the account casting the vote
+    #[structopt(long, default_value = "0")]
+    account: u32,
+
+    /// the vote plan identified on the blockchain
+    #[structopt(long)]
+    vote_plan_id: VotePlanId,
+
+    /// the number of the proposal in the vote plan you vote for
+    #[structopt(long)]
+    proposal_index: u8,
+
+    /// the number of the choice within the proposal you vote for
+    #[structopt(long)]
+    choice: u8,
+
+    /// election public key of a private vote plan. The vote is encrypted
+    /// with it, and `--options-size` must be given
+    #[structopt(long, requires = "options-size")]
+    election_key: Option<PathBuf>,
+
+    /// number of voting options of the proposal, for private votes
+    #[structopt(long)]
+    options_size: Option<usize>,
+
+    #[structopt(flatten)]
+    transaction: TransactionArgs,
+}
+
+impl Vote {
+    pub fn exec(self) -> Result<(), Error> {
+        let payload = match (&self.election_key, self.options_size) {
+            (Some(path), Some(options)) => {
+                let key_line = io::read_line(&Some(path)).map_err(Error::ElectionKeyRead)?;
+                let key = chain_vote::ElectionPublicKey::try_from_bech32_str(&key_line)
+                    .map_err(Error::ElectionKey)?;
+                let mut rng = ChaChaRng::from_entropy();
+                let vote = chain_vote::Vote::new(options, self.choice as usize);
+                let crs = chain_vote::Crs::from_hash(self.vote_plan_id.as_ref());
+                let (encrypted_vote, proof) =
+                    chain_impl_mockchain::vote::encrypt_vote(&mut rng, &crs, &key, vote);
+                Payload::Private {
+                    encrypted_vote,
+                    proof,
+                }
+            }
+            _ => Payload::Public {
+                choice: Choice::new(self.choice),
+            },
+        };
+        let certificate = Certificate::VoteCast(VoteCast::new(
+            self.vote_plan_id,
+            self.proposal_index,
+            payload,
+        ));
+        let account = self.account;
+        transaction::submit(self.transaction, |keystore, fees| {
+            transaction::certificate_draft(keystore, account, certificate, fees)
+        })
+    }
+}
@@ -0,0 +1,172 @@
+//! key evolving signature (KES) helpers
+//!
+//! The block signing key of a genesis praos leader is a `SumEd25519_12`
+//! key. It is valid for a fixed number of periods and is evolved from one
+//! period to the next, erasing what is needed to sign for the previous
+//! periods. A period lasts `kes_update_speed` seconds, starting at the
+//! time of the block 0. The first period of a key is the period of the
+//! blockchain it starts signing at, so a key lasts as long whenever it is
+//! registered.
+
+use crate::crypto::key::SigningKey;
+use chain_crypto::{
+    evolving::{EvolvingStatus, KeyEvolvingAlgorithm},
+    SecretKey, SumEd25519_12,
+};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// the number of periods a `SumEd25519_12` key can sign for
+pub const SUM_ED25519_12_PERIODS: u32 = 1 << 12;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("cannot evolve the KES key back from period {current} to period {target}")]
+    Backward { current: u32, target: u32 },
+    #[error("the KES key cannot be evolved to period {target}, its last period is {last}")]
+    Exhausted { target: u32, last: u32 },
+}
+
+/// the period the key currently signs for
+pub fn period(key: &SigningKey<SumEd25519_12>) -> u32 {
+    let secret = key.as_ref().clone().leak_secret();
+    SumEd25519_12::get_period(&secret)
+}
+
+/// the number of periods the key can still be evolved to
+pub fn remaining_periods(key: &SigningKey<SumEd25519_12>) -> u32 {
+    SUM_ED25519_12_PERIODS - 1 - period(key)
+}
+
+/// evolve the key up to the `target` period. Evolving to the current
+/// period returns the key unchanged.
+pub fn evolve_to(
+    key: &SigningKey<SumEd25519_12>,
+    target: u32,
+) -> Result<SigningKey<SumEd25519_12>, Error> {
+    let last = SUM_ED25519_12_PERIODS - 1;
+    if target > last {
+        return Err(Error::Exhausted { target, last });
+    }
+    let mut secret = key.as_ref().clone().leak_secret();
+    let current = SumEd25519_12::get_period(&secret);
+    if target < current {
+        return Err(Error::Backward { current, target });
+    }
+    for _ in current..target {
+        match SumEd25519_12::update(&mut secret) {
+            EvolvingStatus::Success => {}
+            EvolvingStatus::Failed => return Err(Error::Exhausted { target, last }),
+        }
+    }
+    let key = SecretKey::<SumEd25519_12>::from_binary(secret.as_ref())
+        .expect("an evolved key is a valid key");
+    Ok(key.into())
+}
+
+/// the periods of the KES keys of a blockchain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KesSchedule {
+    block0_time: SystemTime,
+    update_speed: Duration,
+}
+
+impl KesSchedule {
+    /// `update_speed` is the `kes_update_speed` of the blockchain settings,
+    /// in seconds
+    pub fn new(block0_time: SystemTime, update_speed: u32) -> Self {
+        KesSchedule {
+            block0_time,
+            update_speed: Duration::from_secs(u64::from(update_speed.max(1))),
+        }
+    }
+
+    pub fn update_speed(&self) -> Duration {
+        self.update_speed
+    }
+
+    /// the period a key must sign for at the given time
+    pub fn period_at(&self, time: SystemTime) -> u32 {
+        let elapsed = time.duration_since(self.block0_time).unwrap_or_default();
+        let period = elapsed.as_secs() / self.update_speed.as_secs();
+        period.min(u64::from(u32::MAX)) as u32
+    }
+
+    /// the time the given period starts at
+    pub fn period_start(&self, period: u32) -> SystemTime {
+        self.block0_time + self.update_speed * period
+    }
+
+    /// the time after which a `SumEd25519_12` key cannot sign anymore, its
+    /// first period being the period `start_period` of the blockchain
+    pub fn expiry(&self, start_period: u32) -> SystemTime {
+        self.period_start(start_period.saturating_add(SUM_ED25519_12_PERIODS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaChaRng;
+    use rand_core::SeedableRng;
+
+    fn key() -> SigningKey<SumEd25519_12> {
+        SigningKey::generate(ChaChaRng::from_seed([7; 32]))
+    }
+
+    #[test]
+    fn generated_key_starts_at_period_zero() {
+        let key = key();
+        assert_eq!(period(&key), 0);
+        assert_eq!(remaining_periods(&key), SUM_ED25519_12_PERIODS - 1);
+    }
+
+    #[test]
+    fn evolve_forward_only() {
+        let key = evolve_to(&key(), 3).unwrap();
+        assert_eq!(period(&key), 3);
+        assert_eq!(period(&evolve_to(&key, 3).unwrap()), 3);
+        assert_eq!(
+            evolve_to(&key, 2).err(),
+            Some(Error::Backward {
+                current: 3,
+                target: 2
+            })
+        );
+    }
+
+    #[test]
+    fn cannot_evolve_past_the_last_period() {
+        let last = SUM_ED25519_12_PERIODS - 1;
+        let key = evolve_to(&key(), last).unwrap();
+        assert_eq!(remaining_periods(&key), 0);
+        assert_eq!(
+            evolve_to(&key, last + 1).err(),
+            Some(Error::Exhausted {
+                target: last + 1,
+                last
+            })
+        );
+    }
+
+    #[test]
+    fn schedule_periods() {
+        let block0_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let schedule = KesSchedule::new(block0_time, 60);
+        assert_eq!(schedule.period_at(block0_time - Duration::from_secs(1)), 0);
+        assert_eq!(schedule.period_at(block0_time + Duration::from_secs(59)), 0);
+        assert_eq!(schedule.period_at(block0_time + Duration::from_secs(60)), 1);
+        assert_eq!(
+            schedule.period_start(2),
+            block0_time + Duration::from_secs(120)
+        );
+        assert_eq!(
+            schedule.expiry(0),
+            block0_time + Duration::from_secs(60 * u64::from(SUM_ED25519_12_PERIODS))
+        );
+        assert_eq!(
+            schedule.expiry(10),
+            block0_time + Duration::from_secs(60 * u64::from(SUM_ED25519_12_PERIODS + 10))
+        );
+    }
+}
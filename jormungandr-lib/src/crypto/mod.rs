@@ -24,8 +24,20 @@
 //! It provides the same interfaces as for the identifier in the
 //! `key` module but limited to Account only.
 //!
+//! # Password encryption
+//!
+//! Secrets encrypted under a passphrase, to keep keys stored on disk
+//! unreadable without the passphrase.
+//!
+//! # Key evolving signatures
+//!
+//! The periods of the block signing keys of genesis praos leaders, and
+//! their evolution from one period to the next.
+//!
 
 pub mod account;
 pub mod hash;
+pub mod kes;
 pub mod key;
+pub mod password;
 pub(crate) mod serde;
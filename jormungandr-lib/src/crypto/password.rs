@@ -0,0 +1,243 @@
+//! passphrase based encryption of secrets
+//!
+//! A secret is encrypted with ChaCha20-Poly1305, under a key derived
+//! from the passphrase with the memory-hard scrypt function and a random
+//! salt. The [`EncryptedSecret`] carries everything but the passphrase
+//! needed to recover the secret, including the scrypt parameters, so it
+//! can be stored as is in a YAML or JSON file.
+
+use cryptoxide::chacha20poly1305::ChaCha20Poly1305;
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const KEY_SIZE: usize = 32;
+const TAG_SIZE: usize = 16;
+
+/// log2 of the scrypt cost parameter `N` used for newly encrypted secrets
+pub const DEFAULT_LOG_N: u8 = 15;
+/// scrypt block size used for newly encrypted secrets
+pub const DEFAULT_R: u32 = 8;
+/// scrypt parallelization used for newly encrypted secrets
+pub const DEFAULT_P: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid passphrase or corrupted secret")]
+    Decryption,
+    #[error("malformed encrypted secret: invalid {field} length")]
+    Malformed { field: &'static str },
+    #[error("invalid scrypt parameters: log_n {log_n}, r {r}, p {p}")]
+    InvalidKdfParameters { log_n: u8, r: u32, p: u32 },
+}
+
+/// key derivation function turning the passphrase into the encryption key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum Kdf {
+    /// scrypt with the cost parameter `N = 2^log_n`, the block size `r` and
+    /// the parallelization `p`; it uses `128 * r * N` bytes of memory
+    Scrypt { log_n: u8, r: u32, p: u32 },
+}
+
+/// a secret encrypted under a passphrase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedSecret {
+    kdf: Kdf,
+    #[serde(with = "hex_bytes")]
+    salt: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    nonce: Vec<u8>,
+    /// the encrypted secret followed by the authentication tag
+    #[serde(with = "hex_bytes")]
+    ciphertext: Vec<u8>,
+}
+
+impl Kdf {
+    fn derive_key(&self, passphrase: &[u8], salt: &[u8]) -> Result<[u8; KEY_SIZE], Error> {
+        let mut key = [0; KEY_SIZE];
+        match *self {
+            Kdf::Scrypt { log_n, r, p } => {
+                let params = scrypt::Params::new(log_n, r, p)
+                    .map_err(|_| Error::InvalidKdfParameters { log_n, r, p })?;
+                scrypt::scrypt(passphrase, salt, &params, &mut key)
+                    .expect("the key size is a valid scrypt output length");
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Kdf::Scrypt {
+            log_n: DEFAULT_LOG_N,
+            r: DEFAULT_R,
+            p: DEFAULT_P,
+        }
+    }
+}
+
+impl EncryptedSecret {
+    /// encrypt `secret` under `passphrase`, with the default key
+    /// derivation parameters
+    pub fn encrypt<R>(rng: &mut R, passphrase: &[u8], secret: &[u8]) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        Self::encrypt_with(rng, Kdf::default(), passphrase, secret)
+    }
+
+    pub fn encrypt_with<R>(
+        rng: &mut R,
+        kdf: Kdf,
+        passphrase: &[u8],
+        secret: &[u8],
+    ) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut salt = vec![0; SALT_SIZE];
+        let mut nonce = vec![0; NONCE_SIZE];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let key = kdf.derive_key(passphrase, &salt)?;
+        let mut ciphertext = vec![0; secret.len() + TAG_SIZE];
+        let (encrypted, tag) = ciphertext.split_at_mut(secret.len());
+        ChaCha20Poly1305::new(&key, &nonce, &[]).encrypt(secret, encrypted, tag);
+
+        Ok(EncryptedSecret {
+            kdf,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// recover the secret, fails if the passphrase is not the one the
+    /// secret was encrypted with or if the data was tampered with
+    pub fn decrypt(&self, passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.salt.len() != SALT_SIZE {
+            return Err(Error::Malformed { field: "salt" });
+        }
+        if self.nonce.len() != NONCE_SIZE {
+            return Err(Error::Malformed { field: "nonce" });
+        }
+        if self.ciphertext.len() < TAG_SIZE {
+            return Err(Error::Malformed {
+                field: "ciphertext",
+            });
+        }
+
+        let key = self.kdf.derive_key(passphrase, &self.salt)?;
+        let (encrypted, tag) = self.ciphertext.split_at(self.ciphertext.len() - TAG_SIZE);
+        let mut secret = vec![0; encrypted.len()];
+        if ChaCha20Poly1305::new(&key, &self.nonce, &[]).decrypt(encrypted, &mut secret, tag) {
+            Ok(secret)
+        } else {
+            Err(Error::Decryption)
+        }
+    }
+
+    pub fn kdf(&self) -> &Kdf {
+        &self.kdf
+    }
+}
+
+mod hex_bytes {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(&s).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaChaRng;
+    use rand_core::SeedableRng;
+
+    // keep the tests fast, the cost does not matter here
+    const TEST_KDF: Kdf = Kdf::Scrypt {
+        log_n: 4,
+        r: 1,
+        p: 1,
+    };
+
+    fn encrypt(passphrase: &[u8], secret: &[u8]) -> EncryptedSecret {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        EncryptedSecret::encrypt_with(&mut rng, TEST_KDF, passphrase, secret).unwrap()
+    }
+
+    #[test]
+    fn decrypt_with_right_passphrase() {
+        let secret = b"a very secret key";
+        let encrypted = encrypt(b"passphrase", secret);
+        assert_eq!(encrypted.decrypt(b"passphrase").unwrap(), secret);
+    }
+
+    #[test]
+    fn decrypt_with_wrong_passphrase() {
+        let encrypted = encrypt(b"passphrase", b"a very secret key");
+        assert!(matches!(
+            encrypted.decrypt(b"wrong passphrase"),
+            Err(Error::Decryption)
+        ));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let mut encrypted = encrypt(b"passphrase", b"a very secret key");
+        encrypted.ciphertext[0] ^= 1;
+        assert!(matches!(
+            encrypted.decrypt(b"passphrase"),
+            Err(Error::Decryption)
+        ));
+    }
+
+    #[test]
+    fn invalid_kdf_parameters_are_rejected() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let kdf = Kdf::Scrypt {
+            log_n: 4,
+            r: 0,
+            p: 1,
+        };
+        assert!(matches!(
+            EncryptedSecret::encrypt_with(&mut rng, kdf, b"passphrase", b"secret"),
+            Err(Error::InvalidKdfParameters { .. })
+        ));
+    }
+
+    #[test]
+    fn kdf_parameters_are_stored_with_the_secret() {
+        let encrypted = encrypt(b"passphrase", b"a very secret key");
+        let yaml: serde_yaml::Value = serde_yaml::to_value(&encrypted).unwrap();
+        let kdf = &yaml["kdf"];
+        assert_eq!(kdf["algorithm"].as_str(), Some("scrypt"));
+        assert_eq!(kdf["log_n"].as_u64(), Some(4));
+        assert_eq!(kdf["r"].as_u64(), Some(1));
+        assert_eq!(kdf["p"].as_u64(), Some(1));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let encrypted = encrypt(b"passphrase", b"a very secret key");
+        let yaml = serde_yaml::to_string(&encrypted).unwrap();
+        let decoded: EncryptedSecret = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(encrypted, decoded);
+        assert_eq!(
+            decoded.decrypt(b"passphrase").unwrap(),
+            b"a very secret key"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::interfaces::{Address, BlockDate, Value};
+use chain_impl_mockchain::{fragment::FragmentId, key::HeaderId};
+
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+/// The outcome of applying a batch of fragments, in order, to the ledger of
+/// the current tip without adding them to the mempool.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentsSimulation {
+    /// The tip whose ledger the fragments were applied to.
+    #[serde_as(as = "DisplayFromStr")]
+    pub tip: HeaderId,
+    /// The date of the block the fragments were simulated in, the same
+    /// date the mempool checks the expiry of incoming fragments against.
+    pub block_date: BlockDate,
+    pub fragments: Vec<FragmentSimulation>,
+}
+
+/// The outcome of applying a single fragment. Each fragment is applied to
+/// the ledger left by the fragments accepted before it in the batch.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentSimulation {
+    #[serde_as(as = "DisplayFromStr")]
+    pub fragment_id: FragmentId,
+    /// The fee paid by the fragment: its inputs minus its outputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<Value>,
+    /// The fee the ledger fee settings require from the fragment, certificate
+    /// and per vote fees included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_fee: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<BlockDate>,
+    pub expiry: FragmentExpiry,
+    /// The ledger error, when the fragment cannot be applied. `None` when
+    /// the fragment was accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The funds the fragment moves, by address. Empty when the fragment
+    /// was not accepted.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+/// Whether the mempool would accept the expiry date of a fragment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FragmentExpiry {
+    Valid,
+    Expired,
+    ValidForTooLong,
+    /// The fragment has no expiry date.
+    None,
+}
+
+/// The funds leaving and reaching an address or account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub address: Address,
+    pub spent: Value,
+    pub received: Value,
+}
+
+impl FragmentSimulation {
+    pub fn is_accepted(&self) -> bool {
+        self.error.is_none() && matches!(self.expiry, FragmentExpiry::Valid | FragmentExpiry::None)
+    }
+}
+
+impl FragmentsSimulation {
+    /// Whether every fragment would be accepted.
+    pub fn is_accepted(&self) -> bool {
+        self.fragments.iter().all(FragmentSimulation::is_accepted)
+    }
+}
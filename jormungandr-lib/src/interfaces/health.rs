@@ -0,0 +1,96 @@
+use crate::interfaces::NodeState;
+use serde::{Deserialize, Serialize};
+
+/// The answer of a liveness or readiness check of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Health {
+    pub status: HealthStatus,
+    /// Why the check failed, empty when it passed.
+    pub reasons: Vec<UnhealthyReason>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Pass,
+    Fail,
+}
+
+/// A failed health check, tagged with the `reason` field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum UnhealthyReason {
+    /// The node is still starting up.
+    NotRunning { state: NodeState },
+    /// No block was added to the chain for longer than the configured
+    /// `no_blockchain_updates_warning_interval`.
+    TipTooOld {
+        tip_age_secs: u64,
+        max_tip_age_secs: u64,
+    },
+    /// The node is connected to fewer peers than required.
+    NotEnoughPeers { connected: usize, required: usize },
+    /// The mempool cannot accept new fragments.
+    MempoolFull { pending: u64, max_entries: usize },
+    /// A service of the node stopped or panicked, or it was degraded and
+    /// the node runs without it.
+    ServiceStopped { service: String },
+    /// Writing to the block storage failed.
+    StorageWriteFailed { error: String },
+}
+
+impl Health {
+    pub fn new(reasons: Vec<UnhealthyReason>) -> Self {
+        let status = if reasons.is_empty() {
+            HealthStatus::Pass
+        } else {
+            HealthStatus::Fail
+        };
+        Health { status, reasons }
+    }
+
+    pub fn is_pass(&self) -> bool {
+        self.status == HealthStatus::Pass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_reasons() {
+        let health = Health::new(vec![
+            UnhealthyReason::NotRunning {
+                state: NodeState::Bootstrapping,
+            },
+            UnhealthyReason::ServiceStopped {
+                service: "fragment".to_owned(),
+            },
+        ]);
+        assert!(!health.is_pass());
+        let json = serde_json::to_value(&health).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "status": "fail",
+                "reasons": [
+                    { "reason": "not_running", "state": "Bootstrapping" },
+                    { "reason": "service_stopped", "service": "fragment" },
+                ],
+            })
+        );
+        let decoded: Health = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, health);
+    }
+
+    #[test]
+    fn pass_without_reasons() {
+        let health = Health::new(Vec::new());
+        assert!(health.is_pass());
+        assert_eq!(
+            serde_json::to_value(&health).unwrap(),
+            serde_json::json!({ "status": "pass", "reasons": [] })
+        );
+    }
+}
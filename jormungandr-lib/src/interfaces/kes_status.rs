@@ -0,0 +1,66 @@
+use crate::{
+    crypto::hash::Hash,
+    time::{Duration, SystemTime},
+};
+use serde::{Deserialize, Serialize};
+
+/// The period of the KES key the node signs the blocks of a stake pool
+/// with, as a genesis praos leader.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KesStatus {
+    /// The stake pool the key signs the blocks of.
+    pub pool_id: Hash,
+    /// The period of the blockchain the key signs for.
+    pub period: u32,
+    /// The period the key should sign for at this time.
+    pub expected_period: u32,
+    /// The number of periods the key can still be evolved to.
+    pub remaining_periods: u32,
+    /// How long a period lasts, the `kes_update_speed` of the blockchain.
+    pub update_speed: Duration,
+    /// When the key is evolved next.
+    pub next_update: SystemTime,
+    /// When the key cannot sign anymore.
+    pub expiry: SystemTime,
+}
+
+impl KesStatus {
+    /// Whether the key cannot be evolved to the period it should sign for.
+    pub fn is_expired(&self) -> bool {
+        self.expected_period > self.period + self.remaining_periods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(period: u32, expected_period: u32) -> KesStatus {
+        KesStatus {
+            pool_id: Hash::from([1; 32]),
+            period,
+            expected_period,
+            remaining_periods: 4095 - period,
+            update_speed: std::time::Duration::from_secs(43_200).into(),
+            next_update: SystemTime::from_secs_since_epoch(1_600_000_000),
+            expiry: SystemTime::from_secs_since_epoch(1_777_000_000),
+        }
+    }
+
+    #[test]
+    fn expired_when_the_expected_period_is_out_of_reach() {
+        assert!(!status(10, 10).is_expired());
+        assert!(!status(4095, 4095).is_expired());
+        assert!(status(4095, 4096).is_expired());
+    }
+
+    #[test]
+    fn serialize() {
+        let status = status(10, 11);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["period"], 10);
+        assert_eq!(json["update_speed"], "12h");
+        let decoded: KesStatus = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, status);
+    }
+}
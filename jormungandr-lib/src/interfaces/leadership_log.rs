@@ -1,10 +1,29 @@
-use crate::{crypto::hash::Hash, interfaces::BlockDate, time::SystemTime};
+use crate::{
+    crypto::{hash::Hash, key::Identifier},
+    interfaces::BlockDate,
+    time::SystemTime,
+};
+use chain_crypto::Ed25519;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// log identifier in the leadership log. Can be used to update
 /// back some.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct LeadershipLogId(BlockDate);
+///
+/// Several leaders of the same node can be elected for the same slot, the
+/// leader is part of the identifier.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LeadershipLogId(BlockDate, Option<LeaderIdentity>);
+
+/// one of the leaders a node runs as
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderIdentity {
+    /// a BFT leader, identified by its public key
+    Bft(Identifier<Ed25519>),
+    /// a genesis praos leader, identified by the id of its stake pool
+    StakePool(Hash),
+}
 
 /// the status of a leadership log
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -30,6 +49,8 @@ pub struct LeadershipLog {
     wake_at_time: Option<SystemTime>,
     finished_at_time: Option<SystemTime>,
     status: LeadershipLogStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    leader: Option<LeaderIdentity>,
 }
 
 impl LeadershipLog {
@@ -41,12 +62,21 @@ impl LeadershipLog {
             wake_at_time: None,
             finished_at_time: None,
             status: LeadershipLogStatus::Pending,
+            leader: None,
+        }
+    }
+
+    /// set the leader of the node the event is scheduled for
+    pub fn with_leader(self, leader: LeaderIdentity) -> Self {
+        LeadershipLog {
+            leader: Some(leader),
+            ..self
         }
     }
 
     /// retrieve a unique identifier to this log
     pub fn leadership_log_id(&self) -> LeadershipLogId {
-        LeadershipLogId(self.scheduled_at_date)
+        LeadershipLogId(self.scheduled_at_date, self.leader.clone())
     }
 
     pub fn created_at_time(&self) -> &SystemTime {
@@ -67,6 +97,9 @@ impl LeadershipLog {
     pub fn status(&self) -> &LeadershipLogStatus {
         &self.status
     }
+    pub fn leader(&self) -> &Option<LeaderIdentity> {
+        &self.leader
+    }
 
     /// make a leadership event as triggered.
     ///
@@ -102,3 +135,63 @@ impl LeadershipLog {
         self.status = status
     }
 }
+
+impl fmt::Display for LeaderIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LeaderIdentity::Bft(id) => id.fmt(f),
+            LeaderIdentity::StakePool(id) => id.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::key::SigningKey;
+    use rand_chacha::ChaChaRng;
+    use rand_core::SeedableRng;
+
+    fn bft_leader() -> LeaderIdentity {
+        let key: SigningKey<Ed25519> = SigningKey::generate(ChaChaRng::from_seed([1; 32]));
+        LeaderIdentity::Bft(key.identifier())
+    }
+
+    fn stake_pool() -> LeaderIdentity {
+        LeaderIdentity::StakePool(Hash::from([2; 32]))
+    }
+
+    #[test]
+    fn leader_identity_serde_yaml() {
+        for leader in vec![bft_leader(), stake_pool()] {
+            let encoded = serde_yaml::to_string(&leader).unwrap();
+            let decoded: LeaderIdentity = serde_yaml::from_str(&encoded).unwrap();
+            assert_eq!(leader, decoded);
+        }
+    }
+
+    #[test]
+    fn stake_pool_displays_as_hex() {
+        assert_eq!(stake_pool().to_string(), Hash::from([2; 32]).to_string());
+    }
+
+    #[test]
+    fn log_without_leader_deserializes() {
+        let log = LeadershipLog::new(BlockDate::new(1, 2), SystemTime::now());
+        let encoded = serde_yaml::to_string(&log).unwrap();
+        assert!(!encoded.contains("leader"));
+        let decoded: LeadershipLog = serde_yaml::from_str(&encoded).unwrap();
+        assert_eq!(decoded.leader(), &None);
+        assert_eq!(decoded.leadership_log_id(), log.leadership_log_id());
+    }
+
+    #[test]
+    fn same_date_different_leaders_have_different_ids() {
+        let date = BlockDate::new(1, 2);
+        let time = SystemTime::now();
+        let bft = LeadershipLog::new(date, time).with_leader(bft_leader());
+        let pool = LeadershipLog::new(date, time).with_leader(stake_pool());
+        assert_ne!(bft.leadership_log_id(), pool.leadership_log_id());
+        assert_eq!(pool.leader(), &Some(stake_pool()));
+    }
+}
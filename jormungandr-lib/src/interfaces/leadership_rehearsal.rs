@@ -0,0 +1,119 @@
+use crate::{crypto::hash::Hash, interfaces::BlockDate, time::Duration};
+use serde::{Deserialize, Serialize};
+
+/// The slot to rehearse; the first slot after the tip the node is elected
+/// for when absent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeadershipRehearsalRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<BlockDate>,
+}
+
+/// The outcome of building, signing and validating a block for a slot the
+/// node is elected for, without sending the block to the network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeadershipRehearsal {
+    /// The slot the block was built for.
+    pub date: BlockDate,
+    /// The tip the block was built on.
+    pub parent: Hash,
+    pub chain_length: u32,
+    /// The id of the signed block, absent when it could not be signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<Hash>,
+    /// The number of fragments selected from the mempool.
+    pub fragments: usize,
+    pub timings: RehearsalTimings,
+    /// The first stage that failed, absent when the block is valid.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<RehearsalFailure>,
+}
+
+/// How long each stage took, next to the deadlines that apply when the
+/// node produces a block for real.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RehearsalTimings {
+    pub fragment_selection: Duration,
+    pub signing: Duration,
+    pub validation: Duration,
+    pub total: Duration,
+    /// The fragment selection stops taking new fragments after this delay.
+    pub soft_deadline: Duration,
+    /// The fragment selection is interrupted after this delay.
+    pub hard_deadline: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RehearsalFailure {
+    pub stage: RehearsalStage,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RehearsalStage {
+    FragmentSelection,
+    Signing,
+    Validation,
+}
+
+impl LeadershipRehearsal {
+    /// Whether the node produced a valid block before the soft deadline.
+    pub fn is_success(&self) -> bool {
+        self.failure.is_none() && self.timings.total <= self.timings.soft_deadline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rehearsal(total_ms: u64, failure: Option<RehearsalFailure>) -> LeadershipRehearsal {
+        let millis = |ms: u64| Duration::from(std::time::Duration::from_millis(ms));
+        LeadershipRehearsal {
+            date: "3.14".parse().unwrap(),
+            parent: Hash::from([1; 32]),
+            chain_length: 42,
+            block: None,
+            fragments: 0,
+            timings: RehearsalTimings {
+                fragment_selection: millis(0),
+                signing: millis(0),
+                validation: millis(0),
+                total: millis(total_ms),
+                soft_deadline: millis(1000),
+                hard_deadline: millis(2000),
+            },
+            failure,
+        }
+    }
+
+    #[test]
+    fn success_requires_the_soft_deadline() {
+        assert!(rehearsal(999, None).is_success());
+        assert!(!rehearsal(1001, None).is_success());
+    }
+
+    #[test]
+    fn failure_is_not_success() {
+        let failure = RehearsalFailure {
+            stage: RehearsalStage::Signing,
+            reason: "Enclave does not have a leader set".to_owned(),
+        };
+        let rehearsal = rehearsal(10, Some(failure));
+        assert!(!rehearsal.is_success());
+        let json = serde_json::to_value(&rehearsal).unwrap();
+        assert_eq!(json["failure"]["stage"], "signing");
+        let decoded: LeadershipRehearsal = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, rehearsal);
+    }
+
+    #[test]
+    fn request_date_is_optional() {
+        let request: LeadershipRehearsalRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request, LeadershipRehearsalRequest::default());
+        let request: LeadershipRehearsalRequest =
+            serde_json::from_str(r#"{"date":"3.14"}"#).unwrap();
+        assert_eq!(request.date, Some("3.14".parse().unwrap()));
+    }
+}
@@ -12,14 +12,22 @@ mod fragment_log;
 mod fragment_log_persistent;
 mod fragments_batch;
 mod fragments_processing_summary;
+mod fragments_simulation;
+mod health;
+mod kes_status;
 mod leadership_log;
+mod leadership_rehearsal;
 mod linear_fee;
 mod old_address;
+mod partial_transaction;
 mod peer_stats;
 mod ratio;
 mod reward_parameters;
 mod rewards_info;
+mod runtime_setting;
+mod service_status;
 mod settings;
+mod snapshot;
 mod stake;
 mod stake_distribution;
 mod stake_pool_stats;
@@ -54,14 +62,37 @@ pub use self::fragments_batch::FragmentsBatch;
 pub use self::fragments_processing_summary::{
     FragmentRejectionReason, FragmentsProcessingSummary, RejectedFragmentInfo,
 };
-pub use self::leadership_log::{LeadershipLog, LeadershipLogId, LeadershipLogStatus};
+pub use self::fragments_simulation::{
+    BalanceChange, FragmentExpiry, FragmentSimulation, FragmentsSimulation,
+};
+pub use self::health::{Health, HealthStatus, UnhealthyReason};
+pub use self::kes_status::KesStatus;
+pub use self::leadership_log::{
+    LeaderIdentity, LeadershipLog, LeadershipLogId, LeadershipLogStatus,
+};
+pub use self::leadership_rehearsal::{
+    LeadershipRehearsal, LeadershipRehearsalRequest, RehearsalFailure, RehearsalStage,
+    RehearsalTimings,
+};
 pub use self::linear_fee::{LinearFeeDef, PerCertificateFeeDef, PerVoteCertificateFeeDef};
 pub use self::old_address::OldAddress;
+pub use self::partial_transaction::{
+    InputPointer, PartialInput, PartialTransaction, PartialTransactionError,
+    PARTIAL_TRANSACTION_VERSION,
+};
 pub use self::peer_stats::{PeerRecord, PeerStats, Subscription};
 pub use self::ratio::{ParseRatioError, Ratio};
 pub use self::reward_parameters::RewardParams;
 pub use self::rewards_info::EpochRewardsInfo;
+pub use self::runtime_setting::RuntimeSetting;
+pub use self::service_status::{ServicePolicy, ServiceState, ServiceStatus};
 pub use self::settings::{ParametersDef, RatioDef, SettingsDto, TaxTypeDef, TaxTypeSerde};
+pub use self::snapshot::{
+    read_snapshot_block, read_snapshot_ledger, snapshot_blocks_digest, write_snapshot_block,
+    write_snapshot_ledger, SnapshotBlocks, SnapshotLedgers, SnapshotManifest, SnapshotSignature,
+    SnapshotTip, SNAPSHOT_BLOCKS_FILE, SNAPSHOT_LEDGERS_FILE, SNAPSHOT_MANIFEST_FILE,
+    SNAPSHOT_VERSION,
+};
 pub use self::stake::{Stake, StakeDef};
 pub use self::stake_distribution::{StakeDistribution, StakeDistributionDto};
 pub use self::stake_pool_stats::{Rewards, StakePoolStats};
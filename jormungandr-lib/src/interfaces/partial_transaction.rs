@@ -0,0 +1,476 @@
+//! interchange format of a transaction being signed by several parties
+//!
+//! A [`PartialTransaction`] is a balanced transaction waiting for its
+//! witnesses. It describes everything a party needs to check before
+//! signing: the spent inputs with their values and addresses, the outputs,
+//! the certificate and the blockchain the transaction is for. Each party
+//! adds the witnesses of the inputs it controls to its own copy, and the
+//! copies are merged back into a complete transaction.
+
+use crate::{
+    crypto::hash::Hash,
+    interfaces::{
+        AccountIdentifier, Address, BlockDate, Certificate, TransactionOutput, TransactionWitness,
+        Value,
+    },
+};
+use chain_core::property::Serialize as _;
+use chain_crypto::Verification;
+use chain_impl_mockchain::transaction::{
+    self, TransactionSignDataHash, Witness, WitnessAccountData, WitnessUtxoData, WitnessUtxoVersion,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// version of the partial transaction format this library reads and writes
+pub const PARTIAL_TRANSACTION_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum PartialTransactionError {
+    #[error(
+        "unsupported partial transaction version {0}, expected {}",
+        PARTIAL_TRANSACTION_VERSION
+    )]
+    UnsupportedVersion(u32),
+    #[error("the partial transactions are not for the same transaction")]
+    TransactionMismatch,
+    #[error("input {index} does not exist, the transaction has {inputs} inputs")]
+    NoSuchInput { index: usize, inputs: usize },
+    #[error("input {index} already has a different witness")]
+    ConflictingWitness { index: usize },
+    #[error("input {index} is {input} input but the witness is {witness} witness")]
+    WitnessKindMismatch {
+        index: usize,
+        input: &'static str,
+        witness: &'static str,
+    },
+    #[error("the witness of input {index} is not signed by the key of the input address")]
+    InvalidWitness { index: usize },
+    #[error("witnesses are missing for inputs {0:?}")]
+    MissingWitnesses(Vec<usize>),
+}
+
+/// a balanced transaction, with the witnesses collected so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialTransaction {
+    /// version of the format, see [`PARTIAL_TRANSACTION_VERSION`]
+    pub version: u32,
+    /// hash of the block0 of the blockchain the transaction is for
+    pub block0_hash: Hash,
+    /// the data signed by the witnesses, as given by
+    /// `jcli transaction data-for-witness`
+    pub sign_data_hash: Hash,
+    pub valid_until: BlockDate,
+    pub inputs: Vec<PartialInput>,
+    pub outputs: Vec<TransactionOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<Certificate>,
+}
+
+/// an input of the transaction, with its witness once it is signed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialInput {
+    #[serde(flatten)]
+    pub pointer: InputPointer,
+    pub value: Value,
+    /// the address owning the spent funds, whose key signs the input
+    pub address: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness: Option<TransactionWitness>,
+}
+
+/// where the funds spent by an input come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InputPointer {
+    Account { account: AccountIdentifier },
+    Utxo { fragment_id: Hash, output_index: u8 },
+}
+
+impl InputPointer {
+    fn kind(&self) -> &'static str {
+        match self {
+            InputPointer::Account { .. } => "an account",
+            InputPointer::Utxo { .. } => "a UTxO",
+        }
+    }
+}
+
+fn witness_kind(witness: &Witness) -> &'static str {
+    match witness {
+        Witness::Utxo(..) | Witness::OldUtxo(..) => "a UTxO",
+        Witness::Account(..) => "an account",
+        Witness::Multisig(..) => "a multisig",
+    }
+}
+
+impl PartialTransaction {
+    /// fail if the transaction was written in a format version this
+    /// library does not know
+    pub fn check_version(&self) -> Result<(), PartialTransactionError> {
+        if self.version != PARTIAL_TRANSACTION_VERSION {
+            return Err(PartialTransactionError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+
+    /// indices of the inputs still waiting for a witness
+    pub fn missing_witnesses(&self) -> Vec<usize> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| input.witness.is_none())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.inputs.iter().all(|input| input.witness.is_some())
+    }
+
+    /// the witnesses in the order of the inputs, fails unless every input
+    /// is signed
+    pub fn witnesses(&self) -> Result<Vec<TransactionWitness>, PartialTransactionError> {
+        let missing = self.missing_witnesses();
+        if !missing.is_empty() {
+            return Err(PartialTransactionError::MissingWitnesses(missing));
+        }
+        Ok(self
+            .inputs
+            .iter()
+            .filter_map(|input| input.witness.clone())
+            .collect())
+    }
+
+    /// Set the witness of the input at `index`, after checking it signs
+    /// this transaction with the key of the input. Setting the same witness
+    /// again is allowed, replacing it with a different one is not.
+    pub fn set_witness(
+        &mut self,
+        index: usize,
+        witness: TransactionWitness,
+    ) -> Result<(), PartialTransactionError> {
+        let inputs = self.inputs.len();
+        let input = self
+            .inputs
+            .get(index)
+            .ok_or(PartialTransactionError::NoSuchInput { index, inputs })?;
+
+        let matching = matches!(
+            (&input.pointer, witness.as_ref()),
+            (InputPointer::Account { .. }, Witness::Account(..))
+                | (InputPointer::Account { .. }, Witness::Multisig(..))
+                | (InputPointer::Utxo { .. }, Witness::Utxo(..))
+                | (InputPointer::Utxo { .. }, Witness::OldUtxo(..))
+        );
+        if !matching {
+            return Err(PartialTransactionError::WitnessKindMismatch {
+                index,
+                input: input.pointer.kind(),
+                witness: witness_kind(witness.as_ref()),
+            });
+        }
+        if !self.verify_witness(&input.pointer, &input.address, witness.as_ref()) {
+            return Err(PartialTransactionError::InvalidWitness { index });
+        }
+
+        match &input.witness {
+            Some(existing) if existing != &witness => {
+                Err(PartialTransactionError::ConflictingWitness { index })
+            }
+            _ => {
+                self.inputs[index].witness = Some(witness);
+                Ok(())
+            }
+        }
+    }
+
+    /// Check the signature of the witness against the sign data hash and
+    /// the key owning the input: the key of a UTxO address, or the key of a
+    /// single account. The legacy UTxO witnesses carry their own key, and
+    /// the multisig witnesses are checked against the multisig declaration
+    /// by the ledger only.
+    fn verify_witness(&self, pointer: &InputPointer, address: &Address, witness: &Witness) -> bool {
+        let block0_hash = self.block0_hash.into_hash();
+        let sign_data_hash = TransactionSignDataHash::from_bytes(self.sign_data_hash.into());
+        let account: Option<transaction::AccountIdentifier> = match pointer {
+            InputPointer::Account { account } => Some(account.clone().into()),
+            InputPointer::Utxo { .. } => None,
+        };
+        match (account, witness) {
+            (None, Witness::Utxo(signature)) => {
+                let data =
+                    WitnessUtxoData::new(&block0_hash, &sign_data_hash, WitnessUtxoVersion::Normal);
+                match address.1.public_key() {
+                    Some(key) => signature.verify(key, &data) == Verification::Success,
+                    None => false,
+                }
+            }
+            (None, Witness::OldUtxo(key, _, signature)) => {
+                let data =
+                    WitnessUtxoData::new(&block0_hash, &sign_data_hash, WitnessUtxoVersion::Legacy);
+                signature.verify(key, &data) == Verification::Success
+            }
+            (
+                Some(transaction::AccountIdentifier::Single(id)),
+                Witness::Account(counter, signature),
+            ) => {
+                let data = WitnessAccountData::new(&block0_hash, &sign_data_hash, *counter);
+                signature.verify(id.as_ref(), &data) == Verification::Success
+            }
+            (Some(transaction::AccountIdentifier::Multi(_)), Witness::Multisig(..)) => true,
+            _ => false,
+        }
+    }
+
+    /// Add the witnesses of `other` to this transaction. Both must be
+    /// copies of the same transaction, and must not hold different
+    /// witnesses for the same input.
+    pub fn merge(&mut self, other: &PartialTransaction) -> Result<(), PartialTransactionError> {
+        other.check_version()?;
+        if !self.same_transaction(other) {
+            return Err(PartialTransactionError::TransactionMismatch);
+        }
+        let mut merged = self.clone();
+        for (index, input) in other.inputs.iter().enumerate() {
+            if let Some(witness) = &input.witness {
+                merged.set_witness(index, witness.clone())?;
+            }
+        }
+        *self = merged;
+        Ok(())
+    }
+
+    fn same_transaction(&self, other: &PartialTransaction) -> bool {
+        let same_inputs = self.inputs.len() == other.inputs.len()
+            && self.inputs.iter().zip(&other.inputs).all(|(a, b)| {
+                a.pointer == b.pointer && a.value == b.value && a.address == b.address
+            });
+        self.version == other.version
+            && self.block0_hash == other.block0_hash
+            && self.sign_data_hash == other.sign_data_hash
+            && self.valid_until == other.valid_until
+            && same_inputs
+            && self.outputs == other.outputs
+            && certificate_bytes(&self.certificate) == certificate_bytes(&other.certificate)
+    }
+}
+
+fn certificate_bytes(certificate: &Option<Certificate>) -> Option<Vec<u8>> {
+    certificate
+        .as_ref()
+        .map(|certificate| certificate.serialize_as_vec().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_addr::{Discrimination, Kind};
+    use chain_crypto::{Ed25519, SecretKey};
+    use chain_impl_mockchain::account::{self, SpendingCounter};
+    use quickcheck::{Arbitrary, StdThreadGen};
+    use rand::thread_rng;
+
+    /// the keys owning the UTxO and the account inputs of a transaction
+    struct Keys {
+        utxo: SecretKey<Ed25519>,
+        account: SecretKey<Ed25519>,
+    }
+
+    fn transaction(g: &mut StdThreadGen) -> (PartialTransaction, Keys) {
+        let keys = Keys {
+            utxo: SecretKey::generate(thread_rng()),
+            account: SecretKey::generate(thread_rng()),
+        };
+        let utxo_address =
+            chain_addr::Address(Discrimination::Test, Kind::Single(keys.utxo.to_public()));
+        let account = AccountIdentifier::from(transaction::AccountIdentifier::Single(
+            account::Identifier::from(keys.account.to_public()),
+        ));
+        let partial = PartialTransaction {
+            version: PARTIAL_TRANSACTION_VERSION,
+            block0_hash: Hash::arbitrary(g),
+            sign_data_hash: Hash::arbitrary(g),
+            valid_until: BlockDate::arbitrary(g),
+            inputs: vec![
+                PartialInput {
+                    pointer: InputPointer::Utxo {
+                        fragment_id: Hash::arbitrary(g),
+                        output_index: 1,
+                    },
+                    value: Value::arbitrary(g),
+                    address: utxo_address.into(),
+                    witness: None,
+                },
+                PartialInput {
+                    pointer: InputPointer::Account {
+                        account: account.clone(),
+                    },
+                    value: Value::arbitrary(g),
+                    address: account.into_address(Discrimination::Test, "ca"),
+                    witness: None,
+                },
+            ],
+            outputs: vec![TransactionOutput::new(
+                Address::arbitrary(g),
+                Value::arbitrary(g),
+            )],
+            certificate: None,
+        };
+        (partial, keys)
+    }
+
+    fn utxo_witness(partial: &PartialTransaction, key: &SecretKey<Ed25519>) -> TransactionWitness {
+        Witness::new_utxo(
+            &partial.block0_hash.into_hash(),
+            &TransactionSignDataHash::from_bytes(partial.sign_data_hash.into()),
+            |d| key.sign(d),
+        )
+        .into()
+    }
+
+    fn account_witness(
+        partial: &PartialTransaction,
+        key: &SecretKey<Ed25519>,
+        counter: u32,
+    ) -> TransactionWitness {
+        Witness::new_account(
+            &partial.block0_hash.into_hash(),
+            &TransactionSignDataHash::from_bytes(partial.sign_data_hash.into()),
+            SpendingCounter::from(counter),
+            |d| key.sign(d),
+        )
+        .into()
+    }
+
+    #[test]
+    fn merge_collects_witnesses() {
+        let mut g = StdThreadGen::new(10);
+        let (mut first, keys) = transaction(&mut g);
+        let mut second = first.clone();
+        assert_eq!(first.missing_witnesses(), vec![0, 1]);
+
+        first
+            .set_witness(0, utxo_witness(&first, &keys.utxo))
+            .unwrap();
+        second
+            .set_witness(1, account_witness(&second, &keys.account, 0))
+            .unwrap();
+        assert_eq!(first.missing_witnesses(), vec![1]);
+        assert!(first.witnesses().is_err());
+
+        first.merge(&second).unwrap();
+        assert!(first.is_complete());
+        assert_eq!(first.witnesses().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn merge_rejects_other_transaction() {
+        let mut g = StdThreadGen::new(10);
+        let (mut first, _) = transaction(&mut g);
+        let mut second = first.clone();
+        second.outputs.clear();
+        assert!(matches!(
+            first.merge(&second),
+            Err(PartialTransactionError::TransactionMismatch)
+        ));
+    }
+
+    #[test]
+    fn conflicting_witnesses_are_rejected() {
+        let mut g = StdThreadGen::new(10);
+        let (mut first, keys) = transaction(&mut g);
+        let mut second = first.clone();
+        let witness = account_witness(&first, &keys.account, 0);
+        first.set_witness(1, witness.clone()).unwrap();
+        second.set_witness(1, witness).unwrap();
+        first.merge(&second).unwrap();
+
+        let mut third = first.clone();
+        third.inputs[1].witness = None;
+        third
+            .set_witness(1, account_witness(&third, &keys.account, 1))
+            .unwrap();
+        assert!(matches!(
+            first.merge(&third),
+            Err(PartialTransactionError::ConflictingWitness { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn witness_must_match_input_kind() {
+        let mut g = StdThreadGen::new(10);
+        let (mut partial, keys) = transaction(&mut g);
+        let witness = account_witness(&partial, &keys.utxo, 0);
+        assert!(matches!(
+            partial.set_witness(0, witness),
+            Err(PartialTransactionError::WitnessKindMismatch { index: 0, .. })
+        ));
+        let witness = utxo_witness(&partial, &keys.utxo);
+        assert!(matches!(
+            partial.set_witness(2, witness),
+            Err(PartialTransactionError::NoSuchInput {
+                index: 2,
+                inputs: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn witness_must_be_signed_by_the_input_key() {
+        let mut g = StdThreadGen::new(10);
+        let (mut partial, keys) = transaction(&mut g);
+
+        let witness = utxo_witness(&partial, &keys.account);
+        assert!(matches!(
+            partial.set_witness(0, witness),
+            Err(PartialTransactionError::InvalidWitness { index: 0 })
+        ));
+        let witness = account_witness(&partial, &keys.utxo, 0);
+        assert!(matches!(
+            partial.set_witness(1, witness),
+            Err(PartialTransactionError::InvalidWitness { index: 1 })
+        ));
+        let garbage = Witness::Utxo(Arbitrary::arbitrary(&mut g)).into();
+        assert!(matches!(
+            partial.set_witness(0, garbage),
+            Err(PartialTransactionError::InvalidWitness { index: 0 })
+        ));
+
+        // a witness of another transaction does not sign this one
+        let (other, _) = transaction(&mut g);
+        let witness = utxo_witness(&other, &keys.utxo);
+        assert!(matches!(
+            partial.set_witness(0, witness),
+            Err(PartialTransactionError::InvalidWitness { index: 0 })
+        ));
+        assert_eq!(partial.missing_witnesses(), vec![0, 1]);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut g = StdThreadGen::new(10);
+        let (mut partial, keys) = transaction(&mut g);
+        partial
+            .set_witness(1, account_witness(&partial, &keys.account, 0))
+            .unwrap();
+
+        let yaml = serde_yaml::to_string(&partial).unwrap();
+        let decoded: PartialTransaction = serde_yaml::from_str(&yaml).unwrap();
+        decoded.check_version().unwrap();
+        assert!(decoded.same_transaction(&partial));
+        assert_eq!(decoded.inputs, partial.inputs);
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut g = StdThreadGen::new(10);
+        let (mut partial, _) = transaction(&mut g);
+        partial.version = PARTIAL_TRANSACTION_VERSION + 1;
+        assert!(matches!(
+            partial.check_version(),
+            Err(PartialTransactionError::UnsupportedVersion(_))
+        ));
+    }
+}
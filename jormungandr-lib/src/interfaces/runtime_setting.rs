@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A tunable of the node that can be changed while it is running, such as
+/// `log.level` or `mempool.pool_max_entries`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeSetting {
+    /// the name of the tunable: its domain and key, separated with a dot
+    pub name: String,
+    /// the value set at runtime, `None` when the configured value applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// whether a change only applies when the node restarts, otherwise it
+    /// applies to the running node
+    pub applies_on_restart: bool,
+}
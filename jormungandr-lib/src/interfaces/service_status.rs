@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// The state of an internal service of the node, as seen by its supervisor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub policy: ServicePolicy,
+    pub state: ServiceState,
+    /// The number of times the service was restarted.
+    pub restarts: u32,
+    /// Why the service ended the last time, if it ever did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<String>,
+}
+
+/// What the supervisor does when the service ends or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServicePolicy {
+    /// The node stops.
+    Fatal,
+    /// The node keeps running without the service.
+    Degrade,
+    /// The service is restarted after a backoff delay.
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    Running,
+    /// The service ended and waits for its backoff delay to restart.
+    Restarting,
+    /// The service ended and the node runs without it.
+    Degraded,
+    /// The service ended and the node is stopping.
+    Stopped,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let status = ServiceStatus {
+            name: "explorer".to_owned(),
+            policy: ServicePolicy::Degrade,
+            state: ServiceState::Degraded,
+            restarts: 0,
+            last_failure: Some("panicked: index out of bounds".to_owned()),
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "explorer",
+                "policy": "degrade",
+                "state": "degraded",
+                "restarts": 0,
+                "last_failure": "panicked: index out of bounds",
+            })
+        );
+        let decoded: ServiceStatus = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, status);
+    }
+}
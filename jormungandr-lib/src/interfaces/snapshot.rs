@@ -0,0 +1,326 @@
+//! Snapshot archives allowing a node to start from a signed chain state
+//! instead of synchronizing from the genesis block.
+//!
+//! A snapshot is a directory holding three files:
+//!
+//! * [`SNAPSHOT_BLOCKS_FILE`]: the blocks following the block0 up to the
+//!   snapshot tip, each one prefixed with its size as a big endian `u32`;
+//! * [`SNAPSHOT_LEDGERS_FILE`]: the ledger states at the last epoch
+//!   boundaries of the chain, the tip being the last one. Each record is the
+//!   hash of the block followed by the serialized ledger after that block,
+//!   prefixed with its size like the blocks;
+//! * [`SNAPSHOT_MANIFEST_FILE`]: the [`SnapshotManifest`] describing the tip,
+//!   the blocks and the ledgers files, signed by one or more BFT leaders or
+//!   committee members.
+
+use crate::{
+    crypto::{
+        hash::Hash,
+        key::{Identifier, Signature, SigningKey},
+    },
+    interfaces::BlockDate,
+};
+use chain_crypto::{
+    digest::{Context, Digest},
+    Blake2b256, Ed25519, Verification,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+pub const SNAPSHOT_MANIFEST_FILE: &str = "manifest.yaml";
+pub const SNAPSHOT_BLOCKS_FILE: &str = "blocks.bin";
+pub const SNAPSHOT_LEDGERS_FILE: &str = "ledgers.bin";
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+const SIGNING_DATA_TAG: &[u8] = b"jormungandr-snapshot";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub block0: Hash,
+    pub tip: SnapshotTip,
+    pub blocks: SnapshotBlocks,
+    pub ledgers: SnapshotLedgers,
+    #[serde(default)]
+    pub signatures: Vec<SnapshotSignature>,
+}
+
+/// the last block of the snapshot, always the last block of an epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotTip {
+    pub hash: Hash,
+    pub chain_length: u32,
+    pub date: BlockDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotBlocks {
+    /// number of blocks in the blocks file
+    pub count: u64,
+    /// size in bytes of the blocks file
+    pub size: u64,
+    /// Blake2b256 digest of the blocks file
+    pub digest: Hash,
+}
+
+/// the ledger states at the epoch boundaries, the node restores its state
+/// from them instead of applying every block since the block0
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotLedgers {
+    /// blocks of the ledger states in the ledgers file, oldest first, the
+    /// last one being the tip
+    pub boundaries: Vec<Hash>,
+    /// size in bytes of the ledgers file
+    pub size: u64,
+    /// Blake2b256 digest of the ledgers file
+    pub digest: Hash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotSignature {
+    pub signer: Identifier<Ed25519>,
+    pub signature: Signature<Vec<u8>, Ed25519>,
+}
+
+impl SnapshotManifest {
+    pub fn new(
+        block0: Hash,
+        tip: SnapshotTip,
+        blocks: SnapshotBlocks,
+        ledgers: SnapshotLedgers,
+    ) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            block0,
+            tip,
+            blocks,
+            ledgers,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// the bytes covered by the signatures: every field of the manifest
+    /// except the signatures themselves.
+    pub fn signing_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            SIGNING_DATA_TAG.len() + 32 * (4 + self.ledgers.boundaries.len()) + 52,
+        );
+        data.extend_from_slice(SIGNING_DATA_TAG);
+        data.extend_from_slice(&self.version.to_be_bytes());
+        data.extend_from_slice(&<[u8; 32]>::from(self.block0));
+        data.extend_from_slice(&<[u8; 32]>::from(self.tip.hash));
+        data.extend_from_slice(&self.tip.chain_length.to_be_bytes());
+        data.extend_from_slice(&self.tip.date.epoch().to_be_bytes());
+        data.extend_from_slice(&self.tip.date.slot().to_be_bytes());
+        data.extend_from_slice(&self.blocks.count.to_be_bytes());
+        data.extend_from_slice(&self.blocks.size.to_be_bytes());
+        data.extend_from_slice(&<[u8; 32]>::from(self.blocks.digest));
+        data.extend_from_slice(&(self.ledgers.boundaries.len() as u32).to_be_bytes());
+        for boundary in &self.ledgers.boundaries {
+            data.extend_from_slice(&<[u8; 32]>::from(*boundary));
+        }
+        data.extend_from_slice(&self.ledgers.size.to_be_bytes());
+        data.extend_from_slice(&<[u8; 32]>::from(self.ledgers.digest));
+        data
+    }
+
+    /// add the signature of the given key, replacing any previous signature
+    /// of the same signer.
+    pub fn sign(&mut self, key: &SigningKey<Ed25519>) {
+        let signer = key.identifier();
+        let signature = key.sign(&self.signing_data());
+        self.signatures.retain(|s| s.signer != signer);
+        self.signatures
+            .push(SnapshotSignature { signer, signature });
+    }
+
+    /// list the signers among `trusted` whose signature is valid
+    pub fn verified_signers(&self, trusted: &[Identifier<Ed25519>]) -> Vec<Identifier<Ed25519>> {
+        let data = self.signing_data();
+        let mut signers: Vec<Identifier<Ed25519>> = Vec::new();
+        for s in &self.signatures {
+            if trusted.contains(&s.signer)
+                && !signers.contains(&s.signer)
+                && s.signature.verify(&s.signer, &data) == Verification::Success
+            {
+                signers.push(s.signer.clone());
+            }
+        }
+        signers
+    }
+}
+
+/// write one block of the blocks file
+pub fn write_snapshot_block<W: Write>(writer: &mut W, block: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(block.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "block too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(block)
+}
+
+/// read the next block of the blocks file, `None` at the end of the file
+pub fn read_snapshot_block<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut block = vec![0; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut block)?;
+    Ok(Some(block))
+}
+
+/// write the ledger state after block `hash` to the ledgers file
+pub fn write_snapshot_ledger<W: Write>(
+    writer: &mut W,
+    hash: Hash,
+    ledger: &[u8],
+) -> io::Result<()> {
+    let mut record = Vec::with_capacity(32 + ledger.len());
+    record.extend_from_slice(&<[u8; 32]>::from(hash));
+    record.extend_from_slice(ledger);
+    write_snapshot_block(writer, &record)
+}
+
+/// read the next ledger state of the ledgers file and the hash of its block,
+/// `None` at the end of the file
+pub fn read_snapshot_ledger<R: Read>(reader: &mut R) -> io::Result<Option<(Hash, Vec<u8>)>> {
+    let mut record = match read_snapshot_block(reader)? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    if record.len() < 32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "ledger record too short",
+        ));
+    }
+    let ledger = record.split_off(32);
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&record);
+    Ok(Some((Hash::from(hash), ledger)))
+}
+
+/// compute the Blake2b256 digest and the size of the blocks or ledgers file
+pub fn snapshot_blocks_digest<R: Read>(mut reader: R) -> io::Result<(Hash, u64)> {
+    let mut context = Context::<Blake2b256>::new();
+    let mut buffer = [0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.append_data(&buffer[..read]);
+        size += read as u64;
+    }
+    let digest: Digest<Blake2b256> = context.finalize();
+    let mut bytes = [0; 32];
+    bytes.copy_from_slice(digest.as_ref());
+    Ok((Hash::from(bytes), size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn manifest() -> SnapshotManifest {
+        let blocks = vec![vec![1, 2, 3], vec![4; 300]];
+        let mut file = Vec::new();
+        for block in &blocks {
+            write_snapshot_block(&mut file, block).unwrap();
+        }
+        let (digest, size) = snapshot_blocks_digest(file.as_slice()).unwrap();
+        SnapshotManifest::new(
+            Hash::from([1; 32]),
+            SnapshotTip {
+                hash: Hash::from([2; 32]),
+                chain_length: 2,
+                date: BlockDate::new(1, 0),
+            },
+            SnapshotBlocks {
+                count: blocks.len() as u64,
+                size,
+                digest,
+            },
+            SnapshotLedgers {
+                boundaries: vec![Hash::from([3; 32]), Hash::from([2; 32])],
+                size: 1024,
+                digest: Hash::from([4; 32]),
+            },
+        )
+    }
+
+    #[test]
+    fn blocks_file_roundtrip() {
+        let blocks = vec![vec![1, 2, 3], Vec::new(), vec![4; 300]];
+        let mut file = Vec::new();
+        for block in &blocks {
+            write_snapshot_block(&mut file, block).unwrap();
+        }
+        let mut reader = file.as_slice();
+        let mut read = Vec::new();
+        while let Some(block) = read_snapshot_block(&mut reader).unwrap() {
+            read.push(block);
+        }
+        assert_eq!(blocks, read);
+    }
+
+    #[test]
+    fn ledgers_file_roundtrip() {
+        let ledgers = vec![
+            (Hash::from([1; 32]), vec![1, 2, 3]),
+            (Hash::from([2; 32]), Vec::new()),
+        ];
+        let mut file = Vec::new();
+        for (hash, ledger) in &ledgers {
+            write_snapshot_ledger(&mut file, *hash, ledger).unwrap();
+        }
+        let mut reader = file.as_slice();
+        let mut read = Vec::new();
+        while let Some(ledger) = read_snapshot_ledger(&mut reader).unwrap() {
+            read.push(ledger);
+        }
+        assert_eq!(ledgers, read);
+    }
+
+    #[test]
+    fn only_trusted_valid_signatures_are_verified() {
+        let trusted = SigningKey::<Ed25519>::generate(thread_rng());
+        let untrusted = SigningKey::<Ed25519>::generate(thread_rng());
+
+        let mut manifest = manifest();
+        manifest.sign(&trusted);
+        manifest.sign(&untrusted);
+        manifest.sign(&trusted);
+        assert_eq!(manifest.signatures.len(), 2);
+
+        let trusted_ids = vec![trusted.identifier()];
+        assert_eq!(manifest.verified_signers(&trusted_ids), trusted_ids);
+
+        manifest.tip.chain_length += 1;
+        assert!(manifest.verified_signers(&trusted_ids).is_empty());
+        manifest.tip.chain_length -= 1;
+        assert_eq!(manifest.verified_signers(&trusted_ids), trusted_ids);
+
+        manifest.ledgers.boundaries.pop();
+        assert!(manifest.verified_signers(&trusted_ids).is_empty());
+    }
+
+    #[test]
+    fn manifest_serde_roundtrip() {
+        let mut manifest = manifest();
+        manifest.sign(&SigningKey::<Ed25519>::generate(thread_rng()));
+        let yaml = serde_yaml::to_string(&manifest).unwrap();
+        let decoded: SnapshotManifest = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(manifest, decoded);
+    }
+}
@@ -0,0 +1,68 @@
+use super::fixture::{Fixture, FixtureBuilder};
+use super::harness::Bench;
+use super::{apply_blocks, load_blockchain};
+use crate::blockcfg::Block;
+
+const SEED: u64 = 0x0b10_c4a1;
+const BLOCKS: usize = 20;
+const FRAGMENTS_PER_BLOCK: usize = 250;
+
+async fn bench_apply_and_store_block(bench: Bench, block0: &Block, blocks: &[Block]) {
+    let mut bench = bench
+        .parameter("blocks", blocks.len())
+        .parameter("fragments_per_block", FRAGMENTS_PER_BLOCK)
+        .elements(blocks.len());
+    for _ in 0..bench.iterations() {
+        let (blockchain, _tip) = load_blockchain(block0).await;
+        bench.record(apply_blocks(&blockchain, blocks).await);
+    }
+    bench.finish();
+}
+
+fn transfer_blocks(fixture: &mut Fixture) -> Vec<Block> {
+    let transfers = fixture.transfers(BLOCKS * FRAGMENTS_PER_BLOCK);
+    fixture.blocks(transfers, FRAGMENTS_PER_BLOCK)
+}
+
+#[tokio::test]
+async fn apply_and_store_block_many_accounts() {
+    const ACCOUNTS: usize = 100_000;
+    let mut fixture = FixtureBuilder::new(SEED).accounts(ACCOUNTS).build();
+    let blocks = transfer_blocks(&mut fixture);
+
+    let bench = Bench::new("apply_and_store_block_many_accounts").parameter("accounts", ACCOUNTS);
+    bench_apply_and_store_block(bench, &fixture.block0, &blocks).await;
+}
+
+#[tokio::test]
+async fn apply_and_store_block_large_utxo_set() {
+    const ACCOUNTS: usize = 1_000;
+    const UTXOS: usize = 200_000;
+    let mut fixture = FixtureBuilder::new(SEED)
+        .accounts(ACCOUNTS)
+        .utxos(UTXOS)
+        .build();
+    let blocks = transfer_blocks(&mut fixture);
+
+    let bench = Bench::new("apply_and_store_block_large_utxo_set")
+        .parameter("accounts", ACCOUNTS)
+        .parameter("utxos", UTXOS);
+    bench_apply_and_store_block(bench, &fixture.block0, &blocks).await;
+}
+
+#[tokio::test]
+async fn apply_and_store_block_vote_casts() {
+    const ACCOUNTS: usize = 1_000;
+    const PROPOSALS: usize = 100;
+    let mut fixture = FixtureBuilder::new(SEED)
+        .accounts(ACCOUNTS)
+        .vote_proposals(PROPOSALS)
+        .build();
+    let vote_casts = fixture.vote_casts(BLOCKS * FRAGMENTS_PER_BLOCK);
+    let blocks = fixture.blocks(vote_casts, FRAGMENTS_PER_BLOCK);
+
+    let bench = Bench::new("apply_and_store_block_vote_casts")
+        .parameter("accounts", ACCOUNTS)
+        .parameter("proposals", PROPOSALS);
+    bench_apply_and_store_block(bench, &fixture.block0, &blocks).await;
+}
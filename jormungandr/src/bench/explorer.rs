@@ -0,0 +1,52 @@
+use super::fixture::FixtureBuilder;
+use super::harness::Bench;
+use super::{apply_blocks, load_blockchain};
+use crate::explorer::ExplorerDb;
+use std::time::Instant;
+
+const SEED: u64 = 0x0e4b_10e3;
+const ACCOUNTS: usize = 1_000;
+const PROPOSALS: usize = 100;
+const BLOCKS: usize = 20;
+const FRAGMENTS_PER_BLOCK: usize = 250;
+
+#[tokio::test]
+async fn explorer_apply_block() {
+    let mut fixture = FixtureBuilder::new(SEED)
+        .accounts(ACCOUNTS)
+        .vote_proposals(PROPOSALS)
+        .build();
+    // half of the blocks are transfers, the other half vote casts
+    let transfers = fixture.transfers(BLOCKS / 2 * FRAGMENTS_PER_BLOCK);
+    let mut blocks = fixture.blocks(transfers, FRAGMENTS_PER_BLOCK);
+    let vote_casts = fixture.vote_casts(BLOCKS / 2 * FRAGMENTS_PER_BLOCK);
+    blocks.extend(fixture.blocks(vote_casts, FRAGMENTS_PER_BLOCK));
+
+    // the explorer reads the ledger states of the blocks from the blockchain
+    let (blockchain, _tip) = load_blockchain(&fixture.block0).await;
+    apply_blocks(&blockchain, &blocks).await;
+
+    let mut bench = Bench::new("explorer_apply_block")
+        .parameter("accounts", ACCOUNTS)
+        .parameter("proposals", PROPOSALS)
+        .parameter("blocks", blocks.len())
+        .parameter("fragments_per_block", FRAGMENTS_PER_BLOCK)
+        .elements(blocks.len());
+    for _ in 0..bench.iterations() {
+        // the head of the storage is still the block0, the bootstrap only
+        // indexes the block0
+        let explorer = ExplorerDb::bootstrap(fixture.block0.clone(), &blockchain)
+            .await
+            .expect("cannot bootstrap the explorer");
+
+        let started = Instant::now();
+        for block in &blocks {
+            explorer
+                .apply_block(block.clone())
+                .await
+                .expect("cannot index the block");
+        }
+        bench.record(started.elapsed());
+    }
+    bench.finish();
+}
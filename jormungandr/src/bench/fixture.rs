@@ -0,0 +1,388 @@
+//! Synthetic ledgers and blocks.
+
+use crate::blockcfg::{
+    block_builder, Block, BlockDate, BlockVersion, ChainLength, Contents, ContentsBuilder,
+    Fragment, HeaderId,
+};
+use chain_addr::{Address, Discrimination, Kind};
+use chain_crypto::{Ed25519, PublicKey, SecretKey};
+use chain_impl_mockchain::{
+    accounting::account::SpendingCounterIncreasing,
+    certificate::{
+        Proposal, Proposals, SignedCertificate, VoteAction, VoteCast, VotePlan, VotePlanProof,
+    },
+    chaintypes::ConsensusVersion,
+    fee::LinearFee,
+    key::BftLeaderId,
+    testing::VoteTestGen,
+    transaction::{
+        Input, NoExtra, Output, Payload, SingleAccountBindingSignature, Transaction, TxBuilder,
+        Witness,
+    },
+    value::Value,
+    vote::{self, Choice, Options, PayloadType},
+};
+use jormungandr_lib::interfaces::{
+    Block0Configuration, BlockContentMaxSize, BlockchainConfiguration, Initial, InitialUTxO,
+    NumberOfSlotsPerEpoch,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+
+/// outputs of a single initial fund fragment, the block0 refuses more
+const MAX_FUND_OUTPUTS: usize = 254;
+/// the UTxO set is spread over this many addresses
+const UTXO_HOLDERS: usize = 16;
+const ACCOUNT_FUNDS: u64 = 1_000_000_000;
+/// all the blocks are in the first epoch, so no epoch transition gets
+/// measured with the blocks that happen to trigger it
+const SLOTS_PER_EPOCH: u32 = 1_000_000;
+const BLOCK_CONTENT_MAX_SIZE: u32 = 16 * 1024 * 1024;
+
+pub struct FixtureBuilder {
+    seed: u64,
+    accounts: usize,
+    utxos: usize,
+    vote_proposals: usize,
+}
+
+impl FixtureBuilder {
+    pub fn new(seed: u64) -> Self {
+        FixtureBuilder {
+            seed,
+            accounts: 1,
+            utxos: 0,
+            vote_proposals: 0,
+        }
+    }
+
+    /// number of funded accounts, the senders of the transfers and the
+    /// voters of the vote casts
+    pub fn accounts(mut self, count: usize) -> Self {
+        self.accounts = count.max(1);
+        self
+    }
+
+    /// number of outputs of the UTxO set in the block0
+    pub fn utxos(mut self, count: usize) -> Self {
+        self.utxos = count;
+        self
+    }
+
+    /// register a vote plan with that many proposals in the block0
+    pub fn vote_proposals(mut self, count: usize) -> Self {
+        assert!(count <= u8::MAX as usize, "too many proposals");
+        self.vote_proposals = count;
+        self
+    }
+
+    pub fn build(self) -> Fixture {
+        let mut rng = ChaChaRng::seed_from_u64(self.seed);
+        let leader_key = SecretKey::<Ed25519>::generate(&mut rng);
+
+        let accounts: Vec<Account> = std::iter::repeat_with(|| Account::generate(&mut rng))
+            .take(self.accounts)
+            .collect();
+        let utxo_holders: Vec<Address> = std::iter::repeat_with(|| {
+            let key = SecretKey::<Ed25519>::generate(&mut rng);
+            Address(Discrimination::Test, Kind::Single(key.to_public()))
+        })
+        .take(UTXO_HOLDERS)
+        .collect();
+
+        let funds: Vec<InitialUTxO> = accounts
+            .iter()
+            .map(|account| initial_fund(account.address(), ACCOUNT_FUNDS))
+            .chain(
+                utxo_holders
+                    .iter()
+                    .cycle()
+                    .take(self.utxos)
+                    .map(|holder| initial_fund(holder.clone(), 1)),
+            )
+            .collect();
+        let mut initial: Vec<Initial> = funds
+            .chunks(MAX_FUND_OUTPUTS)
+            .map(|chunk| Initial::Fund(chunk.to_vec()))
+            .collect();
+
+        let valid_until = last_date();
+        let vote_plan = if self.vote_proposals > 0 {
+            let vote_plan = vote_plan(self.vote_proposals);
+            initial.push(Initial::Cert(
+                accounts[0].vote_plan_cert(valid_until, &vote_plan).into(),
+            ));
+            Some(vote_plan)
+        } else {
+            None
+        };
+
+        let mut blockchain_configuration = BlockchainConfiguration::new(
+            Discrimination::Test,
+            ConsensusVersion::Bft,
+            LinearFee::new(0, 0, 0),
+        );
+        blockchain_configuration.slots_per_epoch =
+            NumberOfSlotsPerEpoch::new(SLOTS_PER_EPOCH).unwrap();
+        blockchain_configuration.block_content_max_size =
+            BlockContentMaxSize::from(BLOCK_CONTENT_MAX_SIZE);
+        blockchain_configuration.consensus_leader_ids = vec![leader_key.to_public().into()];
+        let block0 = Block0Configuration {
+            blockchain_configuration,
+            initial,
+        }
+        .to_block();
+
+        let block0_id = block0.header().hash();
+        let chain_length = block0.header().chain_length();
+        Fixture {
+            block0_hash: block0_id,
+            block0,
+            leader_id: BftLeaderId::from(leader_key.to_public()),
+            leader_key,
+            valid_until,
+            accounts,
+            vote_plan,
+            vote_proposals: self.vote_proposals,
+            next_sender: 0,
+            next_vote: 0,
+            parent_id: block0_id,
+            chain_length,
+            slot: 0,
+        }
+    }
+}
+
+fn last_date() -> BlockDate {
+    BlockDate {
+        epoch: 0,
+        slot_id: SLOTS_PER_EPOCH - 1,
+    }
+}
+
+fn initial_fund(address: Address, value: u64) -> InitialUTxO {
+    InitialUTxO {
+        address: address.into(),
+        value: value.into(),
+    }
+}
+
+/// a public vote plan open during the whole first epoch
+fn vote_plan(proposals_count: usize) -> VotePlan {
+    let mut proposals = Proposals::new();
+    for _ in 0..proposals_count {
+        let _ = proposals.push(Proposal::new(
+            VoteTestGen::external_proposal_id(),
+            Options::new_length(3).unwrap(),
+            VoteAction::OffChain,
+        ));
+    }
+    VotePlan::new(
+        BlockDate {
+            epoch: 0,
+            slot_id: 0,
+        },
+        BlockDate {
+            epoch: 1,
+            slot_id: 0,
+        },
+        BlockDate {
+            epoch: 2,
+            slot_id: 0,
+        },
+        proposals,
+        PayloadType::Public,
+        Vec::new(),
+    )
+}
+
+/// An account spending its funds with the default lane of its spending
+/// counter.
+struct Account {
+    key: SecretKey<Ed25519>,
+    counters: SpendingCounterIncreasing,
+}
+
+impl Account {
+    fn generate(rng: &mut ChaChaRng) -> Self {
+        Account {
+            key: SecretKey::generate(rng),
+            counters: SpendingCounterIncreasing::default(),
+        }
+    }
+
+    fn public_key(&self) -> PublicKey<Ed25519> {
+        self.key.to_public()
+    }
+
+    fn address(&self) -> Address {
+        Address(Discrimination::Test, Kind::Account(self.public_key()))
+    }
+
+    fn vote_plan_cert(&self, valid_until: BlockDate, vote_plan: &VotePlan) -> SignedCertificate {
+        let auth_data = TxBuilder::new()
+            .set_payload(vote_plan)
+            .set_expiry_date(valid_until)
+            .set_ios(&[], &[])
+            .set_witnesses(&[])
+            .get_auth_data();
+        let signature =
+            SingleAccountBindingSignature::new(&auth_data, |d| self.key.sign_slice(d.0));
+        SignedCertificate::VotePlan(
+            vote_plan.clone(),
+            VotePlanProof {
+                id: self.public_key().into(),
+                signature,
+            },
+        )
+    }
+
+    /// a transaction of `value` from this account, with no fees, then
+    /// increase the spending counter
+    fn transaction<P: Payload>(
+        &mut self,
+        block0_hash: &HeaderId,
+        valid_until: BlockDate,
+        payload: &P,
+        value: u64,
+        outputs: &[Output<Address>],
+    ) -> Transaction<P>
+    where
+        P::Auth: Default,
+    {
+        let builder = TxBuilder::new()
+            .set_payload(payload)
+            .set_expiry_date(valid_until)
+            .set_ios(
+                &[Input::from_account_single(
+                    self.public_key().into(),
+                    Value(value),
+                )],
+                outputs,
+            );
+        let counter = self.counters.get_valid_counter();
+        let witness = Witness::new_account(
+            block0_hash,
+            &builder.get_auth_data_for_witness().hash(),
+            counter,
+            |data| self.key.sign(data),
+        );
+        self.counters.next_verify(counter).unwrap();
+        builder
+            .set_witnesses(&[witness])
+            .set_payload_auth(&Default::default())
+    }
+}
+
+/// A block0 and the keys to add fragments and blocks on top of it.
+pub struct Fixture {
+    pub block0: Block,
+    block0_hash: HeaderId,
+    leader_key: SecretKey<Ed25519>,
+    leader_id: BftLeaderId,
+    valid_until: BlockDate,
+    accounts: Vec<Account>,
+    vote_plan: Option<VotePlan>,
+    vote_proposals: usize,
+    /// account sending the next transfer
+    next_sender: usize,
+    /// index of the next vote cast, over the voters then the proposals
+    next_vote: usize,
+    /// the tip of the blocks built so far
+    parent_id: HeaderId,
+    chain_length: ChainLength,
+    slot: u32,
+}
+
+impl Fixture {
+    /// Transfers between the accounts, each one from the account after the
+    /// sender of the previous one. The transfers are valid when applied in
+    /// order.
+    pub fn transfers(&mut self, count: usize) -> Vec<Fragment> {
+        let accounts_count = self.accounts.len();
+        let mut fragments = Vec::with_capacity(count);
+        for _ in 0..count {
+            let sender = self.next_sender;
+            self.next_sender = (sender + 1) % accounts_count;
+            let receiver = self.accounts[self.next_sender].address();
+            fragments.push(Fragment::Transaction(self.accounts[sender].transaction(
+                &self.block0_hash,
+                self.valid_until,
+                &NoExtra,
+                1,
+                &[Output::from_address(receiver, Value(1))],
+            )));
+        }
+        fragments
+    }
+
+    /// Vote casts on the proposals of the vote plan, every account votes
+    /// once on every proposal.
+    pub fn vote_casts(&mut self, count: usize) -> Vec<Fragment> {
+        let vote_plan = self.vote_plan.as_ref().expect("no vote plan in the block0");
+        let accounts_count = self.accounts.len();
+        assert!(
+            self.next_vote + count <= accounts_count * self.vote_proposals,
+            "not enough accounts and proposals for {} more vote casts",
+            count
+        );
+        let mut fragments = Vec::with_capacity(count);
+        for vote in self.next_vote..self.next_vote + count {
+            let vote_cast = VoteCast::new(
+                vote_plan.to_id(),
+                (vote / accounts_count) as u8,
+                vote::Payload::public(Choice::new(1)),
+            );
+            fragments.push(Fragment::VoteCast(
+                self.accounts[vote % accounts_count].transaction(
+                    &self.block0_hash,
+                    self.valid_until,
+                    &vote_cast,
+                    0,
+                    &[],
+                ),
+            ));
+        }
+        self.next_vote += count;
+        fragments
+    }
+
+    /// Blocks of `per_block` fragments each, chained after the last block
+    /// built by the fixture.
+    pub fn blocks(&mut self, fragments: Vec<Fragment>, per_block: usize) -> Vec<Block> {
+        fragments
+            .chunks(per_block.max(1))
+            .map(|fragments| {
+                let mut contents = ContentsBuilder::new();
+                for fragment in fragments {
+                    contents.push(fragment.clone());
+                }
+                self.block(contents.into())
+            })
+            .collect()
+    }
+
+    fn block(&mut self, contents: Contents) -> Block {
+        self.slot += 1;
+        self.chain_length = self.chain_length.increase();
+        let date = BlockDate {
+            epoch: 0,
+            slot_id: self.slot,
+        };
+        let (parent_id, chain_length) = (self.parent_id, self.chain_length);
+        let (leader_key, leader_id) = (&self.leader_key, &self.leader_id);
+        let block = block_builder(BlockVersion::Ed25519Signed, contents, |hdr_builder| {
+            let final_builder = hdr_builder
+                .set_parent(&parent_id, chain_length)
+                .set_date(date)
+                .into_bft_builder()
+                .expect("Valid Header Builder")
+                .set_consensus_data(leader_id);
+            let signature = leader_key.sign_slice(final_builder.get_authenticated_data());
+            Ok::<_, ()>(final_builder.set_signature(signature.into()).generalize())
+        })
+        .expect("cannot build the block");
+        self.parent_id = block.header().hash();
+        block
+    }
+}
@@ -0,0 +1,173 @@
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// file the results are appended to, one JSON object per line
+const OUTPUT_VAR: &str = "JORMUNGANDR_BENCH_OUTPUT";
+const ITERATIONS_VAR: &str = "JORMUNGANDR_BENCH_ITERATIONS";
+const DEFAULT_ITERATIONS: usize = 10;
+
+/// Timings of the iterations of a benchmark.
+///
+/// The setup of an iteration is not measured: the benchmark measures the
+/// operation itself and records its duration.
+pub struct Bench {
+    name: &'static str,
+    parameters: BTreeMap<&'static str, usize>,
+    elements: usize,
+    samples: Vec<Duration>,
+}
+
+impl Bench {
+    pub fn new(name: &'static str) -> Self {
+        Bench {
+            name,
+            parameters: BTreeMap::new(),
+            elements: 1,
+            samples: Vec::new(),
+        }
+    }
+
+    /// parameter of the synthetic data, kept in the results to only compare
+    /// the runs of the same workload
+    pub fn parameter(mut self, name: &'static str, value: usize) -> Self {
+        self.parameters.insert(name, value);
+        self
+    }
+
+    /// number of elements (blocks, fragments) processed by an iteration
+    pub fn elements(mut self, elements: usize) -> Self {
+        self.elements = elements;
+        self
+    }
+
+    pub fn iterations(&self) -> usize {
+        std::env::var(ITERATIONS_VAR)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|iterations| *iterations > 0)
+            .unwrap_or(DEFAULT_ITERATIONS)
+    }
+
+    pub fn record(&mut self, elapsed: Duration) {
+        self.samples.push(elapsed);
+    }
+
+    /// print the results and append them to the output file if any
+    pub fn finish(self) {
+        let summary = Summary::new(self.name, self.parameters, self.elements, self.samples);
+        println!("{}", summary);
+
+        if let Ok(path) = std::env::var(OUTPUT_VAR) {
+            let line = serde_json::to_string(&summary).expect("cannot serialize the results");
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .unwrap_or_else(|e| panic!("cannot open {}: {}", path, e));
+            writeln!(file, "{}", line).unwrap_or_else(|e| panic!("cannot write {}: {}", path, e));
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    name: &'static str,
+    revision: &'static str,
+    timestamp: u64,
+    parameters: BTreeMap<&'static str, usize>,
+    iterations: usize,
+    elements: usize,
+    mean_ns: u64,
+    median_ns: u64,
+    min_ns: u64,
+    max_ns: u64,
+    /// elements per second at the median duration
+    throughput: f64,
+}
+
+impl Summary {
+    fn new(
+        name: &'static str,
+        parameters: BTreeMap<&'static str, usize>,
+        elements: usize,
+        mut samples: Vec<Duration>,
+    ) -> Self {
+        assert!(!samples.is_empty(), "benchmark {} recorded nothing", name);
+        samples.sort();
+        let iterations = samples.len();
+        let total: Duration = samples.iter().sum();
+        let median = if iterations % 2 == 0 {
+            (samples[iterations / 2 - 1] + samples[iterations / 2]) / 2
+        } else {
+            samples[iterations / 2]
+        };
+        let throughput = if median > Duration::default() {
+            elements as f64 / median.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        Summary {
+            name,
+            revision: env!("SOURCE_VERSION"),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs())
+                .unwrap_or_default(),
+            parameters,
+            iterations,
+            elements,
+            mean_ns: (total / iterations as u32).as_nanos() as u64,
+            median_ns: median.as_nanos() as u64,
+            min_ns: samples[0].as_nanos() as u64,
+            max_ns: samples[iterations - 1].as_nanos() as u64,
+            throughput,
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (name, value) in &self.parameters {
+            write!(f, " {}={}", name, value)?;
+        }
+        write!(
+            f,
+            ": median {:?}, mean {:?}, min {:?}, max {:?} over {} iterations, {:.1} elements/s",
+            Duration::from_nanos(self.median_ns),
+            Duration::from_nanos(self.mean_ns),
+            Duration::from_nanos(self.min_ns),
+            Duration::from_nanos(self.max_ns),
+            self.iterations,
+            self.throughput
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_statistics() {
+        let samples = [40, 10, 30, 20]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        let summary = Summary::new("test", BTreeMap::new(), 100, samples);
+
+        assert_eq!(summary.iterations, 4);
+        assert_eq!(summary.min_ns, Duration::from_millis(10).as_nanos() as u64);
+        assert_eq!(summary.max_ns, Duration::from_millis(40).as_nanos() as u64);
+        assert_eq!(summary.mean_ns, Duration::from_millis(25).as_nanos() as u64);
+        assert_eq!(
+            summary.median_ns,
+            Duration::from_millis(25).as_nanos() as u64
+        );
+        assert!((summary.throughput - 4000.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,71 @@
+//! # Benchmarks of the hot paths of the node
+//!
+//! The benchmarks run against synthetic ledgers and blocks generated by
+//! [`FixtureBuilder`](fixture::FixtureBuilder): a block0 with many accounts,
+//! a large UTxO set or a vote plan, followed by blocks full of transfers or
+//! vote casts signed by a BFT leader. They are tests compiled with the
+//! `with-bench` feature, meant to run one at a time in release mode:
+//!
+//! ```text
+//! cargo test -p jormungandr --release --features with-bench bench:: -- --nocapture --test-threads=1
+//! ```
+//!
+//! Every benchmark prints the statistics of its iterations. When
+//! `JORMUNGANDR_BENCH_OUTPUT` is set, they are also appended as a JSON line
+//! to that file along with the source revision, so the results of successive
+//! revisions can be compared. `JORMUNGANDR_BENCH_ITERATIONS` changes the
+//! number of iterations of every benchmark.
+
+mod blockchain;
+mod explorer;
+mod fixture;
+mod harness;
+mod pool;
+mod storage;
+
+use crate::blockcfg::Block;
+use crate::blockchain::{Blockchain, CheckHeaderProof, PreCheckedHeader, Storage, Tip};
+use crate::start_up;
+use std::time::{Duration, Instant};
+use tracing::Span;
+
+const BLOCK_CACHE_CAPACITY: usize = 1000;
+
+/// a blockchain in memory with only the block0 applied
+async fn load_blockchain(block0: &Block) -> (Blockchain, Tip) {
+    let storage = Storage::memory(Span::none()).expect("cannot create the in-memory storage");
+    start_up::load_blockchain(block0.clone(), storage, BLOCK_CACHE_CAPACITY, false)
+        .await
+        .expect("cannot load the block0")
+}
+
+/// Check and apply the blocks in order, returns the time spent in
+/// `Blockchain::apply_and_store_block`.
+async fn apply_blocks(blockchain: &Blockchain, blocks: &[Block]) -> Duration {
+    let mut elapsed = Duration::default();
+    for block in blocks {
+        let pre_checked = blockchain
+            .pre_check_header(block.header().clone(), false)
+            .await
+            .expect("invalid block header");
+        let (header, parent_ref) = match pre_checked {
+            PreCheckedHeader::HeaderWithCache { header, parent_ref } => (header, parent_ref),
+            _ => panic!(
+                "the parent of block {} is not applied",
+                block.header().hash()
+            ),
+        };
+        let post_checked = blockchain
+            .post_check_header(header, parent_ref, CheckHeaderProof::Enabled)
+            .await
+            .expect("invalid block header");
+
+        let started = Instant::now();
+        blockchain
+            .apply_and_store_block(post_checked, block.clone())
+            .await
+            .expect("cannot apply the block");
+        elapsed += started.elapsed();
+    }
+    elapsed
+}
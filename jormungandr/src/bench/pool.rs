@@ -0,0 +1,115 @@
+use super::fixture::{Fixture, FixtureBuilder};
+use super::harness::Bench;
+use super::load_blockchain;
+use crate::blockcfg::{BlockDate, Fragment};
+use crate::fragment::{
+    pool::internal::Pool,
+    selection::{FragmentSelectionAlgorithm, OldestFirst},
+    FragmentId, Logs,
+};
+use futures::channel::oneshot;
+use std::time::Instant;
+
+const SEED: u64 = 0x0f4a_9e47;
+const ACCOUNTS: usize = 1_000;
+const FRAGMENTS: usize = 10_000;
+
+fn pool_entries(fragments: Vec<Fragment>) -> Vec<(Fragment, FragmentId)> {
+    fragments
+        .into_iter()
+        .map(|fragment| {
+            let id = fragment.id();
+            (fragment, id)
+        })
+        .collect()
+}
+
+#[test]
+fn pool_insert() {
+    let mut fixture = FixtureBuilder::new(SEED).accounts(ACCOUNTS).build();
+    let entries = pool_entries(fixture.transfers(FRAGMENTS));
+
+    let mut bench = Bench::new("pool_insert")
+        .parameter("accounts", ACCOUNTS)
+        .parameter("fragments", FRAGMENTS)
+        .elements(FRAGMENTS);
+    for _ in 0..bench.iterations() {
+        let mut pool = Pool::new(FRAGMENTS);
+        let entries = entries.clone();
+
+        let started = Instant::now();
+        let inserted = pool.insert_all(entries);
+        bench.record(started.elapsed());
+        assert_eq!(inserted.len(), FRAGMENTS);
+    }
+    bench.finish();
+}
+
+async fn bench_select(bench: Bench, fixture: &Fixture, fragments: Vec<Fragment>) {
+    let (_blockchain, tip) = load_blockchain(&fixture.block0).await;
+    let tip_ref = tip.get_ref().await;
+    let entries = pool_entries(fragments);
+    let fragments_count = entries.len();
+
+    let mut bench = bench
+        .parameter("fragments", fragments_count)
+        .elements(fragments_count);
+    for _ in 0..bench.iterations() {
+        let mut pool = Pool::new(fragments_count);
+        pool.insert_all(entries.clone());
+        let mut logs = Logs::new(fragments_count);
+        let ledger = tip_ref
+            .ledger()
+            .begin_block(
+                tip_ref.chain_length().increase(),
+                BlockDate {
+                    epoch: 0,
+                    slot_id: 1,
+                },
+            )
+            .expect("cannot start a block on the block0 ledger");
+        // the deadlines are reached when the senders are dropped, they are
+        // kept until the selection is done
+        let (_soft_deadline, soft_deadline_future) = oneshot::channel();
+        let (_hard_deadline, hard_deadline_future) = oneshot::channel();
+
+        let started = Instant::now();
+        let selection = OldestFirst::new()
+            .select(
+                ledger,
+                tip_ref.epoch_ledger_parameters(),
+                &mut logs,
+                &mut pool,
+                soft_deadline_future,
+                hard_deadline_future,
+            )
+            .await;
+        bench.record(started.elapsed());
+        assert_eq!(selection.rejected_fragments_cnt, 0);
+    }
+    bench.finish();
+}
+
+#[tokio::test]
+async fn oldest_first_select_transfers() {
+    let mut fixture = FixtureBuilder::new(SEED).accounts(ACCOUNTS).build();
+    let transfers = fixture.transfers(FRAGMENTS);
+
+    let bench = Bench::new("oldest_first_select_transfers").parameter("accounts", ACCOUNTS);
+    bench_select(bench, &fixture, transfers).await;
+}
+
+#[tokio::test]
+async fn oldest_first_select_vote_casts() {
+    const PROPOSALS: usize = 100;
+    let mut fixture = FixtureBuilder::new(SEED)
+        .accounts(ACCOUNTS)
+        .vote_proposals(PROPOSALS)
+        .build();
+    let vote_casts = fixture.vote_casts(FRAGMENTS);
+
+    let bench = Bench::new("oldest_first_select_vote_casts")
+        .parameter("accounts", ACCOUNTS)
+        .parameter("proposals", PROPOSALS);
+    bench_select(bench, &fixture, vote_casts).await;
+}
@@ -0,0 +1,40 @@
+use super::fixture::FixtureBuilder;
+use super::harness::Bench;
+use super::{apply_blocks, load_blockchain};
+use futures::prelude::*;
+use std::time::Instant;
+
+const SEED: u64 = 0x05e0_4a6e;
+const ACCOUNTS: usize = 1_000;
+const BLOCKS: usize = 1_000;
+const FRAGMENTS_PER_BLOCK: usize = 20;
+
+#[tokio::test]
+async fn storage_stream_from_to() {
+    let mut fixture = FixtureBuilder::new(SEED).accounts(ACCOUNTS).build();
+    let transfers = fixture.transfers(BLOCKS * FRAGMENTS_PER_BLOCK);
+    let blocks = fixture.blocks(transfers, FRAGMENTS_PER_BLOCK);
+
+    let (blockchain, _tip) = load_blockchain(&fixture.block0).await;
+    apply_blocks(&blockchain, &blocks).await;
+    let from = fixture.block0.header().hash();
+    let to = blocks.last().unwrap().header().hash();
+
+    let mut bench = Bench::new("storage_stream_from_to")
+        .parameter("blocks", BLOCKS)
+        .parameter("fragments_per_block", FRAGMENTS_PER_BLOCK)
+        .elements(BLOCKS);
+    for _ in 0..bench.iterations() {
+        let started = Instant::now();
+        let streamed = blockchain
+            .storage()
+            .stream_from_to(from, to)
+            .expect("cannot stream the blocks")
+            .try_fold(0, |count, _block| future::ok(count + 1))
+            .await
+            .expect("cannot read a block");
+        bench.record(started.elapsed());
+        assert_eq!(streamed, BLOCKS);
+    }
+    bench.finish();
+}
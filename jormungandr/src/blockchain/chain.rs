@@ -459,7 +459,7 @@ impl Blockchain {
         })
     }
 
-    fn apply_block_dry_run(
+    pub fn apply_block_dry_run(
         &self,
         post_checked_header: &PostCheckedHeader,
         block: &Block,
@@ -792,6 +792,101 @@ impl Blockchain {
         Ok(Tip::new(branch))
     }
 
+    /// Restore the state of the blockchain from the ledger states at the
+    /// last epoch boundaries of the storage, instead of applying all the
+    /// blocks since the block0 like [`load_from_storage`](Self::load_from_storage).
+    ///
+    /// `boundaries` are the last blocks before an epoch change, oldest first,
+    /// each one with the ledger state after it. The last boundary is the
+    /// block the state is restored at, the previous ones are needed to
+    /// rebuild the leadership schedule of its epoch. The blocks stored after
+    /// the last boundary are then applied like in `load_from_storage`.
+    ///
+    /// # Errors
+    ///
+    /// The resulted future may fail if
+    ///
+    /// * the block0 is not already in the storage: `Error::Block0NotAlreadyInStorage`;
+    /// * a boundary block is not in the storage: `Error::MissingParentBlock`;
+    /// * the protocol changes of the epoch boundary cannot be applied: `Error::Ledger`;
+    /// * other errors while interacting with the storage (IO errors)
+    pub async fn load_from_epoch_boundaries(
+        &self,
+        block0: Block,
+        boundaries: Vec<(HeaderHash, Ledger)>,
+    ) -> Result<Tip> {
+        let block0_id = block0.header().hash();
+        if !self.storage.block_exists(block0_id)? {
+            return Err(Error::Block0NotAlreadyInStorage);
+        }
+        let head_hash = self
+            .storage
+            .get_tag(MAIN_BRANCH_TAG)?
+            .ok_or_else(|| Error::NoTag(MAIN_BRANCH_TAG.to_owned()))?;
+
+        let mut branch = self.apply_block0(&block0).await?;
+        let block0_ref = branch.get_ref().await;
+
+        let mut previous: Option<Arc<Ref>> = None;
+        for (hash, ledger) in boundaries {
+            if hash == block0_id {
+                previous = Some(block0_ref.clone());
+                continue;
+            }
+            let header = self
+                .storage
+                .get(hash)?
+                .ok_or(Error::MissingParentBlock(hash))?
+                .header()
+                .clone();
+            let epoch = header.block_date().epoch;
+            // only the leadership of the last boundary is used to validate
+            // the blocks that follow, the state of the epoch of the older
+            // boundaries is only needed to build it
+            let leadership = match &previous {
+                Some(previous) => Arc::new(epoch_boundary_leadership(epoch, previous)?),
+                None if epoch == block0_ref.block_date().epoch => {
+                    block0_ref.epoch_leadership_schedule().clone()
+                }
+                None => Arc::new(Leadership::new(epoch, &ledger)),
+            };
+            let ledger_parameters = Arc::new(leadership.ledger_parameters().clone());
+            let reference = self
+                .create_and_store_reference(
+                    hash,
+                    header,
+                    ledger,
+                    block0_ref.time_frame().clone(),
+                    leadership,
+                    None,
+                    ledger_parameters,
+                    previous.take(),
+                )
+                .await;
+            previous = Some(reference);
+        }
+
+        let boundary = previous.unwrap_or(block0_ref);
+        let boundary_hash = boundary.hash();
+        branch.update_ref(boundary).await;
+
+        if head_hash != boundary_hash {
+            let mut block_stream = self
+                .storage
+                .stream_from_to(boundary_hash, head_hash)
+                .map(Box::pin)?;
+            while let Some(block) = block_stream.next().await.transpose()? {
+                branch
+                    .update_ref(
+                        self.handle_bootstrap_block(block, CheckHeaderProof::SkipFromStorage)
+                            .await?,
+                    )
+                    .await;
+            }
+        }
+        Ok(Tip::new(branch))
+    }
+
     pub async fn get_checkpoints(&self, branch: &Branch) -> Checkpoints {
         Checkpoints::new_from(branch.get_ref().await)
     }
@@ -937,6 +1032,23 @@ pub fn new_epoch_leadership_from(
     }
 }
 
+/// The leadership of the epoch following the epoch boundary `previous`, like
+/// [`new_epoch_leadership_from`] builds it without distributing the rewards.
+fn epoch_boundary_leadership(epoch: Epoch, previous: &Arc<Ref>) -> Result<Leadership> {
+    use chain_impl_mockchain::chaintypes::ConsensusVersion;
+
+    let transition_state = previous.ledger().apply_protocol_changes()?;
+    let epoch_state = if transition_state.consensus_version() == ConsensusVersion::GenesisPraos {
+        previous
+            .last_ref_previous_epoch()
+            .map(|r| r.ledger())
+            .unwrap_or_else(|| previous.ledger())
+    } else {
+        Arc::new(transition_state)
+    };
+    Ok(Leadership::new(epoch, &epoch_state))
+}
+
 pub struct StreamReporter<R> {
     stream_info: StreamInfo,
     report: R,
@@ -374,7 +374,10 @@ async fn process_leadership_block(
         .await?;
 
     if let Some(mut msg_box) = explorer_msg_box {
-        msg_box.send(ExplorerMsg::NewBlock(block)).await?;
+        msg_box
+            .send(ExplorerMsg::NewBlock(block))
+            .await
+            .unwrap_or_else(|err| tracing::error!("cannot add block to explorer: {}", err));
     }
 
     process_and_propagate_new_ref(Arc::clone(&new_block_ref), tip_update_mbox, network_msg_box)
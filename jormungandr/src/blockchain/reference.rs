@@ -2,6 +2,7 @@ use crate::blockcfg::{
     BlockDate, ChainLength, EpochRewardsInfo, Header, HeaderHash, Leadership, Ledger,
     LedgerParameters,
 };
+use crate::utils::clock;
 use chain_impl_mockchain::{multiverse, vote::VotePlanStatus};
 use chain_time::{
     era::{EpochPosition, EpochSlotOffset},
@@ -157,7 +158,7 @@ impl Ref {
     /// retrieve the time of the slot of the block. If the block is set
     /// in the future, this function will return an error.
     pub fn elapsed(&self) -> Result<Duration, std::time::SystemTimeError> {
-        SystemTime::now().duration_since(self.time())
+        clock::now().duration_since(self.time())
     }
 
     /// clone all active vote plans at this given state
@@ -14,4 +14,6 @@ pub enum ApiError {
     InvalidCursor(String),
     #[error("invalid address {0}")]
     InvalidAddress(String),
+    #[error("subscription lagged behind the indexing, some events were dropped: {0}")]
+    Lagged(String),
 }
@@ -4,7 +4,7 @@ mod connections;
 mod error;
 mod scalars;
 
-use self::config_param::{EpochStabilityDepth, LinearFee};
+use self::config_param::{ConfigParams, EpochStabilityDepth, LinearFee};
 use self::connections::{
     compute_interval, ConnectionFields, InclusivePaginationInterval, PaginationInterval,
     ValidatedPaginationArguments,
@@ -16,7 +16,8 @@ use self::scalars::{
     VotePlanStatusCount, Weight,
 };
 use super::indexing::{
-    BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerTransaction, StakePoolData,
+    BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerTransaction,
+    ExplorerUpdateProposal, ExplorerUpdateProposalStatus, StakePoolData,
 };
 use super::persistent_sequence::PersistentSequence;
 use crate::blockcfg::{self, FragmentId, HeaderHash};
@@ -24,7 +25,8 @@ use crate::explorer::indexing::ExplorerVote;
 use crate::explorer::{ExplorerDb, Settings as ChainSettings};
 use async_graphql::connection::{query, Connection, Edge, EmptyFields};
 use async_graphql::{
-    Context, EmptyMutation, FieldError, FieldResult, Object, SimpleObject, Subscription, Union,
+    Context, EmptyMutation, Enum, FieldError, FieldResult, Object, SimpleObject, Subscription,
+    Union,
 };
 use cardano_legacy_address::Addr as OldAddress;
 use certificates::*;
@@ -284,6 +286,34 @@ impl Branch {
         .await
     }
 
+    /// protocol update proposals made by the BFT leaders, by proposal date
+    pub async fn update_proposals(&self) -> Vec<UpdateProposalStatus> {
+        let mut update_proposals = self.state.state().get_update_proposals();
+
+        update_proposals.sort_unstable_by_key(|(_id, proposal)| proposal.proposal_date);
+
+        update_proposals
+            .into_iter()
+            .map(|(_id, proposal)| UpdateProposalStatus(proposal))
+            .collect()
+    }
+
+    /// the accepted update proposals whose changes are in effect from the
+    /// given epoch
+    pub async fn config_updates(&self, epoch: EpochNumber) -> Vec<UpdateProposalStatus> {
+        let mut update_proposals = self.state.state().get_update_proposals();
+
+        update_proposals.retain(|(_id, proposal)| {
+            proposal.status == ExplorerUpdateProposalStatus::Accepted(epoch.0)
+        });
+        update_proposals.sort_unstable_by_key(|(_id, proposal)| proposal.proposal_date);
+
+        update_proposals
+            .into_iter()
+            .map(|(_id, proposal)| UpdateProposalStatus(proposal))
+            .collect()
+    }
+
     pub async fn all_stake_pools(
         &self,
         first: Option<i32>,
@@ -967,6 +997,92 @@ impl Address {
     async fn delegation(&self, _context: &Context<'_>) -> FieldResult<Pool> {
         Err(ApiError::Unimplemented.into())
     }
+
+    /// Balance of the address after the given block on the branch of
+    /// that block, or on the main branch after the block at the given
+    /// chain length, or after the tip if neither is given. The rewards of account addresses are only
+    /// accounted for once the account is used in a transaction.
+    async fn balance(
+        &self,
+        context: &Context<'_>,
+        block: Option<String>,
+        chain_length: Option<ChainLength>,
+    ) -> FieldResult<Value> {
+        let db = extract_context(context).await.db;
+        let value = match (block, chain_length) {
+            (Some(_), Some(_)) => {
+                return Err(ApiError::ArgumentError(
+                    "block and chainLength can't be used together".to_owned(),
+                )
+                .into())
+            }
+            (Some(block), None) => {
+                let hash = HeaderHash::from_str(&block)?;
+                if let Some(state) = db.get_branch(&hash).await {
+                    state.state().balance(&self.id, None)
+                } else {
+                    // the state after the block is gone from the multiverse,
+                    // look the block up in the history of its own branch
+                    let (block, branch) = db
+                        .get_block_with_branch(&hash)
+                        .await
+                        .ok_or_else(|| ApiError::NotFound(format!("block {} not found", hash)))?;
+                    branch.state().balance(&self.id, Some(block.chain_length()))
+                }
+            }
+            (None, chain_length) => {
+                let (_hash, tip) = db.get_tip().await;
+                tip.state()
+                    .balance(&self.id, chain_length.map(|chain_length| chain_length.0))
+            }
+        };
+
+        Ok(Value(value))
+    }
+
+    /// Every change of the balance on the main branch, oldest first
+    async fn balance_history(&self, context: &Context<'_>) -> Vec<BalanceChange> {
+        let (_hash, tip) = extract_context(context).await.db.get_tip().await;
+        tip.state()
+            .balance_history(&self.id)
+            .into_iter()
+            .map(|balance| BalanceChange {
+                block: Block::from_valid_hash(balance.block),
+                chain_length: ChainLength(balance.chain_length),
+                balance: Value(balance.value),
+            })
+            .collect()
+    }
+
+    /// The outputs sent to the address and not spent yet on the main
+    /// branch. Always empty for account addresses, see `balance` instead
+    async fn unspent_outputs(&self, context: &Context<'_>) -> Vec<UnspentOutput> {
+        let (_hash, tip) = extract_context(context).await.db.get_tip().await;
+        let mut utxos = tip.state().unspent_outputs(&self.id);
+        utxos.sort_unstable_by_key(|(utxo, _value)| (utxo.transaction_id, utxo.output_index));
+        utxos
+            .into_iter()
+            .map(|(utxo, value)| UnspentOutput {
+                transaction: Transaction::from_valid_id(utxo.transaction_id),
+                index: utxo.output_index.into(),
+                amount: Value(value),
+            })
+            .collect()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct BalanceChange {
+    block: Block,
+    chain_length: ChainLength,
+    balance: Value,
+}
+
+#[derive(SimpleObject)]
+pub struct UnspentOutput {
+    transaction: Transaction,
+    index: i32,
+    amount: Value,
 }
 
 pub struct TaxType(chain_impl_mockchain::rewards::TaxType);
@@ -1520,6 +1636,97 @@ impl VoteProposalStatus {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Enum)]
+pub enum UpdateProposalState {
+    Pending,
+    Accepted,
+    Expired,
+}
+
+#[derive(SimpleObject)]
+pub struct UpdateVoteStatus {
+    voter: BftLeader,
+    date: BlockDate,
+}
+
+pub struct UpdateProposalStatus(Arc<ExplorerUpdateProposal>);
+
+impl UpdateProposalStatus {
+    async fn from_id(id: String, context: &Context<'_>) -> FieldResult<Self> {
+        let id = FragmentId::from_str(&id)?;
+        extract_context(context)
+            .await
+            .db
+            .get_update_proposal_by_id(&id)
+            .await
+            .map(UpdateProposalStatus)
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("Update proposal with id {} not found", id)).into()
+            })
+    }
+}
+
+#[Object]
+impl UpdateProposalStatus {
+    /// id of the fragment carrying the proposal
+    pub async fn id(&self) -> String {
+        format!("{}", self.0.id)
+    }
+
+    pub async fn proposer(&self) -> BftLeader {
+        self.0.proposer_id.clone().into()
+    }
+
+    pub async fn changes(&self) -> ConfigParams {
+        (&self.0.changes).into()
+    }
+
+    pub async fn proposal_date(&self) -> BlockDate {
+        self.0.proposal_date.into()
+    }
+
+    /// last epoch in which the proposal can be accepted, according to the
+    /// `proposal_expiration` setting at the time of the proposal
+    pub async fn expiration_epoch(&self) -> Epoch {
+        Epoch::from_epoch_number(self.0.expiration_epoch)
+    }
+
+    pub async fn votes(&self) -> Vec<UpdateVoteStatus> {
+        let mut votes: Vec<_> = self
+            .0
+            .votes
+            .iter()
+            .map(|(voter, date)| (voter.clone(), **date))
+            .collect();
+        votes.sort_unstable_by_key(|(_voter, date)| *date);
+
+        votes
+            .into_iter()
+            .map(|(voter, date)| UpdateVoteStatus {
+                voter: voter.into(),
+                date: date.into(),
+            })
+            .collect()
+    }
+
+    pub async fn state(&self) -> UpdateProposalState {
+        match self.0.status {
+            ExplorerUpdateProposalStatus::Pending => UpdateProposalState::Pending,
+            ExplorerUpdateProposalStatus::Accepted(_) => UpdateProposalState::Accepted,
+            ExplorerUpdateProposalStatus::Expired(_) => UpdateProposalState::Expired,
+        }
+    }
+
+    /// epoch at the start of which the proposal was accepted or expired
+    pub async fn settled_in(&self) -> Option<Epoch> {
+        match self.0.status {
+            ExplorerUpdateProposalStatus::Pending => None,
+            ExplorerUpdateProposalStatus::Accepted(epoch)
+            | ExplorerUpdateProposalStatus::Expired(epoch) => Some(Epoch::from_epoch_number(epoch)),
+        }
+    }
+}
+
 pub struct Query;
 
 #[Object]
@@ -1600,6 +1807,109 @@ impl Query {
     ) -> FieldResult<VotePlanStatus> {
         VotePlanStatus::vote_plan_from_id(VotePlanId(id), context).await
     }
+
+    pub async fn update_proposal(
+        &self,
+        context: &Context<'_>,
+        id: String,
+    ) -> FieldResult<UpdateProposalStatus> {
+        UpdateProposalStatus::from_id(id, context).await
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Enum)]
+pub enum StakePoolEventKind {
+    Registration,
+    Update,
+    Retirement,
+}
+
+pub struct StakePoolEvent {
+    kind: StakePoolEventKind,
+    pool: Pool,
+    block: HeaderHash,
+    transaction: ExplorerTransaction,
+}
+
+#[Object]
+impl StakePoolEvent {
+    pub async fn kind(&self) -> StakePoolEventKind {
+        self.kind
+    }
+
+    /// the pool, as of the block of the event
+    pub async fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    pub async fn block(&self) -> Block {
+        Block::from_valid_hash(self.block)
+    }
+
+    pub async fn transaction(&self) -> Transaction {
+        Transaction {
+            id: self.transaction.id(),
+            block_hashes: vec![self.block],
+            contents: Some(self.transaction.clone()),
+        }
+    }
+}
+
+/// Changes brought to a vote plan by a block
+pub struct VotePlanEvent {
+    id: certificate::VotePlanId,
+    block: HeaderHash,
+    vote_plan: Arc<super::indexing::ExplorerVotePlan>,
+    created: bool,
+    votes_cast: u32,
+    encrypted_tally: bool,
+    tallied: bool,
+}
+
+impl VotePlanEvent {
+    fn new(block: &ExplorerBlock, vote_plan: Arc<super::indexing::ExplorerVotePlan>) -> Self {
+        VotePlanEvent {
+            id: vote_plan.id.clone(),
+            block: block.id(),
+            vote_plan,
+            created: false,
+            votes_cast: 0,
+            encrypted_tally: false,
+            tallied: false,
+        }
+    }
+}
+
+#[Object]
+impl VotePlanEvent {
+    pub async fn block(&self) -> Block {
+        Block::from_valid_hash(self.block)
+    }
+
+    /// the status of the vote plan after the block
+    pub async fn vote_plan(&self) -> VotePlanStatus {
+        VotePlanStatus::vote_plan_from_data(Arc::clone(&self.vote_plan))
+    }
+
+    /// whether the vote plan was submitted in the block
+    pub async fn created(&self) -> bool {
+        self.created
+    }
+
+    /// number of votes cast in the block
+    pub async fn votes_cast(&self) -> i32 {
+        self.votes_cast as i32
+    }
+
+    /// whether the block starts the tally of the private votes
+    pub async fn encrypted_tally(&self) -> bool {
+        self.encrypted_tally
+    }
+
+    /// whether the block completes the tally
+    pub async fn tallied(&self) -> bool {
+        self.tallied
+    }
 }
 
 pub struct Subscription;
@@ -1622,6 +1932,176 @@ impl Subscription {
                     .map(|(hash, state)| Branch::from_id_and_state(hash, state))
             })
     }
+
+    /// blocks as they get indexed, on any branch
+    async fn blocks(
+        &self,
+        context: &Context<'_>,
+    ) -> impl futures::Stream<Item = FieldResult<Block>> {
+        use futures::StreamExt;
+        extract_context(context)
+            .await
+            .db
+            .block_subscription()
+            .map(|block| {
+                block
+                    .map(|(block, _state)| Block::from_contents(block))
+                    .map_err(lagged)
+            })
+    }
+
+    /// transactions with the given address among their inputs or outputs,
+    /// as their block gets indexed
+    async fn transactions_by_address(
+        &self,
+        context: &Context<'_>,
+        address_bech32: String,
+    ) -> FieldResult<impl futures::Stream<Item = FieldResult<Transaction>>> {
+        use futures::StreamExt;
+        let address = Address::from_bech32(&address_bech32)?.id;
+        Ok(extract_context(context)
+            .await
+            .db
+            .block_subscription()
+            .flat_map(move |block| {
+                let (block, _state) = match block {
+                    Ok(block) => block,
+                    Err(error) => return futures::stream::iter(vec![Err(lagged(error))]),
+                };
+                let mut transactions: Vec<_> = block
+                    .transactions
+                    .values()
+                    .filter(|tx| {
+                        tx.inputs().iter().any(|input| input.address == address)
+                            || tx.outputs().iter().any(|output| output.address == address)
+                    })
+                    .map(|tx| Transaction {
+                        id: tx.id(),
+                        block_hashes: vec![block.id()],
+                        contents: Some(tx.clone()),
+                    })
+                    .collect();
+                transactions
+                    .sort_unstable_by_key(|tx| tx.contents.as_ref().map(|tx| tx.offset_in_block));
+                futures::stream::iter(transactions.into_iter().map(Ok).collect::<Vec<_>>())
+            }))
+    }
+
+    /// registrations, updates and retirements of stake pools, all of them or
+    /// only the ones of the given pool
+    async fn stake_pool_events(
+        &self,
+        context: &Context<'_>,
+        id: Option<PoolId>,
+    ) -> impl futures::Stream<Item = FieldResult<StakePoolEvent>> {
+        use futures::StreamExt;
+        let id = id.map(|id| id.0);
+        extract_context(context)
+            .await
+            .db
+            .block_subscription()
+            .flat_map(move |block| {
+                let (block, state) = match block {
+                    Ok(block) => block,
+                    Err(error) => return futures::stream::iter(vec![Err(lagged(error))]),
+                };
+                let mut events = Vec::new();
+                let mut transactions: Vec<_> = block.transactions.values().collect();
+                transactions.sort_unstable_by_key(|tx| tx.offset_in_block);
+                for tx in transactions {
+                    let (kind, pool_id) = match &tx.certificate {
+                        Some(certificate::Certificate::PoolRegistration(registration)) => {
+                            (StakePoolEventKind::Registration, registration.to_id())
+                        }
+                        Some(certificate::Certificate::PoolUpdate(update)) => {
+                            (StakePoolEventKind::Update, update.pool_id.clone())
+                        }
+                        Some(certificate::Certificate::PoolRetirement(retirement)) => {
+                            (StakePoolEventKind::Retirement, retirement.pool_id.clone())
+                        }
+                        _ => continue,
+                    };
+                    if id.as_ref().map_or(false, |id| *id != pool_id) {
+                        continue;
+                    }
+                    let pool = match state.state().get_stake_pool(&pool_id) {
+                        Some(data) => Pool::new_with_data(pool_id, data),
+                        None => Pool::from_valid_id(pool_id),
+                    };
+                    events.push(Ok(StakePoolEvent {
+                        kind,
+                        pool,
+                        block: block.id(),
+                        transaction: tx.clone(),
+                    }));
+                }
+                futures::stream::iter(events)
+            })
+    }
+
+    /// status of the vote plans changed by a block as it gets indexed: new
+    /// vote plans, votes and tallies. All of them or only the given one
+    async fn vote_plan_events(
+        &self,
+        context: &Context<'_>,
+        id: Option<VotePlanId>,
+    ) -> FieldResult<impl futures::Stream<Item = FieldResult<VotePlanEvent>>> {
+        use futures::StreamExt;
+        let id = id
+            .map(|id| chain_impl_mockchain::certificate::VotePlanId::from_str(&id.0))
+            .transpose()
+            .map_err(|err| -> FieldError { ApiError::InvalidAddress(err.to_string()).into() })?;
+        Ok(extract_context(context)
+            .await
+            .db
+            .block_subscription()
+            .flat_map(move |block| {
+                let (block, state) = match block {
+                    Ok(block) => block,
+                    Err(error) => return futures::stream::iter(vec![Err(lagged(error))]),
+                };
+                let mut events: Vec<VotePlanEvent> = Vec::new();
+                let mut transactions: Vec<_> = block.transactions.values().collect();
+                transactions.sort_unstable_by_key(|tx| tx.offset_in_block);
+                for tx in transactions {
+                    let vote_plan_id = match &tx.certificate {
+                        Some(certificate::Certificate::VotePlan(vote_plan)) => vote_plan.to_id(),
+                        Some(certificate::Certificate::VoteCast(vote_cast)) => {
+                            vote_cast.vote_plan().clone()
+                        }
+                        Some(certificate::Certificate::EncryptedVoteTally(tally)) => {
+                            tally.id().clone()
+                        }
+                        Some(certificate::Certificate::VoteTally(tally)) => tally.id().clone(),
+                        _ => continue,
+                    };
+                    if id.as_ref().map_or(false, |id| *id != vote_plan_id) {
+                        continue;
+                    }
+                    let index = match events.iter().position(|e| e.id == vote_plan_id) {
+                        Some(index) => index,
+                        None => match state.state().get_vote_plan(&vote_plan_id) {
+                            Some(vote_plan) => {
+                                events.push(VotePlanEvent::new(&block, vote_plan));
+                                events.len() - 1
+                            }
+                            None => continue,
+                        },
+                    };
+                    let event = &mut events[index];
+                    match &tx.certificate {
+                        Some(certificate::Certificate::VotePlan(_)) => event.created = true,
+                        Some(certificate::Certificate::VoteCast(_)) => event.votes_cast += 1,
+                        Some(certificate::Certificate::EncryptedVoteTally(_)) => {
+                            event.encrypted_tally = true
+                        }
+                        Some(certificate::Certificate::VoteTally(_)) => event.tallied = true,
+                        _ => (),
+                    }
+                }
+                futures::stream::iter(events.into_iter().map(Ok).collect::<Vec<_>>())
+            }))
+    }
 }
 
 pub type Schema = async_graphql::Schema<Query, EmptyMutation, Subscription>;
@@ -1634,3 +2114,10 @@ pub struct EContext {
 async fn extract_context(context: &Context<'_>) -> EContext {
     context.data_unchecked::<RestContext>().get().await.unwrap()
 }
+
+/// a subscriber that doesn't keep up with the indexing misses events, tell
+/// it instead of leaving silent gaps in the stream
+fn lagged(error: tokio_stream::wrappers::errors::BroadcastStreamRecvError) -> FieldError {
+    tracing::warn!(%error, "explorer subscriber lagged behind, events were dropped");
+    ApiError::Lagged(error.to_string()).into()
+}
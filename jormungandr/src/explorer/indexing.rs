@@ -2,14 +2,17 @@ use super::persistent_sequence::PersistentSequence;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 
-use crate::blockcfg::{Block, BlockDate, ChainLength, Epoch, Fragment, FragmentId, HeaderHash};
+use crate::blockcfg::{
+    Block, BlockDate, ChainLength, ConfigParams, Epoch, Fragment, FragmentId, HeaderHash,
+};
 use cardano_legacy_address::Addr as OldAddress;
 use chain_addr::{Address, Discrimination};
 use chain_core::property::Block as _;
 use chain_core::property::Fragment as _;
 use chain_impl_mockchain::block::Proof;
 use chain_impl_mockchain::certificate::{
-    Certificate, ExternalProposalId, PoolId, PoolRegistration, PoolRetirement, VotePlanId,
+    Certificate, ExternalProposalId, PoolId, PoolRegistration, PoolRetirement, UpdateProposalId,
+    VotePlanId,
 };
 use chain_impl_mockchain::key::BftLeaderId;
 use chain_impl_mockchain::transaction::{InputEnum, TransactionSlice, Witness};
@@ -33,6 +36,11 @@ pub type StakePool = Hamt<PoolId, StakePoolData>;
 
 pub type VotePlans = Hamt<VotePlanId, ExplorerVotePlan>;
 
+pub type UpdateProposals = Hamt<UpdateProposalId, ExplorerUpdateProposal>;
+
+pub type Balances = Hamt<ExplorerAddress, PersistentSequence<ExplorerBalance>>;
+pub type UnspentOutputs = Hamt<ExplorerAddress, Hamt<ExplorerUtxoPointer, Value>>;
+
 #[derive(Clone)]
 pub struct StakePoolData {
     pub registration: PoolRegistration,
@@ -75,6 +83,22 @@ pub struct ExplorerTransaction {
 pub struct ExplorerInput {
     pub address: ExplorerAddress,
     pub value: Value,
+    /// the output spent, for utxo inputs
+    pub utxo: Option<ExplorerUtxoPointer>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExplorerUtxoPointer {
+    pub transaction_id: FragmentId,
+    pub output_index: u8,
+}
+
+/// Balance of an address after the given block
+#[derive(Clone)]
+pub struct ExplorerBalance {
+    pub block: HeaderHash,
+    pub chain_length: ChainLength,
+    pub value: Value,
 }
 
 #[derive(Clone)]
@@ -96,6 +120,20 @@ pub enum ExplorerAddress {
     Old(OldAddress),
 }
 
+impl ExplorerAddress {
+    /// whether the funds sent to this address are held in an account rather
+    /// than in unspent outputs
+    pub fn is_account(&self) -> bool {
+        matches!(
+            self,
+            ExplorerAddress::New(Address(
+                _,
+                chain_addr::Kind::Account(_) | chain_addr::Kind::Multisig(_)
+            ))
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct ExplorerVotePlan {
     pub id: VotePlanId,
@@ -136,6 +174,31 @@ pub enum ExplorerVoteTally {
     },
 }
 
+/// Protocol update proposed by a BFT leader, the proposal id is the id of the
+/// fragment carrying it
+#[derive(Clone)]
+pub struct ExplorerUpdateProposal {
+    pub id: UpdateProposalId,
+    pub proposer_id: BftLeaderId,
+    pub changes: ConfigParams,
+    pub proposal_date: BlockDate,
+    /// last epoch in which the proposal can gather the votes it needs, per
+    /// the `proposal_expiration` setting at the time of the proposal
+    pub expiration_epoch: Epoch,
+    pub votes: Hamt<BftLeaderId, BlockDate>,
+    pub status: ExplorerUpdateProposalStatus,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerUpdateProposalStatus {
+    Pending,
+    /// voted by a majority of the BFT leaders, the changes are in effect
+    /// from the given epoch
+    Accepted(Epoch),
+    /// discarded at the start of the given epoch without enough votes
+    Expired(Epoch),
+}
+
 pub struct ExplorerBlockBuildingContext<'a> {
     pub discrimination: Discrimination,
     pub prev_transactions: &'a Transactions,
@@ -260,6 +323,39 @@ impl ExplorerBlock {
                             &current_block_txs,
                         ))
                     }
+                    Fragment::EncryptedVoteTally(tx) => {
+                        let tx = tx.as_slice();
+                        Some(ExplorerTransaction::from(
+                            &context,
+                            &fragment_id,
+                            &tx,
+                            Some(Certificate::EncryptedVoteTally(tx.payload().into_payload())),
+                            offset,
+                            &current_block_txs,
+                        ))
+                    }
+                    Fragment::UpdateProposal(tx) => {
+                        let tx = tx.as_slice();
+                        Some(ExplorerTransaction::from(
+                            &context,
+                            &fragment_id,
+                            &tx,
+                            Some(Certificate::UpdateProposal(tx.payload().into_payload())),
+                            offset,
+                            &current_block_txs,
+                        ))
+                    }
+                    Fragment::UpdateVote(tx) => {
+                        let tx = tx.as_slice();
+                        Some(ExplorerTransaction::from(
+                            &context,
+                            &fragment_id,
+                            &tx,
+                            Some(Certificate::UpdateVote(tx.payload().into_payload())),
+                            offset,
+                            &current_block_txs,
+                        ))
+                    }
                     Fragment::OldUtxoDeclaration(decl) => {
                         let outputs = decl
                             .addrs
@@ -380,7 +476,11 @@ impl ExplorerTransaction {
                             .into(),
                     );
                     let address = ExplorerAddress::New(Address(context.discrimination, kind));
-                    Some(ExplorerInput { address, value })
+                    Some(ExplorerInput {
+                        address,
+                        value,
+                        utxo: None,
+                    })
                 }
                 (InputEnum::AccountInput(id, value), Witness::Multisig(_, _)) => {
                     let kind = chain_addr::Kind::Multisig(
@@ -390,7 +490,11 @@ impl ExplorerTransaction {
                             .expect("multisig identifier size doesn't match address kind"),
                     );
                     let address = ExplorerAddress::New(Address(context.discrimination, kind));
-                    Some(ExplorerInput { address, value })
+                    Some(ExplorerInput {
+                        address,
+                        value,
+                        utxo: None,
+                    })
                 }
                 (InputEnum::UtxoInput(utxo_pointer), _witness) => {
                     let tx = utxo_pointer.transaction_id;
@@ -415,6 +519,10 @@ impl ExplorerTransaction {
                     Some(ExplorerInput {
                         address: output.address.clone(),
                         value: output.value,
+                        utxo: Some(ExplorerUtxoPointer {
+                            transaction_id: tx,
+                            output_index: index,
+                        }),
                     })
                 }
                 _ => None,
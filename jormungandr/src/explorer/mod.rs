@@ -7,9 +7,10 @@ mod persistent_sequence;
 use self::error::{ExplorerError as Error, Result};
 use self::graphql::EContext;
 use self::indexing::{
-    Addresses, Blocks, ChainLengths, EpochData, Epochs, ExplorerAddress, ExplorerBlock,
-    ExplorerVotePlan, ExplorerVoteProposal, ExplorerVoteTally, StakePool, StakePoolBlocks,
-    StakePoolData, Transactions, VotePlans,
+    Addresses, Balances, Blocks, ChainLengths, EpochData, Epochs, ExplorerAddress, ExplorerBalance,
+    ExplorerBlock, ExplorerUpdateProposal, ExplorerUpdateProposalStatus, ExplorerUtxoPointer,
+    ExplorerVotePlan, ExplorerVoteProposal, ExplorerVoteTally, Hamt, StakePool, StakePoolBlocks,
+    StakePoolData, Transactions, UnspentOutputs, UpdateProposals, VotePlans,
 };
 use self::persistent_sequence::PersistentSequence;
 use tracing::{span, Level};
@@ -26,17 +27,23 @@ use crate::utils::async_msg::MessageQueue;
 use crate::utils::task::TokioServiceInfo;
 use chain_addr::Discrimination;
 use chain_core::property::Block as _;
-use chain_impl_mockchain::certificate::{Certificate, PoolId, VotePlanId};
+use chain_impl_mockchain::certificate::{Certificate, PoolId, UpdateProposalId, VotePlanId};
 use chain_impl_mockchain::fee::LinearFee;
+use chain_impl_mockchain::value::Value;
 use futures::prelude::*;
 use multiverse::Multiverse;
-use std::convert::Infallible;
+use std::collections::HashSet;
+use std::convert::{Infallible, TryInto};
 use std::sync::{
     atomic::{AtomicU32, Ordering},
     Arc,
 };
 use tokio::sync::{broadcast, Mutex, RwLock};
 
+/// number of indexed blocks a slow subscriber can lag behind before
+/// missing some
+const BLOCK_BROADCAST_CAPACITY: usize = 64;
+
 #[derive(Clone)]
 pub struct Explorer {
     pub db: ExplorerDb,
@@ -59,6 +66,8 @@ pub struct ExplorerDb {
     blockchain: Blockchain,
     stable_store: StableIndex,
     tip_broadcast: tokio::sync::broadcast::Sender<(HeaderHash, multiverse::Ref)>,
+    /// every block indexed, with the state of its branch after applying it
+    block_broadcast: tokio::sync::broadcast::Sender<(Arc<ExplorerBlock>, multiverse::Ref)>,
 }
 
 #[derive(Clone)]
@@ -90,6 +99,9 @@ pub(self) struct State {
     stake_pool_data: StakePool,
     stake_pool_blocks: StakePoolBlocks,
     vote_plans: VotePlans,
+    update_proposals: UpdateProposals,
+    balances: Balances,
+    unspent_outputs: UnspentOutputs,
 }
 
 #[derive(Clone)]
@@ -217,6 +229,10 @@ impl ExplorerDb {
             .map_err(Box::new)?
             .ok_or(Error::BlockNotFound(block_id))?;
         let vote_plans = apply_block_to_vote_plans(VotePlans::new(), &block_ref, &block);
+        let update_proposals =
+            apply_block_to_update_proposals(UpdateProposals::new(), &block_ref, &block);
+        let (balances, unspent_outputs) =
+            apply_block_to_balances(Balances::new(), UnspentOutputs::new(), &block_ref, &block);
 
         let initial_state = State {
             transactions,
@@ -227,6 +243,9 @@ impl ExplorerDb {
             stake_pool_data,
             stake_pool_blocks,
             vote_plans,
+            update_proposals,
+            balances,
+            unspent_outputs,
         };
 
         let block0_id = block0.id();
@@ -245,6 +264,7 @@ impl ExplorerDb {
         };
 
         let (tx, _) = broadcast::channel(10);
+        let (block_tx, _) = broadcast::channel(BLOCK_BROADCAST_CAPACITY);
 
         let bootstraped_db = ExplorerDb {
             multiverse,
@@ -255,6 +275,7 @@ impl ExplorerDb {
                 confirmed_block_chain_length: Arc::new(AtomicU32::default()),
             },
             tip_broadcast: tx,
+            block_broadcast: block_tx,
         };
 
         let db = stream
@@ -291,7 +312,7 @@ impl ExplorerDb {
     /// chain length is greater than the current.
     /// This doesn't perform any validation on the given block and the previous state, it
     /// is assumed that the Block is valid
-    async fn apply_block(&self, block: Block) -> Result<multiverse::Ref> {
+    pub(crate) async fn apply_block(&self, block: Block) -> Result<multiverse::Ref> {
         let previous_block = block.header().block_parent_hash();
         let chain_length = block.header().chain_length();
         let block_id = block.header().hash();
@@ -311,6 +332,9 @@ impl ExplorerDb {
             stake_pool_data,
             stake_pool_blocks,
             vote_plans,
+            update_proposals,
+            balances,
+            unspent_outputs,
         } = previous_state.state().clone();
 
         let explorer_block = ExplorerBlock::resolve_from(
@@ -331,6 +355,10 @@ impl ExplorerDb {
             .map_err(Box::new)?
             .ok_or(Error::BlockNotFound(block_id))?;
         let vote_plans = apply_block_to_vote_plans(vote_plans, &block_ref, &explorer_block);
+        let update_proposals =
+            apply_block_to_update_proposals(update_proposals, &block_ref, &explorer_block);
+        let (balances, unspent_outputs) =
+            apply_block_to_balances(balances, unspent_outputs, &block_ref, &explorer_block);
 
         let state_ref = multiverse
             .insert(
@@ -346,9 +374,16 @@ impl ExplorerDb {
                     stake_pool_data,
                     stake_pool_blocks,
                     vote_plans,
+                    update_proposals,
+                    balances,
+                    unspent_outputs,
                 },
             )
             .await;
+
+        let block = Arc::clone(state_ref.state().blocks.lookup(&block_id).unwrap());
+        let _ = self.block_broadcast.send((block, state_ref.clone()));
+
         Ok(state_ref)
     }
 
@@ -362,6 +397,21 @@ impl ExplorerDb {
         None
     }
 
+    /// the block with the given id and the state of a branch going
+    /// through it
+    pub(self) async fn get_block_with_branch(
+        &self,
+        block_id: &HeaderHash,
+    ) -> Option<(Arc<ExplorerBlock>, multiverse::Ref)> {
+        for (_hash, state_ref) in self.multiverse.tips().await.into_iter() {
+            if let Some(block) = state_ref.state().blocks.lookup(block_id).cloned() {
+                return Some((block, state_ref));
+            }
+        }
+
+        None
+    }
+
     pub(self) async fn set_tip(&self, hash: HeaderHash) -> bool {
         // the tip changes which means now a block is confirmed (at least after
         // the initial epoch_stability_depth blocks).
@@ -536,6 +586,19 @@ impl ExplorerDb {
         None
     }
 
+    pub async fn get_update_proposal_by_id(
+        &self,
+        proposal_id: &UpdateProposalId,
+    ) -> Option<Arc<ExplorerUpdateProposal>> {
+        for (_hash, state_ref) in self.multiverse.tips().await.iter() {
+            if let Some(b) = state_ref.state().update_proposals.lookup(proposal_id) {
+                return Some(Arc::clone(b));
+            }
+        }
+
+        None
+    }
+
     pub(self) async fn get_branch(&self, hash: &HeaderHash) -> Option<multiverse::Ref> {
         self.multiverse.get_ref(hash).await
     }
@@ -563,6 +626,17 @@ impl ExplorerDb {
     > {
         tokio_stream::wrappers::BroadcastStream::new(self.tip_broadcast.subscribe())
     }
+
+    pub(self) fn block_subscription(
+        &self,
+    ) -> impl Stream<
+        Item = std::result::Result<
+            (Arc<ExplorerBlock>, multiverse::Ref),
+            tokio_stream::wrappers::errors::BroadcastStreamRecvError,
+        >,
+    > {
+        tokio_stream::wrappers::BroadcastStream::new(self.block_broadcast.subscribe())
+    }
 }
 
 fn apply_block_to_transactions(
@@ -863,6 +937,237 @@ fn apply_block_to_vote_plans(
     vote_plans
 }
 
+fn apply_block_to_update_proposals(
+    mut update_proposals: UpdateProposals,
+    block_ref: &Arc<blockchain::Ref>,
+    block: &ExplorerBlock,
+) -> UpdateProposals {
+    // like the ledger, settle the pending proposals when entering a new
+    // epoch, with the settings of the last block of the previous epoch
+    if let Some(previous) = block_ref
+        .last_ref_previous_epoch()
+        .filter(|previous| previous.hash() == block.parent_hash)
+    {
+        let ledger = previous.ledger();
+        let settings = ledger.settings();
+        let epoch = block.date().epoch;
+        let settled: Vec<_> = update_proposals
+            .iter()
+            .filter(|(_, proposal)| proposal.status == ExplorerUpdateProposalStatus::Pending)
+            .filter_map(|(id, proposal)| {
+                let status = if proposal.votes.size() > settings.bft_leaders.len() / 2 {
+                    ExplorerUpdateProposalStatus::Accepted(epoch)
+                } else if proposal.proposal_date.epoch + settings.proposal_expiration < epoch {
+                    ExplorerUpdateProposalStatus::Expired(epoch)
+                } else {
+                    return None;
+                };
+                Some((
+                    id.clone(),
+                    ExplorerUpdateProposal {
+                        status,
+                        ..(**proposal).clone()
+                    },
+                ))
+            })
+            .collect();
+
+        for (id, proposal) in settled {
+            update_proposals = update_proposals
+                .update(&id, |_| Ok::<_, Infallible>(Some(Arc::new(proposal))))
+                .unwrap();
+        }
+    }
+
+    for tx in block.transactions.values() {
+        if let Some(cert) = &tx.certificate {
+            update_proposals = match cert {
+                Certificate::UpdateProposal(update_proposal) => {
+                    let proposal_expiration = block_ref.ledger().settings().proposal_expiration;
+                    match update_proposals.insert(
+                        tx.id(),
+                        Arc::new(ExplorerUpdateProposal {
+                            id: tx.id(),
+                            proposer_id: update_proposal.proposer_id().clone(),
+                            changes: update_proposal.changes().clone(),
+                            proposal_date: block.date(),
+                            expiration_epoch: block.date().epoch + proposal_expiration,
+                            votes: Default::default(),
+                            status: ExplorerUpdateProposalStatus::Pending,
+                        }),
+                    ) {
+                        Ok(update_proposals) => update_proposals,
+                        Err(_) => {
+                            tracing::warn!(
+                                proposal = %tx.id(),
+                                block = %block.id(),
+                                "update proposal was submitted more than once, skipping it"
+                            );
+                            update_proposals
+                        }
+                    }
+                }
+                Certificate::UpdateVote(update_vote) => match update_proposals
+                    .update::<_, Infallible>(update_vote.proposal_id(), |proposal| {
+                        let votes = proposal.votes.insert_or_update_simple(
+                            update_vote.voter_id().clone(),
+                            Arc::new(block.date()),
+                            |date| Some(Arc::clone(date)),
+                        );
+                        Ok(Some(Arc::new(ExplorerUpdateProposal {
+                            votes,
+                            ..(**proposal).clone()
+                        })))
+                    }) {
+                    Ok(update_proposals) => update_proposals,
+                    Err(_) => {
+                        tracing::warn!(
+                            proposal = %update_vote.proposal_id(),
+                            block = %block.id(),
+                            "vote for an unknown update proposal, skipping it"
+                        );
+                        update_proposals
+                    }
+                },
+                _ => update_proposals,
+            }
+        }
+    }
+
+    update_proposals
+}
+
+fn apply_block_to_balances(
+    mut balances: Balances,
+    mut unspent_outputs: UnspentOutputs,
+    block_ref: &Arc<blockchain::Ref>,
+    block: &ExplorerBlock,
+) -> (Balances, UnspentOutputs) {
+    let mut transactions: Vec<_> = block.transactions.values().collect();
+    // outputs can be spent by a later transaction of the same block
+    transactions.sort_unstable_by_key(|tx| tx.offset_in_block);
+
+    let mut credits = std::collections::HashMap::<ExplorerAddress, u64>::new();
+    let mut debits = std::collections::HashMap::<ExplorerAddress, u64>::new();
+
+    for tx in transactions {
+        for input in tx.inputs() {
+            *debits.entry(input.address.clone()).or_default() += input.value.0;
+
+            if let Some(utxo) = &input.utxo {
+                match unspent_outputs.update(&input.address, |utxos| {
+                    utxos.remove(utxo).map(|utxos| {
+                        if utxos.is_empty() {
+                            None
+                        } else {
+                            Some(Arc::new(utxos))
+                        }
+                    })
+                }) {
+                    Ok(updated) => unspent_outputs = updated,
+                    Err(_) => tracing::warn!(
+                        transaction = %tx.id(),
+                        block = %block.id(),
+                        "spent output is not indexed as unspent, skipping it"
+                    ),
+                }
+            }
+        }
+
+        for (index, output) in tx.outputs().iter().enumerate() {
+            *credits.entry(output.address.clone()).or_default() += output.value.0;
+
+            if output.address.is_account() {
+                continue;
+            }
+            let output_index = match index.try_into() {
+                Ok(output_index) => output_index,
+                Err(_) => {
+                    tracing::warn!(
+                        transaction = %tx.id(),
+                        block = %block.id(),
+                        index,
+                        "output index does not fit in a byte, not indexing the output as unspent"
+                    );
+                    continue;
+                }
+            };
+            let utxo = ExplorerUtxoPointer {
+                transaction_id: tx.id(),
+                output_index,
+            };
+            let value = Arc::new(output.value);
+            unspent_outputs = unspent_outputs.insert_or_update_simple(
+                output.address.clone(),
+                Arc::new(Hamt::new().insert(utxo, Arc::clone(&value)).unwrap()),
+                |utxos| match utxos.insert(utxo, value) {
+                    Ok(utxos) => Some(Arc::new(utxos)),
+                    Err(_) => {
+                        tracing::warn!(
+                            transaction = %tx.id(),
+                            block = %block.id(),
+                            index,
+                            "output was indexed more than once, skipping it"
+                        );
+                        Some(Arc::clone(utxos))
+                    }
+                },
+            );
+        }
+    }
+
+    let touched: HashSet<&ExplorerAddress> = credits.keys().chain(debits.keys()).collect();
+    let ledger = block_ref.ledger();
+
+    for address in touched {
+        let previous = balances
+            .lookup(address)
+            .and_then(|history| history.len().checked_sub(1).and_then(|i| history.get(i)))
+            .map(|balance| balance.value);
+
+        let value = match address {
+            ExplorerAddress::New(chain_addr::Address(_, chain_addr::Kind::Account(key))) => {
+                // the ledger also knows about the rewards
+                ledger
+                    .accounts()
+                    .get_state(&key.clone().into())
+                    .map(|state| state.value())
+                    .unwrap_or_else(|_| Value::zero())
+            }
+            address if address.is_account() => Value(
+                previous
+                    .unwrap_or_else(Value::zero)
+                    .0
+                    .saturating_add(credits.get(address).copied().unwrap_or(0))
+                    .saturating_sub(debits.get(address).copied().unwrap_or(0)),
+            ),
+            address => Value(
+                unspent_outputs
+                    .lookup(address)
+                    .map(|utxos| utxos.iter().map(|(_, value)| value.0).sum())
+                    .unwrap_or(0),
+            ),
+        };
+
+        if previous == Some(value) {
+            continue;
+        }
+
+        let balance = ExplorerBalance {
+            block: block.id(),
+            chain_length: block.chain_length(),
+            value,
+        };
+        balances = balances.insert_or_update_simple(
+            address.clone(),
+            Arc::new(PersistentSequence::new().append(balance.clone())),
+            |history| Some(Arc::new(history.append(balance))),
+        );
+    }
+
+    (balances, unspent_outputs)
+}
+
 impl BlockchainConfig {
     fn from_config_params(params: &ConfigParams) -> BlockchainConfig {
         let mut discrimination: Option<Discrimination> = None;
@@ -915,6 +1220,67 @@ impl State {
             .collect()
     }
 
+    pub fn get_update_proposals(&self) -> Vec<(UpdateProposalId, Arc<ExplorerUpdateProposal>)> {
+        self.update_proposals
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    pub fn get_vote_plan(&self, vote_plan_id: &VotePlanId) -> Option<Arc<ExplorerVotePlan>> {
+        self.vote_plans.lookup(vote_plan_id).map(Arc::clone)
+    }
+
+    pub fn get_stake_pool(&self, pool: &PoolId) -> Option<Arc<StakePoolData>> {
+        self.stake_pool_data.lookup(pool).map(Arc::clone)
+    }
+
+    /// balance of the address after the block of the given chain length on
+    /// this branch, or after the last block if `None`
+    pub fn balance(&self, address: &ExplorerAddress, chain_length: Option<ChainLength>) -> Value {
+        let history = match self.balances.lookup(address) {
+            Some(history) => history,
+            None => return Value::zero(),
+        };
+
+        // number of balance changes up to `chain_length`
+        let mut low = 0;
+        let mut high = history.len();
+        if let Some(chain_length) = chain_length {
+            while low < high {
+                let middle = (low + high) / 2;
+                if history.get(middle).unwrap().chain_length <= chain_length {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+        } else {
+            low = high;
+        }
+
+        low.checked_sub(1)
+            .map_or_else(Value::zero, |index| history.get(index).unwrap().value)
+    }
+
+    pub fn balance_history(&self, address: &ExplorerAddress) -> Vec<ExplorerBalance> {
+        self.balances
+            .lookup(address)
+            .map(|history| {
+                (0..history.len())
+                    .map(|index| history.get(index).unwrap().as_ref().clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn unspent_outputs(&self, address: &ExplorerAddress) -> Vec<(ExplorerUtxoPointer, Value)> {
+        self.unspent_outputs
+            .lookup(address)
+            .map(|utxos| utxos.iter().map(|(utxo, value)| (*utxo, **value)).collect())
+            .unwrap_or_default()
+    }
+
     pub fn get_stake_pools(&self) -> Vec<(PoolId, Arc<StakePoolData>)> {
         self.stake_pool_data
             .iter()
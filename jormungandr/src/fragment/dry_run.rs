@@ -0,0 +1,274 @@
+//! Apply fragments to a copy of the tip ledger, to tell clients what would
+//! happen to them without adding them to the mempool.
+
+use crate::{
+    blockchain::{new_epoch_leadership_from, EpochLeadership, Ref},
+    fragment::{
+        pool::{get_current_block_date, get_transaction_expiry_date},
+        Fragment, FragmentId,
+    },
+};
+use chain_addr::{Address, Discrimination, Kind};
+use chain_impl_mockchain::{
+    certificate::{Certificate, CertificatePayload},
+    fee::{FeeAlgorithm, LinearFee},
+    ledger::{
+        self,
+        check::{valid_transaction_date, TxValidityError},
+        Ledger,
+    },
+    transaction::{InputEnum, Output, Payload, Transaction},
+    value::Value,
+};
+use jormungandr_lib::interfaces::{
+    BalanceChange, FragmentExpiry, FragmentSimulation, FragmentsSimulation,
+};
+use std::{collections::HashMap, convert::TryInto, error::Error as _, iter, sync::Arc};
+
+/// Apply the fragments in order to the ledger of `tip`, as if they were the
+/// content of the next block. A fragment the ledger rejects is skipped and
+/// the next ones are applied to the ledger left by the previous fragments.
+/// With `fail_fast`, the fragments following a rejected one are not applied.
+///
+/// When the current date is in a later epoch than the tip, the fragments are
+/// applied to the ledger of that epoch, built like the leadership task
+/// builds it for a new block, with the fees of that epoch.
+pub fn dry_run(
+    tip: Arc<Ref>,
+    fragments: Vec<Fragment>,
+    fail_fast: bool,
+) -> Result<FragmentsSimulation, ledger::Error> {
+    let block_date = get_current_block_date(&tip);
+    let tip_hash = tip.hash();
+    let chain_length = tip.chain_length().increase();
+    let EpochLeadership {
+        state: epoch_ledger,
+        ledger_parameters,
+        ..
+    } = new_epoch_leadership_from(block_date.epoch, tip, false);
+    let fees = ledger_parameters.fees;
+    let discrimination = epoch_ledger.get_static_parameters().discrimination;
+    let mut ledger = epoch_ledger.begin_block(chain_length, block_date)?;
+
+    // outputs of the fragments applied so far, spendable by the next ones
+    let mut new_outputs = HashMap::new();
+    let mut rejected = false;
+    let mut simulations = Vec::with_capacity(fragments.len());
+    for fragment in fragments {
+        let fragment_id = fragment.id();
+        let valid_until = get_transaction_expiry_date(&fragment);
+        let expiry = match valid_until {
+            None => FragmentExpiry::None,
+            Some(valid_until) => {
+                match valid_transaction_date(epoch_ledger.settings(), valid_until, block_date) {
+                    Ok(()) => FragmentExpiry::Valid,
+                    Err(TxValidityError::TransactionExpired) => FragmentExpiry::Expired,
+                    Err(TxValidityError::TransactionValidForTooLong) => {
+                        FragmentExpiry::ValidForTooLong
+                    }
+                }
+            }
+        };
+        let moves = Moves::of(&fragment, &fees);
+
+        let error = if rejected && fail_fast {
+            Some("not applied, a previous fragment of the batch was rejected".to_owned())
+        } else {
+            match ledger.apply_fragment(&fragment) {
+                Ok(new_ledger) => {
+                    ledger = new_ledger;
+                    None
+                }
+                Err(error) => Some(error_chain(&error)),
+            }
+        };
+
+        let balance_changes = match (&error, &moves) {
+            (None, Some(moves)) => {
+                let changes = moves.balance_changes(discrimination, &epoch_ledger, &new_outputs);
+                for (index, output) in moves.outputs.iter().enumerate() {
+                    new_outputs.insert((fragment_id, index as u8), output.clone());
+                }
+                changes
+            }
+            _ => Vec::new(),
+        };
+        rejected |= error.is_some();
+
+        simulations.push(FragmentSimulation {
+            fragment_id,
+            fee: moves.as_ref().and_then(|moves| moves.fee).map(Into::into),
+            expected_fee: moves.as_ref().map(|moves| moves.expected_fee.into()),
+            valid_until: valid_until.map(Into::into),
+            expiry,
+            error,
+            balance_changes,
+        });
+    }
+
+    Ok(FragmentsSimulation {
+        tip: tip_hash,
+        block_date: block_date.into(),
+        fragments: simulations,
+    })
+}
+
+/// the funds a transaction moves
+struct Moves {
+    fee: Option<Value>,
+    expected_fee: Value,
+    inputs: Vec<InputEnum>,
+    outputs: Vec<Output<Address>>,
+}
+
+impl Moves {
+    fn of(fragment: &Fragment, fees: &LinearFee) -> Option<Self> {
+        match fragment {
+            Fragment::Initial(_) | Fragment::OldUtxoDeclaration(_) => None,
+            Fragment::Transaction(tx) => Some(Self::new(tx, None, fees)),
+            Fragment::OwnerStakeDelegation(tx) => {
+                let certificate =
+                    Certificate::OwnerStakeDelegation(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::StakeDelegation(tx) => {
+                let certificate =
+                    Certificate::StakeDelegation(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::PoolRegistration(tx) => {
+                let certificate =
+                    Certificate::PoolRegistration(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::PoolRetirement(tx) => {
+                let certificate =
+                    Certificate::PoolRetirement(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::PoolUpdate(tx) => {
+                let certificate = Certificate::PoolUpdate(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::UpdateProposal(tx) => {
+                let certificate =
+                    Certificate::UpdateProposal(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::UpdateVote(tx) => {
+                let certificate = Certificate::UpdateVote(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::VotePlan(tx) => {
+                let certificate = Certificate::VotePlan(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::VoteCast(tx) => {
+                let certificate = Certificate::VoteCast(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::VoteTally(tx) => {
+                let certificate = Certificate::VoteTally(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+            Fragment::EncryptedVoteTally(tx) => {
+                let certificate =
+                    Certificate::EncryptedVoteTally(tx.as_slice().payload().into_payload());
+                Some(Self::new(tx, Some(certificate), fees))
+            }
+        }
+    }
+
+    fn new<P: Payload>(
+        tx: &Transaction<P>,
+        certificate: Option<Certificate>,
+        fees: &LinearFee,
+    ) -> Self {
+        let payload = certificate.as_ref().map(CertificatePayload::from);
+        let expected_fee = fees.calculate(
+            payload.as_ref().map(|payload| payload.as_slice()),
+            tx.nb_inputs(),
+            tx.nb_outputs(),
+        );
+        let fee = tx
+            .total_input()
+            .and_then(|input| tx.total_output().and_then(|output| input - output))
+            .ok();
+        let tx = tx.as_slice();
+        Moves {
+            fee,
+            expected_fee,
+            inputs: tx.inputs().iter().map(|input| input.to_enum()).collect(),
+            outputs: tx.outputs().iter().collect(),
+        }
+    }
+
+    fn balance_changes(
+        &self,
+        discrimination: Discrimination,
+        ledger: &Ledger,
+        new_outputs: &HashMap<(FragmentId, u8), Output<Address>>,
+    ) -> Vec<BalanceChange> {
+        let mut changes: Vec<(Address, u64, u64)> = Vec::new();
+        let mut spent = Vec::new();
+        for input in &self.inputs {
+            match input {
+                InputEnum::AccountInput(account, value) => {
+                    let kind = match account.to_single_account() {
+                        Some(account) => Kind::Account(account.into()),
+                        None => match account.to_multi_account().as_ref().try_into() {
+                            Ok(account) => Kind::Multisig(account),
+                            Err(_) => continue,
+                        },
+                    };
+                    spent.push((Address(discrimination, kind), *value));
+                }
+                InputEnum::UtxoInput(pointer) => {
+                    let key = (pointer.transaction_id, pointer.output_index);
+                    if let Some(output) = ledger
+                        .utxo_out(key.0, key.1)
+                        .or_else(|| new_outputs.get(&key))
+                    {
+                        spent.push((output.address.clone(), output.value));
+                    }
+                }
+            }
+        }
+        for (address, value) in spent {
+            let change = change_of(&mut changes, address);
+            change.1 = change.1.saturating_add(value.0);
+        }
+        for output in &self.outputs {
+            let change = change_of(&mut changes, output.address.clone());
+            change.2 = change.2.saturating_add(output.value.0);
+        }
+
+        changes
+            .into_iter()
+            .map(|(address, spent, received)| BalanceChange {
+                address: address.into(),
+                spent: Value(spent).into(),
+                received: Value(received).into(),
+            })
+            .collect()
+    }
+}
+
+fn change_of(changes: &mut Vec<(Address, u64, u64)>, address: Address) -> &mut (Address, u64, u64) {
+    match changes.iter().position(|(known, _, _)| *known == address) {
+        Some(position) => &mut changes[position],
+        None => {
+            changes.push((address, 0, 0));
+            changes.last_mut().unwrap()
+        }
+    }
+}
+
+fn error_chain(error: &ledger::Error) -> String {
+    let mut msg = error.to_string();
+    for e in iter::successors(error.source(), |&e| e.source()) {
+        msg.push_str(": ");
+        msg.push_str(&e.to_string());
+    }
+    msg
+}
@@ -17,6 +17,12 @@ impl Logs {
         }
     }
 
+    /// Changes the maximum number of entries, the least recently used
+    /// entries are dropped when the logs shrink.
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.entries.resize(max_entries);
+    }
+
     pub fn exists(&self, fragment_id: FragmentId) -> bool {
         let fragment_id: Hash = fragment_id.into();
         self.entries.contains(&fragment_id)
@@ -1,9 +1,11 @@
+mod dry_run;
 mod entry;
 mod logs;
-mod pool;
+pub(crate) mod pool;
 mod process;
 pub mod selection;
 
+pub use self::dry_run::dry_run;
 pub use self::entry::PoolEntry;
 pub use self::logs::Logs;
 pub use self::pool::Pool;
@@ -10,7 +10,7 @@ use crate::{
     },
     intercom::{NetworkMsg, PropagateMsg},
     metrics::{Metrics, MetricsBackend},
-    utils::async_msg::MessageBox,
+    utils::{async_msg::MessageBox, clock},
 };
 use chain_impl_mockchain::{
     block::BlockDate, fragment::Contents, setting::Settings, transaction::Transaction,
@@ -77,6 +77,13 @@ impl Pool {
         &mut self.logs
     }
 
+    /// Changes the maximum number of fragments in the pool. The fragments
+    /// already in the pool are kept, new fragments are rejected until the
+    /// pool size goes below the new maximum.
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.pool.set_max_entries(max_entries);
+    }
+
     /// Sets the persistent log to a file.
     /// The file must be opened for writing.
     pub fn set_persistent_log(&mut self, file: File) {
@@ -289,6 +296,39 @@ impl Pool {
         (contents, ledger)
     }
 
+    /// Select the fragments of a block from a copy of the pool, so the
+    /// selected fragments stay in the pool and the logs are not updated.
+    pub async fn select_rehearsal(
+        &self,
+        ledger: ApplyBlockLedger,
+        ledger_params: LedgerParameters,
+        selection_alg: FragmentSelectionAlgorithmParams,
+        soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+        hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    ) -> (Contents, ApplyBlockLedger) {
+        let mut pool = self.pool.clone();
+        // the rejections are recorded in logs thrown away afterwards
+        let mut logs = Logs::new(pool.len().max(1));
+        let FragmentSelectionResult {
+            contents, ledger, ..
+        } = match selection_alg {
+            FragmentSelectionAlgorithmParams::OldestFirst => {
+                let mut selection_alg = OldestFirst::new();
+                selection_alg
+                    .select(
+                        ledger,
+                        &ledger_params,
+                        &mut logs,
+                        &mut pool,
+                        soft_deadline_future,
+                        hard_deadline_future,
+                    )
+                    .await
+            }
+        };
+        (contents, ledger)
+    }
+
     // Remove from logs fragments that were confirmed (or rejected) in a branch
     pub fn prune_after_ledger_branch(&mut self, branch_date: BlockDateDto) {
         self.logs.remove_logs_after_date(branch_date);
@@ -316,8 +356,8 @@ impl Pool {
     }
 }
 
-fn get_current_block_date(tip: &Ref) -> BlockDate {
-    let time = std::time::SystemTime::now();
+pub(super) fn get_current_block_date(tip: &Ref) -> BlockDate {
+    let time = clock::now();
     let era = tip.epoch_leadership_schedule().era();
     let epoch_position = tip
         .time_frame()
@@ -357,7 +397,7 @@ fn is_transaction_valid<E>(tx: &Transaction<E>) -> bool {
     tx.verify_possibly_balanced().is_ok()
 }
 
-fn get_transaction_expiry_date(fragment: &Fragment) -> Option<BlockDate> {
+pub(super) fn get_transaction_expiry_date(fragment: &Fragment) -> Option<BlockDate> {
     match fragment {
         Fragment::Initial(_) => None,
         Fragment::OldUtxoDeclaration(_) => None,
@@ -376,7 +416,7 @@ fn get_transaction_expiry_date(fragment: &Fragment) -> Option<BlockDate> {
     }
 }
 
-pub(super) mod internal {
+pub(crate) mod internal {
     use super::*;
 
     use std::{
@@ -496,6 +536,16 @@ pub(super) mod internal {
             self.index.len()
         }
 
+        /// iterate from the front to the back of the queue
+        fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+            let mut next = self.head as *const IndexedDequeueEntry<K, V>;
+            std::iter::from_fn(move || {
+                let entry = unsafe { next.as_ref() }?;
+                next = entry.next;
+                Some((&entry.key, &entry.value))
+            })
+        }
+
         fn contains(&self, key: &K) -> bool {
             self.index.contains_key(&IndexedDequeueKeyRef(key))
         }
@@ -547,6 +597,19 @@ pub(super) mod internal {
         total_size_bytes: usize,
     }
 
+    impl Clone for Pool {
+        fn clone(&self) -> Self {
+            let mut pool = Pool::new(self.max_entries);
+            // pushing to the back in the front to back order keeps the order
+            pool.return_to_pool(
+                self.entries
+                    .iter()
+                    .map(|(id, fragment)| (fragment.clone(), *id)),
+            );
+            pool
+        }
+    }
+
     impl Pool {
         pub fn new(max_entries: usize) -> Self {
             Pool {
@@ -559,12 +622,16 @@ pub(super) mod internal {
             }
         }
 
+        pub fn set_max_entries(&mut self, max_entries: usize) {
+            self.max_entries = max_entries;
+        }
+
         /// Returns clones of registered fragments
         pub fn insert_all(
             &mut self,
             fragments: impl IntoIterator<Item = (Fragment, FragmentId)>,
         ) -> Vec<(Fragment, FragmentId)> {
-            let max_fragments = self.max_entries - self.entries.len();
+            let max_fragments = self.max_entries.saturating_sub(self.entries.len());
             fragments
                 .into_iter()
                 .filter(|(fragment, id)| {
@@ -707,6 +774,24 @@ pub(super) mod internal {
             assert!(pool.remove_oldest().is_none());
         }
 
+        #[quickcheck]
+        fn cloned_pool_keeps_the_order(fragments_in: (Fragment, Fragment, Fragment)) {
+            let fragments = vec![
+                (fragments_in.0.clone(), fragments_in.0.id()),
+                (fragments_in.1.clone(), fragments_in.1.id()),
+                (fragments_in.2.clone(), fragments_in.2.id()),
+            ];
+            let mut pool = Pool::new(3);
+            let inserted = pool.insert_all(fragments);
+            let mut cloned = pool.clone();
+            assert_eq!(cloned.total_size_bytes, pool.total_size_bytes);
+            for expected in inserted.iter() {
+                assert_eq!(Some(expected.clone()), cloned.remove_oldest());
+            }
+            assert!(cloned.remove_oldest().is_none());
+            assert_eq!(pool.len(), inserted.len(), "the original pool is untouched");
+        }
+
         #[test]
         fn expired_transactions_are_removed() {
             let mut pool = Pool::new(1);
@@ -3,6 +3,7 @@ use crate::{
     fragment::{Logs, Pool},
     intercom::{NetworkMsg, TransactionMsg},
     metrics::{Metrics, MetricsBackend},
+    settings::runtime::{Change, RuntimeSettings, MEMPOOL_DOMAIN},
     utils::{
         async_msg::{MessageBox, MessageQueue},
         task::TokioServiceInfo,
@@ -10,6 +11,7 @@ use crate::{
 };
 
 use chain_core::property::Fragment;
+use runtime_settings::{Event, Subscriber};
 use std::collections::HashMap;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -26,6 +28,7 @@ pub struct Process {
     pool_max_entries: usize,
     logs_max_entries: usize,
     network_msg_box: MessageBox<NetworkMsg>,
+    settings: Option<Subscriber>,
 }
 
 #[derive(Debug, Error)]
@@ -46,6 +49,15 @@ impl Process {
             pool_max_entries,
             logs_max_entries,
             network_msg_box,
+            settings: None,
+        }
+    }
+
+    /// Apply the changes of the mempool runtime settings while running.
+    pub fn with_runtime_settings(self, settings: &RuntimeSettings) -> Self {
+        Process {
+            settings: Some(settings.subscribe(MEMPOOL_DOMAIN)),
+            ..self
         }
     }
 
@@ -93,9 +105,22 @@ impl Process {
         }
         let logs = Logs::new(std::cmp::max(self.logs_max_entries, self.pool_max_entries));
 
+        async fn next_setting(settings: &mut Option<Subscriber>) -> Option<Event> {
+            match settings {
+                Some(settings) => settings.await,
+                None => future::pending().await,
+            }
+        }
+
         let mut wakeup = Box::pin(hourly_wakeup(persistent_log_dir.is_some()));
 
         async move {
+            let mut settings = self.settings;
+            // the limits in use, the configured ones apply when a runtime
+            // setting is removed
+            let mut pool_max_entries = self.pool_max_entries;
+            let mut logs_max_entries = self.logs_max_entries;
+
             let persistent_log = match &persistent_log_dir {
                 None => None,
                 Some(dir) => {
@@ -185,6 +210,7 @@ impl Process {
                                     reply_handle,
                                     soft_deadline_future,
                                     hard_deadline_future,
+                                    rehearsal,
                                 } => {
                                     let span = span!(
                                         Level::DEBUG,
@@ -192,15 +218,25 @@ impl Process {
                                         kind = "older_first",
                                     );
                                     async {
-                                        let contents = pool
-                                        .select(
-                                            ledger,
-                                            ledger_params,
-                                            selection_alg,
-                                            soft_deadline_future,
-                                            hard_deadline_future,
-                                        )
-                                        .await;
+                                        let contents = if rehearsal {
+                                            pool.select_rehearsal(
+                                                ledger,
+                                                ledger_params,
+                                                selection_alg,
+                                                soft_deadline_future,
+                                                hard_deadline_future,
+                                            )
+                                            .await
+                                        } else {
+                                            pool.select(
+                                                ledger,
+                                                ledger_params,
+                                                selection_alg,
+                                                soft_deadline_future,
+                                                hard_deadline_future,
+                                            )
+                                            .await
+                                        };
                                         reply_handle.reply_ok(contents);
                                     }
                                     .instrument(span)
@@ -210,6 +246,23 @@ impl Process {
                         };
                         tracing::trace!("item handling finished");
                     }
+                    Some(event) = next_setting(&mut settings) => {
+                        match Change::from_event(&event) {
+                            Some(Change::PoolMaxEntries(max_entries)) => {
+                                pool_max_entries = max_entries.unwrap_or(self.pool_max_entries);
+                                tracing::info!(max_entries = pool_max_entries, "changing the maximum number of fragments in the mempool");
+                                pool.set_max_entries(pool_max_entries);
+                            }
+                            Some(Change::LogMaxEntries(max_entries)) => {
+                                logs_max_entries = max_entries.unwrap_or(self.logs_max_entries);
+                            }
+                            _ => continue,
+                        }
+                        // the logs keep an entry for every fragment of the pool
+                        let max_entries = std::cmp::max(logs_max_entries, pool_max_entries);
+                        tracing::info!(max_entries, "changing the maximum number of fragment logs");
+                        pool.logs().set_max_entries(max_entries);
+                    }
                     _ = &mut wakeup => {
                         async {
                             pool.close_persistent_log().await;
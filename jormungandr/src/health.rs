@@ -0,0 +1,284 @@
+//! Liveness and readiness checks of the node, for orchestrators to restart
+//! a node that cannot recover by itself and to drain traffic from a node
+//! that is not in sync.
+//!
+//! A failed liveness check means restarting the node may help: one of its
+//! services stopped or its storage cannot be written. A failed readiness
+//! check means the node should not receive requests for now: it is starting
+//! up, its tip is behind, it has no peers or its mempool is full.
+
+use crate::{
+    blockchain::Ref,
+    rest::Context,
+    settings::runtime::Tunable,
+    utils::{clock, task::ServicesMonitor},
+};
+use chain_time::{
+    era::{EpochPosition, EpochSlotOffset},
+    Epoch,
+};
+use jormungandr_lib::interfaces::{Health, NodeState, UnhealthyReason};
+use std::{
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// the tag written to the storage to check it can be written
+const STORAGE_PROBE_TAG: &str = "health-probe";
+
+/// the storage is written at most once per interval, however often the
+/// checks are requested
+const STORAGE_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct HealthChecks {
+    services: ServicesMonitor,
+    /// the tip is behind when no block was added for longer than this
+    tip_max_age: Duration,
+    /// the number of connected peers below which the node is not ready
+    min_peers: usize,
+    /// the configured size of the mempool, unless changed at runtime
+    pool_max_entries: usize,
+    storage_probe: StorageProbe,
+}
+
+/// the time and the error of the last write to the storage
+#[derive(Clone, Default)]
+struct StorageProbe(Arc<Mutex<Option<(Instant, Option<String>)>>>);
+
+impl HealthChecks {
+    pub fn new(
+        services: ServicesMonitor,
+        tip_max_age: Duration,
+        min_peers: usize,
+        pool_max_entries: usize,
+    ) -> Self {
+        HealthChecks {
+            services,
+            tip_max_age,
+            min_peers,
+            pool_max_entries,
+            storage_probe: StorageProbe::default(),
+        }
+    }
+
+    pub async fn liveness(&self, context: &Context) -> Health {
+        Health::new(self.liveness_reasons(context).await)
+    }
+
+    pub async fn readiness(&self, context: &Context) -> Health {
+        let mut reasons = self.liveness_reasons(context).await;
+
+        let state = context.node_state();
+        if *state != NodeState::Running {
+            reasons.push(UnhealthyReason::NotRunning {
+                state: state.clone(),
+            });
+        }
+
+        if let Ok(tip) = context.blockchain_tip() {
+            let tip = tip.get_ref().await;
+            match tip_age(&tip, clock::now()) {
+                Some(tip_age) => reasons.extend(self.check_tip_age(tip_age)),
+                None => tracing::warn!("cannot convert the block tip date to system time"),
+            }
+        }
+
+        if let Ok(full) = context.try_full() {
+            let stats = full.stats_counter.get_stats();
+            reasons.extend(self.check_peers(stats.peer_connected_cnt));
+            reasons.extend(check_mempool(
+                stats.tx_pending,
+                self.pool_max_entries(context),
+            ));
+        }
+
+        Health::new(reasons)
+    }
+
+    async fn liveness_reasons(&self, context: &Context) -> Vec<UnhealthyReason> {
+        let mut reasons: Vec<_> = self
+            .services
+            .stopped()
+            .into_iter()
+            .map(|service| UnhealthyReason::ServiceStopped { service })
+            .collect();
+
+        // the storage is only opened once the node has prepared it
+        if let (Ok(blockchain), Ok(tip)) = (context.blockchain(), context.blockchain_tip()) {
+            let tip = tip.get_ref().await;
+            let probe = self.storage_probe.check(Instant::now(), || {
+                blockchain.storage().put_tag(STORAGE_PROBE_TAG, tip.hash())
+            });
+            if let Some(error) = probe {
+                reasons.push(UnhealthyReason::StorageWriteFailed { error });
+            }
+        }
+
+        reasons
+    }
+
+    fn check_tip_age(&self, tip_age: Duration) -> Option<UnhealthyReason> {
+        if tip_age > self.tip_max_age {
+            Some(UnhealthyReason::TipTooOld {
+                tip_age_secs: tip_age.as_secs(),
+                max_tip_age_secs: self.tip_max_age.as_secs(),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn check_peers(&self, connected: usize) -> Option<UnhealthyReason> {
+        if connected < self.min_peers {
+            Some(UnhealthyReason::NotEnoughPeers {
+                connected,
+                required: self.min_peers,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn pool_max_entries(&self, context: &Context) -> usize {
+        context
+            .runtime_settings()
+            .ok()
+            .and_then(|settings| settings.get(Tunable::PoolMaxEntries).ok().flatten())
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(self.pool_max_entries)
+    }
+}
+
+/// the time elapsed since the slot of the tip
+pub fn tip_age(tip: &Ref, now: SystemTime) -> Option<Duration> {
+    let era = tip.epoch_leadership_schedule().era();
+    let tip_date = tip.block_date();
+    let tip_slot = era.from_era_to_slot(EpochPosition {
+        epoch: Epoch(tip_date.epoch),
+        slot: EpochSlotOffset(tip_date.slot_id),
+    });
+    let tip_time = tip.time_frame().slot_to_systemtime(tip_slot)?;
+    // the tip can be a little ahead of the system clock
+    Some(now.duration_since(tip_time).unwrap_or_default())
+}
+
+fn check_mempool(pending: u64, max_entries: usize) -> Option<UnhealthyReason> {
+    if pending >= max_entries as u64 {
+        Some(UnhealthyReason::MempoolFull {
+            pending,
+            max_entries,
+        })
+    } else {
+        None
+    }
+}
+
+impl StorageProbe {
+    /// the error of the last write, writing again when the last write is
+    /// older than [`STORAGE_PROBE_INTERVAL`]
+    fn check<E, F>(&self, now: Instant, write: F) -> Option<String>
+    where
+        E: Display,
+        F: FnOnce() -> Result<(), E>,
+    {
+        let mut last = self.0.lock().unwrap();
+        match &*last {
+            Some((written, error))
+                if now.saturating_duration_since(*written) < STORAGE_PROBE_INTERVAL =>
+            {
+                error.clone()
+            }
+            _ => {
+                let error = write().err().map(|error| error.to_string());
+                *last = Some((now, error.clone()));
+                error
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn health_checks() -> HealthChecks {
+        HealthChecks::new(ServicesMonitor::default(), Duration::from_secs(60), 2, 100)
+    }
+
+    #[test]
+    fn old_tip_is_not_ready() {
+        let checks = health_checks();
+        assert!(checks.check_tip_age(Duration::from_secs(60)).is_none());
+        assert!(matches!(
+            checks.check_tip_age(Duration::from_secs(61)),
+            Some(UnhealthyReason::TipTooOld {
+                tip_age_secs: 61,
+                max_tip_age_secs: 60,
+            })
+        ));
+    }
+
+    #[test]
+    fn not_enough_peers_is_not_ready() {
+        let checks = health_checks();
+        assert!(matches!(
+            checks.check_peers(1),
+            Some(UnhealthyReason::NotEnoughPeers {
+                connected: 1,
+                required: 2,
+            })
+        ));
+        assert!(checks.check_peers(2).is_none());
+
+        let checks = HealthChecks::new(ServicesMonitor::default(), Duration::from_secs(60), 0, 100);
+        assert!(checks.check_peers(0).is_none());
+    }
+
+    #[test]
+    fn full_mempool_is_not_ready() {
+        assert!(check_mempool(99, 100).is_none());
+        assert!(matches!(
+            check_mempool(100, 100),
+            Some(UnhealthyReason::MempoolFull {
+                pending: 100,
+                max_entries: 100,
+            })
+        ));
+    }
+
+    #[test]
+    fn storage_is_written_once_per_interval() {
+        let probe = StorageProbe::default();
+        let writes = Cell::new(0);
+        let write = |result: Result<(), &'static str>| {
+            writes.set(writes.get() + 1);
+            result
+        };
+        let start = Instant::now();
+
+        assert_eq!(
+            probe.check(start, || write(Err("disk full"))),
+            Some("disk full".to_owned())
+        );
+        // the failure is reported until the next write
+        assert_eq!(
+            probe.check(start + STORAGE_PROBE_INTERVAL / 2, || write(Ok(()))),
+            Some("disk full".to_owned())
+        );
+        assert_eq!(writes.get(), 1);
+
+        assert_eq!(
+            probe.check(start + STORAGE_PROBE_INTERVAL, || write(Ok(()))),
+            None
+        );
+        assert_eq!(writes.get(), 2);
+        assert_eq!(
+            probe.check(start + STORAGE_PROBE_INTERVAL, || write(Err("disk full"))),
+            None
+        );
+        assert_eq!(writes.get(), 2);
+    }
+}
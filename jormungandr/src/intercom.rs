@@ -522,6 +522,9 @@ pub enum TransactionMsg {
         reply_handle: ReplyHandle<(FragmentContents, ApplyBlockLedger)>,
         soft_deadline_future: futures::channel::oneshot::Receiver<()>,
         hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+        /// select from a copy of the mempool, leaving the mempool and the
+        /// fragment logs untouched
+        rehearsal: bool,
     },
 }
 
@@ -6,6 +6,8 @@ use crate::{
     },
     secure::enclave::Enclave as SecureEnclave,
 };
+use chain_impl_mockchain::{certificate::PoolId, key::BftLeaderId};
+use jormungandr_lib::interfaces::LeaderIdentity;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -38,6 +40,16 @@ impl Enclave {
         }
     }
 
+    /// whether the enclave holds the keys of a leader
+    pub fn is_leader(&self) -> bool {
+        self.inner.has_leader()
+    }
+
+    /// the identities of the leaders the enclave holds the keys of
+    pub fn leaders(&self) -> Vec<LeaderIdentity> {
+        self.inner.leaders()
+    }
+
     /// ask the enclave to attempt computing some leadership schedule for the
     /// given settings
     ///
@@ -64,9 +76,10 @@ impl Enclave {
     ///       run we will only need the block signing data.
     pub fn query_header_bft_finalize(
         &self,
+        leader_id: &BftLeaderId,
         block_builder: HeaderBftBuilder<HeaderSetConsensusSignature>,
     ) -> Result<HeaderBft, EnclaveError> {
-        if let Some(block) = self.inner.create_header_bft(block_builder) {
+        if let Some(block) = self.inner.create_header_bft(leader_id, block_builder) {
             Ok(block)
         } else {
             Err(EnclaveError::EmptyEnclave)
@@ -80,9 +93,13 @@ impl Enclave {
     ///       run we will only need the block signing data.
     pub fn query_header_genesis_praos_finalize(
         &self,
+        pool_id: &PoolId,
         block_builder: HeaderGenesisPraosBuilder<HeaderSetConsensusSignature>,
     ) -> Result<HeaderGenesisPraos, EnclaveError> {
-        if let Some(block) = self.inner.create_header_genesis_praos(block_builder) {
+        if let Some(block) = self
+            .inner
+            .create_header_genesis_praos(pool_id, block_builder)
+        {
             Ok(block)
         } else {
             Err(EnclaveError::EmptyEnclave)
@@ -29,11 +29,11 @@ impl LeadershipLogHandle {
     /// marked as awaken.
     ///
     pub async fn mark_wake(&self) {
-        self.logs.mark_wake(self.internal_id).await
+        self.logs.mark_wake(self.internal_id.clone()).await
     }
 
     pub async fn set_status(&self, status: LeadershipLogStatus) {
-        self.logs.set_status(self.internal_id, status).await
+        self.logs.set_status(self.internal_id.clone(), status).await
     }
 
     /// make a leadership event as finished.
@@ -47,7 +47,7 @@ impl LeadershipLogHandle {
     /// marked as finished.
     ///
     pub async fn mark_finished(&self) {
-        self.logs.mark_finished(self.internal_id).await
+        self.logs.mark_finished(self.internal_id.clone()).await
     }
 }
 
@@ -108,7 +108,7 @@ pub(super) mod internal {
         pub fn insert(&mut self, log: LeadershipLog) -> LeadershipLogId {
             let id = log.leadership_log_id();
 
-            self.entries.put(id, log);
+            self.entries.put(id.clone(), log);
             id
         }
 
@@ -60,7 +60,9 @@
 mod enclave;
 mod logs;
 mod process;
+mod rehearsal;
 
 pub use self::enclave::{Enclave, EnclaveError, LeaderEvent};
 pub use self::logs::{LeadershipLogHandle, Logs};
 pub use self::process::{Module, ModuleConfig};
+pub use self::rehearsal::{Rehearsal, RehearsalError};
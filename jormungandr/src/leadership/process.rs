@@ -1,7 +1,7 @@
 use crate::{
     blockcfg::{
-        block_builder, ApplyBlockLedger, BlockVersion, Contents, LeaderOutput, Leadership,
-        LedgerParameters,
+        block_builder, ApplyBlockLedger, Block, BlockVersion, ChainLength, Contents, HeaderId,
+        LeaderOutput, Leadership, LedgerParameters,
     },
     blockchain::{new_epoch_leadership_from, EpochLeadership, LeadershipBlock, Ref, Tip},
     intercom::{unary_reply, BlockMsg, Error as IntercomError, TransactionMsg},
@@ -9,7 +9,7 @@ use crate::{
         enclave::{Enclave, EnclaveError, LeaderEvent, Schedule},
         LeadershipLogHandle, Logs,
     },
-    utils::{async_msg::MessageBox, task::TokioServiceInfo},
+    utils::{async_msg::MessageBox, clock, task::TokioServiceInfo},
 };
 use chain_time::{
     era::{EpochPosition, EpochSlotOffset},
@@ -21,8 +21,9 @@ use jormungandr_lib::{
     time::SystemTime,
 };
 use std::cmp::Ordering;
-use std::{sync::Arc, time::Instant};
+use std::sync::Arc;
 use thiserror::Error;
+use tokio::time::Instant;
 use tracing::{span, Level, Span};
 use tracing_futures::Instrument;
 
@@ -119,7 +120,7 @@ impl Module {
     fn current_slot(&self) -> Result<Slot, LeadershipError> {
         let time_frame = self.tip_ref.time_frame();
 
-        let now = SystemTime::now();
+        let now = SystemTime::from(clock::now());
         if let Some(current_slot) = time_frame.slot_at(now.as_ref()) {
             Ok(current_slot)
         } else {
@@ -153,7 +154,7 @@ impl Module {
     fn epoch_instant(&self, epoch: Epoch) -> Result<Instant, LeadershipError> {
         let epoch_time = self.epoch_time(epoch)?;
 
-        match epoch_time.as_ref().duration_since(SystemTime::now().into()) {
+        match epoch_time.as_ref().duration_since(clock::now()) {
             Err(err) => {
                 // only possible if `epoch_time` is earlier than now. I.e. if the next
                 // epoch is in the past.
@@ -221,7 +222,7 @@ impl Module {
     fn slot_instant(&self, epoch: Epoch, slot: EpochSlotOffset) -> Option<Instant> {
         let slot_time = self.slot_time(epoch, slot);
 
-        match slot_time.as_ref().duration_since(SystemTime::now().into()) {
+        match slot_time.as_ref().duration_since(clock::now()) {
             Err(_err) => {
                 // this may happen if the epoch/slot is long gone
                 None
@@ -232,7 +233,7 @@ impl Module {
 
     async fn wait(mut self) -> Result<Self, LeadershipError> {
         let deadline = self.wait_peek_deadline().await?;
-        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
+        tokio::time::sleep_until(deadline).await;
         let tip = self.tip.clone();
         self.tip_ref = tip.get_ref().await;
         Ok(self)
@@ -287,20 +288,64 @@ impl Module {
     async fn action(mut self) -> Result<Self, LeadershipError> {
         match self.schedule.as_mut() {
             Some(schedule) => match schedule.next_event() {
-                Some(event) => self.action_entry(event).await,
+                Some(event) => {
+                    // other leaders of the node elected for the same slot
+                    let mut same_slot = Vec::new();
+                    while schedule
+                        .peek()
+                        .map_or(false, |next| next.date == event.date)
+                    {
+                        same_slot.extend(schedule.next_event());
+                    }
+                    self.action_reject_same_slot(same_slot).await?;
+                    self.action_entry(event).await
+                }
                 None => self.action_schedule().await,
             },
             None => self.action_schedule().await,
         }
     }
 
-    async fn action_entry(self, event: LeaderEvent) -> Result<Self, LeadershipError> {
-        let module = self;
-
+    fn event_log(&self, event: &LeaderEvent) -> LeadershipLog {
         let epoch = Epoch(event.date.epoch);
         let slot = EpochSlotOffset(event.date.slot_id);
-        let scheduled_at_time = module.slot_time(epoch, slot);
+        let scheduled_at_time = self.slot_time(epoch, slot);
         let log = LeadershipLog::new(event.date.into(), scheduled_at_time);
+        match event.leader() {
+            Some(leader) => log.with_leader(leader),
+            None => log,
+        }
+    }
+
+    // only one block is produced per slot: the blocks of several leaders of
+    // the node for the same slot would compete with each other, the first
+    // leader in the order of the secret files produces it
+    async fn action_reject_same_slot(
+        &self,
+        events: Vec<LeaderEvent>,
+    ) -> Result<(), LeadershipError> {
+        for event in events {
+            tracing::info!(
+                event_date = %event.date.to_string(),
+                "another leader of the node produces the block of this slot"
+            );
+            let log = match self.logs.insert(self.event_log(&event)).await {
+                Ok(log) => log,
+                Err(()) => return Err(LeadershipError::CannotUpdateLogs),
+            };
+            log.set_status(LeadershipLogStatus::Rejected {
+                reason: "Another leader of the node produces the block of this slot".to_owned(),
+            })
+            .await;
+            log.mark_finished().await;
+        }
+        Ok(())
+    }
+
+    async fn action_entry(self, event: LeaderEvent) -> Result<Self, LeadershipError> {
+        let module = self;
+
+        let log = module.event_log(&event);
 
         let entry = match module.logs.insert(log).await {
             Ok(log) => Entry { event, log },
@@ -315,7 +360,7 @@ impl Module {
     }
 
     async fn action_run_entry(self, entry: Entry) -> Result<Self, LeadershipError> {
-        let now = SystemTime::now();
+        let now = SystemTime::from(clock::now());
         let event_start = self.event_slot_time(&entry.event);
         let event_end = self.event_following_slot_time(&entry.event);
         let event_end_hard =
@@ -354,7 +399,7 @@ impl Module {
                     );
 
                     // await the right_time before starting the action
-                    tokio::time::sleep_until(tokio::time::Instant::from_std(right_time)).await;
+                    tokio::time::sleep_until(right_time).await;
                     self.action_run_entry_in_bound(entry, event_end, event_end_hard)
                         .await
                 } else {
@@ -379,7 +424,7 @@ impl Module {
     ) -> Result<Self, LeadershipError> {
         use futures::future::{select, Either};
 
-        let now = SystemTime::now();
+        let now = SystemTime::from(clock::now());
 
         // we can safely unwrap here as we just proved that `now <= event_end`
         // so that `now` is earlier to `event_end`.
@@ -408,12 +453,10 @@ impl Module {
 
         async {
             let (soft_deadline_tx, soft_deadline_rx) = futures::channel::oneshot::channel();
-            let soft_deadline_future =
-                tokio::time::sleep_until(tokio::time::Instant::from_std(deadline));
+            let soft_deadline_future = tokio::time::sleep_until(deadline);
 
             let (hard_deadline_tx, hard_deadline_rx) = futures::channel::oneshot::channel();
-            let hard_deadline_future =
-                tokio::time::sleep_until(tokio::time::Instant::from_std(hard_deadline));
+            let hard_deadline_future = tokio::time::sleep_until(hard_deadline);
 
             let build_block_future =
                 self.action_run_entry_build_block(entry, soft_deadline_rx, hard_deadline_rx);
@@ -495,112 +538,43 @@ impl Module {
             ledger_parameters,
             soft_deadline_future,
             hard_deadline_future,
+            false,
         )
         .await?;
 
-        let event_logs_error = event_logs.clone();
-        let signing = {
-            let ver = match event.output {
-                LeaderOutput::None => BlockVersion::Genesis,
-                LeaderOutput::Bft(_) => BlockVersion::Ed25519Signed,
-                LeaderOutput::GenesisPraos(..) => BlockVersion::KesVrfproof,
-            };
-
-            let LeaderEvent { date, output } = event;
-
-            match output {
-                LeaderOutput::None => block_builder(ver, contents, |hdr_builder| {
-                    Ok(hdr_builder
-                        .set_parent(&parent_id, chain_length)
-                        .set_date(date)
-                        .into_unsigned_header()
-                        .expect("Valid Header Builder")
-                        .generalize())
-                })
-                .map(Some),
-                LeaderOutput::Bft(leader_id) => {
-                    let block = block_builder(ver, contents, |hdr_builder| {
-                        let final_builder = hdr_builder
-                            .set_parent(&parent_id, chain_length)
-                            .set_date(date)
-                            .into_bft_builder()
-                            .expect("Valid Header Builder")
-                            .set_consensus_data(&leader_id);
-
-                        enclave
-                            .query_header_bft_finalize(final_builder)
-                            .map(|h| h.generalize())
-                    });
-
-                    match block {
-                        Ok(block) => Ok(Some(block)),
-                        Err(e) => {
-                            event_logs_error
-                                .set_status(LeadershipLogStatus::Rejected {
-                                    reason: format!("Cannot sign the block: {}", e),
-                                })
-                                .await;
-                            Ok(None)
-                        }
-                    }
-                }
-                LeaderOutput::GenesisPraos(node_id, vrfproof) => {
-                    let block = block_builder(ver, contents, |hdr_builder| {
-                        let final_builder = hdr_builder
-                            .set_parent(&parent_id, chain_length)
-                            .set_date(date)
-                            .into_genesis_praos_builder()
-                            .expect("Valid Header Builder")
-                            .set_consensus_data(&node_id, &vrfproof.into());
-
-                        enclave
-                            .query_header_genesis_praos_finalize(final_builder)
-                            .map(|h| h.generalize())
-                    });
-
-                    match block {
-                        Ok(block) => Ok(Some(block)),
-                        Err(e) => {
-                            event_logs_error
-                                .set_status(LeadershipLogStatus::Rejected {
-                                    reason: format!("Cannot sign the block: {}", e),
-                                })
-                                .await;
-                            Ok(None)
-                        }
-                    }
-                }
+        let block = match build_block(&enclave, event, contents, &parent_id, chain_length) {
+            Ok(block) => block,
+            Err(e) => {
+                event_logs
+                    .set_status(LeadershipLogStatus::Rejected {
+                        reason: format!("Cannot sign the block: {}", e),
+                    })
+                    .await;
+                return Ok(());
             }
         };
 
-        match signing {
-            Ok(maybe_block) => {
-                if let Some(block) = maybe_block {
-                    let id = block.header().hash();
-                    let parent = block.header().block_parent_hash();
-                    let chain_length: u32 = block.header().chain_length().into();
-                    let ledger = ledger.finish(&block.header().get_consensus_eval_context());
-                    let leadership_block = LeadershipBlock {
-                        block,
-                        new_ledger: ledger,
-                        leadership,
-                    };
-                    sender
-                        .send(BlockMsg::LeadershipBlock(leadership_block))
-                        .map_err(|_send_error| LeadershipError::CannotSendLeadershipBlock)
-                        .await?;
-                    event_logs
-                        .set_status(LeadershipLogStatus::Block {
-                            block: id.into(),
-                            parent: parent.into(),
-                            chain_length,
-                        })
-                        .await;
-                };
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+        let id = block.header().hash();
+        let parent = block.header().block_parent_hash();
+        let chain_length: u32 = block.header().chain_length().into();
+        let ledger = ledger.finish(&block.header().get_consensus_eval_context());
+        let leadership_block = LeadershipBlock {
+            block,
+            new_ledger: ledger,
+            leadership,
+        };
+        sender
+            .send(BlockMsg::LeadershipBlock(leadership_block))
+            .map_err(|_send_error| LeadershipError::CannotSendLeadershipBlock)
+            .await?;
+        event_logs
+            .set_status(LeadershipLogStatus::Block {
+                block: id.into(),
+                parent: parent.into(),
+                chain_length,
+            })
+            .await;
+        Ok(())
     }
 
     async fn action_schedule(self) -> Result<Self, LeadershipError> {
@@ -698,12 +672,13 @@ impl Entry {
     }
 }
 
-async fn prepare_block(
+pub(super) async fn prepare_block(
     mut fragment_pool: MessageBox<TransactionMsg>,
     ledger: ApplyBlockLedger,
     epoch_parameters: Arc<LedgerParameters>,
     soft_deadline_future: futures::channel::oneshot::Receiver<()>,
     hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    rehearsal: bool,
 ) -> Result<(Contents, ApplyBlockLedger), LeadershipError> {
     use crate::fragment::selection::FragmentSelectionAlgorithmParams;
 
@@ -716,6 +691,7 @@ async fn prepare_block(
         reply_handle,
         soft_deadline_future,
         hard_deadline_future,
+        rehearsal,
     };
 
     if fragment_pool.try_send(msg).is_err() {
@@ -726,6 +702,60 @@ async fn prepare_block(
     }
 }
 
+/// build the block of the leader event and sign it with the enclave
+pub(super) fn build_block(
+    enclave: &Enclave,
+    event: LeaderEvent,
+    contents: Contents,
+    parent_id: &HeaderId,
+    chain_length: ChainLength,
+) -> Result<Block, EnclaveError> {
+    let ver = match event.output {
+        LeaderOutput::None => BlockVersion::Genesis,
+        LeaderOutput::Bft(_) => BlockVersion::Ed25519Signed,
+        LeaderOutput::GenesisPraos(..) => BlockVersion::KesVrfproof,
+    };
+
+    let LeaderEvent { date, output } = event;
+
+    match output {
+        LeaderOutput::None => block_builder(ver, contents, |hdr_builder| {
+            Ok(hdr_builder
+                .set_parent(parent_id, chain_length)
+                .set_date(date)
+                .into_unsigned_header()
+                .expect("Valid Header Builder")
+                .generalize())
+        }),
+        LeaderOutput::Bft(leader_id) => block_builder(ver, contents, |hdr_builder| {
+            let final_builder = hdr_builder
+                .set_parent(parent_id, chain_length)
+                .set_date(date)
+                .into_bft_builder()
+                .expect("Valid Header Builder")
+                .set_consensus_data(&leader_id);
+
+            enclave
+                .query_header_bft_finalize(&leader_id, final_builder)
+                .map(|h| h.generalize())
+        }),
+        LeaderOutput::GenesisPraos(node_id, vrfproof) => {
+            block_builder(ver, contents, |hdr_builder| {
+                let final_builder = hdr_builder
+                    .set_parent(parent_id, chain_length)
+                    .set_date(date)
+                    .into_genesis_praos_builder()
+                    .expect("Valid Header Builder")
+                    .set_consensus_data(&node_id, &vrfproof.into());
+
+                enclave
+                    .query_header_genesis_praos_finalize(&node_id, final_builder)
+                    .map(|h| h.generalize())
+            })
+        }
+    }
+}
+
 fn too_late(now: SystemTime, event_end: SystemTime) -> bool {
     event_end <= now
 }
@@ -0,0 +1,510 @@
+//! Rehearse the production of a block for a slot the node is elected for:
+//! the fragments are selected from a copy of the mempool, the block is
+//! signed with the enclave keys and validated against the tip, then dropped.
+//! Nothing is sent to the network and the fragment pool is left untouched.
+
+use crate::{
+    blockcfg::{Block, BlockDate, ChainLength, Leadership},
+    blockchain::{
+        new_epoch_leadership_from, Blockchain, CheckHeaderProof, PreCheckedHeader, Ref, Tip,
+    },
+    intercom::TransactionMsg,
+    leadership::{
+        enclave::{Enclave, EnclaveError, LeaderEvent},
+        process::{build_block, prepare_block},
+    },
+    utils::async_msg::MessageBox,
+};
+use futures::future::{select, Either};
+use jormungandr_lib::interfaces::{
+    LeadershipRehearsal, RehearsalFailure, RehearsalStage, RehearsalTimings,
+};
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::time::Instant;
+
+#[derive(Debug, Error)]
+pub enum RehearsalError {
+    #[error("the node is not running as a leader")]
+    NotLeader,
+    #[error("cannot rehearse slot {date}, it is not after the tip at {tip}")]
+    DateNotAfterTip { date: BlockDate, tip: BlockDate },
+    #[error(
+        "cannot rehearse slot {date}, the leaders are only known up to the epoch after the tip at {tip}"
+    )]
+    DateTooFar { date: BlockDate, tip: BlockDate },
+    #[error("slot {date} is out of its epoch of {slots_per_epoch} slots")]
+    SlotOutOfEpoch {
+        date: BlockDate,
+        slots_per_epoch: u32,
+    },
+    #[error("the node is not elected for slot {0}")]
+    NotElected(BlockDate),
+    #[error("the node is not elected for any slot of the current or the next epoch")]
+    NoLeaderEvent,
+    #[error("cannot query enclave for leader schedules")]
+    CannotSchedule(#[source] EnclaveError),
+}
+
+#[derive(Clone)]
+pub struct Rehearsal {
+    enclave: Enclave,
+    tip: Tip,
+    blockchain: Blockchain,
+    pool: MessageBox<TransactionMsg>,
+    // the maximum number of slots the fragment selection can run for
+    block_hard_deadline: u32,
+    rewards_report_all: bool,
+}
+
+impl Rehearsal {
+    pub fn new(
+        enclave: Enclave,
+        tip: Tip,
+        blockchain: Blockchain,
+        pool: MessageBox<TransactionMsg>,
+        block_hard_deadline: u32,
+        rewards_report_all: bool,
+    ) -> Self {
+        Rehearsal {
+            enclave,
+            tip,
+            blockchain,
+            pool,
+            block_hard_deadline,
+            rewards_report_all,
+        }
+    }
+
+    /// rehearse the block of the given slot, or of the first slot following
+    /// the tip the node is elected for when no slot is given.
+    ///
+    /// The slot does not have to be in the future: the rehearsal is not
+    /// bound to the wall clock, only to the deadlines of a slot. It has to be
+    /// in the epoch of the tip or in the next one though.
+    pub async fn run(
+        &self,
+        date: Option<BlockDate>,
+    ) -> Result<LeadershipRehearsal, RehearsalError> {
+        if !self.enclave.is_leader() {
+            return Err(RehearsalError::NotLeader);
+        }
+
+        let tip_ref = self.tip.get_ref().await;
+        let event = match date {
+            Some(date) => self.event_at(&tip_ref, date).await?,
+            None => self.next_event(&tip_ref).await?,
+        };
+
+        let slot_duration = Duration::from_secs(tip_ref.time_frame().slot_duration());
+        let soft_deadline = slot_duration;
+        let hard_deadline = slot_duration * self.block_hard_deadline;
+
+        let parent_id = tip_ref.hash();
+        let chain_length = tip_ref.chain_length().increase();
+        let mut rehearsal = LeadershipRehearsal {
+            date: event.date.into(),
+            parent: parent_id.into(),
+            chain_length: chain_length.into(),
+            block: None,
+            fragments: 0,
+            timings: RehearsalTimings {
+                fragment_selection: Duration::default().into(),
+                signing: Duration::default().into(),
+                validation: Duration::default().into(),
+                total: Duration::default().into(),
+                soft_deadline: soft_deadline.into(),
+                hard_deadline: hard_deadline.into(),
+            },
+            failure: None,
+        };
+
+        let started = Instant::now();
+        let result = self
+            .rehearse(
+                &tip_ref,
+                event,
+                chain_length,
+                started,
+                soft_deadline,
+                hard_deadline,
+                &mut rehearsal,
+            )
+            .await;
+        rehearsal.timings.total = started.elapsed().into();
+        if let Err((stage, reason)) = result {
+            rehearsal.failure = Some(RehearsalFailure { stage, reason });
+        }
+
+        Ok(rehearsal)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn rehearse(
+        &self,
+        tip_ref: &Arc<Ref>,
+        event: LeaderEvent,
+        chain_length: ChainLength,
+        started: Instant,
+        soft_deadline: Duration,
+        hard_deadline: Duration,
+        rehearsal: &mut LeadershipRehearsal,
+    ) -> Result<(), (RehearsalStage, String)> {
+        let leadership = new_epoch_leadership_from(
+            event.date.epoch,
+            Arc::clone(tip_ref),
+            self.rewards_report_all,
+        );
+        let ledger = leadership
+            .state
+            .clone()
+            .begin_block(chain_length, event.date)
+            .map_err(|e| (RehearsalStage::Validation, e.to_string()))?;
+
+        let (soft_deadline_tx, soft_deadline_rx) = futures::channel::oneshot::channel();
+        let (hard_deadline_tx, hard_deadline_rx) = futures::channel::oneshot::channel();
+        let selection = prepare_block(
+            self.pool.clone(),
+            ledger,
+            leadership.ledger_parameters.clone(),
+            soft_deadline_rx,
+            hard_deadline_rx,
+            true,
+        );
+        let soft_deadline_future = tokio::time::sleep_until(started + soft_deadline);
+        let hard_deadline_future = tokio::time::sleep_until(started + hard_deadline);
+
+        // the deadlines are signalled the same way as when producing a block,
+        // a closed channel means the selection already completed
+        let selection = match select(Box::pin(selection), Box::pin(soft_deadline_future)).await {
+            Either::Left((result, _)) => result,
+            Either::Right((_, selection)) => {
+                let _ = soft_deadline_tx.send(());
+                match select(selection, Box::pin(hard_deadline_future)).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right((_, selection)) => {
+                        let _ = hard_deadline_tx.send(());
+                        selection.await
+                    }
+                }
+            }
+        };
+        rehearsal.timings.fragment_selection = started.elapsed().into();
+        let (contents, _ledger) =
+            selection.map_err(|e| (RehearsalStage::FragmentSelection, e.to_string()))?;
+        rehearsal.fragments = contents.iter().count();
+
+        let signing = Instant::now();
+        let block = build_block(
+            &self.enclave,
+            event,
+            contents,
+            &tip_ref.hash(),
+            chain_length,
+        );
+        rehearsal.timings.signing = signing.elapsed().into();
+        let block = block.map_err(|e| (RehearsalStage::Signing, e.to_string()))?;
+        rehearsal.block = Some(block.header().hash().into());
+
+        let validation = Instant::now();
+        let result = self.validate(&block).await;
+        rehearsal.timings.validation = validation.elapsed().into();
+        result.map_err(|reason| (RehearsalStage::Validation, reason))
+    }
+
+    /// check the block the same way a block received from the network is
+    /// checked, without storing it
+    async fn validate(&self, block: &Block) -> Result<(), String> {
+        let header = block.header().clone();
+        let pre_checked = self
+            .blockchain
+            .pre_check_header(header, false)
+            .await
+            .map_err(|e| e.to_string())?;
+        let (header, parent_ref) = match pre_checked {
+            PreCheckedHeader::HeaderWithCache { header, parent_ref } => (header, parent_ref),
+            PreCheckedHeader::AlreadyPresent { .. } => {
+                return Err("the block is already in the storage".to_owned())
+            }
+            PreCheckedHeader::MissingParent { .. } => {
+                return Err("the parent of the block is not in the storage".to_owned())
+            }
+        };
+        let post_checked = self
+            .blockchain
+            .post_check_header(header, parent_ref, CheckHeaderProof::Enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.blockchain
+            .apply_block_dry_run(&post_checked, block)
+            .map(|_ledger| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn event_at(
+        &self,
+        tip_ref: &Arc<Ref>,
+        date: BlockDate,
+    ) -> Result<LeaderEvent, RehearsalError> {
+        let tip = tip_ref.block_date();
+        if date <= tip {
+            return Err(RehearsalError::DateNotAfterTip { date, tip });
+        }
+        // the leaders of later epochs depend on blocks not produced yet
+        if date.epoch > tip.epoch + 1 {
+            return Err(RehearsalError::DateTooFar { date, tip });
+        }
+
+        let leadership = self.epoch_leadership(tip_ref, date.epoch);
+        let slots_per_epoch = leadership.era().slots_per_epoch();
+        if date.slot_id >= slots_per_epoch {
+            return Err(RehearsalError::SlotOutOfEpoch {
+                date,
+                slots_per_epoch,
+            });
+        }
+
+        self.enclave
+            .query_schedules(leadership, date.slot_id, 1)
+            .await
+            .map_err(RehearsalError::CannotSchedule)?
+            .next_event()
+            .ok_or(RehearsalError::NotElected(date))
+    }
+
+    async fn next_event(&self, tip_ref: &Arc<Ref>) -> Result<LeaderEvent, RehearsalError> {
+        let tip = tip_ref.block_date();
+
+        let leadership = self.epoch_leadership(tip_ref, tip.epoch);
+        let slot_start = tip.slot_id + 1;
+        let nb_slots = leadership
+            .era()
+            .slots_per_epoch()
+            .saturating_sub(slot_start);
+        if let Some(event) = self
+            .enclave
+            .query_schedules(leadership, slot_start, nb_slots)
+            .await
+            .map_err(RehearsalError::CannotSchedule)?
+            .next_event()
+        {
+            return Ok(event);
+        }
+
+        let leadership = self.epoch_leadership(tip_ref, tip.epoch + 1);
+        let nb_slots = leadership.era().slots_per_epoch();
+        self.enclave
+            .query_schedules(leadership, 0, nb_slots)
+            .await
+            .map_err(RehearsalError::CannotSchedule)?
+            .next_event()
+            .ok_or(RehearsalError::NoLeaderEvent)
+    }
+
+    fn epoch_leadership(&self, tip_ref: &Arc<Ref>, epoch: u32) -> Arc<Leadership> {
+        if epoch == tip_ref.block_date().epoch {
+            Arc::clone(tip_ref.epoch_leadership_schedule())
+        } else {
+            new_epoch_leadership_from(epoch, Arc::clone(tip_ref), false).leadership
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blockcfg::{BftLeader, Fragment, FragmentId, HeaderHash, Leader},
+        blockchain::Storage,
+        fragment,
+        intercom::{self, NetworkMsg},
+        metrics::Metrics,
+        secure::enclave::Enclave as SecureEnclave,
+        start_up,
+        utils::{async_msg, clock, task::TokioServiceInfo},
+    };
+    use chain_addr::{Address, Discrimination, Kind};
+    use chain_core::property::Fragment as _;
+    use chain_crypto::{Ed25519, SecretKey};
+    use chain_impl_mockchain::{
+        account::SpendingCounter,
+        chaintypes::ConsensusVersion,
+        fee::LinearFee,
+        transaction::{Input, Output, TxBuilder, Witness},
+    };
+    use futures::prelude::*;
+    use jormungandr_lib::interfaces::{
+        Block0Configuration, BlockDate as BlockDateDto, BlockchainConfiguration, FragmentLog,
+        FragmentOrigin, Initial, InitialUTxO, NumberOfSlotsPerEpoch,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+    use std::path::PathBuf;
+    use std::time::UNIX_EPOCH;
+    use tracing::{span, Level};
+
+    const BLOCK0_DATE: u64 = 1_600_000_000;
+    const FUNDS: u64 = 1_000;
+
+    struct Node {
+        rehearsal: Rehearsal,
+        pool: MessageBox<TransactionMsg>,
+        block0_hash: HeaderHash,
+        account: SecretKey<Ed25519>,
+    }
+
+    /// a BFT leader with a fragment pool, the wall clock at the block0 date
+    async fn start_node() -> Node {
+        clock::start_virtual(UNIX_EPOCH + Duration::from_secs(BLOCK0_DATE));
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let leader = SecretKey::<Ed25519>::generate(&mut rng);
+        let account = SecretKey::<Ed25519>::generate(&mut rng);
+
+        let mut blockchain_configuration = BlockchainConfiguration::new(
+            Discrimination::Test,
+            ConsensusVersion::Bft,
+            LinearFee::new(0, 0, 0),
+        );
+        blockchain_configuration.block0_date =
+            (UNIX_EPOCH + Duration::from_secs(BLOCK0_DATE)).into();
+        blockchain_configuration.slots_per_epoch = NumberOfSlotsPerEpoch::new(10).unwrap();
+        blockchain_configuration.consensus_leader_ids = vec![leader.to_public().into()];
+        let block0 = Block0Configuration {
+            blockchain_configuration,
+            initial: vec![Initial::Fund(vec![InitialUTxO {
+                address: Address(Discrimination::Test, Kind::Account(account.to_public())).into(),
+                value: FUNDS.into(),
+            }])],
+        }
+        .to_block();
+        let block0_hash = block0.header().hash();
+
+        let storage = Storage::memory(span!(Level::TRACE, "storage")).unwrap();
+        let (blockchain, tip) = start_up::load_blockchain(block0, storage, 1024, false)
+            .await
+            .unwrap();
+
+        let (network_msgbox, network_queue) = async_msg::channel::<NetworkMsg>(64);
+        let (pool, pool_queue) = async_msg::channel(64);
+        // the fragments are propagated to nowhere
+        tokio::spawn(network_queue.for_each(|_| future::ready(())));
+        let process = fragment::Process::new(100, 100, network_msgbox);
+        tokio::spawn(
+            process
+                .start(
+                    TokioServiceInfo::current("fragment"),
+                    Metrics::builder().build(),
+                    pool_queue,
+                    None::<PathBuf>,
+                    tip.clone(),
+                )
+                .map(|result| result.unwrap()),
+        );
+
+        let enclave = Enclave::new(SecureEnclave::new(vec![Leader {
+            bft_leader: Some(BftLeader { sig_key: leader }),
+            genesis_leader: None,
+        }]));
+        Node {
+            rehearsal: Rehearsal::new(enclave, tip, blockchain, pool.clone(), 50, false),
+            pool,
+            block0_hash,
+            account,
+        }
+    }
+
+    impl Node {
+        /// send all the funds of the account to another address
+        async fn send_transaction(&self) -> FragmentId {
+            let source = self.account.to_public();
+            let destination = Address(
+                Discrimination::Test,
+                Kind::Account(SecretKey::<Ed25519>::generate(rand::thread_rng()).to_public()),
+            );
+            let builder = TxBuilder::new()
+                .set_nopayload()
+                .set_expiry_date(BlockDate {
+                    epoch: 1,
+                    slot_id: 0,
+                })
+                .set_ios(
+                    &[Input::from_account_single(source.into(), FUNDS.into())],
+                    &[Output::from_address(destination, FUNDS.into())],
+                );
+            let witness = Witness::new_account(
+                &self.block0_hash,
+                &builder.get_auth_data_for_witness().hash(),
+                SpendingCounter::zero(),
+                |data| self.account.sign(data),
+            );
+            let fragment =
+                Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()));
+            let id = fragment.id();
+
+            let (reply_handle, reply_future) = intercom::unary_reply();
+            self.pool
+                .clone()
+                .send(TransactionMsg::SendTransactions {
+                    origin: FragmentOrigin::Rest,
+                    fragments: vec![fragment],
+                    fail_fast: true,
+                    reply_handle,
+                })
+                .await
+                .unwrap();
+            let summary = reply_future.await.unwrap();
+            assert_eq!(summary.accepted, vec![id]);
+            id
+        }
+
+        async fn logs(&self) -> Vec<FragmentLog> {
+            let (reply_handle, reply_future) = intercom::unary_reply();
+            self.pool
+                .clone()
+                .send(TransactionMsg::GetLogs(reply_handle))
+                .await
+                .unwrap();
+            reply_future.await.unwrap()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rehearsal_leaves_the_mempool_untouched() {
+        let node = start_node().await;
+        let id = node.send_transaction().await;
+
+        for _ in 0..2 {
+            let rehearsal = node.rehearsal.run(None).await.unwrap();
+            assert!(rehearsal.failure.is_none(), "{:?}", rehearsal.failure);
+            assert!(rehearsal.block.is_some());
+            assert_eq!(rehearsal.fragments, 1);
+            assert_eq!(rehearsal.date, BlockDateDto::new(0, 1));
+
+            let logs = node.logs().await;
+            assert_eq!(logs.len(), 1);
+            assert_eq!(*logs[0].fragment_id(), id.into());
+            assert!(logs[0].is_pending());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rehearsal_is_limited_to_the_next_epoch() {
+        let node = start_node().await;
+        let date = |epoch, slot_id| Some(BlockDate { epoch, slot_id });
+
+        assert!(matches!(
+            node.rehearsal.run(date(0, 0)).await,
+            Err(RehearsalError::DateNotAfterTip { .. })
+        ));
+        assert!(matches!(
+            node.rehearsal.run(date(0, 10)).await,
+            Err(RehearsalError::SlotOutOfEpoch { .. })
+        ));
+        let rehearsal = node.rehearsal.run(date(1, 9)).await.unwrap();
+        assert!(rehearsal.failure.is_none(), "{:?}", rehearsal.failure);
+        assert!(matches!(
+            node.rehearsal.run(date(2, 0)).await,
+            Err(RehearsalError::DateTooFar { .. })
+        ));
+    }
+}
@@ -13,8 +13,11 @@ use crate::{
     diagnostic::Diagnostic,
     metrics::MetricsBackend,
     secure::enclave::Enclave,
-    settings::start::Settings,
-    utils::{async_msg, task::Services},
+    settings::{runtime::RuntimeSettings, start::Settings},
+    utils::{
+        async_msg,
+        task::{RestartPolicy, Services},
+    },
 };
 use chain_impl_mockchain::leadership::LeadershipConsensus;
 use futures::executor::block_on;
@@ -28,12 +31,15 @@ use tracing::{span, Level, Span};
 use std::sync::Arc;
 use std::time::Duration;
 
+#[cfg(all(test, feature = "with-bench"))]
+mod bench;
 pub mod blockcfg;
 pub mod blockchain;
 pub mod client;
 pub mod diagnostic;
 pub mod explorer;
 pub mod fragment;
+pub mod health;
 pub mod intercom;
 pub mod leadership;
 pub mod log;
@@ -42,6 +48,8 @@ pub mod network;
 pub mod rest;
 pub mod secure;
 pub mod settings;
+#[cfg(test)]
+mod simulation;
 pub mod start_up;
 pub mod state;
 pub mod stuck_notifier;
@@ -62,6 +70,7 @@ fn start() -> Result<(), start_up::Error> {
 
 pub struct BootstrappedNode {
     settings: Settings,
+    node_secrets: Vec<secure::NodeSecret>,
     blockchain: Blockchain,
     blockchain_tip: blockchain::Tip,
     block0_hash: HeaderHash,
@@ -69,6 +78,7 @@ pub struct BootstrappedNode {
     rest_context: Option<rest::ContextLock>,
     services: Services,
     initial_peers: Vec<topology::Peer>,
+    runtime_settings: RuntimeSettings,
     _logger_guards: Vec<WorkerGuard>,
 }
 
@@ -80,6 +90,12 @@ const CLIENT_TASK_QUEUE_LEN: usize = 32;
 const TOPOLOGY_TASK_QUEUE_LEN: usize = 32;
 const WATCH_CLIENT_TASK_QUEUE_LEN: usize = 32;
 const BOOTSTRAP_RETRY_WAIT: Duration = Duration::from_secs(5);
+/// the policy of the services the node can run without
+const NON_CRITICAL_SERVICE: RestartPolicy = RestartPolicy::Restart {
+    min_backoff: Duration::from_secs(1),
+    max_backoff: Duration::from_secs(60),
+    max_restarts: 10,
+};
 
 fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::Error> {
     if let Some(context) = bootstrapped_node.rest_context.as_ref() {
@@ -141,8 +157,15 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
 
             let (explorer_msgbox, explorer_queue) = async_msg::channel(EXPLORER_TASK_QUEUE_LEN);
 
-            services.spawn_future("explorer", move |info| async move {
-                explorer.start(info, explorer_queue).await
+            // the queue is consumed by the explorer, which is degraded
+            // rather than restarted so the node keeps producing blocks
+            let mut explorer_queue = Some(explorer_queue);
+            services.spawn_supervised("explorer", RestartPolicy::Degrade, move |info| {
+                let explorer = explorer.clone();
+                let explorer_queue = explorer_queue
+                    .take()
+                    .expect("the explorer service is started only once");
+                async move { explorer.start(info, explorer_queue).await }
             });
             Some((explorer_msgbox, context))
         } else {
@@ -260,28 +283,51 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             None
         }
     });
-    let leader_secret = bootstrapped_node
-        .settings
-        .secret
-        .map::<Result<_, start_up::Error>, _>(|secret_path| {
-            let secret = secure::NodeSecret::load_from_file(secret_path.as_path())?;
-            if let (Some(leaders), Some(leader)) = (&bft_leaders, secret.bft()) {
-                let public_key = &leader.sig_key.to_public();
-                if !leaders.contains(public_key) {
-                    tracing::warn!(
-                        "node was started with a BFT secret key but the corresponding \
-                        public key {} is not listed among consensus leaders",
-                        public_key
-                    );
-                }
-            };
-            Ok(Leader {
-                bft_leader: secret.bft(),
-                genesis_leader: secret.genesis(),
-            })
-        })
-        .transpose()?;
-    let enclave = Enclave::new(leader_secret);
+    let mut secret_files = secure::kes::SecretFiles::new();
+    let mut kes_start_periods = Vec::new();
+    let mut leaders = Vec::new();
+    for secret in bootstrapped_node.node_secrets {
+        if let (Some(leaders), Some(leader)) = (&bft_leaders, secret.bft()) {
+            let public_key = &leader.sig_key.to_public();
+            if !leaders.contains(public_key) {
+                tracing::warn!(
+                    "node was started with a BFT secret key but the corresponding \
+                    public key {} is not listed among consensus leaders",
+                    public_key
+                );
+            }
+        };
+        let genesis_leader = secret.genesis();
+        if let (Some(leader), Some(file)) = (&genesis_leader, secret.file()) {
+            secret_files.insert(leader.node_id.clone(), file.clone());
+        }
+        if let (Some(leader), Some(start_period)) = (&genesis_leader, secret.kes_start_period()) {
+            kes_start_periods.push((leader.node_id.clone(), start_period));
+        }
+        leaders.push(Leader {
+            bft_leader: secret.bft(),
+            genesis_leader,
+        });
+    }
+    let enclave = Enclave::new(leaders);
+    for (pool_id, start_period) in kes_start_periods {
+        enclave.set_kes_start_period(pool_id, start_period);
+    }
+
+    if !enclave.stake_pools().is_empty() {
+        // sign the first blocks with keys of the current period already
+        block_on(secure::kes::evolve(
+            &enclave,
+            &blockchain_tip,
+            &secret_files,
+        ));
+
+        let enclave = enclave.clone();
+        let tip = blockchain_tip.clone();
+        services.spawn_supervised("kes", NON_CRITICAL_SERVICE, move |_| {
+            secure::kes::evolve_keys(enclave.clone(), tip.clone(), secret_files.clone())
+        });
+    }
 
     {
         let logs = leadership_logs.clone();
@@ -307,13 +353,23 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         });
     }
 
+    let leadership_rehearsal = leadership::Rehearsal::new(
+        leadership::Enclave::new(enclave.clone()),
+        blockchain_tip.clone(),
+        blockchain.clone(),
+        fragment_msgbox.clone(),
+        bootstrapped_node.settings.block_hard_deadline,
+        bootstrapped_node.settings.rewards_report_all,
+    );
+
     {
         let blockchain_tip = blockchain_tip.clone();
         let process = fragment::Process::new(
             bootstrapped_node.settings.mempool.pool_max_entries.into(),
             bootstrapped_node.settings.mempool.log_max_entries.into(),
             network_msgbox.clone(),
-        );
+        )
+        .with_runtime_settings(&bootstrapped_node.runtime_settings);
         let fragment_log_dir = bootstrapped_node
             .settings
             .mempool
@@ -338,6 +394,7 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             transaction_task: fragment_msgbox,
             topology_task: topology_msgbox,
             leadership_logs,
+            leadership_rehearsal,
             enclave,
             network_state,
             explorer: explorer.as_ref().map(|(_msg_box, context)| context.clone()),
@@ -357,9 +414,9 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             .settings
             .no_blockchain_updates_warning_interval;
 
-        services.spawn_future("stuck_notifier", move |_| {
+        services.spawn_supervised("stuck_notifier", NON_CRITICAL_SERVICE, move |_| {
             stuck_notifier::check_last_block_time(
-                blockchain_tip,
+                blockchain_tip.clone(),
                 no_blockchain_updates_warning_interval,
             )
         });
@@ -393,11 +450,13 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
 fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, start_up::Error> {
     let InitializedNode {
         settings,
+        node_secrets,
         block0,
         storage,
         rest_context,
         mut services,
         cancellation_token,
+        runtime_settings,
         _logger_guards,
     } = initialized_node;
 
@@ -422,6 +481,7 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
 
     Ok(BootstrappedNode {
         settings,
+        node_secrets,
         blockchain,
         blockchain_tip,
         block0_hash,
@@ -429,6 +489,7 @@ fn bootstrap(initialized_node: InitializedNode) -> Result<BootstrappedNode, star
         rest_context,
         services,
         initial_peers,
+        runtime_settings,
         _logger_guards,
     })
 }
@@ -468,9 +529,33 @@ async fn bootstrap_internal(
 
     let cache_capacity = 102_400;
 
-    let (blockchain, blockchain_tip) =
-        start_up::load_blockchain(block0, storage, cache_capacity, settings.rewards_report_all)
-            .await?;
+    let snapshot_boundaries = match &settings.snapshot {
+        Some(snapshot) => {
+            let boundaries = start_up::import_snapshot(snapshot, &block0, &storage)?;
+            if boundaries.is_some() {
+                tracing::info!("snapshot imported from {}", snapshot.display());
+            }
+            boundaries
+        }
+        None => None,
+    };
+
+    let (blockchain, blockchain_tip) = match snapshot_boundaries {
+        Some(boundaries) => {
+            start_up::load_blockchain_from_snapshot(
+                block0,
+                storage,
+                cache_capacity,
+                settings.rewards_report_all,
+                boundaries,
+            )
+            .await?
+        }
+        None => {
+            start_up::load_blockchain(block0, storage, cache_capacity, settings.rewards_report_all)
+                .await?
+        }
+    };
 
     if let Some(context) = &rest_context {
         let mut context = context.write().await;
@@ -549,11 +634,13 @@ async fn bootstrap_internal(
 
 pub struct InitializedNode {
     pub settings: Settings,
+    pub node_secrets: Vec<secure::NodeSecret>,
     pub block0: blockcfg::Block,
     pub storage: blockchain::Storage,
     pub rest_context: Option<rest::ContextLock>,
     pub services: Services,
     pub cancellation_token: CancellationToken,
+    pub runtime_settings: RuntimeSettings,
     pub _logger_guards: Vec<WorkerGuard>,
 }
 
@@ -623,7 +710,8 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
     let raw_settings = RawSettings::load(command_line)?;
 
     let log_settings = raw_settings.log_settings();
-    let (_logger_guards, log_info_msgs) = log_settings.init_log()?;
+    let configured_log_level = log_settings.config.level;
+    let (_logger_guards, log_level_handle, log_info_msgs) = log_settings.init_log()?;
 
     let init_span = span!(Level::TRACE, "task", kind = "init");
     let async_span = init_span.clone();
@@ -641,7 +729,17 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
     let diagnostic = Diagnostic::new()?;
     tracing::debug!("system settings are: {}", diagnostic);
 
-    let settings = raw_settings.try_into_settings()?;
+    let mut settings = raw_settings.try_into_settings()?;
+
+    // values changed at runtime override the configuration
+    let runtime_settings = RuntimeSettings::open(settings.storage.as_deref())?;
+    runtime_settings.apply(&mut settings)?;
+    if let Some(level) = runtime_settings.log_level()? {
+        tracing::info!(%level, "log level overridden by the runtime settings");
+        if let Err(error) = log_level_handle.set(level) {
+            tracing::warn!(%error, "cannot change the log level");
+        }
+    }
 
     let storage = start_up::prepare_storage(&settings)?;
     if exit_after_storage_setup {
@@ -656,17 +754,45 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
         return Err(network::bootstrap::Error::EmptyTrustedPeers.into());
     }
 
+    // load the secrets before anything runs in the background, an
+    // encrypted secret file may need a passphrase from the terminal
+    let node_secrets = settings
+        .secrets
+        .iter()
+        .map(|path| {
+            secure::NodeSecret::load_from_file(path, settings.secret_passphrase_file.as_deref())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    secure::check_unique_leaders(&node_secrets)?;
+
     let mut services = Services::new();
 
     let cancellation_token = CancellationToken::new();
     init_os_signal_watchers(&mut services, cancellation_token.clone());
 
+    {
+        let runtime_settings = runtime_settings.clone();
+        services.spawn_supervised("log_level", NON_CRITICAL_SERVICE, move |_| {
+            runtime_settings
+                .clone()
+                .follow_log_level(log_level_handle.clone(), configured_log_level)
+        });
+    }
+
     let rest_context = match settings.rest.clone() {
         Some(rest) => {
             use tokio::sync::RwLock;
 
             let mut context = rest::Context::new();
             context.set_diagnostic_data(diagnostic);
+            context.set_runtime_settings(runtime_settings.clone());
+            context.set_services(services.monitor());
+            context.set_health_checks(health::HealthChecks::new(
+                services.monitor(),
+                settings.no_blockchain_updates_warning_interval,
+                settings.readiness_min_peers,
+                settings.mempool.pool_max_entries.into(),
+            ));
             context.set_node_state(NodeState::PreparingStorage);
             let context = Arc::new(RwLock::new(context));
 
@@ -729,11 +855,13 @@ fn initialize_node() -> Result<InitializedNode, start_up::Error> {
 
     Ok(InitializedNode {
         settings,
+        node_secrets,
         block0,
         storage,
         rest_context,
         services,
         cancellation_token,
+        runtime_settings,
         _logger_guards,
     })
 }
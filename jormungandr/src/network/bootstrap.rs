@@ -1,27 +1,40 @@
 use super::grpc;
-use crate::blockcfg::Block;
-use crate::blockchain::{self, Blockchain, BootstrapError, Error as BlockchainError, Tip};
+use crate::blockcfg::{Block, HeaderHash};
+use crate::blockchain::{
+    self, Blockchain, BootstrapError, Checkpoints, Error as BlockchainError, Tip,
+};
 use crate::network::convert::Decode;
 use crate::settings::start::network::Peer;
 use crate::topology;
 use chain_core::property::Deserialize;
 use chain_network::data as net_data;
-use chain_network::error::Error as NetworkError;
-use futures::prelude::*;
+use chain_network::error::{Code, Error as NetworkError};
+use futures::{future, prelude::*, stream};
 use tokio_util::sync::CancellationToken;
 
+use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::fmt::Debug;
+use std::net::SocketAddr;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to connect to bootstrap peer")]
     Connect(#[source] grpc::ConnectError),
+    #[error("no peers available to bootstrap from")]
+    NoBootstrapPeers,
     #[error("connection broken")]
     ClientNotReady(#[source] NetworkError),
     #[error("peers not available")]
     PeersNotAvailable(#[source] NetworkError),
     #[error("bootstrap pull request failed")]
     PullRequestFailed(#[source] NetworkError),
+    #[error("bootstrap header pull request failed")]
+    PullHeadersFailed(#[source] NetworkError),
+    #[error("decoding of a header failed")]
+    HeaderDecodingFailed(#[source] NetworkError),
+    #[error("received header {0} is not connected to the header chain")]
+    HeaderChainBroken(HeaderHash),
     #[error("could not get the blockchain tip from a peer")]
     TipFailed(#[source] NetworkError),
     #[error("decoding of a peer failed")]
@@ -40,8 +53,37 @@ pub enum Error {
     Interrupted,
 }
 
+#[derive(thiserror::Error, Debug)]
+enum BlockRangeError {
+    #[error("peer sent {received} blocks out of {expected} requested")]
+    Incomplete { expected: usize, received: usize },
+    #[error("peer sent block {received} instead of {expected}")]
+    UnexpectedBlock {
+        expected: HeaderHash,
+        received: HeaderHash,
+    },
+}
+
 const MAX_BOOTSTRAP_PEERS: u32 = 32;
 
+/// Maximum number of peers the block ranges are downloaded from concurrently.
+const MAX_DOWNLOAD_PEERS: usize = 4;
+
+/// Maximum number of headers pulled in one round. The blocks of a round are
+/// downloaded and stored before the next round of headers is requested,
+/// which bounds the memory used by the header chain.
+const MAX_HEADERS_PER_ROUND: usize = 8192;
+
+/// Number of blocks requested from a peer in a single `GetBlocks` call.
+const BLOCK_RANGE_SIZE: usize = 128;
+
+/// Maximum number of block ranges in flight at the same time.
+const MAX_CONCURRENT_RANGES: usize = 8;
+
+/// Number of consecutive failed block downloads storing no new block after
+/// which the bootstrap gives up.
+const MAX_RESUMES_WITHOUT_PROGRESS: u32 = 3;
+
 pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>, Error> {
     tracing::info!("getting peers from bootstrap peer {}", peer.connection);
 
@@ -62,69 +104,463 @@ pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>,
     Ok(peers)
 }
 
-pub async fn bootstrap_from_peer(
-    peer: &Peer,
+async fn with_cancellation_token<T>(
+    future: impl Future<Output = T> + Unpin,
+    token: &CancellationToken,
+) -> Result<T, Error> {
+    use futures::future::{select, Either};
+
+    match select(future, token.cancelled().boxed()).await {
+        Either::Left((result, _)) => Ok(result),
+        Either::Right(((), _)) => Err(Error::Interrupted),
+    }
+}
+
+/// Bootstrap the blockchain from several peers at once.
+///
+/// The header chain is pulled from the first reachable peer, then the blocks
+/// are downloaded in ranges concurrently from up to `MAX_DOWNLOAD_PEERS` peers
+/// and checked against the header chain before being applied in order.
+///
+/// Every round starts from the checkpoints of the current tip, so a failure
+/// leaves the blocks stored so far in place and the next round resumes from
+/// the last stored block. When the header peer fails, the header chain is
+/// pulled from the next connected peer; a failed block download is resumed
+/// as long as the previous attempt made progress. Once the connected peers
+/// have all failed, the bootstrap goes on with the next peers of the list,
+/// until none is left.
+pub async fn bootstrap_from_peers(
+    peers: &[Peer],
     blockchain: Blockchain,
     tip: Tip,
     cancellation_token: CancellationToken,
 ) -> Result<(), Error> {
-    use chain_network::data::BlockId;
-    use std::convert::TryFrom;
-
-    async fn with_cancellation_token<T>(
-        future: impl Future<Output = T> + Unpin,
-        token: &CancellationToken,
-    ) -> Result<T, Error> {
-        use futures::future::{select, Either};
-
-        match select(future, token.cancelled().boxed()).await {
-            Either::Left((result, _)) => Ok(result),
-            Either::Right(((), _)) => Err(Error::Interrupted),
+    let mut remaining = peers;
+    let mut last_error = None;
+
+    loop {
+        let connected =
+            with_cancellation_token(connect_to_peers(remaining).boxed(), &cancellation_token)
+                .await?;
+        let (clients, rest) = match connected {
+            Ok(connected) => connected,
+            // report why the previous peers failed rather than the
+            // unreachable ones
+            Err(error) => break Err(last_error.unwrap_or(error)),
+        };
+        remaining = rest;
+
+        let error =
+            match bootstrap_from_clients(&clients, &blockchain, &tip, &cancellation_token).await {
+                Ok(()) => break Ok(()),
+                Err(error) => error,
+            };
+
+        let interrupted = match &error {
+            Error::Interrupted => true,
+            Error::Blockchain(e) => matches!(**e, BootstrapError::Interrupted),
+            _ => false,
+        };
+        if interrupted || remaining.is_empty() {
+            break Err(error);
         }
+
+        tracing::warn!(
+            reason = %error,
+            "bootstrap failed with all the connected peers, trying the {} peers left",
+            remaining.len(),
+        );
+        last_error = Some(error);
     }
+}
+
+/// Bootstrap from the connected peers, switching the header peer and
+/// resuming the downloads until all of them failed.
+async fn bootstrap_from_clients(
+    clients: &[(SocketAddr, grpc::Client)],
+    blockchain: &Blockchain,
+    tip: &Tip,
+    cancellation_token: &CancellationToken,
+) -> Result<(), Error> {
+    let mut attempts = Attempts::new(clients.len());
 
-    tracing::debug!("connecting to bootstrap peer {}", peer.connection);
+    loop {
+        let (header_peer, mut client) = clients[attempts.header_peer()].clone();
+        let stored_tip = tip.get_ref().await.hash();
+
+        let result = bootstrap_round(
+            header_peer,
+            &mut client,
+            clients,
+            blockchain,
+            tip,
+            cancellation_token,
+        )
+        .await;
+        let progressed = tip.get_ref().await.hash() != stored_tip;
+
+        let error = match result {
+            Ok(Round::Completed) => break Ok(()),
+            Ok(Round::Continue) => {
+                attempts.round_completed();
+                continue;
+            }
+            Err(error) => error,
+        };
 
-    let mut client = with_cancellation_token(grpc::connect(peer).boxed(), &cancellation_token)
+        let retry = match &error {
+            Error::Interrupted => false,
+            Error::Blockchain(e) if matches!(**e, BootstrapError::Interrupted) => false,
+            Error::Blockchain(e) if matches!(**e, BootstrapError::PullStreamFailed(_)) => {
+                attempts.download_failed(progressed)
+            }
+            _ => attempts.header_peer_failed(),
+        };
+        if !retry {
+            break Err(error);
+        }
+
+        tracing::warn!(
+            reason = %error,
+            "bootstrap round with header peer {} failed, resuming from the last stored block with header peer {}",
+            header_peer,
+            clients[attempts.header_peer()].0,
+        );
+    }
+}
+
+enum Round {
+    /// the node is at the tip of the header peer
+    Completed,
+    /// the blocks of a round of headers were applied, more may follow
+    Continue,
+}
+
+/// Pull the next round of headers from the header peer and download their
+/// blocks from all the peers.
+async fn bootstrap_round(
+    header_peer: SocketAddr,
+    client: &mut grpc::Client,
+    clients: &[(SocketAddr, grpc::Client)],
+    blockchain: &Blockchain,
+    tip: &Tip,
+    cancellation_token: &CancellationToken,
+) -> Result<Round, Error> {
+    tracing::debug!("pulling the header chain from peer {}", header_peer);
+
+    let remote_tip = with_cancellation_token(client.tip().boxed(), cancellation_token)
         .await?
-        .map_err(Error::Connect)?;
+        .and_then(|header| header.decode())
+        .map_err(Error::TipFailed)?
+        .id();
 
-    loop {
-        let remote_tip = with_cancellation_token(client.tip().boxed(), &cancellation_token)
-            .await?
-            .and_then(|header| header.decode())
-            .map_err(Error::TipFailed)?
-            .id();
+    if remote_tip == tip.get_ref().await.hash() {
+        return Ok(Round::Completed);
+    }
+
+    let checkpoints = blockchain.get_checkpoints(tip.branch()).await;
+
+    tracing::info!(
+        "pulling headers starting from checkpoints: {:?}; to tip {}",
+        checkpoints,
+        remote_tip,
+    );
+
+    let headers = pull_header_chain(client, checkpoints, remote_tip, cancellation_token).await?;
+
+    if headers.is_empty() {
+        tracing::info!("no new headers received from peer {}", header_peer);
+        return Ok(Round::Completed);
+    }
 
-        if remote_tip == tip.get_ref().await.hash() {
-            break Ok(());
+    tracing::info!(
+        "downloading {} blocks from {} peers",
+        headers.len(),
+        clients.len()
+    );
+
+    blockchain::bootstrap_from_stream(
+        blockchain.clone(),
+        tip.clone(),
+        download_blocks(clients, headers),
+        cancellation_token.clone(),
+    )
+    .await
+    .map_err(Box::new)?;
+
+    Ok(Round::Continue)
+}
+
+/// Keeps track of the failed rounds of a bootstrap.
+///
+/// The header peer is replaced by the next connected peer when it fails,
+/// until every peer has failed once. A failed block download is retried
+/// from the last stored block, and the bootstrap gives up after
+/// `MAX_RESUMES_WITHOUT_PROGRESS` consecutive failures storing no block.
+#[derive(Debug)]
+struct Attempts {
+    peers: usize,
+    header_peer: usize,
+    failed_header_peers: usize,
+    resumes_without_progress: u32,
+}
+
+impl Attempts {
+    fn new(peers: usize) -> Self {
+        Attempts {
+            peers,
+            header_peer: 0,
+            failed_header_peers: 0,
+            resumes_without_progress: 0,
         }
+    }
 
-        let checkpoints = blockchain.get_checkpoints(tip.branch()).await;
-        let checkpoints = net_data::block::try_ids_from_iter(checkpoints).unwrap();
+    /// index of the peer the headers are pulled from
+    fn header_peer(&self) -> usize {
+        self.header_peer
+    }
 
-        let remote_tip = BlockId::try_from(remote_tip.as_ref()).unwrap();
+    /// a round stored its blocks, every peer can be the header peer again
+    fn round_completed(&mut self) {
+        self.failed_header_peers = 0;
+        self.resumes_without_progress = 0;
+    }
 
-        tracing::info!(
-            "pulling blocks starting from checkpoints: {:?}; to tip {:?}",
-            checkpoints,
-            remote_tip,
-        );
+    /// switch to the next header peer, returns false when all of them failed
+    /// since the last completed round
+    fn header_peer_failed(&mut self) -> bool {
+        self.failed_header_peers += 1;
+        self.header_peer = (self.header_peer + 1) % self.peers;
+        self.failed_header_peers < self.peers
+    }
 
-        let stream = with_cancellation_token(
-            client.pull_blocks(checkpoints, remote_tip).boxed(),
-            &cancellation_token,
-        )
+    /// returns whether the download is to be resumed
+    fn download_failed(&mut self, progressed: bool) -> bool {
+        if progressed {
+            self.resumes_without_progress = 0;
+        } else {
+            self.resumes_without_progress += 1;
+        }
+        self.resumes_without_progress <= MAX_RESUMES_WITHOUT_PROGRESS
+    }
+}
+
+/// Connect to up to `MAX_DOWNLOAD_PEERS` of the given peers, trying them in
+/// order. The peers not tried yet are returned with the clients.
+async fn connect_to_peers(
+    peers: &[Peer],
+) -> Result<(Vec<(SocketAddr, grpc::Client)>, &[Peer]), Error> {
+    let mut clients = Vec::with_capacity(MAX_DOWNLOAD_PEERS);
+    let mut last_error = None;
+    let mut tried = 0;
+
+    while tried < peers.len() && clients.len() < MAX_DOWNLOAD_PEERS {
+        let wanted = MAX_DOWNLOAD_PEERS - clients.len();
+        let batch = &peers[tried..peers.len().min(tried + wanted)];
+        tried += batch.len();
+        let results = future::join_all(batch.iter().map(|peer| async move {
+            tracing::debug!("connecting to bootstrap peer {}", peer.connection);
+            (peer.connection, grpc::connect(peer).await)
+        }))
+        .await;
+
+        for (addr, result) in results {
+            match result {
+                Ok(client) => clients.push((addr, client)),
+                Err(e) => {
+                    tracing::warn!(reason = %e, "unable to reach bootstrap peer {}", addr);
+                    last_error = Some(e);
+                }
+            }
+        }
+    }
+
+    if clients.is_empty() {
+        return Err(last_error.map_or(Error::NoBootstrapPeers, Error::Connect));
+    }
+
+    Ok((clients, &peers[tried..]))
+}
+
+/// Pull at most `MAX_HEADERS_PER_ROUND` headers towards `remote_tip` and check
+/// that they form a chain rooted in one of the checkpoints.
+async fn pull_header_chain(
+    client: &mut grpc::Client,
+    checkpoints: Checkpoints,
+    remote_tip: HeaderHash,
+    cancellation_token: &CancellationToken,
+) -> Result<Vec<HeaderHash>, Error> {
+    let known = checkpoints.iter().cloned().collect::<HashSet<_>>();
+    let checkpoints = net_data::block::try_ids_from_iter(checkpoints).unwrap();
+    let remote_tip = net_data::BlockId::try_from(remote_tip.as_ref()).unwrap();
+
+    let stream = with_cancellation_token(
+        client.pull_headers(checkpoints, remote_tip).boxed(),
+        cancellation_token,
+    )
+    .await?
+    .map_err(Error::PullHeadersFailed)?;
+    let mut stream = stream.take(MAX_HEADERS_PER_ROUND).boxed();
+
+    let mut headers: Vec<HeaderHash> = Vec::new();
+    while let Some(header) = with_cancellation_token(stream.next(), cancellation_token).await? {
+        let header = header
+            .and_then(|header| header.decode())
+            .map_err(Error::HeaderDecodingFailed)?;
+        let parent = header.block_parent_hash();
+        let connected = match headers.last() {
+            Some(previous) => *previous == parent,
+            None => known.contains(&parent),
+        };
+        if !connected {
+            return Err(Error::HeaderChainBroken(header.hash()));
+        }
+        headers.push(header.hash());
+    }
+
+    Ok(headers)
+}
+
+/// Download the blocks of the given header chain in ranges, spreading the
+/// ranges over the connected peers. The blocks are yielded in chain order.
+fn download_blocks(
+    clients: &[(SocketAddr, grpc::Client)],
+    headers: Vec<HeaderHash>,
+) -> impl Stream<Item = Result<net_data::Block, NetworkError>> + Unpin {
+    let clients = clients.to_vec();
+    let ranges = headers
+        .chunks(BLOCK_RANGE_SIZE)
+        .map(<[HeaderHash]>::to_vec)
+        .collect::<Vec<_>>();
+
+    stream::iter(ranges.into_iter().enumerate())
+        .map(move |(index, range)| fetch_block_range(clients.clone(), index, range))
+        .buffered(MAX_CONCURRENT_RANGES)
+        .map_ok(|blocks| stream::iter(blocks.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+}
+
+/// Fetch one range of blocks. Consecutive ranges start on different peers to
+/// spread the load; when a peer fails, the range is requested from the next one.
+async fn fetch_block_range(
+    clients: Vec<(SocketAddr, grpc::Client)>,
+    index: usize,
+    range: Vec<HeaderHash>,
+) -> Result<Vec<net_data::Block>, NetworkError> {
+    let mut last_error = None;
+
+    for attempt in 0..clients.len() {
+        let (addr, mut client) = clients[(index + attempt) % clients.len()].clone();
+        match fetch_block_range_from(&mut client, &range).await {
+            Ok(blocks) => return Ok(blocks),
+            Err(e) => {
+                tracing::debug!(reason = %e, "failed to download block range from peer {}", addr);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.expect("there is always at least one peer to download from"))
+}
+
+async fn fetch_block_range_from(
+    client: &mut grpc::Client,
+    range: &[HeaderHash],
+) -> Result<Vec<net_data::Block>, NetworkError> {
+    let ids = net_data::block::try_ids_from_iter(range.iter().cloned()).unwrap();
+    let blocks = client
+        .get_blocks(ids)
         .await?
-        .map_err(Error::PullRequestFailed)?;
+        .try_collect::<Vec<_>>()
+        .await?;
 
-        blockchain::bootstrap_from_stream(
-            blockchain.clone(),
-            tip.clone(),
-            stream,
-            cancellation_token.clone(),
-        )
-        .await
-        .map_err(Box::new)?;
+    if blocks.len() != range.len() {
+        return Err(NetworkError::new(
+            Code::NotFound,
+            BlockRangeError::Incomplete {
+                expected: range.len(),
+                received: blocks.len(),
+            },
+        ));
+    }
+
+    for (block, expected) in blocks.iter().zip(range) {
+        let received = Block::deserialize(block.as_bytes())
+            .map_err(|e| NetworkError::new(Code::InvalidArgument, e))?
+            .header()
+            .hash();
+        if received != *expected {
+            return Err(NetworkError::new(
+                Code::InvalidArgument,
+                BlockRangeError::UnexpectedBlock {
+                    expected: *expected,
+                    received,
+                },
+            ));
+        }
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_peer_failure_switches_to_the_next_peer() {
+        let mut attempts = Attempts::new(3);
+        assert_eq!(attempts.header_peer(), 0);
+
+        assert!(attempts.header_peer_failed());
+        assert_eq!(attempts.header_peer(), 1);
+        assert!(attempts.header_peer_failed());
+        assert_eq!(attempts.header_peer(), 2);
+        // every peer failed once
+        assert!(!attempts.header_peer_failed());
+    }
+
+    #[test]
+    fn header_peer_failure_with_a_single_peer() {
+        let mut attempts = Attempts::new(1);
+        assert!(!attempts.header_peer_failed());
+    }
+
+    #[test]
+    fn completed_round_resets_the_header_peer_failures() {
+        let mut attempts = Attempts::new(2);
+        assert!(attempts.header_peer_failed());
+        attempts.round_completed();
+        assert!(attempts.header_peer_failed());
+        assert_eq!(attempts.header_peer(), 0);
+        assert!(!attempts.header_peer_failed());
+    }
+
+    #[test]
+    fn download_failure_resumes_while_progressing() {
+        let mut attempts = Attempts::new(2);
+        for _ in 0..10 {
+            assert!(attempts.download_failed(true));
+        }
+        assert_eq!(attempts.header_peer(), 0);
+
+        for _ in 0..MAX_RESUMES_WITHOUT_PROGRESS {
+            assert!(attempts.download_failed(false));
+        }
+        assert!(!attempts.download_failed(false));
+    }
+
+    #[test]
+    fn download_progress_resets_the_resumes() {
+        let mut attempts = Attempts::new(2);
+        for _ in 0..MAX_RESUMES_WITHOUT_PROGRESS {
+            assert!(attempts.download_failed(false));
+        }
+        assert!(attempts.download_failed(true));
+        for _ in 0..MAX_RESUMES_WITHOUT_PROGRESS {
+            assert!(attempts.download_failed(false));
+        }
+        assert!(!attempts.download_failed(false));
     }
 }
@@ -637,8 +637,6 @@ pub async fn bootstrap(
         return Err(bootstrap::Error::EmptyTrustedPeers);
     }
 
-    let mut bootstrapped = false;
-
     let (netboot_peers, _) = match select(
         netboot_peers(config, span).boxed(),
         cancellation_token.cancelled().boxed(),
@@ -656,53 +654,54 @@ pub async fn bootstrap(
     let mut rng = rand::thread_rng();
     bootstrap_peers.shuffle(&mut rng);
 
-    for peer in &bootstrap_peers {
-        let span =
-            span!(parent: span, Level::DEBUG, "bootstrap", peer_addr = %peer.address().to_string());
-        let res = bootstrap::bootstrap_from_peer(
-            &Peer::new(peer.address()),
-            blockchain.clone(),
-            branch.clone(),
-            cancellation_token.clone(),
-        )
-        .instrument(span.clone())
-        .await;
+    let peers = bootstrap_peers
+        .iter()
+        .map(|peer| Peer::new(peer.address()))
+        .collect::<Vec<_>>();
+    let span = span!(parent: span, Level::DEBUG, "bootstrap", peers = peers.len());
+    let res = bootstrap::bootstrap_from_peers(
+        &peers,
+        blockchain.clone(),
+        branch.clone(),
+        cancellation_token.clone(),
+    )
+    .instrument(span.clone())
+    .await;
 
-        match res {
-            Err(bootstrap::Error::Connect(e)) => {
-                async move {
-                    tracing::warn!(reason = %e, "unable to reach peer for initial bootstrap");
-                }
-                .instrument(span)
-                .await;
+    let bootstrapped = match res {
+        Err(bootstrap::Error::Connect(e)) => {
+            async move {
+                tracing::warn!(reason = %e, "unable to reach any peer for initial bootstrap");
             }
-            Err(bootstrap::Error::Interrupted) => {
-                async move {
-                    tracing::warn!("the bootstrap process was interrupted");
-                }
-                .instrument(span)
-                .await;
-                return Err(bootstrap::Error::Interrupted);
+            .instrument(span)
+            .await;
+            false
+        }
+        Err(bootstrap::Error::Interrupted) => {
+            async move {
+                tracing::warn!("the bootstrap process was interrupted");
             }
-            Err(e) => {
-                async move {
-                    tracing::warn!(error = ?e, "initial bootstrap failed");
-                }
-                .instrument(span)
-                .await;
+            .instrument(span)
+            .await;
+            return Err(bootstrap::Error::Interrupted);
+        }
+        Err(e) => {
+            async move {
+                tracing::warn!(error = ?e, "initial bootstrap failed");
             }
-            Ok(()) => {
-                async move {
-                    tracing::info!("initial bootstrap completed");
-                }
-                .instrument(span)
-                .await;
-
-                bootstrapped = true;
-                break;
+            .instrument(span)
+            .await;
+            false
+        }
+        Ok(()) => {
+            async move {
+                tracing::info!("initial bootstrap completed");
             }
+            .instrument(span)
+            .await;
+            true
         }
-    }
+    };
 
     blockchain
         .gc(branch.get_ref().await)
@@ -3,13 +3,15 @@ use std::sync::Arc;
 use crate::{
     blockchain::{Blockchain, Tip},
     diagnostic::Diagnostic,
+    health::HealthChecks,
     intercom::{NetworkMsg, TopologyMsg, TransactionMsg},
-    leadership::Logs as LeadershipLogs,
+    leadership::{Logs as LeadershipLogs, Rehearsal as LeadershipRehearsal},
     metrics::backends::SimpleCounter,
     network::GlobalStateR as NetworkStateR,
     rest::ServerStopper,
     secure::enclave::Enclave,
-    utils::async_msg::MessageBox,
+    settings::runtime::RuntimeSettings,
+    utils::{async_msg::MessageBox, task::ServicesMonitor},
 };
 use jormungandr_lib::interfaces::NodeState;
 
@@ -28,6 +30,9 @@ pub struct Context {
     blockchain: Option<Blockchain>,
     blockchain_tip: Option<Tip>,
     bootstrap_stopper: Option<CancellationToken>,
+    runtime_settings: Option<RuntimeSettings>,
+    health_checks: Option<HealthChecks>,
+    services: Option<ServicesMonitor>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -44,6 +49,12 @@ pub enum Error {
     BlockchainTip,
     #[error("Diagnostic data not set in REST context")]
     Diagnostic,
+    #[error("Runtime settings not set in REST context")]
+    RuntimeSettings,
+    #[error("Health checks not set in REST context")]
+    HealthChecks,
+    #[error("Services monitor not set in REST context")]
+    Services,
 }
 
 impl warp::reject::Reject for Error {}
@@ -65,6 +76,9 @@ impl Context {
             blockchain: Default::default(),
             blockchain_tip: Default::default(),
             bootstrap_stopper: Default::default(),
+            runtime_settings: Default::default(),
+            health_checks: Default::default(),
+            services: Default::default(),
         }
     }
 
@@ -108,6 +122,30 @@ impl Context {
         self.diagnostic.as_ref().ok_or(Error::Diagnostic)
     }
 
+    pub fn set_runtime_settings(&mut self, runtime_settings: RuntimeSettings) {
+        self.runtime_settings = Some(runtime_settings);
+    }
+
+    pub fn runtime_settings(&self) -> Result<&RuntimeSettings, Error> {
+        self.runtime_settings.as_ref().ok_or(Error::RuntimeSettings)
+    }
+
+    pub fn set_health_checks(&mut self, health_checks: HealthChecks) {
+        self.health_checks = Some(health_checks);
+    }
+
+    pub fn health_checks(&self) -> Result<&HealthChecks, Error> {
+        self.health_checks.as_ref().ok_or(Error::HealthChecks)
+    }
+
+    pub fn set_services(&mut self, services: ServicesMonitor) {
+        self.services = Some(services);
+    }
+
+    pub fn services(&self) -> Result<&ServicesMonitor, Error> {
+        self.services.as_ref().ok_or(Error::Services)
+    }
+
     pub fn set_blockchain(&mut self, blockchain: Blockchain) {
         self.blockchain = Some(blockchain)
     }
@@ -145,6 +183,7 @@ pub struct FullContext {
     pub topology_task: MessageBox<TopologyMsg>,
     pub transaction_task: MessageBox<TransactionMsg>,
     pub leadership_logs: LeadershipLogs,
+    pub leadership_rehearsal: LeadershipRehearsal,
     pub enclave: Enclave,
     pub network_state: NetworkStateR,
     pub explorer: Option<crate::explorer::Explorer>,
@@ -76,6 +76,35 @@ pub async fn get_block_next_id(
         .ok_or_else(warp::reject::not_found)
 }
 
+#[derive(Deserialize)]
+pub struct GetSnapshotBlocksQuery {
+    count: Option<u32>,
+}
+
+pub async fn get_snapshot_blocks(
+    block_id_hex: String,
+    query: GetSnapshotBlocksQuery,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    let count = query.count.unwrap_or(1);
+    logic::get_snapshot_blocks(&context, &block_id_hex, count as usize)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+}
+
+pub async fn get_snapshot_ledgers(
+    block_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_snapshot_ledgers(&context, &block_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .ok_or_else(warp::reject::not_found)
+}
+
 pub async fn get_stake_distribution(context: ContextLock) -> Result<impl Reply, Rejection> {
     let context = context.read().await;
     logic::get_stake_distribution(&context)
@@ -28,11 +28,12 @@ use chain_impl_mockchain::{
     value::ValueError,
 };
 use jormungandr_lib::{
+    crypto::hash::Hash as LibHash,
     interfaces::{
-        AccountState, EpochRewardsInfo, FragmentLog, FragmentOrigin, FragmentsProcessingSummary,
-        LeadershipLog, NodeStatsDto, PeerStats, Rewards as StakePoolRewards, SettingsDto,
-        StakeDistribution, StakeDistributionDto, StakePoolStats, TaxTypeSerde, TransactionOutput,
-        Value, VotePlanStatus,
+        write_snapshot_block, write_snapshot_ledger, AccountState, EpochRewardsInfo, FragmentLog,
+        FragmentOrigin, FragmentsProcessingSummary, LeadershipLog, NodeStatsDto, PeerStats,
+        Rewards as StakePoolRewards, SettingsDto, StakeDistribution, StakeDistributionDto,
+        StakePoolStats, TaxTypeSerde, TransactionOutput, Value, VotePlanStatus,
     },
     time::SystemTime,
 };
@@ -207,6 +208,86 @@ pub async fn get_block_next_id(
     }
 }
 
+/// Up to `count` blocks following block `block_id` in the chain of the tip,
+/// in the format of the snapshot blocks file.
+pub async fn get_snapshot_blocks(
+    context: &Context,
+    block_id_hex: &str,
+    count: usize,
+) -> Result<Option<Vec<u8>>, Error> {
+    let blockchain = context.blockchain()?;
+    let block_id = parse_block_hash(block_id_hex)?;
+    let tip = context.blockchain_tip()?.get_ref().await;
+    let maybe_stream = blockchain
+        .storage()
+        .stream_from_to(block_id, tip.hash())
+        .map(Some)
+        .or_else(|e| match e {
+            StorageError::CannotIterate | StorageError::BlockNotFound => Ok(None),
+            e => Err(Error::Storage(e)),
+        })?;
+
+    if let Some(stream) = maybe_stream {
+        Some(
+            stream
+                .map_err(Into::into)
+                .take(count)
+                .try_fold(Vec::new(), |mut bytes, block| async move {
+                    let block = block.serialize_as_vec().map_err(Error::Serialize)?;
+                    write_snapshot_block(&mut bytes, &block).map_err(Error::Serialize)?;
+                    Ok(bytes)
+                })
+                .await,
+        )
+        .transpose()
+    } else {
+        Ok(None)
+    }
+}
+
+/// number of epoch boundaries whose ledger states are part of a snapshot:
+/// the tip of the snapshot and the two boundaries before it are needed to
+/// rebuild the leadership schedule of the epoch of the tip
+const SNAPSHOT_BOUNDARIES: usize = 3;
+
+/// The ledger states of the snapshot ending with block `block_id`, in the
+/// format of the snapshot ledgers file. The block must be the last block of
+/// an epoch in the chain of the tip.
+pub async fn get_snapshot_ledgers(
+    context: &Context,
+    block_id_hex: &str,
+) -> Result<Option<Vec<u8>>, Error> {
+    let block_id = parse_block_hash(block_id_hex)?;
+    let mut boundary = Some(context.blockchain_tip()?.get_ref().await);
+    while let Some(reference) = boundary.as_ref() {
+        if reference.hash() == block_id {
+            break;
+        }
+        boundary = reference.last_ref_previous_epoch().cloned();
+    }
+    let mut boundaries = match boundary {
+        Some(reference) => vec![reference],
+        None => return Ok(None),
+    };
+    while boundaries.len() < SNAPSHOT_BOUNDARIES {
+        match boundaries.last().unwrap().last_ref_previous_epoch() {
+            Some(previous) => boundaries.push(previous.clone()),
+            None => break,
+        }
+    }
+
+    let mut bytes = Vec::new();
+    for reference in boundaries.iter().rev() {
+        let ledger = reference
+            .ledger()
+            .serialize_as_vec()
+            .map_err(Error::Serialize)?;
+        write_snapshot_ledger(&mut bytes, LibHash::from_hash(reference.hash()), &ledger)
+            .map_err(Error::Serialize)?;
+    }
+    Ok(Some(bytes))
+}
+
 pub async fn get_stake_distribution(
     context: &Context,
 ) -> Result<Option<StakeDistributionDto>, Error> {
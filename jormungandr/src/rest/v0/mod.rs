@@ -145,6 +145,25 @@ pub fn filter(
         root.and(get.or(get_at)).boxed()
     };
 
+    let snapshot = {
+        let root = warp::path!("snapshot" / ..);
+
+        let blocks = warp::path!("blocks" / String)
+            .and(warp::get())
+            .and(warp::query())
+            .and(with_context.clone())
+            .and_then(handlers::get_snapshot_blocks)
+            .boxed();
+
+        let ledgers = warp::path!("ledgers" / String)
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_snapshot_ledgers)
+            .boxed();
+
+        root.and(blocks.or(ledgers)).boxed()
+    };
+
     let stake_pools = warp::path!("stake_pools")
         .and(warp::get())
         .and(with_context.clone())
@@ -236,6 +255,7 @@ pub fn filter(
         .or(network)
         .or(settings)
         .or(stake)
+        .or(snapshot)
         .or(stake_pools)
         .or(stake_pool)
         .or(message)
@@ -1,6 +1,6 @@
 use crate::rest::{v1::logic, ContextLock};
-use jormungandr_lib::interfaces::{FragmentsBatch, VotePlanId};
-use warp::{reject::Reject, Rejection, Reply};
+use jormungandr_lib::interfaces::{FragmentsBatch, Health, LeadershipRehearsalRequest, VotePlanId};
+use warp::{http::StatusCode, reject::Reject, Rejection, Reply};
 
 impl Reject for logic::Error {}
 
@@ -15,6 +15,17 @@ pub async fn post_fragments(
         .map_err(warp::reject::custom)
 }
 
+pub async fn simulate_fragments(
+    fragments: FragmentsBatch,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::simulate_fragments(&context, fragments)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
 #[derive(Deserialize)]
 pub struct GetMessageStatusesQuery {
     fragment_ids: String,
@@ -64,3 +75,127 @@ pub async fn get_account_votes(
         .ok_or_else(warp::reject::not_found)
         .map(|r| warp::reply::json(&r))
 }
+
+pub async fn get_runtime_settings(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_runtime_settings(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_runtime_setting(
+    name: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_runtime_setting(&context, &name)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn put_runtime_setting(
+    name: String,
+    value: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::put_runtime_setting(&context, &name, value)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn delete_runtime_setting(
+    name: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::delete_runtime_setting(&context, &name)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_liveness(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_liveness(&context)
+        .await
+        .map(health_reply)
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_readiness(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_readiness(&context)
+        .await
+        .map(health_reply)
+        .map_err(warp::reject::custom)
+}
+
+/// orchestrators only look at the status code, the body tells why
+fn health_reply(health: Health) -> impl Reply {
+    let status = if health.is_pass() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    warp::reply::with_status(warp::reply::json(&health), status)
+}
+
+pub async fn get_services(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_services(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn rehearse_leadership(
+    request: LeadershipRehearsalRequest,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::rehearse_leadership(&context, request)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_kes_status(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_kes_status(&context)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
+
+pub async fn get_leaders(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_leaders(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_leadership_logs(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_leadership_logs(&context)
+        .await
+        .map(|r| warp::reply::json(&r))
+        .map_err(warp::reject::custom)
+}
+
+pub async fn get_leader_leadership_logs(
+    leader: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_leader_leadership_logs(&context, &leader)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
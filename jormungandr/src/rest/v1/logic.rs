@@ -1,7 +1,10 @@
 use crate::{
     blockchain::StorageError,
     intercom::{self, TransactionMsg},
+    leadership::RehearsalError,
     rest::Context,
+    secure,
+    settings::runtime::{self, Tunable},
 };
 use chain_crypto::{
     digest::Error as DigestError, hash::Error as HashError, PublicKey, PublicKeyFromStrError,
@@ -9,12 +12,15 @@ use chain_crypto::{
 use chain_impl_mockchain::{
     account::{AccountAlg, Identifier},
     fragment::FragmentId,
+    ledger,
     value::ValueError,
 };
 use futures::{channel::mpsc::SendError, channel::mpsc::TrySendError, prelude::*};
 use jormungandr_lib::interfaces::{
     AccountVotes, FragmentLog, FragmentOrigin, FragmentStatus, FragmentsBatch,
-    FragmentsProcessingSummary, VotePlanId,
+    FragmentsProcessingSummary, FragmentsSimulation, Health, KesStatus, LeaderIdentity,
+    LeadershipLog, LeadershipRehearsal, LeadershipRehearsalRequest, RuntimeSetting, ServiceStatus,
+    VotePlanId,
 };
 use std::{collections::HashMap, convert::TryInto, str::FromStr};
 use tracing::{span, Level};
@@ -45,6 +51,12 @@ pub enum Error {
     Hex(#[from] hex::FromHexError),
     #[error("Could not process all fragments")]
     Fragments(FragmentsProcessingSummary),
+    #[error("Could not simulate the fragments on the tip ledger")]
+    Simulation(#[source] ledger::Error),
+    #[error(transparent)]
+    RuntimeSettings(#[from] runtime::Error),
+    #[error(transparent)]
+    Rehearsal(#[from] RehearsalError),
 }
 
 fn parse_account_id(id_hex: &str) -> Result<Identifier, Error> {
@@ -107,6 +119,14 @@ pub async fn post_fragments(
     }
 }
 
+pub async fn simulate_fragments(
+    context: &Context,
+    batch: FragmentsBatch,
+) -> Result<FragmentsSimulation, Error> {
+    let tip = context.blockchain_tip()?.get_ref().await;
+    crate::fragment::dry_run(tip, batch.fragments, batch.fail_fast).map_err(Error::Simulation)
+}
+
 pub async fn get_fragment_logs(context: &Context) -> Result<Vec<FragmentLog>, Error> {
     let span =
         span!(parent: context.span()?, Level::TRACE, "fragment_logs", request = "fragment_logs");
@@ -203,3 +223,110 @@ pub async fn get_account_votes(
     .instrument(span)
     .await
 }
+
+fn runtime_setting(context: &Context, tunable: Tunable) -> Result<RuntimeSetting, Error> {
+    Ok(RuntimeSetting {
+        name: tunable.to_string(),
+        value: context.runtime_settings()?.get(tunable)?,
+        applies_on_restart: tunable.applies_on_restart(),
+    })
+}
+
+pub async fn get_runtime_settings(context: &Context) -> Result<Vec<RuntimeSetting>, Error> {
+    Tunable::ALL
+        .iter()
+        .map(|tunable| runtime_setting(context, *tunable))
+        .collect()
+}
+
+pub async fn get_runtime_setting(context: &Context, name: &str) -> Result<RuntimeSetting, Error> {
+    runtime_setting(context, name.parse()?)
+}
+
+pub async fn put_runtime_setting(
+    context: &Context,
+    name: &str,
+    value: String,
+) -> Result<RuntimeSetting, Error> {
+    let tunable = name.parse()?;
+    context.runtime_settings()?.set(tunable, &value)?;
+    runtime_setting(context, tunable)
+}
+
+pub async fn delete_runtime_setting(
+    context: &Context,
+    name: &str,
+) -> Result<RuntimeSetting, Error> {
+    let tunable = name.parse()?;
+    context.runtime_settings()?.remove(tunable)?;
+    runtime_setting(context, tunable)
+}
+
+pub async fn get_liveness(context: &Context) -> Result<Health, Error> {
+    Ok(context.health_checks()?.liveness(context).await)
+}
+
+pub async fn get_readiness(context: &Context) -> Result<Health, Error> {
+    Ok(context.health_checks()?.readiness(context).await)
+}
+
+pub async fn get_services(context: &Context) -> Result<Vec<ServiceStatus>, Error> {
+    Ok(context.services()?.statuses())
+}
+
+pub async fn rehearse_leadership(
+    context: &Context,
+    request: LeadershipRehearsalRequest,
+) -> Result<LeadershipRehearsal, Error> {
+    let span =
+        span!(parent: context.span()?, Level::TRACE, "leadership_rehearsal", date = ?request.date);
+    async move {
+        let rehearsal = context.try_full()?.leadership_rehearsal.clone();
+        rehearsal
+            .run(request.date.map(Into::into))
+            .await
+            .map_err(Into::into)
+    }
+    .instrument(span)
+    .await
+}
+
+pub async fn get_kes_status(context: &Context) -> Result<Option<Vec<KesStatus>>, Error> {
+    let enclave = &context.try_full()?.enclave;
+    let statuses = secure::kes::status(enclave, context.blockchain_tip()?).await;
+    Ok(Some(statuses).filter(|statuses| !statuses.is_empty()))
+}
+
+pub async fn get_leaders(context: &Context) -> Result<Vec<LeaderIdentity>, Error> {
+    Ok(context.try_full()?.enclave.leaders())
+}
+
+pub async fn get_leadership_logs(context: &Context) -> Result<Vec<LeadershipLog>, Error> {
+    Ok(context.try_full()?.leadership_logs.logs().await)
+}
+
+/// the leadership logs of one of the leaders of the node, `None` when the
+/// node does not run as this leader
+pub async fn get_leader_leadership_logs(
+    context: &Context,
+    leader: &str,
+) -> Result<Option<Vec<LeadershipLog>>, Error> {
+    let full_context = context.try_full()?;
+    let leader = match full_context
+        .enclave
+        .leaders()
+        .into_iter()
+        .find(|identity| identity.to_string() == leader)
+    {
+        Some(leader) => leader,
+        None => return Ok(None),
+    };
+    let logs = full_context
+        .leadership_logs
+        .logs()
+        .await
+        .into_iter()
+        .filter(|log| log.leader().as_ref() == Some(&leader))
+        .collect();
+    Ok(Some(logs))
+}
@@ -1,7 +1,11 @@
 mod handlers;
 mod logic;
 
-use crate::rest::{display_internal_server_error, ContextLock};
+use crate::{
+    leadership::RehearsalError,
+    rest::{display_internal_server_error, ContextLock},
+    settings::runtime,
+};
 
 use jormungandr_lib::interfaces::VotePlanId;
 
@@ -23,6 +27,13 @@ pub fn filter(
             .and_then(handlers::post_fragments)
             .boxed();
 
+        let simulate = warp::path!("simulate")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_context.clone())
+            .and_then(handlers::simulate_fragments)
+            .boxed();
+
         let status = warp::path!("statuses")
             .and(warp::get())
             .and(warp::query())
@@ -36,7 +47,7 @@ pub fn filter(
             .and_then(handlers::get_fragment_logs)
             .boxed();
 
-        root.and(post.or(status).or(logs)).boxed()
+        root.and(post.or(simulate).or(status).or(logs)).boxed()
     };
 
     let votes_with_plan = warp::path!("votes" / "plan" / VotePlanId / "account-votes" / String)
@@ -46,14 +57,114 @@ pub fn filter(
 
     let votes = warp::path!("votes" / "plan" / "account-votes" / String)
         .and(warp::get())
-        .and(with_context)
+        .and(with_context.clone())
         .and_then(handlers::get_account_votes);
 
+    let runtime_settings = {
+        let root = warp::path!("runtime-settings" / ..);
+
+        let list = warp::path::end()
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_runtime_settings)
+            .boxed();
+
+        let get = warp::path!(String)
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_runtime_setting)
+            .boxed();
+
+        let put = warp::path!(String)
+            .and(warp::put())
+            .and(warp::body::json())
+            .and(with_context.clone())
+            .and_then(handlers::put_runtime_setting)
+            .boxed();
+
+        let delete = warp::path!(String)
+            .and(warp::delete())
+            .and(with_context.clone())
+            .and_then(handlers::delete_runtime_setting)
+            .boxed();
+
+        root.and(list.or(get).or(put).or(delete)).boxed()
+    };
+
+    let health = {
+        let root = warp::path!("health" / ..);
+
+        let live = warp::path!("live")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_liveness)
+            .boxed();
+
+        let ready = warp::path!("ready")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_readiness)
+            .boxed();
+
+        root.and(live.or(ready)).boxed()
+    };
+
+    let leadership = {
+        let root = warp::path!("leadership" / ..);
+
+        let rehearsal = warp::path!("rehearsal")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_context.clone())
+            .and_then(handlers::rehearse_leadership)
+            .boxed();
+
+        let kes = warp::path!("kes")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_kes_status)
+            .boxed();
+
+        let leaders = warp::path!("leaders")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_leaders)
+            .boxed();
+
+        let logs = warp::path!("logs")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_leadership_logs)
+            .boxed();
+
+        let leader_logs = warp::path!("logs" / String)
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_leader_leadership_logs)
+            .boxed();
+
+        root.and(rehearsal.or(kes).or(leaders).or(logs).or(leader_logs))
+            .boxed()
+    };
+
+    let services = warp::path!("services")
+        .and(warp::get())
+        .and(with_context)
+        .and_then(handlers::get_services);
+
     let routes = fragments;
 
-    root.and(routes.or(votes_with_plan).or(votes))
-        .recover(handle_rejection)
-        .boxed()
+    root.and(
+        routes
+            .or(votes_with_plan)
+            .or(votes)
+            .or(runtime_settings)
+            .or(health)
+            .or(leadership)
+            .or(services),
+    )
+    .recover(handle_rejection)
+    .boxed()
 }
 
 /// Convert rejections to actual HTTP errors
@@ -67,6 +178,22 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
                 serde_json::to_string(&summary).unwrap(),
                 StatusCode::BAD_REQUEST,
             ),
+            logic::Error::RuntimeSettings(runtime::Error::Unknown(_)) => {
+                (err.to_string(), StatusCode::NOT_FOUND)
+            }
+            logic::Error::RuntimeSettings(runtime::Error::InvalidValue { .. }) => {
+                (err.to_string(), StatusCode::BAD_REQUEST)
+            }
+            logic::Error::Rehearsal(RehearsalError::NotLeader)
+            | logic::Error::Rehearsal(RehearsalError::NotElected(_))
+            | logic::Error::Rehearsal(RehearsalError::NoLeaderEvent) => {
+                (err.to_string(), StatusCode::NOT_FOUND)
+            }
+            logic::Error::Rehearsal(RehearsalError::DateNotAfterTip { .. })
+            | logic::Error::Rehearsal(RehearsalError::DateTooFar { .. })
+            | logic::Error::Rehearsal(RehearsalError::SlotOutOfEpoch { .. }) => {
+                (err.to_string(), StatusCode::BAD_REQUEST)
+            }
             err => (
                 display_internal_server_error(err),
                 StatusCode::INTERNAL_SERVER_ERROR,
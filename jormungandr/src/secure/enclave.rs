@@ -2,13 +2,28 @@ use crate::blockcfg::{
     BlockDate, HeaderBft, HeaderBftBuilder, HeaderGenesisPraos, HeaderGenesisPraosBuilder,
     HeaderSetConsensusSignature,
 };
-use chain_impl_mockchain::leadership::{Leader, LeaderOutput, Leadership};
+use chain_crypto::SumEd25519_12;
+use chain_impl_mockchain::{
+    certificate::PoolId,
+    key::BftLeaderId,
+    leadership::{Leader, LeaderOutput, Leadership},
+};
 use chain_time::Epoch;
-use std::sync::Arc;
+use jormungandr_lib::{
+    crypto::{kes, key::SigningKey},
+    interfaces::LeaderIdentity,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone)]
 pub struct Enclave {
-    leader_data: Arc<Option<Leader>>,
+    // the KES keys of the genesis leaders are evolved in place, every clone
+    // of the enclave signs with the evolved keys
+    leaders: Arc<RwLock<Vec<Leader>>>,
+    // the KES period of the blockchain the first period of the KES key of
+    // each stake pool is
+    kes_start_periods: Arc<RwLock<HashMap<PoolId, u32>>>,
 }
 
 pub struct LeaderEvent {
@@ -25,38 +40,139 @@ pub struct Schedule {
 }
 
 impl Enclave {
-    pub fn new(leader_data: Option<Leader>) -> Self {
+    pub fn new(leaders: Vec<Leader>) -> Self {
         Enclave {
-            leader_data: Arc::new(leader_data),
+            leaders: Arc::new(RwLock::new(leaders)),
+            kes_start_periods: Default::default(),
+        }
+    }
+
+    pub fn has_leader(&self) -> bool {
+        !self.leaders.read().unwrap().is_empty()
+    }
+
+    /// the identities of the leaders of the enclave, in the order they were
+    /// configured. A leader with both a BFT and a genesis praos key has both
+    /// identities.
+    pub fn leaders(&self) -> Vec<LeaderIdentity> {
+        let leaders = self.leaders.read().unwrap();
+        let mut identities = Vec::new();
+        for leader in leaders.iter() {
+            if let Some(bft) = &leader.bft_leader {
+                identities.push(LeaderIdentity::Bft(bft.sig_key.to_public().into()));
+            }
+            if let Some(genesis) = &leader.genesis_leader {
+                identities.push(LeaderIdentity::StakePool(genesis.node_id.clone().into()));
+            }
         }
+        identities
     }
 
+    /// the stake pools of the genesis leaders of the enclave
+    pub fn stake_pools(&self) -> Vec<PoolId> {
+        let leaders = self.leaders.read().unwrap();
+        leaders
+            .iter()
+            .filter_map(|leader| leader.genesis_leader.as_ref())
+            .map(|leader| leader.node_id.clone())
+            .collect()
+    }
+
+    /// the period of the KES key of the genesis leader of the stake pool,
+    /// if any
+    pub fn kes_period(&self, pool_id: &PoolId) -> Option<u32> {
+        let leaders = self.leaders.read().unwrap();
+        let leader = leaders
+            .iter()
+            .filter_map(|leader| leader.genesis_leader.as_ref())
+            .find(|leader| &leader.node_id == pool_id)?;
+        Some(kes::period(&SigningKey::from(leader.sig_key.clone())))
+    }
+
+    /// the KES period of the blockchain the first period of the KES key of
+    /// the stake pool is, if known
+    pub fn kes_start_period(&self, pool_id: &PoolId) -> Option<u32> {
+        self.kes_start_periods.read().unwrap().get(pool_id).copied()
+    }
+
+    pub fn set_kes_start_period(&self, pool_id: PoolId, start_period: u32) {
+        self.kes_start_periods
+            .write()
+            .unwrap()
+            .insert(pool_id, start_period);
+    }
+
+    /// evolve the KES key of the genesis leader of the stake pool up to
+    /// `period`. The key of the previous periods is dropped. Returns the
+    /// evolved key so it can be persisted, or `None` when the enclave is not
+    /// a leader of this stake pool.
+    pub fn evolve_kes(
+        &self,
+        pool_id: &PoolId,
+        period: u32,
+    ) -> Result<Option<SigningKey<SumEd25519_12>>, kes::Error> {
+        let mut leaders = self.leaders.write().unwrap();
+        let leader = match leaders
+            .iter_mut()
+            .filter_map(|leader| leader.genesis_leader.as_mut())
+            .find(|leader| &leader.node_id == pool_id)
+        {
+            Some(leader) => leader,
+            None => return Ok(None),
+        };
+        let key = kes::evolve_to(&SigningKey::from(leader.sig_key.clone()), period)?;
+        leader.sig_key = key.clone().into_secret_key();
+        Ok(Some(key))
+    }
+
+    /// sign the header with the key of the genesis leader of the stake pool
     pub fn create_header_genesis_praos(
         &self,
+        pool_id: &PoolId,
         header_builder: HeaderGenesisPraosBuilder<HeaderSetConsensusSignature>,
     ) -> Option<HeaderGenesisPraos> {
-        let leader = self
-            .leader_data
-            .as_ref()
-            .as_ref()?
-            .genesis_leader
-            .as_ref()?;
+        let leaders = self.leaders.read().unwrap();
+        let leader = leaders
+            .iter()
+            .filter_map(|leader| leader.genesis_leader.as_ref())
+            .find(|leader| &leader.node_id == pool_id)?;
         let data = header_builder.get_authenticated_data();
         let signature = leader.sig_key.sign_slice(data);
         Some(header_builder.set_signature(signature.into()))
     }
 
+    /// sign the header with the key of the BFT leader
     pub fn create_header_bft(
         &self,
+        leader_id: &BftLeaderId,
         header_builder: HeaderBftBuilder<HeaderSetConsensusSignature>,
     ) -> Option<HeaderBft> {
-        let leader = self.leader_data.as_ref().as_ref()?.bft_leader.as_ref()?;
+        let leaders = self.leaders.read().unwrap();
+        let leader = leaders
+            .iter()
+            .filter_map(|leader| leader.bft_leader.as_ref())
+            .find(|leader| &leader.sig_key.to_public() == leader_id.as_public_key())?;
         let data = header_builder.get_authenticated_data();
         let signature = leader.sig_key.sign_slice(data);
         Some(header_builder.set_signature(signature.into()))
     }
 }
 
+impl LeaderEvent {
+    /// the leader of the node elected for the slot
+    pub fn leader(&self) -> Option<LeaderIdentity> {
+        match &self.output {
+            LeaderOutput::None => None,
+            LeaderOutput::Bft(leader_id) => Some(LeaderIdentity::Bft(
+                leader_id.as_public_key().clone().into(),
+            )),
+            LeaderOutput::GenesisPraos(pool_id, _) => {
+                Some(LeaderIdentity::StakePool(pool_id.clone().into()))
+            }
+        }
+    }
+}
+
 impl Schedule {
     pub fn new(
         enclave: Arc<Enclave>,
@@ -76,24 +192,23 @@ impl Schedule {
     }
 
     fn fill(&mut self) {
-        let leader = if let Some(leader) = self.enclave.leader_data.as_ref() {
-            leader
-        } else {
-            return;
-        };
-
-        if !self.current_slot_data.is_empty() {
+        let leaders = self.enclave.leaders.read().unwrap();
+        if leaders.is_empty() || !self.current_slot_data.is_empty() {
             return;
         }
 
         while self.current_slot < self.stop_at_slot && self.current_slot_data.is_empty() {
             let date = self.leadership.date_at_slot(self.current_slot);
-            match self.leadership.is_leader_for_date(leader, date) {
-                LeaderOutput::None => (),
-                leader_output => self.current_slot_data.push(LeaderEvent {
-                    date,
-                    output: leader_output,
-                }),
+            // several leaders of the enclave can be elected for the same
+            // slot, the events are popped in the order of the leaders
+            for leader in leaders.iter().rev() {
+                match self.leadership.is_leader_for_date(leader, date) {
+                    LeaderOutput::None => (),
+                    leader_output => self.current_slot_data.push(LeaderEvent {
+                        date,
+                        output: leader_output,
+                    }),
+                }
             }
 
             self.current_slot += 1;
@@ -114,3 +229,76 @@ impl Schedule {
         Epoch(self.leadership.epoch())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_impl_mockchain::{
+        chaintypes::ConsensusVersion,
+        leadership::GenesisLeader,
+        milli::Milli,
+        testing::{
+            scenario::{prepare_scenario, wallet},
+            ConfigBuilder,
+        },
+    };
+
+    #[test]
+    fn several_leaders_elected_for_the_same_slot() {
+        // with an active slot coefficient of 1 every stake pool is elected
+        // for every slot
+        let (ledger, controller) = prepare_scenario()
+            .with_config(
+                ConfigBuilder::new()
+                    .with_consensus_version(ConsensusVersion::GenesisPraos)
+                    .with_active_slots_coeff(Milli::from_millis(1000)),
+            )
+            .with_initials(vec![
+                wallet("Alice")
+                    .with(1_000)
+                    .owns("alice_stake_pool")
+                    .delegates_to("alice_stake_pool"),
+                wallet("Bob")
+                    .with(1_000)
+                    .owns("bob_stake_pool")
+                    .delegates_to("bob_stake_pool"),
+            ])
+            .build()
+            .unwrap();
+
+        let pools = vec![
+            controller.stake_pool("alice_stake_pool").unwrap(),
+            controller.stake_pool("bob_stake_pool").unwrap(),
+        ];
+        let enclave = Enclave::new(
+            pools
+                .iter()
+                .map(|pool| Leader {
+                    bft_leader: None,
+                    genesis_leader: Some(GenesisLeader {
+                        node_id: pool.id(),
+                        sig_key: pool.kes().private_key().clone(),
+                        vrf_key: pool.vrf().private_key().clone(),
+                    }),
+                })
+                .collect(),
+        );
+        let leaders = enclave.leaders();
+        assert_eq!(leaders.len(), 2);
+
+        let leadership = Leadership::new(0, &ledger.ledger);
+        let mut schedule = Schedule::new(Arc::new(enclave), Arc::new(leadership), 0, 2);
+        for slot in 0..2 {
+            // the events of the slot are in the order of the leaders
+            for leader in &leaders {
+                let peeked = schedule.peek().map(|event| (event.date, event.leader()));
+                let event = schedule.next_event().unwrap();
+                assert_eq!(event.date.slot_id, slot);
+                assert_eq!(event.leader().as_ref(), Some(leader));
+                assert_eq!(peeked, Some((event.date, event.leader())));
+            }
+        }
+        assert!(schedule.peek().is_none());
+        assert!(schedule.next_event().is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! Evolution of the KES keys the node signs its blocks with, as the
+//! genesis praos leader of one or more stake pools.
+//!
+//! A key must sign for the period of the current time: the time elapsed
+//! since the block 0 divided by the `kes_update_speed` of the blockchain.
+//! The first period of a key is the period of the blockchain it started
+//! signing at, which the node records in the secret file when it first
+//! evolves the key. The key is evolved in the enclave then written back to
+//! the node secret file, so the previous periods are lost for good and a
+//! restarted node does not sign with an older key.
+
+use crate::{
+    blockchain::{Ref, Tip},
+    secure::{enclave::Enclave, SecretFile},
+    utils::clock,
+};
+use chain_impl_mockchain::certificate::PoolId;
+use jormungandr_lib::{
+    crypto::kes::{KesSchedule, SUM_ED25519_12_PERIODS},
+    interfaces::KesStatus,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+/// warn when the key cannot sign anymore within this delay
+const EXPIRY_WARNING: Duration = Duration::from_secs(7 * 24 * 3600);
+/// check the period at least this often, the `kes_update_speed` of the
+/// blockchain can change
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// the KES periods of the blockchain of the given tip
+pub fn schedule(tip: &Ref) -> KesSchedule {
+    let ledger = tip.ledger();
+    let block0_time = SystemTime::UNIX_EPOCH
+        + Duration::from_secs(ledger.get_static_parameters().block0_start_time.0);
+    KesSchedule::new(block0_time, ledger.settings().kes_update_speed)
+}
+
+/// the KES period of the blockchain the first period of the key of the
+/// stake pool is. A key the node never evolved is taken to sign for the
+/// current period.
+fn start_period(enclave: &Enclave, pool_id: &PoolId, key_period: u32, expected_period: u32) -> u32 {
+    enclave
+        .kes_start_period(pool_id)
+        .unwrap_or_else(|| expected_period.saturating_sub(key_period))
+}
+
+/// the secret files the evolved KES keys of the stake pools are written to
+pub type SecretFiles = HashMap<PoolId, SecretFile>;
+
+/// the periods of the KES keys of the stake pools of the enclave, empty
+/// when the node is not a genesis praos leader
+pub async fn status(enclave: &Enclave, tip: &Tip) -> Vec<KesStatus> {
+    let schedule = schedule(&*tip.get_ref().await);
+    let expected_period = schedule.period_at(clock::now());
+    enclave
+        .stake_pools()
+        .into_iter()
+        .filter_map(|pool_id| {
+            let key_period = enclave.kes_period(&pool_id)?;
+            let start_period = start_period(enclave, &pool_id, key_period, expected_period);
+            Some(KesStatus {
+                pool_id: pool_id.into(),
+                period: start_period + key_period,
+                expected_period,
+                remaining_periods: SUM_ED25519_12_PERIODS - 1 - key_period,
+                update_speed: schedule.update_speed().into(),
+                next_update: schedule.period_start(expected_period + 1).into(),
+                expiry: schedule.expiry(start_period).into(),
+            })
+        })
+        .collect()
+}
+
+/// evolve the KES keys of the enclave to the current period, again at the
+/// start of every period
+pub async fn evolve_keys(enclave: Enclave, tip: Tip, files: SecretFiles) {
+    loop {
+        let wait = evolve(&enclave, &tip, &files).await;
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// evolve the KES keys of the enclave to the current period, returns how
+/// long to wait before checking the period again
+pub async fn evolve(enclave: &Enclave, tip: &Tip, files: &SecretFiles) -> Duration {
+    let schedule = schedule(&*tip.get_ref().await);
+    let now = clock::now();
+    let expected_period = schedule.period_at(now);
+    let next_check = schedule
+        .period_start(expected_period + 1)
+        .duration_since(now)
+        .unwrap_or_default()
+        .min(MAX_CHECK_INTERVAL);
+
+    let pools = enclave.stake_pools();
+    if pools.is_empty() {
+        return MAX_CHECK_INTERVAL;
+    }
+    for pool_id in pools.iter() {
+        let start_period = match evolve_pool(enclave, pool_id, expected_period, files.get(pool_id))
+        {
+            Some(start_period) => start_period,
+            None => continue,
+        };
+        let expiry = schedule.expiry(start_period);
+        if let Ok(left) = expiry.duration_since(now) {
+            if left < EXPIRY_WARNING {
+                tracing::warn!(
+                    %pool_id,
+                    expiry = %jormungandr_lib::time::SystemTime::from(expiry),
+                    remaining_periods = (start_period + SUM_ED25519_12_PERIODS)
+                        .saturating_sub(expected_period + 1),
+                    "the KES key expires soon, register a new one with the stake pool"
+                );
+            }
+        }
+    }
+
+    next_check
+}
+
+/// evolve the key of the stake pool to the expected period of the
+/// blockchain, returns the period of the blockchain the first period of the
+/// key is
+fn evolve_pool(
+    enclave: &Enclave,
+    pool_id: &PoolId,
+    expected_period: u32,
+    file: Option<&SecretFile>,
+) -> Option<u32> {
+    let key_period = enclave.kes_period(pool_id)?;
+    let known_start = enclave.kes_start_period(pool_id).is_some();
+    let start_period = start_period(enclave, pool_id, key_period, expected_period);
+    let target = expected_period.saturating_sub(start_period);
+    // the start period of a new key is written to the secret file right
+    // away, a restarted node would take the key to start later otherwise
+    if target <= key_period && known_start {
+        return Some(start_period);
+    }
+    enclave.set_kes_start_period(pool_id.clone(), start_period);
+
+    match enclave.evolve_kes(pool_id, target.max(key_period)) {
+        Ok(Some(key)) => {
+            if target > key_period {
+                tracing::info!(%pool_id, period = expected_period, "evolved the KES key");
+            }
+            if let Some(file) = file {
+                if let Err(error) = file.store_kes_key(&key, start_period) {
+                    tracing::error!(
+                        %pool_id,
+                        %error,
+                        "cannot write the evolved KES key to the node secret file"
+                    );
+                }
+            }
+        }
+        Ok(None) => (),
+        Err(error) => {
+            tracing::error!(
+                %pool_id,
+                %error,
+                "the KES key has expired, the node cannot produce the blocks of the stake \
+                pool until a new key is registered"
+            );
+        }
+    }
+    Some(start_period)
+}
@@ -3,36 +3,60 @@ use chain_impl_mockchain::leadership::{BftLeader, GenesisLeader};
 use jormungandr_lib::crypto::{
     hash::Hash,
     key::{Identifier, SigningKey},
+    password::{self, EncryptedSecret, Kdf},
 };
-use serde::Deserialize;
-use std::path::Path;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 pub mod enclave;
+pub mod kes;
+
+/// environment variable holding the passphrase of an encrypted secret file
+pub const PASSPHRASE_ENV: &str = "JORMUNGANDR_SECRET_PASSPHRASE";
 
 /// hold the node's bft secret setting
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Bft {
     signing_key: SigningKey<Ed25519>,
 }
 
 /// the genesis praos setting
 ///
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct GenesisPraos {
     node_id: Hash,
     sig_key: SigningKey<SumEd25519_12>,
     vrf_key: SigningKey<RistrettoGroup2HashDh>,
+    /// the KES period of the blockchain the first period of `sig_key` is,
+    /// set by the node when it first evolves the key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kes_start_period: Option<u32>,
 }
 
 #[derive(Clone, Deserialize)]
 pub struct OwnerKey(Identifier<Ed25519>);
 
 /// Node Secret(s)
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct NodeSecret {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bft: Option<Bft>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub genesis: Option<GenesisPraos>,
+    /// the file the secrets were loaded from
+    #[serde(skip)]
+    file: Option<SecretFile>,
+}
+
+/// the node secret file, to write back the evolved KES key. The passphrase
+/// of an encrypted file is kept to encrypt it again.
+#[derive(Clone)]
+pub struct SecretFile {
+    path: PathBuf,
+    encryption: Option<(String, Kdf)>,
 }
 
 /// Node Secret's Public parts
@@ -47,12 +71,62 @@ pub enum NodeSecretFromFileError {
     Io(#[from] std::io::Error),
     #[error("Invalid Node secret file: {0}")]
     Format(#[from] serde_yaml::Error),
+    #[error("Cannot read the passphrase of the node's secrets: {0}")]
+    Passphrase(#[source] std::io::Error),
+    #[error("Cannot decrypt node's secrets: {0}")]
+    Decryption(#[from] password::Error),
+    #[error("The leader {0} is in several secret files")]
+    DuplicateLeader(String),
+}
+
+#[derive(Debug, Error)]
+pub enum NodeSecretToFileError {
+    #[error("Cannot read node's secrets back")]
+    Read(#[from] NodeSecretFromFileError),
+    #[error("Cannot write node's secrets: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Cannot serialize node's secrets: {0}")]
+    Format(#[from] serde_yaml::Error),
+    #[error("Cannot encrypt node's secrets: {0}")]
+    Encryption(#[source] password::Error),
+    #[error("The node secret file has no genesis praos secrets")]
+    NoGenesis,
 }
 
 impl NodeSecret {
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<NodeSecret, NodeSecretFromFileError> {
-        let file = std::fs::File::open(path)?;
-        Ok(serde_yaml::from_reader(file)?)
+    /// Load the secrets from a YAML file, which may be encrypted with
+    /// `jcli key encrypt`. The passphrase is read from `passphrase_file`,
+    /// from the `JORMUNGANDR_SECRET_PASSPHRASE` environment variable, or
+    /// prompted for, in that order.
+    pub fn load_from_file<P: AsRef<Path>>(
+        path: P,
+        passphrase_file: Option<&Path>,
+    ) -> Result<NodeSecret, NodeSecretFromFileError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let (mut secret, encryption): (NodeSecret, _) =
+            match serde_yaml::from_str::<EncryptedSecret>(&content) {
+                Ok(encrypted) => {
+                    let passphrase = read_passphrase(path, passphrase_file)
+                        .map_err(NodeSecretFromFileError::Passphrase)?;
+                    let secret = encrypted.decrypt(passphrase.as_bytes())?;
+                    (
+                        serde_yaml::from_slice(&secret)?,
+                        Some((passphrase, *encrypted.kdf())),
+                    )
+                }
+                Err(_) => (serde_yaml::from_str(&content)?, None),
+            };
+        secret.file = Some(SecretFile {
+            path: path.to_owned(),
+            encryption,
+        });
+        Ok(secret)
+    }
+
+    /// the file the secrets were loaded from
+    pub fn file(&self) -> Option<&SecretFile> {
+        self.file.as_ref()
     }
 
     pub fn bft(&self) -> Option<BftLeader> {
@@ -61,6 +135,14 @@ impl NodeSecret {
         })
     }
 
+    /// the KES period of the blockchain the first period of the genesis
+    /// praos key is, if the node already evolved it
+    pub fn kes_start_period(&self) -> Option<u32> {
+        self.genesis
+            .as_ref()
+            .and_then(|genesis| genesis.kes_start_period)
+    }
+
     pub fn genesis(&self) -> Option<GenesisLeader> {
         self.genesis.clone().map(|genesis| GenesisLeader {
             node_id: Blake2b256::from(genesis.node_id).into(),
@@ -69,3 +151,215 @@ impl NodeSecret {
         })
     }
 }
+
+/// check no leader is in several of the secret files, the node would
+/// otherwise create the same block once for each of its copies
+pub fn check_unique_leaders(secrets: &[NodeSecret]) -> Result<(), NodeSecretFromFileError> {
+    let mut bft_leaders = HashSet::new();
+    let mut genesis_leaders = HashSet::new();
+    for secret in secrets {
+        if let Some(bft) = &secret.bft {
+            let id = bft.signing_key.identifier();
+            if !bft_leaders.insert(id.clone()) {
+                return Err(NodeSecretFromFileError::DuplicateLeader(id.to_string()));
+            }
+        }
+        if let Some(genesis) = &secret.genesis {
+            if !genesis_leaders.insert(genesis.node_id) {
+                return Err(NodeSecretFromFileError::DuplicateLeader(
+                    genesis.node_id.to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl SecretFile {
+    /// replace the KES key of the genesis praos secrets in the file, with
+    /// the KES period of the blockchain its first period is. The file is
+    /// replaced at once so a crash cannot leave it half written.
+    pub fn store_kes_key(
+        &self,
+        key: &SigningKey<SumEd25519_12>,
+        start_period: u32,
+    ) -> Result<(), NodeSecretToFileError> {
+        let mut secret = self.read()?;
+        let genesis = secret
+            .genesis
+            .as_mut()
+            .ok_or(NodeSecretToFileError::NoGenesis)?;
+        genesis.sig_key = key.clone();
+        genesis.kes_start_period = Some(start_period);
+
+        let content = match &self.encryption {
+            Some((passphrase, kdf)) => {
+                let secret = serde_yaml::to_vec(&secret)?;
+                let encrypted =
+                    EncryptedSecret::encrypt_with(&mut OsRng, *kdf, passphrase.as_bytes(), &secret)
+                        .map_err(NodeSecretToFileError::Encryption)?;
+                serde_yaml::to_string(&encrypted)?
+            }
+            None => serde_yaml::to_string(&secret)?,
+        };
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".new");
+        let temporary = PathBuf::from(temporary);
+        std::fs::write(&temporary, content)?;
+        std::fs::set_permissions(&temporary, std::fs::metadata(&self.path)?.permissions())?;
+        std::fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    fn read(&self) -> Result<NodeSecret, NodeSecretFromFileError> {
+        let content = std::fs::read_to_string(&self.path)?;
+        match &self.encryption {
+            Some((passphrase, _)) => {
+                let encrypted: EncryptedSecret = serde_yaml::from_str(&content)?;
+                let secret = encrypted.decrypt(passphrase.as_bytes())?;
+                Ok(serde_yaml::from_slice(&secret)?)
+            }
+            None => Ok(serde_yaml::from_str(&content)?),
+        }
+    }
+}
+
+fn read_passphrase(path: &Path, passphrase_file: Option<&Path>) -> std::io::Result<String> {
+    if let Some(passphrase_file) = passphrase_file {
+        let passphrase = std::fs::read_to_string(passphrase_file)?;
+        return Ok(passphrase.lines().next().unwrap_or_default().to_owned());
+    }
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    rpassword::prompt_password_stdout(&format!(
+        "Passphrase for the node secret file '{}': ",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jormungandr_lib::crypto::kes;
+    use rand::thread_rng;
+    use std::fs;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    fn node_secret() -> NodeSecret {
+        NodeSecret {
+            bft: None,
+            genesis: Some(GenesisPraos {
+                node_id: Hash::from([1; 32]),
+                sig_key: SigningKey::generate(thread_rng()),
+                vrf_key: SigningKey::generate(thread_rng()),
+                kes_start_period: None,
+            }),
+            file: None,
+        }
+    }
+
+    fn kes_key(secret: &NodeSecret) -> SigningKey<SumEd25519_12> {
+        secret.genesis.as_ref().unwrap().sig_key.clone()
+    }
+
+    /// evolve the KES key of the secret file, then load the file again
+    fn store_and_reload(path: &Path, passphrase_file: Option<&Path>) {
+        let secret = NodeSecret::load_from_file(path, passphrase_file).unwrap();
+        assert_eq!(secret.kes_start_period(), None);
+        let key = kes_key(&secret);
+        let evolved = kes::evolve_to(&key, 3).unwrap();
+        secret.file().unwrap().store_kes_key(&evolved, 7).unwrap();
+
+        let reloaded = NodeSecret::load_from_file(path, passphrase_file).unwrap();
+        assert_eq!(reloaded.kes_start_period(), Some(7));
+        let reloaded_key = kes_key(&reloaded);
+        assert_eq!(kes::period(&reloaded_key), 3);
+        assert_eq!(reloaded_key.identifier(), key.identifier());
+        assert_eq!(
+            reloaded.genesis().unwrap().node_id,
+            secret.genesis().unwrap().node_id
+        );
+    }
+
+    #[test]
+    fn store_kes_key_in_plain_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.yaml");
+        fs::write(&path, serde_yaml::to_string(&node_secret()).unwrap()).unwrap();
+
+        store_and_reload(&path, None);
+        assert!(!dir.path().join("secret.yaml.new").exists());
+    }
+
+    #[test]
+    fn store_kes_key_in_encrypted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.yaml");
+        let passphrase_file = dir.path().join("passphrase");
+        fs::write(&passphrase_file, PASSPHRASE).unwrap();
+        let kdf = Kdf::Scrypt {
+            log_n: 1,
+            r: 1,
+            p: 1,
+        };
+        let secret = serde_yaml::to_vec(&node_secret()).unwrap();
+        let encrypted =
+            EncryptedSecret::encrypt_with(&mut OsRng, kdf, PASSPHRASE.as_bytes(), &secret).unwrap();
+        fs::write(&path, serde_yaml::to_string(&encrypted).unwrap()).unwrap();
+
+        store_and_reload(&path, Some(&passphrase_file));
+        // the file is still encrypted, with the same key derivation
+        let encrypted: EncryptedSecret =
+            serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(*encrypted.kdf(), kdf);
+    }
+
+    #[test]
+    fn store_kes_key_requires_genesis_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.yaml");
+        let secret = NodeSecret {
+            genesis: None,
+            ..node_secret()
+        };
+        fs::write(&path, serde_yaml::to_string(&secret).unwrap()).unwrap();
+
+        let secret = NodeSecret::load_from_file(&path, None).unwrap();
+        let key = SigningKey::generate(thread_rng());
+        assert!(matches!(
+            secret.file().unwrap().store_kes_key(&key, 0),
+            Err(NodeSecretToFileError::NoGenesis)
+        ));
+    }
+
+    #[test]
+    fn leaders_must_be_unique_across_secret_files() {
+        let bft = Bft {
+            signing_key: SigningKey::generate(thread_rng()),
+        };
+        let other_bft = Bft {
+            signing_key: SigningKey::generate(thread_rng()),
+        };
+        let with_bft = |bft: &Bft| NodeSecret {
+            bft: Some(bft.clone()),
+            genesis: None,
+            file: None,
+        };
+
+        assert!(
+            check_unique_leaders(&[node_secret(), with_bft(&bft), with_bft(&other_bft)]).is_ok()
+        );
+        assert!(matches!(
+            check_unique_leaders(&[with_bft(&bft), with_bft(&other_bft), with_bft(&bft)]),
+            Err(NodeSecretFromFileError::DuplicateLeader(_))
+        ));
+        // same node id, with different keys
+        assert!(matches!(
+            check_unique_leaders(&[node_secret(), node_secret()]),
+            Err(NodeSecretFromFileError::DuplicateLeader(_))
+        ));
+    }
+}
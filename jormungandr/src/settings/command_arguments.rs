@@ -20,13 +20,25 @@ pub struct StartArguments {
     #[structopt(long = "storage", parse(from_os_str))]
     pub storage: Option<PathBuf>,
 
+    /// Path to a signed snapshot archive to import into an empty storage
+    /// instead of synchronizing the blockchain from the block0
+    #[structopt(long = "snapshot", parse(from_os_str))]
+    pub snapshot: Option<PathBuf>,
+
     /// Set the node config (in YAML format) to use as general configuration
     #[structopt(long = "config", parse(from_os_str))]
     pub node_config: Option<PathBuf>,
 
-    /// Set the secret node config (in YAML format).
+    /// Set the secret node config (in YAML format). Can be given several
+    /// times to run as the leader of several stake pools.
     #[structopt(long = "secret", parse(from_os_str))]
-    pub secret: Option<PathBuf>,
+    pub secret: Vec<PathBuf>,
+
+    /// Read the passphrase of an encrypted secret file from the given file,
+    /// instead of the `JORMUNGANDR_SECRET_PASSPHRASE` environment variable
+    /// or a prompt.
+    #[structopt(long = "secret-passphrase-file", parse(from_os_str))]
+    pub secret_passphrase_file: Option<PathBuf>,
 
     /// Path to the genesis block (the block0) of the blockchain
     #[structopt(long = "genesis-block", parse(try_from_str))]
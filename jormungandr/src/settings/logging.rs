@@ -12,12 +12,17 @@ use tracing_appender::non_blocking::WorkerGuard;
 use tracing::subscriber::SetGlobalDefaultError;
 #[allow(unused_imports)]
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, Registry};
 
 pub struct LogSettings {
     pub config: LogSettingsEntry,
     pub msgs: LogInfoMsg,
 }
 
+/// Changes the level of the logger after it was initialized.
+#[derive(Clone)]
+pub struct LogLevelHandle(reload::Handle<LevelFilter, Registry>);
+
 /// A wrapper to return an optional string message that we
 /// have to manually log with `info!`, we need this because
 /// some code executes before the logs are initialized.
@@ -100,12 +105,14 @@ impl FromStr for LogOutput {
 }
 
 impl LogSettings {
-    pub fn init_log(self) -> Result<(Vec<WorkerGuard>, LogInfoMsg), Error> {
+    pub fn init_log(self) -> Result<(Vec<WorkerGuard>, LogLevelHandle, LogInfoMsg), Error> {
         use tracing_subscriber::prelude::*;
 
         // Worker guards that need to be held on to.
         let mut guards = Vec::new();
 
+        let (level, level_handle) = reload::Layer::new(self.config.level);
+
         // configure the registry subscriber as the global default,
         // panics if something goes wrong.
         match self.config.output {
@@ -118,7 +125,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -128,7 +135,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -143,7 +150,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -153,7 +160,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -178,7 +185,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -188,7 +195,7 @@ impl LogSettings {
                             .with_level(true)
                             .with_writer(non_blocking);
                         tracing_subscriber::registry()
-                            .with(self.config.level)
+                            .with(level)
                             .with(layer)
                             .init();
                     }
@@ -199,7 +206,7 @@ impl LogSettings {
                 self.config.format.require_default()?;
                 let layer = tracing_journald::layer().map_err(Error::Journald)?;
                 tracing_subscriber::registry()
-                    .with(self.config.level)
+                    .with(level)
                     .with(layer)
                     .init();
             }
@@ -210,13 +217,19 @@ impl LogSettings {
                     .map_err(Error::Gelf)?;
                 tokio::spawn(task);
                 tracing_subscriber::registry()
-                    .with(self.config.level)
+                    .with(level)
                     .with(layer)
                     .init();
             }
         }
 
-        Ok((guards, self.msgs))
+        Ok((guards, LogLevelHandle(level_handle), self.msgs))
+    }
+}
+
+impl LogLevelHandle {
+    pub fn set(&self, level: LevelFilter) -> Result<(), reload::Error> {
+        self.0.reload(level)
     }
 }
 
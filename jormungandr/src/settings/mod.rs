@@ -1,5 +1,6 @@
 mod command_arguments;
 pub mod logging;
+pub mod runtime;
 pub mod start;
 
 pub use self::command_arguments::CommandLine;
@@ -0,0 +1,435 @@
+//! Tunables of the node that can be changed while it is running.
+//!
+//! They are kept in the `settings` store, next to the blocks in the storage
+//! directory, so a value set at runtime overrides the configuration file on
+//! the next starts too. Modules subscribe to the domain of their tunables to
+//! apply the changes as they are made.
+
+use crate::settings::{logging::LogLevelHandle, start, start::Settings};
+use jormungandr_lib::interfaces::{Mempool, TrustedPeer};
+use multiaddr::Multiaddr;
+use runtime_settings::{Domain, Event, Settings as Store, Subscriber};
+use std::{
+    fmt::{self, Display},
+    path::Path,
+    str::FromStr,
+};
+use thiserror::Error;
+use tracing::level_filters::LevelFilter;
+
+/// the directory of the store, within the storage directory of the node
+const STORE_DIRECTORY: &str = "runtime-settings";
+
+pub const LOG_DOMAIN: &str = "log";
+pub const MEMPOOL_DOMAIN: &str = "mempool";
+pub const NETWORK_DOMAIN: &str = "network";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("runtime settings storage error")]
+    Store(#[from] runtime_settings::Error),
+    #[error("unknown runtime setting `{0}`")]
+    Unknown(String),
+    #[error("invalid value `{value}` for the runtime setting `{tunable}`: {reason}")]
+    InvalidValue {
+        tunable: Tunable,
+        value: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tunable {
+    /// `log.level`: the level of the logger
+    LogLevel,
+    /// `mempool.pool_max_entries`: the maximum number of fragments in the mempool
+    PoolMaxEntries,
+    /// `mempool.log_max_entries`: the maximum number of entries in the fragment logs
+    LogMaxEntries,
+    /// `network.preferred_list`: comma separated addresses of the preferred peers
+    PreferredList,
+    /// `network.quarantine_whitelist`: comma separated addresses of the
+    /// peers that are never quarantined
+    QuarantineWhitelist,
+}
+
+/// A change of a tunable the running node applies.
+pub enum Change {
+    /// `None` when the level was removed and the configured level applies
+    LogLevel(Option<LevelFilter>),
+    /// `None` when the limit was removed and the configured limit applies
+    PoolMaxEntries(Option<usize>),
+    /// `None` when the limit was removed and the configured limit applies
+    LogMaxEntries(Option<usize>),
+}
+
+#[derive(Clone)]
+pub struct RuntimeSettings {
+    store: Store,
+}
+
+impl Tunable {
+    pub const ALL: [Tunable; 5] = [
+        Tunable::LogLevel,
+        Tunable::PoolMaxEntries,
+        Tunable::LogMaxEntries,
+        Tunable::PreferredList,
+        Tunable::QuarantineWhitelist,
+    ];
+
+    pub fn domain(self) -> &'static str {
+        match self {
+            Tunable::LogLevel => LOG_DOMAIN,
+            Tunable::PoolMaxEntries | Tunable::LogMaxEntries => MEMPOOL_DOMAIN,
+            Tunable::PreferredList | Tunable::QuarantineWhitelist => NETWORK_DOMAIN,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Tunable::LogLevel => "level",
+            Tunable::PoolMaxEntries => "pool_max_entries",
+            Tunable::LogMaxEntries => "log_max_entries",
+            Tunable::PreferredList => "preferred_list",
+            Tunable::QuarantineWhitelist => "quarantine_whitelist",
+        }
+    }
+
+    /// whether a change only applies when the node restarts: the peers of
+    /// the network tunables are read when the topology starts. The other
+    /// tunables apply to the running node.
+    pub fn applies_on_restart(self) -> bool {
+        matches!(self, Tunable::PreferredList | Tunable::QuarantineWhitelist)
+    }
+
+    fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            Tunable::LogLevel => parse_level(value).map(|_| ()),
+            Tunable::PoolMaxEntries | Tunable::LogMaxEntries => parse_entries(value).map(|_| ()),
+            Tunable::PreferredList => parse_addresses(value).map(|_| ()),
+            Tunable::QuarantineWhitelist => parse_addresses(value).and_then(|addresses| {
+                match addresses.iter().find(|address| {
+                    jormungandr_lib::multiaddr::to_tcp_socket_addr(address).is_none()
+                }) {
+                    Some(address) => Err(format!("`{}` is not a TCP address", address)),
+                    None => Ok(()),
+                }
+            }),
+        }
+    }
+}
+
+impl Display for Tunable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.domain(), self.key())
+    }
+}
+
+impl FromStr for Tunable {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tunable::ALL
+            .iter()
+            .copied()
+            .find(|tunable| tunable.to_string() == s)
+            .ok_or_else(|| Error::Unknown(s.to_owned()))
+    }
+}
+
+impl Change {
+    /// parse the change of a tunable the running node applies, `None` for
+    /// the other tunables.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        let tunable = event.key.parse().ok()?;
+        let value = event.value.as_deref();
+        let change = match tunable {
+            Tunable::LogLevel => value.map(parse_level).transpose().map(Change::LogLevel),
+            Tunable::PoolMaxEntries => value
+                .map(parse_entries)
+                .transpose()
+                .map(Change::PoolMaxEntries),
+            Tunable::LogMaxEntries => value
+                .map(parse_entries)
+                .transpose()
+                .map(Change::LogMaxEntries),
+            Tunable::PreferredList | Tunable::QuarantineWhitelist => return None,
+        };
+        change
+            .map_err(|reason| {
+                tracing::warn!(setting = %tunable, %reason, "ignoring invalid runtime setting")
+            })
+            .ok()
+    }
+}
+
+impl RuntimeSettings {
+    /// open the store in the storage directory of the node. Without storage
+    /// directory, the settings are only kept until the node stops.
+    pub fn open(storage: Option<&Path>) -> Result<Self, Error> {
+        let store = match storage {
+            Some(storage) => Store::open(storage.join(STORE_DIRECTORY))?,
+            None => Store::temporary()?,
+        };
+        Ok(Self { store })
+    }
+
+    fn domain(&self, tunable: Tunable) -> Domain {
+        self.store.domain(tunable.domain())
+    }
+
+    pub fn get(&self, tunable: Tunable) -> Result<Option<String>, Error> {
+        self.domain(tunable).get(tunable.key()).map_err(Into::into)
+    }
+
+    /// set the value of the tunable, once checked. Returns the previous value.
+    pub fn set(&self, tunable: Tunable, value: &str) -> Result<Option<String>, Error> {
+        tunable.check(value).map_err(|reason| Error::InvalidValue {
+            tunable,
+            value: value.to_owned(),
+            reason,
+        })?;
+        self.domain(tunable)
+            .insert(tunable.key(), value)
+            .map_err(Into::into)
+    }
+
+    /// remove the value of the tunable, the configured value applies again
+    pub fn remove(&self, tunable: Tunable) -> Result<Option<String>, Error> {
+        self.domain(tunable)
+            .remove(tunable.key())
+            .map_err(Into::into)
+    }
+
+    /// subscribe to the changes of the tunables of the given domain
+    pub fn subscribe(&self, domain: &str) -> Subscriber {
+        self.store.domain(domain).subscribe()
+    }
+
+    pub fn log_level(&self) -> Result<Option<LevelFilter>, Error> {
+        self.parsed(Tunable::LogLevel, parse_level)
+    }
+
+    /// override the settings loaded from the configuration file with the
+    /// values set at runtime
+    pub fn apply(&self, settings: &mut Settings) -> Result<(), Error> {
+        self.apply_mempool(&mut settings.mempool)?;
+        if let Some(addresses) = self.parsed(Tunable::PreferredList, parse_addresses)? {
+            let peers: Vec<_> = addresses
+                .into_iter()
+                .map(|address| TrustedPeer { address, id: None })
+                .collect();
+            settings.network.layers.preferred_list.peers = start::resolve_trusted_peers(&peers);
+        }
+        if let Some(addresses) = self.parsed(Tunable::QuarantineWhitelist, parse_addresses)? {
+            settings
+                .network
+                .policy
+                .set_quarantine_whitelist(addresses.into_iter().collect());
+        }
+        Ok(())
+    }
+
+    fn apply_mempool(&self, mempool: &mut Mempool) -> Result<(), Error> {
+        if let Some(entries) = self.parsed(Tunable::PoolMaxEntries, parse_entries)? {
+            mempool.pool_max_entries = entries.into();
+        }
+        if let Some(entries) = self.parsed(Tunable::LogMaxEntries, parse_entries)? {
+            mempool.log_max_entries = entries.into();
+        }
+        Ok(())
+    }
+
+    /// apply the changes of the log level until the store is closed
+    pub async fn follow_log_level(self, handle: LogLevelHandle, configured: LevelFilter) {
+        let mut subscriber = self.subscribe(LOG_DOMAIN);
+        while let Some(event) = (&mut subscriber).await {
+            if let Some(Change::LogLevel(level)) = Change::from_event(&event) {
+                let level = level.unwrap_or(configured);
+                match handle.set(level) {
+                    Ok(()) => tracing::info!(%level, "log level changed"),
+                    Err(error) => tracing::warn!(%error, "cannot change the log level"),
+                }
+            }
+        }
+    }
+
+    fn parsed<T>(
+        &self,
+        tunable: Tunable,
+        parse: fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, Error> {
+        self.get(tunable)?
+            .map(|value| {
+                parse(&value).map_err(|reason| Error::InvalidValue {
+                    tunable,
+                    value,
+                    reason,
+                })
+            })
+            .transpose()
+    }
+}
+
+fn parse_level(value: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(value.trim()).map_err(|error| error.to_string())
+}
+
+fn parse_entries(value: &str) -> Result<usize, String> {
+    match value.trim().parse() {
+        Ok(0) => Err("the number of entries cannot be zero".to_owned()),
+        Ok(entries) => Ok(entries),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn parse_addresses(value: &str) -> Result<Vec<Multiaddr>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address
+                .parse()
+                .map_err(|error| format!("`{}`: {}", address, error))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(key: &str, value: Option<&str>) -> Event {
+        Event {
+            key: key.to_owned(),
+            value: value.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn tunable_names_roundtrip() {
+        for tunable in Tunable::ALL.iter().copied() {
+            assert_eq!(tunable.to_string().parse::<Tunable>().unwrap(), tunable);
+        }
+        assert_eq!(
+            "mempool.pool_max_entries".parse::<Tunable>().unwrap(),
+            Tunable::PoolMaxEntries
+        );
+        assert!(matches!(
+            "mempool.unknown".parse::<Tunable>(),
+            Err(Error::Unknown(name)) if name == "mempool.unknown"
+        ));
+        assert!("pool_max_entries".parse::<Tunable>().is_err());
+    }
+
+    #[test]
+    fn only_network_tunables_apply_on_restart() {
+        let on_restart: Vec<_> = Tunable::ALL
+            .iter()
+            .copied()
+            .filter(|tunable| tunable.applies_on_restart())
+            .collect();
+        assert_eq!(
+            on_restart,
+            vec![Tunable::PreferredList, Tunable::QuarantineWhitelist]
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let settings = RuntimeSettings::open(None).unwrap();
+        for (tunable, value) in &[
+            (Tunable::LogLevel, "loud"),
+            (Tunable::PoolMaxEntries, "0"),
+            (Tunable::PoolMaxEntries, "-1"),
+            (Tunable::LogMaxEntries, "many"),
+            (Tunable::PreferredList, "not an address"),
+            (Tunable::QuarantineWhitelist, "/dns4/example.com/tcp/3000"),
+        ] {
+            assert!(
+                matches!(
+                    settings.set(*tunable, value),
+                    Err(Error::InvalidValue { .. })
+                ),
+                "{} accepted `{}`",
+                tunable,
+                value
+            );
+            assert_eq!(settings.get(*tunable).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let settings = RuntimeSettings::open(None).unwrap();
+        assert_eq!(settings.set(Tunable::LogLevel, "debug").unwrap(), None);
+        assert_eq!(
+            settings.set(Tunable::LogLevel, "warn").unwrap(),
+            Some("debug".to_owned())
+        );
+        assert_eq!(settings.log_level().unwrap(), Some(LevelFilter::WARN));
+        assert_eq!(
+            settings.remove(Tunable::LogLevel).unwrap(),
+            Some("warn".to_owned())
+        );
+        assert_eq!(settings.log_level().unwrap(), None);
+        assert_eq!(settings.remove(Tunable::LogLevel).unwrap(), None);
+    }
+
+    #[test]
+    fn apply_overrides_the_configured_mempool() {
+        let settings = RuntimeSettings::open(None).unwrap();
+        let mut mempool = Mempool::default();
+        settings.apply_mempool(&mut mempool).unwrap();
+        assert_eq!(
+            mempool.pool_max_entries,
+            Mempool::default().pool_max_entries
+        );
+
+        settings.set(Tunable::PoolMaxEntries, "12").unwrap();
+        settings.set(Tunable::LogMaxEntries, " 34 ").unwrap();
+        settings.apply_mempool(&mut mempool).unwrap();
+        assert_eq!(usize::from(mempool.pool_max_entries), 12);
+        assert_eq!(usize::from(mempool.log_max_entries), 34);
+    }
+
+    #[test]
+    fn changes_are_parsed_from_the_events() {
+        assert!(matches!(
+            Change::from_event(&event("log.level", Some("trace"))),
+            Some(Change::LogLevel(Some(level))) if level == LevelFilter::TRACE
+        ));
+        assert!(matches!(
+            Change::from_event(&event("log.level", None)),
+            Some(Change::LogLevel(None))
+        ));
+        assert!(matches!(
+            Change::from_event(&event("mempool.pool_max_entries", Some("5"))),
+            Some(Change::PoolMaxEntries(Some(5)))
+        ));
+        assert!(matches!(
+            Change::from_event(&event("mempool.log_max_entries", None)),
+            Some(Change::LogMaxEntries(None))
+        ));
+        assert!(Change::from_event(&event("mempool.pool_max_entries", Some("0"))).is_none());
+        assert!(Change::from_event(&event("network.preferred_list", Some(""))).is_none());
+        assert!(Change::from_event(&event("mempool.unknown", Some("5"))).is_none());
+    }
+
+    #[test]
+    fn subscribers_follow_their_domain() {
+        let settings = RuntimeSettings::open(None).unwrap();
+        let mut subscriber = settings.subscribe(MEMPOOL_DOMAIN);
+        settings.set(Tunable::LogLevel, "info").unwrap();
+        settings.set(Tunable::PoolMaxEntries, "7").unwrap();
+        settings.remove(Tunable::PoolMaxEntries).unwrap();
+
+        let event = subscriber.next().unwrap();
+        assert_eq!(event.key, "mempool.pool_max_entries");
+        assert_eq!(event.value.as_deref(), Some("7"));
+        let event = subscriber.next().unwrap();
+        assert_eq!(event.key, "mempool.pool_max_entries");
+        assert_eq!(event.value, None);
+    }
+}
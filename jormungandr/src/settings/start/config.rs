@@ -18,7 +18,13 @@ use std::path::PathBuf;
 pub struct Config {
     #[serde(default)]
     pub secret_file: Option<PathBuf>,
+    /// secret files of the other leaders the node runs as
+    #[serde(default)]
+    pub secret_files: Vec<PathBuf>,
     pub storage: Option<PathBuf>,
+    /// signed snapshot archive to import when the storage is empty
+    #[serde(default)]
+    pub snapshot: Option<PathBuf>,
     pub log: Option<ConfigLogSettings>,
 
     /// setting of the mempool, fragment logs and related data
@@ -45,6 +51,10 @@ pub struct Config {
     #[serde(default)]
     pub no_blockchain_updates_warning_interval: Option<Duration>,
 
+    /// the number of connected peers below which the node is not ready
+    #[serde(default)]
+    pub readiness_min_peers: Option<usize>,
+
     #[serde(default)]
     pub bootstrap_from_trusted_peers: bool,
 
@@ -46,8 +46,10 @@ pub enum Error {
 pub struct Settings {
     pub network: network::Configuration,
     pub storage: Option<PathBuf>,
+    pub snapshot: Option<PathBuf>,
     pub block_0: Block0Info,
-    pub secret: Option<PathBuf>,
+    pub secrets: Vec<PathBuf>,
+    pub secret_passphrase_file: Option<PathBuf>,
     pub rest: Option<Rest>,
     pub mempool: Mempool,
     pub rewards_report_all: bool,
@@ -56,6 +58,7 @@ pub struct Settings {
     #[cfg(feature = "prometheus-metrics")]
     pub prometheus: bool,
     pub no_blockchain_updates_warning_interval: std::time::Duration,
+    pub readiness_min_peers: usize,
     pub block_hard_deadline: u32,
 }
 
@@ -177,11 +180,23 @@ impl RawSettings {
             (None, None) => None,
         };
 
-        let secret = command_arguments
-            .secret
+        let snapshot = command_arguments
+            .snapshot
             .clone()
-            .or_else(|| config.as_ref().map(|cfg| cfg.secret_file.clone()).flatten());
-        if secret.is_none() {
+            .or_else(|| config.as_ref().and_then(|cfg| cfg.snapshot.clone()));
+
+        let secrets = if !command_arguments.secret.is_empty() {
+            command_arguments.secret.clone()
+        } else if let Some(cfg) = config.as_ref() {
+            cfg.secret_file
+                .iter()
+                .chain(cfg.secret_files.iter())
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if secrets.is_empty() {
             tracing::warn!(
                 "Node started without path to the stored secret keys (not a stake pool or a BFT leader)"
             );
@@ -212,11 +227,19 @@ impl RawSettings {
                     .map_or(false, |settings| settings.enabled)
             });
 
+        let readiness_min_peers = config
+            .as_ref()
+            .and_then(|cfg| cfg.readiness_min_peers)
+            // a node without trusted peers may be on its own
+            .unwrap_or_else(|| usize::from(!network.trusted_peers.is_empty()));
+
         Ok(Settings {
             storage,
+            snapshot,
             block_0,
             network,
-            secret,
+            secrets,
+            secret_passphrase_file: command_arguments.secret_passphrase_file.clone(),
             rewards_report_all: command_line.rewards_report_all,
             rest,
             mempool: config
@@ -235,6 +258,7 @@ impl RawSettings {
                 .unwrap_or_else(|| {
                     std::time::Duration::from_secs(DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL)
                 }),
+            readiness_min_peers,
             block_hard_deadline: config
                 .as_ref()
                 .and_then(|config| config.block_hard_deadline)
@@ -243,7 +267,9 @@ impl RawSettings {
     }
 }
 
-fn resolve_trusted_peers(peers: &[jormungandr_lib::interfaces::TrustedPeer]) -> Vec<TrustedPeer> {
+pub(crate) fn resolve_trusted_peers(
+    peers: &[jormungandr_lib::interfaces::TrustedPeer],
+) -> Vec<TrustedPeer> {
     peers
         .iter()
         .filter_map(|config_peer| match TrustedPeer::resolve(config_peer) {
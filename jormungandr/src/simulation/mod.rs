@@ -0,0 +1,300 @@
+//! # Deterministic network simulator
+//!
+//! Runs several nodes in the test process, each with the `blockchain`,
+//! `client`, `leadership`, `fragment` and `topology` tasks of a real node,
+//! connected by a [`SimulatedNetwork`] instead of gRPC connections. Slots
+//! follow the virtual clock of [`clock`](crate::utils::clock), so the
+//! simulation is meant to run on a paused tokio runtime, where a minute of
+//! consensus takes a few milliseconds:
+//!
+//! ```ignore
+//! #[tokio::test(start_paused = true)]
+//! async fn consensus() {
+//!     let simulation = SimulationBuilder::new(SEED).bft_leaders(3).start().await;
+//!     simulation.run_for(Duration::from_secs(60)).await;
+//!     assert!(simulation.wait_converged(Duration::from_secs(10)).await);
+//! }
+//! ```
+//!
+//! The keys of the nodes and the fate of every message on the network
+//! (latency, loss) are drawn from the seed of the simulation, so a failing
+//! run is reproduced by running it again with the same seed.
+
+mod network;
+mod node;
+
+pub use self::network::{LinkConditions, LinkConditionsError, SimulatedNetwork};
+pub use self::node::SimulatedNode;
+
+use self::node::NodeConfig;
+use crate::blockcfg::{BftLeader, HeaderHash, Leader};
+use crate::topology::Gossips;
+use crate::utils::clock;
+use chain_addr::Discrimination;
+use chain_crypto::{Ed25519, SecretKey};
+use chain_impl_mockchain::{chaintypes::ConsensusVersion, fee::LinearFee};
+use jormungandr_lib::crypto::key::SigningKey;
+use jormungandr_lib::interfaces::{
+    Block0Configuration, BlockchainConfiguration, NumberOfSlotsPerEpoch, SlotDuration,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// start of the simulated blockchains, fixed so that runs do not depend on
+/// the clock of the host
+const BLOCK0_DATE: u64 = 1_600_000_000;
+const SLOT_DURATION: u8 = 2;
+const SLOTS_PER_EPOCH: u32 = 60;
+const GOSSIP_INTERVAL: Duration = Duration::from_secs(1);
+const NODE_PORT: u16 = 3000;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct SimulationBuilder {
+    seed: u64,
+    bft_leaders: usize,
+    passive_nodes: usize,
+    conditions: LinkConditions,
+}
+
+impl SimulationBuilder {
+    pub fn new(seed: u64) -> Self {
+        SimulationBuilder {
+            seed,
+            bft_leaders: 1,
+            passive_nodes: 0,
+            conditions: LinkConditions::default(),
+        }
+    }
+
+    /// number of nodes producing blocks, each one is a BFT leader
+    pub fn bft_leaders(mut self, count: usize) -> Self {
+        self.bft_leaders = count;
+        self
+    }
+
+    /// number of nodes only following the chain
+    pub fn passive_nodes(mut self, count: usize) -> Self {
+        self.passive_nodes = count;
+        self
+    }
+
+    pub fn link_conditions(mut self, conditions: LinkConditions) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Start the nodes. The wall clock of the current thread becomes
+    /// virtual, starting at the date of the block0.
+    pub async fn start(self) -> Simulation {
+        let block0_date = UNIX_EPOCH + Duration::from_secs(BLOCK0_DATE);
+        clock::start_virtual(block0_date);
+
+        let mut rng = ChaChaRng::seed_from_u64(self.seed);
+        let leaders: Vec<BftLeader> = (0..self.bft_leaders)
+            .map(|_| BftLeader {
+                sig_key: SecretKey::<Ed25519>::generate(&mut rng),
+            })
+            .collect();
+
+        let mut blockchain_configuration = BlockchainConfiguration::new(
+            Discrimination::Test,
+            ConsensusVersion::Bft,
+            LinearFee::new(0, 0, 0),
+        );
+        blockchain_configuration.block0_date = block0_date.into();
+        blockchain_configuration.slot_duration = SlotDuration::new(SLOT_DURATION).unwrap();
+        blockchain_configuration.slots_per_epoch =
+            NumberOfSlotsPerEpoch::new(SLOTS_PER_EPOCH).unwrap();
+        blockchain_configuration.consensus_leader_ids = leaders
+            .iter()
+            .map(|leader| leader.sig_key.to_public().into())
+            .collect();
+        let block0 = Block0Configuration {
+            blockchain_configuration,
+            initial: Vec::new(),
+        }
+        .to_block();
+
+        let network = SimulatedNetwork::new(rng.gen(), self.conditions);
+
+        let leaders = leaders.into_iter().map(Some);
+        let passive = std::iter::repeat_with(|| None).take(self.passive_nodes);
+        let mut nodes = Vec::new();
+        for (index, bft_leader) in leaders.chain(passive).enumerate() {
+            let config = NodeConfig {
+                address: node_address(index),
+                block0: block0.clone(),
+                leader: bft_leader.map(|bft_leader| Leader {
+                    bft_leader: Some(bft_leader),
+                    genesis_leader: None,
+                }),
+                node_key: SigningKey::generate(&mut rng),
+                gossip_interval: GOSSIP_INTERVAL,
+            };
+            nodes.push(SimulatedNode::start(&network, config).await);
+        }
+
+        // every node starts knowing every other one, the topology tasks take
+        // it from there
+        let mut profiles = Vec::new();
+        for node in &nodes {
+            profiles.push(node.self_gossip().await);
+        }
+        for (index, node) in nodes.iter().enumerate() {
+            let others = profiles
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, profile)| profile.clone())
+                .collect::<Vec<_>>();
+            node.accept_gossips(Gossips::from(others)).await;
+        }
+
+        Simulation { network, nodes }
+    }
+}
+
+fn node_address(index: usize) -> SocketAddr {
+    let index = index + 1;
+    SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(10, 0, (index >> 8) as u8, index as u8)),
+        NODE_PORT,
+    )
+}
+
+pub struct Simulation {
+    network: SimulatedNetwork,
+    nodes: Vec<SimulatedNode>,
+}
+
+impl Simulation {
+    pub fn network(&self) -> &SimulatedNetwork {
+        &self.network
+    }
+
+    pub fn node(&self, index: usize) -> &SimulatedNode {
+        &self.nodes[index]
+    }
+
+    pub fn addresses(&self) -> Vec<SocketAddr> {
+        self.nodes.iter().map(SimulatedNode::address).collect()
+    }
+
+    /// let the simulation run for the given (virtual) time
+    pub async fn run_for(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+
+    pub async fn tips(&self) -> Vec<HeaderHash> {
+        let mut tips = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            tips.push(node.tip_hash().await);
+        }
+        tips
+    }
+
+    /// whether all the nodes currently have the same tip
+    pub async fn converged(&self) -> bool {
+        let tips = self.tips().await;
+        tips.windows(2).all(|pair| pair[0] == pair[1])
+    }
+
+    /// run until all the nodes have the same tip, or the timeout expires
+    pub async fn wait_converged(&self, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if self.converged().await {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 0x6a6f726d;
+
+    #[test]
+    fn drop_rate_is_a_probability() {
+        let conditions =
+            |drop_rate| LinkConditions::new(Duration::from_millis(50), Duration::ZERO, drop_rate);
+
+        for drop_rate in [0.0, 0.5, 1.0] {
+            assert!(conditions(drop_rate).is_ok(), "{} rejected", drop_rate);
+        }
+        for drop_rate in [-0.1, 1.5, f64::NAN, f64::INFINITY] {
+            assert!(
+                matches!(
+                    conditions(drop_rate),
+                    Err(LinkConditionsError::InvalidDropRate(_))
+                ),
+                "{} accepted",
+                drop_rate
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bft_leaders_agree_on_one_chain() {
+        let simulation = SimulationBuilder::new(SEED)
+            .bft_leaders(3)
+            .passive_nodes(1)
+            .start()
+            .await;
+
+        simulation.run_for(Duration::from_secs(120)).await;
+
+        assert!(simulation.wait_converged(Duration::from_secs(10)).await);
+        let tip = simulation.node(0).tip().await;
+        assert!(u32::from(tip.chain_length()) >= 30);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lossy_network_converges() {
+        let simulation = SimulationBuilder::new(SEED)
+            .bft_leaders(4)
+            .link_conditions(
+                LinkConditions::new(Duration::from_millis(200), Duration::from_millis(300), 0.1)
+                    .unwrap(),
+            )
+            .start()
+            .await;
+
+        simulation.run_for(Duration::from_secs(120)).await;
+        simulation
+            .network()
+            .set_conditions(LinkConditions::default());
+
+        assert!(simulation.wait_converged(Duration::from_secs(30)).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partitions_fork_and_heal() {
+        let simulation = SimulationBuilder::new(SEED).bft_leaders(4).start().await;
+        let addresses = simulation.addresses();
+
+        simulation.run_for(Duration::from_secs(20)).await;
+        assert!(simulation.wait_converged(Duration::from_secs(10)).await);
+
+        simulation
+            .network()
+            .partition(&[&addresses[..2], &addresses[2..]]);
+        simulation.run_for(Duration::from_secs(60)).await;
+        assert_ne!(
+            simulation.node(0).tip_hash().await,
+            simulation.node(2).tip_hash().await
+        );
+
+        simulation.network().heal();
+        assert!(simulation.wait_converged(Duration::from_secs(60)).await);
+    }
+}
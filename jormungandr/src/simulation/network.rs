@@ -0,0 +1,319 @@
+use crate::blockcfg::HeaderHash;
+use crate::intercom::{
+    self, BlockMsg, ClientMsg, NetworkMsg, PropagateMsg, TopologyMsg, TransactionMsg,
+};
+use crate::network::p2p::Address;
+use crate::topology::{self, Peer};
+use crate::utils::async_msg::{MessageBox, MessageQueue};
+use jormungandr_lib::interfaces::FragmentOrigin;
+use poldercast::layer::Selection;
+
+use futures::prelude::*;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+
+const RESPONSE_BUFFER: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum LinkConditionsError {
+    #[error("drop rate {0} is not a probability between 0 and 1")]
+    InvalidDropRate(f64),
+}
+
+/// conditions applied to every message sent between two nodes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConditions {
+    /// minimum time for a message to reach its destination
+    latency: Duration,
+    /// maximum random delay added to the latency
+    jitter: Duration,
+    /// probability for a message to be lost, between 0 and 1
+    drop_rate: f64,
+}
+
+impl LinkConditions {
+    pub fn new(
+        latency: Duration,
+        jitter: Duration,
+        drop_rate: f64,
+    ) -> Result<Self, LinkConditionsError> {
+        if !(0.0..=1.0).contains(&drop_rate) {
+            return Err(LinkConditionsError::InvalidDropRate(drop_rate));
+        }
+        Ok(LinkConditions {
+            latency,
+            jitter,
+            drop_rate,
+        })
+    }
+}
+
+impl Default for LinkConditions {
+    fn default() -> Self {
+        LinkConditions {
+            latency: Duration::from_millis(50),
+            jitter: Duration::from_millis(20),
+            drop_rate: 0.0,
+        }
+    }
+}
+
+/// the task queues of a node, as reachable by its peers
+#[derive(Clone)]
+pub(super) struct Inbox {
+    pub block_box: MessageBox<BlockMsg>,
+    pub fragment_box: MessageBox<TransactionMsg>,
+    pub topology_box: MessageBox<TopologyMsg>,
+    pub client_box: MessageBox<ClientMsg>,
+}
+
+struct State {
+    rng: ChaChaRng,
+    conditions: LinkConditions,
+    nodes: HashMap<Address, Inbox>,
+    /// group of each node while the network is partitioned
+    partition: Option<HashMap<Address, usize>>,
+}
+
+/// In-memory network connecting the nodes of a simulation.
+///
+/// Every delivery draws its fate (dropped or delayed, and by how much) from
+/// a random generator seeded at creation, so that a run on a paused tokio
+/// clock can be reproduced from the seed.
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+    state: Arc<Mutex<State>>,
+}
+
+impl SimulatedNetwork {
+    pub fn new(seed: u64, conditions: LinkConditions) -> Self {
+        SimulatedNetwork {
+            state: Arc::new(Mutex::new(State {
+                rng: ChaChaRng::seed_from_u64(seed),
+                conditions,
+                nodes: HashMap::new(),
+                partition: None,
+            })),
+        }
+    }
+
+    pub(super) fn register(&self, address: Address, inbox: Inbox) {
+        self.state.lock().unwrap().nodes.insert(address, inbox);
+    }
+
+    pub fn set_conditions(&self, conditions: LinkConditions) {
+        self.state.lock().unwrap().conditions = conditions;
+    }
+
+    /// split the network: from now on nodes only reach the nodes of their
+    /// own group. Nodes not listed in any group are isolated.
+    pub fn partition(&self, groups: &[&[Address]]) {
+        let partition = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, addresses)| addresses.iter().map(move |address| (*address, group)))
+            .collect();
+        self.state.lock().unwrap().partition = Some(partition);
+    }
+
+    /// remove the partition, every node reaches every other node again
+    pub fn heal(&self) {
+        self.state.lock().unwrap().partition = None;
+    }
+
+    /// decide whether a message from `from` reaches `to`, and when
+    fn route(&self, from: Address, to: Address) -> Option<(Duration, Inbox)> {
+        let mut state = self.state.lock().unwrap();
+        let inbox = state.nodes.get(&to)?.clone();
+        if let Some(partition) = &state.partition {
+            match (partition.get(&from), partition.get(&to)) {
+                (Some(a), Some(b)) if a == b => {}
+                _ => return None,
+            }
+        }
+        let conditions = state.conditions;
+        if state.rng.gen_bool(conditions.drop_rate) {
+            tracing::trace!(%from, %to, "message dropped");
+            return None;
+        }
+        let jitter = conditions.jitter.mul_f64(state.rng.gen::<f64>());
+        Some((conditions.latency + jitter, inbox))
+    }
+
+    /// run `f` with the inbox of `to` once a message sent now by `from`
+    /// reaches it, unless the message is lost on the way
+    fn deliver<F, Fut>(&self, from: Address, to: Address, f: F)
+    where
+        F: FnOnce(Inbox) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        if let Some((delay, inbox)) = self.route(from, to) {
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                f(inbox).await
+            });
+        }
+    }
+
+    fn choose(&self, peers: &[Peer]) -> Option<Address> {
+        let mut state = self.state.lock().unwrap();
+        peers.choose(&mut state.rng).map(Peer::address)
+    }
+
+    /// ask `peer` for the given blocks on behalf of `node`, the answer is
+    /// streamed to the block task of `node`
+    fn fetch_blocks(&self, node: Address, peer: Address, ids: Vec<HeaderHash>) {
+        let network = self.clone();
+        self.deliver(node, peer, move |mut inbox| async move {
+            let (handle, future) = intercom::stream_reply(RESPONSE_BUFFER);
+            if inbox
+                .client_box
+                .send(ClientMsg::GetBlocks(ids, handle))
+                .await
+                .is_err()
+            {
+                return;
+            }
+            if let Ok(blocks) = future.await {
+                network.respond(peer, node, blocks, BlockMsg::NetworkBlocks);
+            }
+        });
+    }
+
+    /// ask `peer` for the headers from `from` to `to` on behalf of `node`,
+    /// the answer is streamed to the block task of `node`
+    fn pull_headers(&self, node: Address, peer: Address, from: Vec<HeaderHash>, to: HeaderHash) {
+        let network = self.clone();
+        self.deliver(node, peer, move |mut inbox| async move {
+            let (handle, future) = intercom::stream_reply(RESPONSE_BUFFER);
+            if inbox
+                .client_box
+                .send(ClientMsg::PullHeaders(from, to, handle))
+                .await
+                .is_err()
+            {
+                return;
+            }
+            if let Ok(headers) = future.await {
+                network.respond(peer, node, headers, BlockMsg::ChainHeaders);
+            }
+        });
+    }
+
+    fn respond<T, S>(
+        &self,
+        peer: Address,
+        node: Address,
+        response: S,
+        to_msg: fn(intercom::RequestStreamHandle<T, ()>) -> BlockMsg,
+    ) where
+        T: Send + 'static,
+        S: Stream<Item = Result<T, intercom::Error>> + Send + 'static,
+    {
+        self.deliver(peer, node, move |mut inbox| async move {
+            let (handle, sink, _reply) = intercom::stream_request(RESPONSE_BUFFER);
+            if inbox.block_box.send(to_msg(handle)).await.is_ok() {
+                if let Err(e) = response.forward(sink).await {
+                    tracing::debug!(reason = %e, "response stream interrupted");
+                }
+            }
+        });
+    }
+}
+
+/// Stands for the network task of a node: what the node asks its network
+/// task to send is delivered to the peers over the simulated network, to the
+/// peers the topology task of the node selects.
+pub(super) async fn process_network_messages(
+    network: SimulatedNetwork,
+    address: Address,
+    mut topology_box: MessageBox<TopologyMsg>,
+    mut input: MessageQueue<NetworkMsg>,
+) {
+    while let Some(msg) = input.next().await {
+        match msg {
+            NetworkMsg::Propagate(PropagateMsg::Block(header)) => {
+                let selection = Selection::Topic {
+                    topic: topology::topic::BLOCKS,
+                };
+                for peer in view(&mut topology_box, selection).await {
+                    let header = header.clone();
+                    network.deliver(address, peer.address(), move |mut inbox| async move {
+                        let _ = inbox
+                            .block_box
+                            .send(BlockMsg::AnnouncedBlock(header, address))
+                            .await;
+                    });
+                }
+            }
+            NetworkMsg::Propagate(PropagateMsg::Fragment(fragment)) => {
+                let selection = Selection::Topic {
+                    topic: topology::topic::MESSAGES,
+                };
+                for peer in view(&mut topology_box, selection).await {
+                    let fragment = fragment.clone();
+                    network.deliver(address, peer.address(), move |mut inbox| async move {
+                        let (reply_handle, _reply_future) = intercom::unary_reply();
+                        let _ = inbox
+                            .fragment_box
+                            .send(TransactionMsg::SendTransactions {
+                                origin: FragmentOrigin::Network,
+                                fragments: vec![fragment],
+                                fail_fast: false,
+                                reply_handle,
+                            })
+                            .await;
+                    });
+                }
+            }
+            NetworkMsg::Propagate(PropagateMsg::Gossip(peer, gossips)) => {
+                network.deliver(address, peer.address(), move |mut inbox| async move {
+                    let _ = inbox
+                        .topology_box
+                        .send(TopologyMsg::AcceptGossip(gossips))
+                        .await;
+                });
+            }
+            NetworkMsg::GetBlocks(ids) => {
+                let selection = Selection::Topic {
+                    topic: topology::topic::BLOCKS,
+                };
+                let peers = view(&mut topology_box, selection).await;
+                if let Some(peer) = network.choose(&peers) {
+                    network.fetch_blocks(address, peer, ids);
+                }
+            }
+            NetworkMsg::GetNextBlock(peer, id) => {
+                network.fetch_blocks(address, peer, vec![id]);
+            }
+            NetworkMsg::PullHeaders {
+                node_address,
+                from,
+                to,
+            } => {
+                network.pull_headers(address, node_address, from.into(), to);
+            }
+            NetworkMsg::PeerInfo(reply) => reply.reply_ok(Vec::new()),
+        }
+    }
+}
+
+async fn view(topology_box: &mut MessageBox<TopologyMsg>, selection: Selection) -> Vec<Peer> {
+    let (reply_handle, reply_future) = intercom::unary_reply();
+    if topology_box
+        .send(TopologyMsg::View(selection, reply_handle))
+        .await
+        .is_err()
+    {
+        return Vec::new();
+    }
+    reply_future
+        .await
+        .map(|view| view.peers)
+        .unwrap_or_default()
+}
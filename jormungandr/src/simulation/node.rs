@@ -0,0 +1,243 @@
+use super::network::{self, Inbox, SimulatedNetwork};
+use crate::blockcfg::{Block, HeaderHash, Leader};
+use crate::blockchain::{self, Ref, Storage, Tip};
+use crate::intercom::{self, TopologyMsg};
+use crate::network::p2p::Address;
+use crate::secure::enclave::Enclave;
+use crate::settings::start::network::{
+    Configuration, Protocol, DEFAULT_MAX_CONNECTIONS, DEFAULT_MAX_INBOUND_CONNECTIONS,
+};
+use crate::topology::{
+    self,
+    layers::{LayersConfig, PreferredListConfig, RingsConfig},
+    Gossips, Peer, QuarantineConfig,
+};
+use crate::utils::{async_msg, async_msg::MessageBox, task::TokioServiceInfo};
+use crate::{client, fragment, leadership, metrics::Metrics, start_up};
+use chain_crypto::Ed25519;
+use jormungandr_lib::crypto::key::SigningKey;
+use poldercast::layer::Selection;
+
+use futures::prelude::*;
+use tracing::{span, Level};
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const QUEUE_LEN: usize = 64;
+const BLOCK_CACHE_CAPACITY: usize = 1024;
+const BLOCK_CACHE_TTL: Duration = Duration::from_secs(120);
+const POOL_MAX_ENTRIES: usize = 10_000;
+const LOG_MAX_ENTRIES: usize = 10_000;
+const LEADERSHIP_LOGS_CAPACITY: usize = 1024;
+const BLOCK_HARD_DEADLINE: u32 = 50;
+const PREFERRED_VIEW_MAX: usize = 20;
+
+pub(super) struct NodeConfig {
+    pub address: Address,
+    pub block0: Block,
+    pub leader: Option<Leader>,
+    pub node_key: SigningKey<Ed25519>,
+    pub gossip_interval: Duration,
+}
+
+/// A node of the simulation, running the `blockchain`, `client`,
+/// `leadership`, `fragment` and `topology` tasks of a real node on the
+/// current runtime. Its network task is replaced by the simulated network.
+pub struct SimulatedNode {
+    address: Address,
+    tip: Tip,
+    topology_box: MessageBox<TopologyMsg>,
+}
+
+impl SimulatedNode {
+    pub(super) async fn start(network: &SimulatedNetwork, config: NodeConfig) -> Self {
+        let NodeConfig {
+            address,
+            block0,
+            leader,
+            node_key,
+            gossip_interval,
+        } = config;
+
+        let span = span!(Level::TRACE, "simulated_node", address = %address);
+        let storage = Storage::memory(span).expect("cannot create the in-memory storage");
+        let (blockchain, tip) =
+            start_up::load_blockchain(block0, storage, BLOCK_CACHE_CAPACITY, false)
+                .await
+                .expect("cannot load the block0");
+
+        let (network_msgbox, network_queue) = async_msg::channel(QUEUE_LEN);
+        let (block_msgbox, block_queue) = async_msg::channel(QUEUE_LEN);
+        let (fragment_msgbox, fragment_queue) = async_msg::channel(QUEUE_LEN);
+        let (client_msgbox, client_queue) = async_msg::channel(QUEUE_LEN);
+        let (topology_msgbox, topology_queue) = async_msg::channel(QUEUE_LEN);
+        let (watch_msgbox, watch_queue) = async_msg::channel(QUEUE_LEN);
+        let stats_counter = Metrics::builder().build();
+
+        network.register(
+            address,
+            Inbox {
+                block_box: block_msgbox.clone(),
+                fragment_box: fragment_msgbox.clone(),
+                topology_box: topology_msgbox.clone(),
+                client_box: client_msgbox,
+            },
+        );
+
+        tokio::spawn(blockchain::start(
+            blockchain::TaskData {
+                blockchain: blockchain.clone(),
+                blockchain_tip: tip.clone(),
+                stats_counter: stats_counter.clone(),
+                network_msgbox: network_msgbox.clone(),
+                fragment_msgbox: fragment_msgbox.clone(),
+                explorer_msgbox: None,
+                watch_msgbox,
+                garbage_collection_interval: BLOCK_CACHE_TTL,
+            },
+            TokioServiceInfo::current("block"),
+            block_queue,
+        ));
+        // nobody watches the simulated nodes
+        tokio::spawn(watch_queue.for_each(|_| future::ready(())));
+
+        tokio::spawn(client::start(
+            TokioServiceInfo::current("client-query"),
+            client::TaskData {
+                storage: blockchain.storage().clone(),
+                blockchain_tip: tip.clone(),
+            },
+            client_queue,
+        ));
+
+        tokio::spawn(topology::start(topology::TaskData {
+            network_msgbox: network_msgbox.clone(),
+            topology_queue,
+            initial_peers: Vec::new(),
+            config: network_config(address, node_key, gossip_interval),
+            stats_counter: stats_counter.clone(),
+        }));
+
+        tokio::spawn(network::process_network_messages(
+            network.clone(),
+            address,
+            topology_msgbox.clone(),
+            network_queue,
+        ));
+
+        {
+            let process = fragment::Process::new(POOL_MAX_ENTRIES, LOG_MAX_ENTRIES, network_msgbox);
+            let tip = tip.clone();
+            tokio::spawn(async move {
+                if let Err(e) = process
+                    .start(
+                        TokioServiceInfo::current("fragment"),
+                        stats_counter,
+                        fragment_queue,
+                        None::<PathBuf>,
+                        tip,
+                    )
+                    .await
+                {
+                    tracing::error!(reason = %e, "fragment task failed");
+                }
+            });
+        }
+
+        {
+            let config = leadership::ModuleConfig {
+                service_info: TokioServiceInfo::current("leadership"),
+                logs: leadership::Logs::new(LEADERSHIP_LOGS_CAPACITY),
+                tip: tip.clone(),
+                pool: fragment_msgbox,
+                enclave: leadership::Enclave::new(Enclave::new(leader.into_iter().collect())),
+                block_message: block_msgbox,
+                rewards_report_all: false,
+                block_hard_deadline: BLOCK_HARD_DEADLINE,
+            };
+            tokio::spawn(async move {
+                if let Err(e) = leadership::Module::new(config)
+                    .and_then(|module| module.run())
+                    .await
+                {
+                    tracing::error!(reason = %e, "leadership task failed");
+                }
+            });
+        }
+
+        SimulatedNode {
+            address,
+            tip,
+            topology_box: topology_msgbox,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub async fn tip(&self) -> Arc<Ref> {
+        self.tip.get_ref().await
+    }
+
+    pub async fn tip_hash(&self) -> HeaderHash {
+        self.tip().await.hash()
+    }
+
+    /// the profile the topology task of this node gossips about itself
+    pub(super) async fn self_gossip(&self) -> Peer {
+        let (reply_handle, reply_future) = intercom::unary_reply();
+        self.topology_box
+            .clone()
+            .send(TopologyMsg::View(Selection::Any, reply_handle))
+            .await
+            .expect("topology task is not running");
+        reply_future
+            .await
+            .expect("topology task did not answer")
+            .self_node
+    }
+
+    pub(super) async fn accept_gossips(&self, gossips: Gossips) {
+        self.topology_box
+            .clone()
+            .send(TopologyMsg::AcceptGossip(gossips))
+            .await
+            .expect("topology task is not running");
+    }
+}
+
+fn network_config(
+    address: Address,
+    node_key: SigningKey<Ed25519>,
+    gossip_interval: Duration,
+) -> Configuration {
+    Configuration {
+        listen_address: None,
+        public_address: Some(address),
+        node_key,
+        trusted_peers: Vec::new(),
+        protocol: Protocol::Grpc,
+        max_connections: DEFAULT_MAX_CONNECTIONS,
+        max_inbound_connections: DEFAULT_MAX_INBOUND_CONNECTIONS,
+        timeout: Duration::from_secs(15),
+        policy: QuarantineConfig::default(),
+        layers: LayersConfig {
+            preferred_list: PreferredListConfig {
+                view_max: PREFERRED_VIEW_MAX,
+                peers: Vec::new(),
+            },
+            rings: RingsConfig::default(),
+        },
+        // the simulated nodes live on a private network
+        allow_private_addresses: true,
+        gossip_interval,
+        network_stuck_check: topology::DEFAULT_NETWORK_STUCK_INTERVAL,
+        max_bootstrap_attempts: None,
+        bootstrap_from_trusted_peers: false,
+        skip_bootstrap: true,
+        http_fetch_block0_service: Vec::new(),
+    }
+}
@@ -22,6 +22,8 @@ pub enum Error {
     LoggingInitializationError(#[from] logging::Error),
     #[error("Error in the overall configuration of the node")]
     ConfigurationError(#[from] settings::Error),
+    #[error("Error while loading the runtime settings")]
+    RuntimeSettings(#[from] settings::runtime::Error),
     #[error("I/O Error with {reason}")]
     Io {
         #[source]
@@ -53,6 +55,8 @@ pub enum Error {
     NodeSecrets(#[from] secure::NodeSecretFromFileError),
     #[error("Block 0 is set to start in the future")]
     Block0InFuture,
+    #[error("Error while importing the snapshot")]
+    Snapshot(#[from] super::SnapshotError),
     #[error("Error while loading the explorer from storage")]
     ExplorerBootstrapError(#[from] explorer::error::ExplorerError),
     #[error("A service has terminated with an error")]
@@ -79,6 +83,7 @@ impl Error {
             Error::Interrupted => 0,
             Error::LoggingInitializationError { .. } => 1,
             Error::ConfigurationError { .. } => 2,
+            Error::RuntimeSettings { .. } => 2,
             Error::Io { .. } => 3,
             Error::ParseError { .. } => 4,
             Error::StorageError { .. } => 5,
@@ -92,6 +97,7 @@ impl Error {
             Error::ExplorerBootstrapError { .. } => 11,
             Error::ServiceTerminatedWithError { .. } => 12,
             Error::DiagnosticError { .. } => 13,
+            Error::Snapshot { .. } => 14,
         }
     }
 }
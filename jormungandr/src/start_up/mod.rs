@@ -1,10 +1,12 @@
 mod error;
+mod snapshot;
 
 use tracing::{span, Level};
 
 pub use self::error::{Error, ErrorKind};
+pub use self::snapshot::{import_snapshot, Error as SnapshotError};
 use crate::{
-    blockcfg::{Block, HeaderId},
+    blockcfg::{Block, HeaderHash, HeaderId, Ledger},
     blockchain::{Blockchain, Error as BlockchainError, Storage, Tip},
     network,
     settings::start::Settings,
@@ -167,10 +169,39 @@ pub async fn load_blockchain(
         Ok(branch) => Ok(branch),
     }
     .map_err(Box::new)?;
+    log_loaded_tip(&tip).await;
+    Ok((blockchain, tip))
+}
+
+/// Load the blockchain of a storage a snapshot has just been imported into,
+/// restoring the ledger states of the snapshot instead of applying every
+/// block since the block0.
+pub async fn load_blockchain_from_snapshot(
+    block0: Block,
+    storage: Storage,
+    cache_capacity: usize,
+    rewards_report_all: bool,
+    boundaries: Vec<(HeaderHash, Ledger)>,
+) -> Result<(Blockchain, Tip), Error> {
+    let blockchain = Blockchain::new(
+        block0.header().hash(),
+        storage,
+        cache_capacity,
+        rewards_report_all,
+    );
+
+    let tip = blockchain
+        .load_from_epoch_boundaries(block0, boundaries)
+        .await
+        .map_err(Box::new)?;
+    log_loaded_tip(&tip).await;
+    Ok((blockchain, tip))
+}
+
+async fn log_loaded_tip(tip: &Tip) {
     let tip_ref = tip.get_ref().await;
     tracing::info!(
         "Loaded from storage tip is : {}",
         tip_ref.header().description()
     );
-    Ok((blockchain, tip))
 }
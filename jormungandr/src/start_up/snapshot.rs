@@ -0,0 +1,316 @@
+//! Import of a signed snapshot archive into an empty block storage, so the
+//! node does not have to synchronize every block since the block0 from the
+//! network nor to apply them.
+
+use crate::blockcfg::{Block, ConfigParam, Fragment, HeaderHash, Ledger};
+use crate::blockchain::{Storage, StorageError, MAIN_BRANCH_TAG};
+use chain_core::property::Deserialize as _;
+use chain_crypto::{Ed25519, PublicKey};
+use jormungandr_lib::{
+    crypto::{hash::Hash, key::Identifier},
+    interfaces::{
+        read_snapshot_block, read_snapshot_ledger, snapshot_blocks_digest, SnapshotManifest,
+        SNAPSHOT_BLOCKS_FILE, SNAPSHOT_LEDGERS_FILE, SNAPSHOT_MANIFEST_FILE, SNAPSHOT_VERSION,
+    },
+};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("cannot read snapshot file {path}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("snapshot manifest is malformed")]
+    Manifest(#[from] serde_yaml::Error),
+    #[error("unsupported snapshot version {0}")]
+    UnsupportedVersion(u32),
+    #[error("snapshot is for block0 {snapshot}, the node uses {node}")]
+    Block0Mismatch {
+        snapshot: HeaderHash,
+        node: HeaderHash,
+    },
+    #[error("snapshot manifest is not signed by any BFT leader or committee member of the block0")]
+    NotSigned,
+    #[error("the blocks file does not match the digest of the manifest")]
+    DigestMismatch,
+    #[error("the ledgers file does not match the digest of the manifest")]
+    LedgersDigestMismatch,
+    #[error("block {index} of the snapshot is malformed")]
+    BlockMalformed {
+        index: u64,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("block {0} of the snapshot is not connected to the previous block")]
+    BlockNotConnected(HeaderHash),
+    #[error("snapshot ends at block {found}, the manifest announces {expected}")]
+    TipMismatch {
+        expected: HeaderHash,
+        found: HeaderHash,
+    },
+    #[error("the manifest does not end the epoch boundaries with the tip")]
+    BoundariesMissingTip,
+    #[error("epoch boundary {0} is not a block of the snapshot")]
+    BoundaryNotInChain(HeaderHash),
+    #[error("ledger state {index} of the snapshot is malformed")]
+    LedgerMalformed {
+        index: usize,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("ledger state {index} of the snapshot is for block {found}, the manifest announces {expected}")]
+    LedgerMismatch {
+        index: usize,
+        expected: HeaderHash,
+        found: HeaderHash,
+    },
+    #[error("the snapshot has {found} ledger states, the manifest announces {expected}")]
+    LedgersCountMismatch { expected: usize, found: usize },
+    #[error("cannot write the snapshot blocks to the storage")]
+    Storage(#[from] StorageError),
+}
+
+/// Import the snapshot in `dir` into the storage.
+///
+/// The snapshot is only imported in an empty storage: when the storage
+/// already holds a chain, the node resumes from it and the snapshot is
+/// ignored. The manifest must be signed by at least one of the BFT leaders or
+/// committee members declared in the block0, the stored blocks must form
+/// a chain from the block0 to the signed tip and the ledger states must be
+/// the ones of the epoch boundaries of the manifest. The whole archive is
+/// checked before the first block is written, so a corrupt snapshot leaves
+/// the storage empty.
+///
+/// Returns the ledger states at the epoch boundaries, oldest first, if the
+/// snapshot has been imported.
+pub fn import_snapshot(
+    dir: &Path,
+    block0: &Block,
+    storage: &Storage,
+) -> Result<Option<Vec<(HeaderHash, Ledger)>>, Error> {
+    let block0_hash = block0.header().hash();
+
+    if storage.get_tag(MAIN_BRANCH_TAG)?.is_some() {
+        tracing::warn!(
+            "storage is not empty, ignoring the snapshot at {}",
+            dir.display()
+        );
+        return Ok(None);
+    }
+
+    let manifest_path = dir.join(SNAPSHOT_MANIFEST_FILE);
+    let manifest: SnapshotManifest = serde_yaml::from_reader(open(&manifest_path)?)?;
+
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(Error::UnsupportedVersion(manifest.version));
+    }
+    let snapshot_block0 = manifest.block0.into_hash();
+    if snapshot_block0 != block0_hash {
+        return Err(Error::Block0Mismatch {
+            snapshot: snapshot_block0,
+            node: block0_hash,
+        });
+    }
+
+    let signers = manifest.verified_signers(&trusted_signers(block0));
+    if signers.is_empty() {
+        return Err(Error::NotSigned);
+    }
+    for signer in &signers {
+        tracing::info!("snapshot manifest signed by {}", signer);
+    }
+
+    let blocks_path = dir.join(SNAPSHOT_BLOCKS_FILE);
+    let ledgers_path = dir.join(SNAPSHOT_LEDGERS_FILE);
+    if !digest_matches(&blocks_path, manifest.blocks.digest, manifest.blocks.size)? {
+        return Err(Error::DigestMismatch);
+    }
+    if !digest_matches(
+        &ledgers_path,
+        manifest.ledgers.digest,
+        manifest.ledgers.size,
+    )? {
+        return Err(Error::LedgersDigestMismatch);
+    }
+
+    let tip = manifest.tip.hash.into_hash();
+    let boundaries: Vec<HeaderHash> = manifest
+        .ledgers
+        .boundaries
+        .iter()
+        .map(|boundary| boundary.into_hash())
+        .collect();
+    if boundaries.last() != Some(&tip) {
+        return Err(Error::BoundariesMissingTip);
+    }
+
+    // check the whole chain before writing anything to the storage
+    let mut next_boundary = 0;
+    if boundaries[next_boundary] == block0_hash {
+        next_boundary += 1;
+    }
+    let count = for_each_block(&blocks_path, block0_hash, |block| {
+        if boundaries.get(next_boundary) == Some(&block.header().hash()) {
+            next_boundary += 1;
+        }
+        Ok(())
+    })?;
+    if let Some(boundary) = boundaries.get(next_boundary) {
+        return Err(Error::BoundaryNotInChain(*boundary));
+    }
+    if count.tip != tip || count.blocks != manifest.blocks.count {
+        return Err(Error::TipMismatch {
+            expected: tip,
+            found: count.tip,
+        });
+    }
+    let ledgers = read_ledgers(&ledgers_path, &boundaries)?;
+
+    tracing::info!(
+        "importing {} blocks from the snapshot up to {} ({})",
+        manifest.blocks.count,
+        manifest.tip.hash,
+        manifest.tip.date
+    );
+
+    // the tag is written last: an import interrupted before it leaves a
+    // storage without tag, in which the snapshot is imported again
+    put_block(storage, block0)?;
+    for_each_block(&blocks_path, block0_hash, |block| {
+        put_block(storage, &block)
+    })?;
+    storage.put_tag(MAIN_BRANCH_TAG, tip)?;
+
+    Ok(Some(ledgers))
+}
+
+fn put_block(storage: &Storage, block: &Block) -> Result<(), Error> {
+    match storage.put_block(block) {
+        Ok(()) | Err(StorageError::BlockAlreadyPresent) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn digest_matches(path: &Path, digest: Hash, size: u64) -> Result<bool, Error> {
+    let (actual, actual_size) =
+        snapshot_blocks_digest(open(path)?).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    Ok(actual == digest && actual_size == size)
+}
+
+struct ChainCount {
+    tip: HeaderHash,
+    blocks: u64,
+}
+
+/// read the blocks of the blocks file in order, checking that each one is
+/// the child of the previous one
+fn for_each_block<F>(path: &Path, block0_hash: HeaderHash, mut f: F) -> Result<ChainCount, Error>
+where
+    F: FnMut(Block) -> Result<(), Error>,
+{
+    let mut reader = open(path)?;
+    let mut parent = block0_hash;
+    let mut index = 0;
+    while let Some(bytes) = read_snapshot_block(&mut reader)
+        .map_err(|source| Error::BlockMalformed { index, source })?
+    {
+        let block = Block::deserialize(bytes.as_slice())
+            .map_err(|source| Error::BlockMalformed { index, source })?;
+        let header = block.header();
+        if header.block_parent_hash() != parent {
+            return Err(Error::BlockNotConnected(header.hash()));
+        }
+        parent = header.hash();
+        index += 1;
+        f(block)?;
+    }
+    Ok(ChainCount {
+        tip: parent,
+        blocks: index,
+    })
+}
+
+/// read the ledger states, which must be the ones of the `boundaries` in
+/// the same order
+fn read_ledgers(
+    path: &Path,
+    boundaries: &[HeaderHash],
+) -> Result<Vec<(HeaderHash, Ledger)>, Error> {
+    let mut reader = open(path)?;
+    let mut ledgers = Vec::with_capacity(boundaries.len());
+    while let Some((hash, bytes)) =
+        read_snapshot_ledger(&mut reader).map_err(|source| Error::LedgerMalformed {
+            index: ledgers.len(),
+            source,
+        })?
+    {
+        let index = ledgers.len();
+        let hash = hash.into_hash();
+        match boundaries.get(index) {
+            Some(expected) if *expected == hash => {}
+            Some(expected) => {
+                return Err(Error::LedgerMismatch {
+                    index,
+                    expected: *expected,
+                    found: hash,
+                })
+            }
+            None => {
+                return Err(Error::LedgersCountMismatch {
+                    expected: boundaries.len(),
+                    found: index + 1,
+                })
+            }
+        }
+        let ledger = Ledger::deserialize(bytes.as_slice())
+            .map_err(|source| Error::LedgerMalformed { index, source })?;
+        ledgers.push((hash, ledger));
+    }
+    if ledgers.len() < boundaries.len() {
+        return Err(Error::LedgersCountMismatch {
+            expected: boundaries.len(),
+            found: ledgers.len(),
+        });
+    }
+    Ok(ledgers)
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, Error> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// the BFT leaders and committee members declared in the block0
+fn trusted_signers(block0: &Block) -> Vec<Identifier<Ed25519>> {
+    block0
+        .contents()
+        .iter()
+        .filter_map(|fragment| match fragment {
+            Fragment::Initial(config_params) => Some(config_params),
+            _ => None,
+        })
+        .flat_map(|config_params| config_params.iter())
+        .filter_map(|param| match param {
+            ConfigParam::AddBftLeader(leader) => Some(leader.as_public_key().clone().into()),
+            ConfigParam::AddCommitteeId(committee) => {
+                PublicKey::<Ed25519>::from_binary(committee.as_ref())
+                    .ok()
+                    .map(Into::into)
+            }
+            _ => None,
+        })
+        .collect()
+}
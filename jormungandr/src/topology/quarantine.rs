@@ -170,6 +170,13 @@ pub struct QuarantineConfig {
     quarantine_whitelist: HashSet<multiaddr::Multiaddr>,
 }
 
+impl QuarantineConfig {
+    /// replace the addresses of the peers that are never quarantined
+    pub fn set_quarantine_whitelist(&mut self, whitelist: HashSet<multiaddr::Multiaddr>) {
+        self.quarantine_whitelist = whitelist;
+    }
+}
+
 impl Default for QuarantineConfig {
     fn default() -> Self {
         Self {
@@ -0,0 +1,47 @@
+//! # Wall clock
+//!
+//! Everything deriving the current slot from the time (leadership, chain
+//! selection, fragment expiry) reads it with [`now`] instead of the system
+//! clock, so that the simulator used in tests can drive slots with the
+//! tokio clock, which can be paused and advanced at will.
+
+use std::time::SystemTime;
+
+/// the current wall clock time
+#[cfg(not(test))]
+#[inline]
+pub fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+#[cfg(test)]
+pub use self::virtual_clock::{now, start_virtual};
+
+#[cfg(test)]
+mod virtual_clock {
+    use std::cell::Cell;
+    use std::time::SystemTime;
+    use tokio::time::Instant;
+
+    thread_local! {
+        static ORIGIN: Cell<Option<(SystemTime, Instant)>> = Cell::new(None);
+    }
+
+    /// from now on, [`now`] returns `origin` plus the time elapsed on the
+    /// tokio clock of the current thread.
+    ///
+    /// The clock is per thread so that tests running in parallel on their
+    /// own current thread runtime do not interfere with each other.
+    pub fn start_virtual(origin: SystemTime) {
+        ORIGIN.with(|cell| cell.set(Some((origin, Instant::now()))));
+    }
+
+    /// the current wall clock time, virtual if [`start_virtual`] was
+    /// called on this thread
+    pub fn now() -> SystemTime {
+        ORIGIN.with(|cell| match cell.get() {
+            Some((origin, started)) => origin + started.elapsed(),
+            None => SystemTime::now(),
+        })
+    }
+}
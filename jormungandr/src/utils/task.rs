@@ -7,16 +7,19 @@
 
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
+use jormungandr_lib::interfaces::{ServicePolicy, ServiceState, ServiceStatus};
 use thiserror::Error;
 use tokio::runtime::{Handle, Runtime};
 use tokio::task::JoinHandle;
 use tracing::{span, Level, Span};
 use tracing_futures::Instrument;
 
+use std::any::Any;
 use std::error;
 use std::fmt::Debug;
 use std::future::Future;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 /// hold onto the different services created
@@ -24,6 +27,36 @@ pub struct Services {
     services: Vec<Service>,
     finish_listener: FuturesUnordered<JoinHandle<Result<(), Box<dyn error::Error + Send + Sync>>>>,
     runtime: Runtime,
+    monitor: ServicesMonitor,
+}
+
+/// the status of the services, shared with the REST API
+#[derive(Clone, Default)]
+pub struct ServicesMonitor(Arc<Mutex<Vec<ServiceStatus>>>);
+
+/// the status of one service in the [`ServicesMonitor`]. The service is
+/// marked as stopped when dropped, which also happens when it panics.
+struct MonitoredService {
+    index: usize,
+    monitor: ServicesMonitor,
+}
+
+/// what to do when a supervised service ends or panics
+#[derive(Debug, Clone, Copy)]
+pub enum RestartPolicy {
+    /// stop the node, the behaviour of the services that are not supervised
+    Fatal,
+    /// keep the node running without the service
+    Degrade,
+    /// start the service again after a delay, doubling from `min_backoff` up
+    /// to `max_backoff` with each consecutive failure. A run longer than
+    /// `max_backoff` resets the delay. The service is degraded after
+    /// `max_restarts` consecutive failures.
+    Restart {
+        min_backoff: Duration,
+        max_backoff: Duration,
+        max_restarts: u32,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -89,9 +122,15 @@ impl Services {
             services: Vec::new(),
             finish_listener: FuturesUnordered::new(),
             runtime: Runtime::new().unwrap(),
+            monitor: ServicesMonitor::default(),
         }
     }
 
+    /// get a handle to follow the services that stopped running
+    pub fn monitor(&self) -> ServicesMonitor {
+        self.monitor.clone()
+    }
+
     /// Spawn the given Future in a new dedicated runtime
     pub fn spawn_future<F, T>(&mut self, name: &'static str, f: F)
     where
@@ -109,8 +148,10 @@ impl Services {
             handle,
         };
         let span_parent = future_service_info.span.clone();
+        let monitored = self.monitor.register(name, ServicePolicy::Fatal);
         let handle = self.runtime.spawn(
             async move {
+                let _monitored = monitored;
                 f(future_service_info).await;
                 tracing::info!("service `{}` finished", name);
                 Ok::<_, std::convert::Infallible>(()).map_err(Into::into)
@@ -147,8 +188,10 @@ impl Services {
             handle,
         };
         let parent_span = future_service_info.span.clone();
+        let monitored = self.monitor.register(name, ServicePolicy::Fatal);
         let handle = self.runtime.spawn(
             async move {
+                let _monitored = monitored;
                 let res = f(future_service_info).await;
                 if let Err(err) = &res {
                     tracing::error!(reason = %err.to_string(), "service finished with error");
@@ -170,6 +213,29 @@ impl Services {
         self.services.push(task);
     }
 
+    /// Spawn the given service under supervision: when it ends or panics,
+    /// the restart policy decides whether the node stops, keeps running
+    /// without it or starts it again. The service can be started several
+    /// times, so it is created with a `FnMut`.
+    pub fn spawn_supervised<F, T>(&mut self, name: &'static str, policy: RestartPolicy, f: F)
+    where
+        F: FnMut(TokioServiceInfo) -> T,
+        F: Send + 'static,
+        T: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.runtime.handle().clone();
+        let now = Instant::now();
+        let monitored = self.monitor.register(name, policy.into());
+        let span = span!(Level::TRACE, "service", kind = name);
+        let handle =
+            self.runtime
+                .spawn(supervise(name, policy, f, handle, span.clone(), monitored));
+        self.finish_listener.push(handle);
+
+        let task = Service::new(name, now);
+        self.services.push(task);
+    }
+
     /// select on all the started services. this function will block until first services returns
     pub fn wait_any_finished(self) -> Result<(), ServiceError> {
         let finish_listener = self.finish_listener;
@@ -186,7 +252,7 @@ impl Services {
                 if join_error.is_cancelled() {
                     Err(ServiceError::Cancelled)
                 } else if join_error.is_panic() {
-                    let desc = join_error.into_panic().downcast_ref::<String>().cloned();
+                    let desc = panic_reason(join_error.into_panic().as_ref());
                     Err(ServiceError::Panic(desc))
                 } else {
                     unreachable!("JoinError is either Cancelled or Panic")
@@ -405,6 +471,20 @@ impl TokioServiceInfo {
     }
 }
 
+#[cfg(test)]
+impl TokioServiceInfo {
+    /// information for a service running directly on the current runtime,
+    /// outside of [`Services`]
+    pub fn current(name: &'static str) -> Self {
+        TokioServiceInfo {
+            name,
+            up_time: Instant::now(),
+            span: span!(Level::TRACE, "service", kind = name),
+            handle: Handle::current(),
+        }
+    }
+}
+
 impl Service {
     /// get the time this service has been running since
     #[inline]
@@ -424,6 +504,179 @@ impl Service {
     }
 }
 
+/// run the service until the restart policy gives up on it
+async fn supervise<F, T>(
+    name: &'static str,
+    policy: RestartPolicy,
+    mut f: F,
+    handle: Handle,
+    span: Span,
+    monitored: MonitoredService,
+) -> Result<(), Box<dyn error::Error + Send + Sync>>
+where
+    F: FnMut(TokioServiceInfo) -> T,
+    T: Future<Output = ()> + Send + 'static,
+{
+    let mut failures = 0;
+    loop {
+        let started = Instant::now();
+        let service_info = TokioServiceInfo {
+            name,
+            up_time: started,
+            span: span.clone(),
+            handle: handle.clone(),
+        };
+        let service = f(service_info).instrument(span!(
+            parent: &span,
+            Level::TRACE,
+            "service",
+            kind = name
+        ));
+        let (failure, panic) = match handle.spawn(service).await {
+            Ok(()) => ("finished".to_owned(), None),
+            Err(join_error) if join_error.is_panic() => {
+                let panic = join_error.into_panic();
+                let reason = panic_reason(panic.as_ref())
+                    .unwrap_or_else(|| "could not serialize the panic".to_owned());
+                (format!("panicked: {}", reason), Some(panic))
+            }
+            // the runtime is shutting down
+            Err(_) => return Ok(()),
+        };
+
+        let delay = match policy {
+            RestartPolicy::Fatal => {
+                monitored.update(|status| status.last_failure = Some(failure));
+                if let Some(panic) = panic {
+                    std::panic::resume_unwind(panic);
+                }
+                tracing::info!("service `{}` finished", name);
+                return Ok(());
+            }
+            RestartPolicy::Degrade => None,
+            RestartPolicy::Restart {
+                min_backoff,
+                max_backoff,
+                max_restarts,
+            } => {
+                if started.elapsed() > max_backoff {
+                    failures = 0;
+                }
+                if failures < max_restarts {
+                    Some(
+                        min_backoff
+                            .checked_mul(1 << failures.min(31))
+                            .map_or(max_backoff, |delay| delay.min(max_backoff)),
+                    )
+                } else {
+                    None
+                }
+            }
+        };
+
+        match delay {
+            Some(delay) => {
+                tracing::warn!(
+                    %failure,
+                    ?delay,
+                    "service `{}` ended, restarting it", name
+                );
+                monitored.update(|status| {
+                    status.state = ServiceState::Restarting;
+                    status.last_failure = Some(failure);
+                });
+                tokio::time::sleep(delay).await;
+                failures += 1;
+                monitored.update(|status| {
+                    status.state = ServiceState::Running;
+                    status.restarts += 1;
+                });
+            }
+            None => {
+                tracing::error!(
+                    %failure,
+                    "service `{}` ended, the node keeps running without it", name
+                );
+                monitored.update(|status| {
+                    status.state = ServiceState::Degraded;
+                    status.last_failure = Some(failure);
+                });
+                // finishing would stop the node
+                return future::pending().await;
+            }
+        }
+    }
+}
+
+fn panic_reason(panic: &(dyn Any + Send)) -> Option<String> {
+    panic.downcast_ref::<String>().cloned().or_else(|| {
+        panic
+            .downcast_ref::<&str>()
+            .map(|reason| reason.to_string())
+    })
+}
+
+impl From<RestartPolicy> for ServicePolicy {
+    fn from(policy: RestartPolicy) -> Self {
+        match policy {
+            RestartPolicy::Fatal => ServicePolicy::Fatal,
+            RestartPolicy::Degrade => ServicePolicy::Degrade,
+            RestartPolicy::Restart { .. } => ServicePolicy::Restart,
+        }
+    }
+}
+
+impl ServicesMonitor {
+    fn register(&self, name: &'static str, policy: ServicePolicy) -> MonitoredService {
+        let mut statuses = self.0.lock().unwrap();
+        statuses.push(ServiceStatus {
+            name: name.to_owned(),
+            policy,
+            state: ServiceState::Running,
+            restarts: 0,
+            last_failure: None,
+        });
+        MonitoredService {
+            index: statuses.len() - 1,
+            monitor: self.clone(),
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<ServiceStatus> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// the names of the services that stopped running, either taking the
+    /// node down with them or leaving it running without them
+    pub fn stopped(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|status| matches!(status.state, ServiceState::Stopped | ServiceState::Degraded))
+            .map(|status| status.name.clone())
+            .collect()
+    }
+}
+
+impl MonitoredService {
+    fn update(&self, f: impl FnOnce(&mut ServiceStatus)) {
+        if let Ok(mut statuses) = self.monitor.0.lock() {
+            f(&mut statuses[self.index]);
+        }
+    }
+}
+
+impl Drop for MonitoredService {
+    fn drop(&mut self) {
+        self.update(|status| {
+            if status.state != ServiceState::Degraded {
+                status.state = ServiceState::Stopped;
+            }
+        });
+    }
+}
+
 impl<Msg> Clone for TaskMessageBox<Msg> {
     fn clone(&self) -> Self {
         TaskMessageBox(self.0.clone())
@@ -435,3 +688,129 @@ impl<Msg> TaskMessageBox<Msg> {
         self.0.send(a).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    /// supervise a service that ends as soon as it starts, counting its runs
+    fn supervise_failing(
+        policy: RestartPolicy,
+        panics: bool,
+    ) -> (
+        ServicesMonitor,
+        Arc<AtomicU32>,
+        JoinHandle<Result<(), Box<dyn error::Error + Send + Sync>>>,
+    ) {
+        let monitor = ServicesMonitor::default();
+        let monitored = monitor.register("failing", policy.into());
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        let supervisor = tokio::spawn(supervise(
+            "failing",
+            policy,
+            move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if panics {
+                        panic!("service failure");
+                    }
+                }
+            },
+            Handle::current(),
+            Span::none(),
+            monitored,
+        ));
+        (monitor, runs, supervisor)
+    }
+
+    async fn wait_for_state(monitor: &ServicesMonitor, state: ServiceState) -> ServiceStatus {
+        loop {
+            let status = monitor.statuses().remove(0);
+            if status.state == state {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restarts_with_backoff_until_degraded() {
+        let policy = RestartPolicy::Restart {
+            min_backoff: SECOND,
+            max_backoff: 4 * SECOND,
+            max_restarts: 4,
+        };
+        let started = tokio::time::Instant::now();
+        let (monitor, runs, supervisor) = supervise_failing(policy, true);
+
+        let status = wait_for_state(&monitor, ServiceState::Degraded).await;
+        // the delays double from 1s and are capped at 4s: 1 + 2 + 4 + 4
+        let elapsed = started.elapsed();
+        assert!(
+            elapsed >= 11 * SECOND && elapsed < 12 * SECOND,
+            "{:?}",
+            elapsed
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 5);
+        assert_eq!(status.restarts, 4);
+        assert_eq!(
+            status.last_failure.as_deref(),
+            Some("panicked: service failure")
+        );
+        assert_eq!(monitor.stopped(), vec!["failing".to_owned()]);
+
+        // the node keeps running without the service
+        tokio::time::sleep(60 * SECOND).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 5);
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restarting_service_is_not_stopped() {
+        let policy = RestartPolicy::Restart {
+            min_backoff: 10 * SECOND,
+            max_backoff: 10 * SECOND,
+            max_restarts: 1,
+        };
+        let (monitor, runs, supervisor) = supervise_failing(policy, false);
+
+        let status = wait_for_state(&monitor, ServiceState::Restarting).await;
+        assert_eq!(status.last_failure.as_deref(), Some("finished"));
+        assert!(monitor.stopped().is_empty());
+
+        let status = wait_for_state(&monitor, ServiceState::Degraded).await;
+        assert_eq!(status.restarts, 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn degraded_service_is_not_restarted() {
+        let (monitor, runs, supervisor) = supervise_failing(RestartPolicy::Degrade, false);
+
+        let status = wait_for_state(&monitor, ServiceState::Degraded).await;
+        assert_eq!(status.restarts, 0);
+        assert_eq!(status.last_failure.as_deref(), Some("finished"));
+        assert_eq!(monitor.stopped(), vec!["failing".to_owned()]);
+
+        tokio::time::sleep(60 * SECOND).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        supervisor.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fatal_service_stops_the_node() {
+        let (monitor, runs, supervisor) = supervise_failing(RestartPolicy::Fatal, false);
+
+        supervisor.await.unwrap().unwrap();
+        let status = monitor.statuses().remove(0);
+        assert_eq!(status.state, ServiceState::Stopped);
+        assert_eq!(status.last_failure.as_deref(), Some("finished"));
+        assert_eq!(monitor.stopped(), vec!["failing".to_owned()]);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
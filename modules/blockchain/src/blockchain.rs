@@ -47,6 +47,14 @@ impl Blockchain {
         Arc::clone(&self.tip)
     }
 
+    /// get the reference of a block still held by the `Blockchain`
+    ///
+    /// Only the most recently used references are kept, up to the
+    /// configured `cache_capacity`.
+    pub fn get(&self, hash: &HeaderId) -> Option<Arc<Reference>> {
+        self.cache.peek(hash).cloned()
+    }
+
     /// get an iterator for all the branches currently being considered by
     /// the `Blockchain`.
     ///
@@ -74,7 +82,7 @@ impl Blockchain {
             Ok(self.put_head(new_reference, true, epoch_transition))
         } else {
             Ok(Event::MissingParent {
-                parent: block.header().hash(),
+                parent: parent_hash,
             })
         }
     }
@@ -52,6 +52,30 @@ It contains the details as to what and how it was added. A new branch
 may have been created, it may even be the new tip. It will also tell if an
 epoch transition occurs: that way the application may notify the different
 module with the appropriate data.
+
+# Replaying a blockchain
+
+A `Blockchain` can be rebuilt without running a node, validating every
+block on the way: `Blockchain::replay_storage` replays the main branch of
+the block storage directory of a (stopped) node and `Blockchain::replay`
+adds the blocks of any stream of blocks, such as blocks downloaded from a
+node.
+
+```no_run
+# fn main() -> Result<(), blockchain::ReplayError> {
+use blockchain::{Blockchain, Configuration, Storage};
+
+let storage = Storage::open("/var/lib/jormungandr/storage")?;
+let blockchain = Blockchain::replay_storage(&Configuration::default(), &storage)?;
+
+let tip = blockchain.tip();
+println!("{} vote plans at {}", tip.active_vote_plans().len(), tip.hash());
+# Ok(())
+# }
+```
+
+The ledger of any `Reference` still held by the `Blockchain` can then be
+queried: accounts, unspent outputs, stake distribution and vote plans.
 */
 
 pub(crate) mod block0;
@@ -59,10 +83,16 @@ mod blockchain;
 mod checkpoints;
 mod epoch_info;
 mod reference;
+mod replay;
+mod storage;
+#[cfg(test)]
+mod testing;
 
 pub use self::{
     blockchain::{Blockchain, Configuration, Event},
     checkpoints::Checkpoints,
     epoch_info::{EpochInfo, EpochInfoError},
     reference::{Error, Reference, Selection},
+    replay::ReplayError,
+    storage::{Storage, StorageError, MAIN_BRANCH_TAG},
 };
@@ -1,10 +1,18 @@
 use crate::{EpochInfo, EpochInfoError};
+use chain_addr::Address;
 use chain_impl_mockchain::{
+    account::Identifier,
+    accounting::account::AccountState,
     block::Block,
     chaintypes::ConsensusVersion,
+    fragment::FragmentId,
     header::{BlockDate, ChainLength, Epoch, Header, HeaderId},
     leadership::Leadership,
     ledger::{self, Ledger, RewardsInfoParameters},
+    stake::StakeDistribution,
+    transaction::Output,
+    utxo,
+    vote::VotePlanStatus,
 };
 use std::{
     sync::Arc,
@@ -330,6 +338,38 @@ impl Reference {
         &self.ledger
     }
 
+    /// get the state of the account in the ledger left by this block
+    pub fn account(&self, identifier: &Identifier) -> Option<&AccountState<()>> {
+        self.ledger.accounts().get_state(identifier).ok()
+    }
+
+    /// get the transaction output, if it is still unspent in the ledger left by this block
+    pub fn utxo(&self, fragment_id: FragmentId, index: u8) -> Option<&Output<Address>> {
+        self.ledger.utxo_out(fragment_id, index)
+    }
+
+    /// iterate over the unspent transaction outputs of the given address
+    pub fn utxos_of<'a>(
+        &'a self,
+        address: &'a Address,
+    ) -> impl Iterator<Item = utxo::Entry<'a, Address>> + 'a {
+        self.ledger
+            .utxos()
+            .filter(move |entry| entry.output.address == *address)
+    }
+
+    /// compute the stake distribution of the ledger left by this block
+    ///
+    /// This is not the distribution the leadership schedule of the epoch
+    /// was built from, see `EpochInfo::epoch_leadership_schedule`.
+    pub fn stake_distribution(&self) -> StakeDistribution {
+        self.ledger.get_stake_distribution()
+    }
+
+    pub fn active_vote_plans(&self) -> Vec<VotePlanStatus> {
+        self.ledger.active_vote_plans()
+    }
+
     /// retrieve the block's epoch info
     pub fn epoch_info(&self) -> Arc<EpochInfo> {
         Arc::clone(&self.epoch_info)
@@ -366,3 +406,99 @@ impl Reference {
         self.previous_epoch_state.as_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        account_address, utxo_address, TestChain, ACCOUNT_FUNDS, SLOTS_PER_EPOCH, UTXO_FUNDS,
+    };
+    use chain_impl_mockchain::value::Value;
+
+    #[test]
+    fn query_the_block0_ledger() {
+        let chain = TestChain::new();
+        let reference = Reference::new(&chain.block0).unwrap();
+
+        let account = reference
+            .account(&Identifier::from(chain.account_key.clone()))
+            .unwrap();
+        assert_eq!(account.value(), Value(ACCOUNT_FUNDS));
+
+        let utxo_address = utxo_address(&chain.utxo_key);
+        let utxos: Vec<_> = reference.utxos_of(&utxo_address).collect();
+        assert_eq!(utxos.len(), 1);
+        let entry = &utxos[0];
+        assert_eq!(entry.output.value, Value(UTXO_FUNDS));
+        let output = reference
+            .utxo(entry.fragment_id, entry.output_index)
+            .unwrap();
+        assert_eq!(output.address, utxo_address);
+        assert!(reference
+            .utxo(entry.fragment_id, entry.output_index + 1)
+            .is_none());
+        assert_eq!(
+            reference
+                .utxos_of(&account_address(&chain.account_key))
+                .count(),
+            0
+        );
+
+        assert!(reference.stake_distribution().to_pools.is_empty());
+        assert!(reference.active_vote_plans().is_empty());
+    }
+
+    #[test]
+    fn chain_keeps_the_previous_epoch_state() {
+        let mut chain = TestChain::new();
+        let mut reference = Arc::new(Reference::new(&chain.block0).unwrap());
+        let block0_id = reference.hash();
+        for block in chain.next_blocks(SLOTS_PER_EPOCH as usize) {
+            reference = Arc::new(Arc::clone(&reference).chain(&block).unwrap());
+        }
+
+        assert_eq!(reference.block_date().epoch, 1);
+        assert_eq!(u32::from(reference.chain_length()), SLOTS_PER_EPOCH);
+        assert!(reference.previous_epoch_state().is_some());
+        // the ledger is not changed by empty blocks
+        assert_eq!(
+            reference
+                .account(&Identifier::from(chain.account_key.clone()))
+                .unwrap()
+                .value(),
+            Value(ACCOUNT_FUNDS)
+        );
+        let block0 = Arc::new(Reference::new(&chain.block0).unwrap());
+        assert_eq!(
+            reference
+                .approximate_common_ancestor(&block0)
+                .map(|ancestor| ancestor.hash()),
+            Some(block0_id)
+        );
+    }
+
+    #[test]
+    fn chain_rejects_a_block_not_following_the_reference() {
+        let mut chain = TestChain::new();
+        let block0 = Arc::new(Reference::new(&chain.block0).unwrap());
+        let first = chain.next_block();
+        let second = chain.next_block();
+
+        assert!(matches!(
+            Arc::clone(&block0).chain(&second),
+            Err(Error::NotTheParentBlock { .. })
+        ));
+
+        let same_date = chain.sign(
+            block0.hash(),
+            block0.chain_length().increase(),
+            block0.block_date(),
+        );
+        assert!(matches!(
+            Arc::clone(&block0).chain(&same_date),
+            Err(Error::InvalidBlockDate { .. })
+        ));
+
+        assert!(Arc::clone(&block0).chain(&first).is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{
+    storage::{Storage, StorageError},
+    Blockchain, Configuration, Error, Event, Reference,
+};
+use chain_impl_mockchain::{block::Block, header::HeaderId};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Cannot read the blocks to replay")]
+    Storage(
+        #[source]
+        #[from]
+        StorageError,
+    ),
+
+    #[error("The block0 is invalid")]
+    Block0(#[source] Error),
+
+    #[error("The block {block} is invalid")]
+    InvalidBlock {
+        block: HeaderId,
+        #[source]
+        source: Error,
+    },
+
+    #[error(
+        "The block {block} does not follow the replayed blocks, its parent {parent} is unknown"
+    )]
+    MissingParent { block: HeaderId, parent: HeaderId },
+
+    #[error("The block stream failed")]
+    Stream(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Blockchain {
+    /// start a blockchain from its block0, validating the initial ledger
+    pub fn from_block0(configuration: &Configuration, block0: &Block) -> Result<Self, Error> {
+        let block0 = Reference::new(block0)?;
+        Ok(Self::new(configuration, Arc::new(block0)))
+    }
+
+    /// replay the main branch of the block storage of a node, from its
+    /// block0 to its tip
+    pub fn replay_storage(
+        configuration: &Configuration,
+        storage: &Storage,
+    ) -> Result<Self, ReplayError> {
+        let block0 = storage.block0()?;
+        let block0_id = block0.header().hash();
+        let head = storage.head()?;
+
+        let mut blockchain =
+            Self::from_block0(configuration, &block0).map_err(ReplayError::Block0)?;
+        if head != block0_id {
+            blockchain.replay(storage.blocks(block0_id, head)?)?;
+        }
+        Ok(blockchain)
+    }
+
+    /// validate and add every block of the stream, in order, stopping at the
+    /// first invalid one. Every block must follow a block already known.
+    ///
+    /// Returns the reference of the last block added.
+    pub fn replay<I, E>(&mut self, blocks: I) -> Result<Arc<Reference>, ReplayError>
+    where
+        I: IntoIterator<Item = Result<Block, E>>,
+        E: Into<ReplayError>,
+    {
+        let mut last = self.tip();
+        for block in blocks {
+            let block = block.map_err(Into::into)?;
+            let event = self
+                .put(&block)
+                .map_err(|source| ReplayError::InvalidBlock {
+                    block: block.header().hash(),
+                    source,
+                })?;
+
+            match event {
+                Event::Added { new_reference, .. } => last = new_reference,
+                Event::MissingParent { parent } => {
+                    return Err(ReplayError::MissingParent {
+                        block: block.header().hash(),
+                        parent,
+                    })
+                }
+            }
+        }
+        Ok(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{configuration, TestChain, SLOTS_PER_EPOCH};
+    use chain_impl_mockchain::header::BlockDate;
+
+    fn blocks(blocks: Vec<Block>) -> impl Iterator<Item = Result<Block, ReplayError>> {
+        blocks.into_iter().map(Ok)
+    }
+
+    #[test]
+    fn replay_moves_the_tip_to_the_last_block() {
+        let mut chain = TestChain::new();
+        let mut blockchain = Blockchain::from_block0(&configuration(), &chain.block0).unwrap();
+        // cross an epoch boundary
+        let replayed = chain.next_blocks(SLOTS_PER_EPOCH as usize + 2);
+        let last_id = replayed.last().unwrap().header().hash();
+
+        let last = blockchain.replay(blocks(replayed)).unwrap();
+
+        assert_eq!(last.hash(), last_id);
+        assert_eq!(blockchain.tip().hash(), last_id);
+        assert_eq!(
+            blockchain.tip().block_date(),
+            BlockDate {
+                epoch: 1,
+                slot_id: 2
+            }
+        );
+        assert!(blockchain.get(&last_id).is_some());
+    }
+
+    #[test]
+    fn replay_of_nothing_keeps_the_tip() {
+        let chain = TestChain::new();
+        let mut blockchain = Blockchain::from_block0(&configuration(), &chain.block0).unwrap();
+        let last = blockchain.replay(blocks(Vec::new())).unwrap();
+        assert_eq!(last.hash(), chain.block0.header().hash());
+    }
+
+    #[test]
+    fn replay_stops_at_a_block_with_an_unknown_parent() {
+        let mut chain = TestChain::new();
+        let mut blockchain = Blockchain::from_block0(&configuration(), &chain.block0).unwrap();
+        let skipped = chain.next_block();
+        let orphan = chain.next_block();
+        let orphan_id = orphan.header().hash();
+
+        match blockchain.replay(blocks(vec![orphan])) {
+            Err(ReplayError::MissingParent { block, parent }) => {
+                assert_eq!(block, orphan_id);
+                assert_eq!(parent, skipped.header().hash());
+            }
+            _ => panic!("the block should miss its parent"),
+        }
+        assert_eq!(blockchain.tip().hash(), chain.block0.header().hash());
+    }
+
+    #[test]
+    fn replay_stops_at_an_invalid_block() {
+        let mut chain = TestChain::new();
+        let mut blockchain = Blockchain::from_block0(&configuration(), &chain.block0).unwrap();
+        let first = chain.next_block();
+        // the chain length does not follow its parent
+        let invalid = chain.sign(
+            first.header().hash(),
+            first.header().chain_length(),
+            BlockDate {
+                epoch: 0,
+                slot_id: 5,
+            },
+        );
+        let invalid_id = invalid.header().hash();
+        let first_id = first.header().hash();
+
+        match blockchain.replay(blocks(vec![first, invalid])) {
+            Err(ReplayError::InvalidBlock { block, .. }) => assert_eq!(block, invalid_id),
+            _ => panic!("the block should be rejected"),
+        }
+        assert_eq!(blockchain.tip().hash(), first_id);
+    }
+
+    #[test]
+    fn replay_stops_at_a_stream_error() {
+        let mut chain = TestChain::new();
+        let mut blockchain = Blockchain::from_block0(&configuration(), &chain.block0).unwrap();
+        let first = chain.next_block();
+        let first_id = first.header().hash();
+        let stream = vec![
+            Ok(first),
+            Err(ReplayError::Stream("connection lost".into())),
+            Ok(chain.next_block()),
+        ];
+
+        assert!(matches!(
+            blockchain.replay(stream),
+            Err(ReplayError::Stream(_))
+        ));
+        assert_eq!(blockchain.tip().hash(), first_id);
+    }
+}
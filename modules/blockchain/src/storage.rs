@@ -0,0 +1,201 @@
+use chain_core::property::Deserialize as _;
+use chain_impl_mockchain::{block::Block, header::HeaderId};
+use chain_storage::BlockStore;
+use std::path::Path;
+use thiserror::Error;
+
+/// the tag the node keeps on the tip of its main branch
+pub const MAIN_BRANCH_TAG: &str = "HEAD";
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("block storage backend error")]
+    Backend(
+        #[source]
+        #[from]
+        chain_storage::Error,
+    ),
+
+    #[error("cannot decode a block or a block id from the storage")]
+    Deserialize(#[source] std::io::Error),
+
+    #[error("the storage does not contain a block0")]
+    NoBlock0,
+
+    #[error("the storage does not have the `{0}' tag")]
+    NoTag(String),
+
+    #[error("block {from} is not an ancestor of block {to}")]
+    CannotIterate { from: HeaderId, to: HeaderId },
+}
+
+/// read access to the block storage directory of a node
+///
+/// The storage is locked by the process using it: the node must be stopped
+/// before opening its storage directory.
+pub struct Storage {
+    store: BlockStore,
+}
+
+impl Storage {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        let store = BlockStore::file(path, HeaderId::zero_hash().as_bytes().to_vec())?;
+        Ok(Self { store })
+    }
+
+    /// get the first block of the blockchain stored
+    pub fn block0(&self) -> Result<Block, StorageError> {
+        let block0 = self
+            .store
+            .get_blocks_by_chain_length(0)?
+            .into_iter()
+            .next()
+            .ok_or(StorageError::NoBlock0)?;
+        decode_block(block0.as_ref())
+    }
+
+    /// get the header id of the block the given tag points to
+    pub fn tag(&self, tag: &str) -> Result<Option<HeaderId>, StorageError> {
+        self.store
+            .get_tag(tag)?
+            .map(|id| HeaderId::deserialize(id.as_ref()).map_err(StorageError::Deserialize))
+            .transpose()
+    }
+
+    /// get the header id of the tip of the main branch of the node
+    pub fn head(&self) -> Result<HeaderId, StorageError> {
+        self.tag(MAIN_BRANCH_TAG)?
+            .ok_or_else(|| StorageError::NoTag(MAIN_BRANCH_TAG.to_owned()))
+    }
+
+    pub fn get(&self, id: HeaderId) -> Result<Option<Block>, StorageError> {
+        match self.store.get_block(id.as_bytes()) {
+            Ok(block) => decode_block(block.as_ref()).map(Some),
+            Err(chain_storage::Error::BlockNotFound) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// iterate over the blocks following `from`, up to `to` included,
+    /// from the oldest to the most recent
+    pub fn blocks(
+        &self,
+        from: HeaderId,
+        to: HeaderId,
+    ) -> Result<impl Iterator<Item = Result<Block, StorageError>>, StorageError> {
+        let distance = self
+            .store
+            .is_ancestor(from.as_bytes(), to.as_bytes())?
+            .ok_or(StorageError::CannotIterate { from, to })?;
+
+        let iter = self.store.iter(to.as_bytes(), distance)?;
+        Ok(iter.map(|block| {
+            block
+                .map_err(StorageError::from)
+                .and_then(|block| decode_block(block.as_ref()))
+        }))
+    }
+}
+
+fn decode_block(bytes: &[u8]) -> Result<Block, StorageError> {
+    Block::deserialize(bytes).map_err(StorageError::Deserialize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{configuration, TestChain},
+        Blockchain,
+    };
+    use chain_core::property::Serialize as _;
+    use chain_storage::BlockInfo;
+    use tempfile::TempDir;
+
+    /// write the blocks in a node storage directory, with the main branch
+    /// tag on `head` if any
+    fn store(dir: &TempDir, blocks: &[Block], head: Option<HeaderId>) {
+        let store =
+            BlockStore::file(dir.path(), HeaderId::zero_hash().as_bytes().to_vec()).unwrap();
+        for block in blocks {
+            let header = block.header();
+            let info = BlockInfo::new(
+                header.hash().as_bytes().to_vec(),
+                header.block_parent_hash().as_bytes().to_vec(),
+                header.chain_length().into(),
+            );
+            store
+                .put_block(&block.serialize_as_vec().unwrap(), info)
+                .unwrap();
+        }
+        if let Some(head) = head {
+            store.put_tag(MAIN_BRANCH_TAG, head.as_bytes()).unwrap();
+        }
+    }
+
+    #[test]
+    fn read_the_blocks_of_the_main_branch() {
+        let mut chain = TestChain::new();
+        let mut blocks = vec![chain.block0.clone()];
+        blocks.extend(chain.next_blocks(5));
+        let ids: Vec<HeaderId> = blocks.iter().map(|block| block.header().hash()).collect();
+        let dir = TempDir::new().unwrap();
+        store(&dir, &blocks, Some(ids[5]));
+
+        let storage = Storage::open(dir.path()).unwrap();
+        assert_eq!(storage.block0().unwrap().header().hash(), ids[0]);
+        assert_eq!(storage.head().unwrap(), ids[5]);
+        assert_eq!(
+            storage.get(ids[3]).unwrap().unwrap().header().hash(),
+            ids[3]
+        );
+
+        let read: Vec<HeaderId> = storage
+            .blocks(ids[1], ids[4])
+            .unwrap()
+            .map(|block| block.unwrap().header().hash())
+            .collect();
+        assert_eq!(read, ids[2..=4].to_vec());
+
+        assert!(matches!(
+            storage.blocks(ids[4], ids[1]),
+            Err(StorageError::CannotIterate { .. })
+        ));
+    }
+
+    #[test]
+    fn replay_the_storage_up_to_the_head() {
+        let mut chain = TestChain::new();
+        let mut blocks = vec![chain.block0.clone()];
+        blocks.extend(chain.next_blocks(5));
+        let head = blocks[3].header().hash();
+        let dir = TempDir::new().unwrap();
+        store(&dir, &blocks, Some(head));
+
+        let storage = Storage::open(dir.path()).unwrap();
+        let blockchain = Blockchain::replay_storage(&configuration(), &storage).unwrap();
+        assert_eq!(blockchain.tip().hash(), head);
+    }
+
+    #[test]
+    fn unknown_blocks_and_tags() {
+        let mut chain = TestChain::new();
+        let unknown = chain.next_block().header().hash();
+        let dir = TempDir::new().unwrap();
+        store(&dir, &[chain.block0.clone()], None);
+
+        let storage = Storage::open(dir.path()).unwrap();
+        assert!(storage.get(unknown).unwrap().is_none());
+        assert!(storage.tag(MAIN_BRANCH_TAG).unwrap().is_none());
+        assert!(matches!(storage.head(), Err(StorageError::NoTag(_))));
+    }
+
+    #[test]
+    fn empty_storage_has_no_block0() {
+        let dir = TempDir::new().unwrap();
+        store(&dir, &[], None);
+
+        let storage = Storage::open(dir.path()).unwrap();
+        assert!(matches!(storage.block0(), Err(StorageError::NoBlock0)));
+    }
+}
@@ -0,0 +1,151 @@
+//! a BFT blockchain, with blocks signed by its only leader, to exercise the
+//! module without a node
+
+use crate::Configuration;
+use chain_addr::{Address, Discrimination, Kind};
+use chain_crypto::{Ed25519, PublicKey, SecretKey};
+use chain_impl_mockchain::{
+    block::{builder as block_builder, Block, BlockVersion},
+    chaintypes::ConsensusVersion,
+    fee::LinearFee,
+    fragment::Contents,
+    header::{BlockDate, ChainLength, HeaderId},
+    key::BftLeaderId,
+    ledger::RewardsInfoParameters,
+};
+use jormungandr_lib::interfaces::{
+    Block0Configuration, BlockchainConfiguration, Initial, InitialUTxO, NumberOfSlotsPerEpoch,
+};
+use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+pub const SLOTS_PER_EPOCH: u32 = 10;
+pub const UTXO_FUNDS: u64 = 100;
+pub const ACCOUNT_FUNDS: u64 = 1_000;
+
+/// small caches, the default capacity is sized for a node
+pub fn configuration() -> Configuration {
+    Configuration {
+        heads_capacity: 16,
+        cache_capacity: 64,
+        rewards_info_params: RewardsInfoParameters::default(),
+    }
+}
+
+pub struct TestChain {
+    pub block0: Block,
+    /// owner of an output of the block0
+    pub utxo_key: PublicKey<Ed25519>,
+    /// account funded by the block0
+    pub account_key: PublicKey<Ed25519>,
+    leader_key: SecretKey<Ed25519>,
+    leader_id: BftLeaderId,
+    parent_id: HeaderId,
+    chain_length: ChainLength,
+    date: BlockDate,
+}
+
+impl TestChain {
+    pub fn new() -> Self {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        let leader_key = SecretKey::<Ed25519>::generate(&mut rng);
+        let utxo_key = SecretKey::<Ed25519>::generate(&mut rng).to_public();
+        let account_key = SecretKey::<Ed25519>::generate(&mut rng).to_public();
+
+        let mut blockchain_configuration = BlockchainConfiguration::new(
+            Discrimination::Test,
+            ConsensusVersion::Bft,
+            LinearFee::new(0, 0, 0),
+        );
+        // in the past, so no block is in the future
+        blockchain_configuration.block0_date = "1600000000".parse().unwrap();
+        blockchain_configuration.slots_per_epoch =
+            NumberOfSlotsPerEpoch::new(SLOTS_PER_EPOCH).unwrap();
+        blockchain_configuration.consensus_leader_ids = vec![leader_key.to_public().into()];
+        let block0 = Block0Configuration {
+            blockchain_configuration,
+            initial: vec![Initial::Fund(vec![
+                InitialUTxO {
+                    address: utxo_address(&utxo_key).into(),
+                    value: UTXO_FUNDS.into(),
+                },
+                InitialUTxO {
+                    address: account_address(&account_key).into(),
+                    value: ACCOUNT_FUNDS.into(),
+                },
+            ])],
+        }
+        .to_block();
+
+        Self {
+            parent_id: block0.header().hash(),
+            chain_length: block0.header().chain_length(),
+            date: block0.header().block_date(),
+            leader_id: BftLeaderId::from(leader_key.to_public()),
+            leader_key,
+            utxo_key,
+            account_key,
+            block0,
+        }
+    }
+
+    /// the next empty block, in the following slot
+    pub fn next_block(&mut self) -> Block {
+        let date = if self.date.slot_id + 1 < SLOTS_PER_EPOCH {
+            BlockDate {
+                epoch: self.date.epoch,
+                slot_id: self.date.slot_id + 1,
+            }
+        } else {
+            BlockDate {
+                epoch: self.date.epoch + 1,
+                slot_id: 0,
+            }
+        };
+        self.block_at(date)
+    }
+
+    /// `count` empty blocks, one per slot, following the last block built
+    pub fn next_blocks(&mut self, count: usize) -> Vec<Block> {
+        std::iter::repeat_with(|| self.next_block())
+            .take(count)
+            .collect()
+    }
+
+    /// an empty block at the given date, following the last block built
+    pub fn block_at(&mut self, date: BlockDate) -> Block {
+        self.chain_length = self.chain_length.increase();
+        self.date = date;
+        let block = self.sign(self.parent_id, self.chain_length, date);
+        self.parent_id = block.header().hash();
+        block
+    }
+
+    /// a block with the given parent and chain length, not following the
+    /// blocks built so far
+    pub fn sign(&self, parent_id: HeaderId, chain_length: ChainLength, date: BlockDate) -> Block {
+        let (leader_key, leader_id) = (&self.leader_key, &self.leader_id);
+        block_builder(
+            BlockVersion::Ed25519Signed,
+            Contents::empty(),
+            |hdr_builder| {
+                let final_builder = hdr_builder
+                    .set_parent(&parent_id, chain_length)
+                    .set_date(date)
+                    .into_bft_builder()
+                    .expect("Valid Header Builder")
+                    .set_consensus_data(leader_id);
+                let signature = leader_key.sign_slice(final_builder.get_authenticated_data());
+                Ok::<_, ()>(final_builder.set_signature(signature.into()).generalize())
+            },
+        )
+        .expect("cannot build the block")
+    }
+}
+
+pub fn utxo_address(key: &PublicKey<Ed25519>) -> Address {
+    Address(Discrimination::Test, Kind::Single(key.clone()))
+}
+
+pub fn account_address(key: &PublicKey<Ed25519>) -> Address {
+    Address(Discrimination::Test, Kind::Account(key.clone()))
+}
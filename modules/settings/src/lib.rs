@@ -1,6 +1,7 @@
 use sled::Tree;
 use std::{
     future::Future,
+    path::Path,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -30,8 +31,14 @@ pub struct Domain {
 /// subscriber (`Future`).
 pub struct Subscriber(sled::Subscriber);
 
+/// A change of a value within the subscribed Domain.
 #[derive(Debug, Clone)]
-pub struct Event;
+pub struct Event {
+    /// the full key of the value, domain included
+    pub key: String,
+    /// the new value, `None` if the value was removed
+    pub value: Option<String>,
+}
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -57,7 +64,20 @@ impl Domain {
     where
         K: std::fmt::Display,
     {
-        format!("{}.{}", self.domain(), key)
+        if self.domain.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.domain(), key)
+        }
+    }
+
+    /// the prefix of all the keys of this domain and its subdomains
+    fn prefix(&self) -> String {
+        if self.domain.is_empty() {
+            String::new()
+        } else {
+            format!("{}.", self.domain())
+        }
     }
 
     /// Get the domain full name
@@ -108,13 +128,40 @@ impl Domain {
     {
         let key = self.key(key);
         let res = self.inner.insert(key, value.as_ref())?;
+        self.inner.flush()?;
 
-        if let Some(prev) = res {
-            let prev = String::from_utf8_lossy(prev.as_ref()).into_owned();
-            Ok(Some(prev))
-        } else {
-            Ok(None)
-        }
+        Ok(res.map(|prev| String::from_utf8_lossy(prev.as_ref()).into_owned()))
+    }
+
+    /// remove the value associated to the given key (if any) within the
+    /// current Domain.
+    ///
+    /// The removed value is returned.
+    pub fn remove<K>(&self, key: K) -> Result<Option<String>, Error>
+    where
+        K: std::fmt::Display,
+    {
+        let key = self.key(key);
+        let res = self.inner.remove(key)?;
+        self.inner.flush()?;
+
+        Ok(res.map(|prev| String::from_utf8_lossy(prev.as_ref()).into_owned()))
+    }
+
+    /// get all the key/values of this domain and of its subdomains
+    ///
+    /// The keys are relative to this domain.
+    pub fn entries(&self) -> Result<Vec<(String, String)>, Error> {
+        let prefix = self.prefix();
+        self.inner
+            .scan_prefix(&prefix)
+            .map(|entry| {
+                let (key, value) = entry?;
+                let key = String::from_utf8_lossy(&key[prefix.len()..]).into_owned();
+                let value = String::from_utf8_lossy(value.as_ref()).into_owned();
+                Ok((key, value))
+            })
+            .collect()
     }
 
     /// subscribe to changes in this domain
@@ -122,11 +169,14 @@ impl Domain {
     /// any changes in this domain or any of its subdomain will
     /// raise an Event.
     pub fn subscribe(&self) -> Subscriber {
-        Subscriber(self.inner.watch_prefix(&self.domain))
+        Subscriber(self.inner.watch_prefix(self.prefix()))
     }
 }
 
 impl Settings {
+    /// name of the `sled`'s Tree used by `open` and `temporary`
+    pub const TREE: &'static str = "settings";
+
     /// create a new settings in within the given `sled`'s Tree.
     pub fn new(inner: Tree) -> Self {
         Self {
@@ -134,6 +184,26 @@ impl Settings {
         }
     }
 
+    /// open (or create) the settings database in the given directory
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let db = sled::open(path)?;
+        Ok(Self::new(db.open_tree(Self::TREE)?))
+    }
+
+    /// create settings that are not persisted and removed once dropped
+    pub fn temporary() -> Result<Self, Error> {
+        let db = sled::Config::new().temporary(true).open()?;
+        Ok(Self::new(db.open_tree(Self::TREE)?))
+    }
+
+    /// get all the key/values of all the domains
+    pub fn entries(&self) -> Result<Vec<(String, String)>, Error> {
+        self.inner.entries()
+    }
+
     /// create a settings domain
     ///
     /// # panics
@@ -148,11 +218,24 @@ impl Settings {
     }
 }
 
+impl From<sled::Event> for Event {
+    fn from(event: sled::Event) -> Self {
+        let (key, value) = match event {
+            sled::Event::Insert { key, value } => (key, Some(value)),
+            sled::Event::Remove { key } => (key, None),
+        };
+        Self {
+            key: String::from_utf8_lossy(key.as_ref()).into_owned(),
+            value: value.map(|value| String::from_utf8_lossy(value.as_ref()).into_owned()),
+        }
+    }
+}
+
 impl Iterator for Subscriber {
     type Item = Event;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|_| Event)
+        self.0.next().map(Event::from)
     }
 }
 
@@ -165,7 +248,112 @@ impl Future for Subscriber {
         match Future::poll(pinned, cx) {
             Poll::Pending => Poll::Pending,
             Poll::Ready(None) => Poll::Ready(None),
-            Poll::Ready(Some(_)) => Poll::Ready(Some(Event)),
+            Poll::Ready(Some(event)) => Poll::Ready(Some(Event::from(event))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, value: &str) -> (String, String) {
+        (key.to_owned(), value.to_owned())
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let settings = Settings::temporary().unwrap();
+        let domain = settings.domain("network");
+
+        assert_eq!(domain.get("peers").unwrap(), None);
+        assert_eq!(domain.insert("peers", "a").unwrap(), None);
+        assert_eq!(domain.insert("peers", "b").unwrap(), Some("a".to_owned()));
+        assert_eq!(domain.get("peers").unwrap(), Some("b".to_owned()));
+        assert_eq!(domain.remove("peers").unwrap(), Some("b".to_owned()));
+        assert_eq!(domain.get("peers").unwrap(), None);
+        assert_eq!(domain.remove("peers").unwrap(), None);
+    }
+
+    #[test]
+    fn entries_of_a_domain() {
+        let settings = Settings::temporary().unwrap();
+        let network = settings.domain("network");
+        network.insert("peers", "a").unwrap();
+        network
+            .sub_domain("policy")
+            .insert("quarantine", "30s")
+            .unwrap();
+        settings.domain("networking").insert("peers", "b").unwrap();
+        settings.domain("log").insert("level", "info").unwrap();
+
+        assert_eq!(
+            network.entries().unwrap(),
+            vec![entry("peers", "a"), entry("policy.quarantine", "30s")]
+        );
+        assert_eq!(
+            settings.entries().unwrap(),
+            vec![
+                entry("log.level", "info"),
+                entry("network.peers", "a"),
+                entry("network.policy.quarantine", "30s"),
+                entry("networking.peers", "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn subscriber_follows_the_domain_and_its_subdomains() {
+        let settings = Settings::temporary().unwrap();
+        let network = settings.domain("network");
+        let mut subscriber = network.subscribe();
+
+        settings.domain("log").insert("level", "info").unwrap();
+        settings.domain("networking").insert("peers", "b").unwrap();
+        network.insert("peers", "a").unwrap();
+        network
+            .sub_domain("policy")
+            .insert("quarantine", "30s")
+            .unwrap();
+        network.remove("peers").unwrap();
+
+        let events: Vec<_> = (&mut subscriber)
+            .take(3)
+            .map(|event| (event.key, event.value))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("network.peers".to_owned(), Some("a".to_owned())),
+                (
+                    "network.policy.quarantine".to_owned(),
+                    Some("30s".to_owned())
+                ),
+                ("network.peers".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn temporary_settings_are_independent() {
+        let first = Settings::temporary().unwrap();
+        let second = Settings::temporary().unwrap();
+        first.domain("log").insert("level", "info").unwrap();
+        assert_eq!(second.domain("log").get("level").unwrap(), None);
+        assert!(second.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn opened_settings_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let settings = Settings::open(dir.path()).unwrap();
+            settings.domain("log").insert("level", "info").unwrap();
         }
+        let settings = Settings::open(dir.path()).unwrap();
+        assert_eq!(
+            settings.domain("log").get("level").unwrap(),
+            Some("info".to_owned())
+        );
     }
 }
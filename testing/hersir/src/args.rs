@@ -8,4 +8,10 @@ pub struct Args {
 
     #[structopt(long, short)]
     pub verbose: bool,
+
+    /// Run the scenario (in YAML format) against the network instead of the
+    /// session mode of the configuration, and exit with an error if one of
+    /// its assertions fails.
+    #[structopt(long, parse(from_os_str))]
+    pub scenario: Option<PathBuf>,
 }
@@ -32,6 +32,16 @@ pub enum Error {
     IO(#[from] std::io::Error),
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_yaml::Error),
+    #[error("{0}")]
+    AssertionFailed(String),
+    #[error("the tip of node '{node}' did not reach epoch {epoch} within {timeout}s")]
+    WaitEpochTimeout {
+        node: String,
+        epoch: u32,
+        timeout: u64,
+    },
+    #[error("Scenario failed, {failed} of {total} steps did not pass")]
+    ScenarioFailed { failed: usize, total: usize },
     #[error("Circular dependency in network topology")]
     CircularTrust,
     #[error("INTERNAL ERROR: {0}")]
@@ -0,0 +1,510 @@
+//! Declarative scenarios, run without user interaction.
+//!
+//! A scenario is a YAML file listing timed steps played against the network
+//! of the hersir configuration. Each step runs an action and then checks its
+//! assertions on the nodes through their REST API:
+//!
+//! ```yaml
+//! steps:
+//!   - action:
+//!       spawn:
+//!         alias: leader
+//!   - action:
+//!       spawn:
+//!         alias: passive
+//!   - action:
+//!       send_tx:
+//!         id: first
+//!         from: alice
+//!         to: bob
+//!         via: passive
+//!         ada: 100
+//!     wait: 10
+//!     expect:
+//!       - fragment:
+//!           id: first
+//!           node: leader
+//!           status: in_a_block
+//!       - balance:
+//!           wallet: bob
+//!           node: leader
+//!           value: 1000100
+//!   - at: 60
+//!     action:
+//!       partition:
+//!         groups: [[leader], [passive]]
+//!   - action:
+//!       wait_epoch:
+//!         node: leader
+//!   - action: heal
+//!     wait: 30
+//!     expect:
+//!       - same_tip:
+//!           nodes: [leader, passive]
+//! ```
+//!
+//! A partition restarts the running nodes with only the trusted peers of
+//! their own group, nodes to partition should use a persistent storage to
+//! keep their blockchain across the restart.
+//!
+//! Scenarios using `link_faults` run the network with fault injection: the
+//! links of the topology go through proxies adding latency, jitter, loss or
+//! bandwidth limits, or blocking the traffic in one direction, without
+//! restarting the nodes:
+//!
+//! ```yaml
+//!   - action:
+//!       link_faults:
+//!         from: passive
+//!         to: leader
+//!         latency: 500
+//!         loss: 0.1
+//!   - action:
+//!       link_faults:
+//!         from: leader
+//!         to: passive
+//!         blocked: true
+//!     wait: 60
+//!   - action: clear_faults
+//! ```
+
+mod runner;
+
+pub use runner::{ScenarioReport, ScenarioRunner, StepReport};
+
+use crate::error::Error;
+use jormungandr_testing_utils::testing::network::NodeAlias;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+const DEFAULT_ADA: u64 = 100;
+const DEFAULT_WAIT_EPOCH_TIMEOUT: u64 = 600;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    /// the network has to route its links through fault injection proxies
+    pub fn needs_fault_injection(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| matches!(step.action, Action::LinkFaults { .. }))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// seconds since the start of the scenario to run the step at. The step
+    /// runs right after the previous one if not set or already passed.
+    #[serde(default)]
+    pub at: Option<u64>,
+    pub action: Action,
+    /// seconds to wait after the action before checking the assertions
+    #[serde(default)]
+    pub wait: u64,
+    #[serde(default)]
+    pub expect: Vec<Assertion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Action {
+    /// start a node of the configuration, with its spawn parameters
+    Spawn {
+        alias: NodeAlias,
+        /// wait for the node to bootstrap before the next step
+        #[serde(default = "default_true")]
+        wait: bool,
+    },
+    /// stop a running node
+    Kill { alias: NodeAlias },
+    /// restart the running nodes so they only connect to the nodes of their
+    /// own group. The running nodes listed in no group are isolated.
+    Partition { groups: Vec<Vec<NodeAlias>> },
+    /// restart the running nodes with all their trusted peers
+    Heal,
+    /// send a transaction between two wallets of the configuration
+    SendTx {
+        /// name to refer to the fragment in assertions
+        #[serde(default)]
+        id: Option<String>,
+        from: String,
+        to: String,
+        via: NodeAlias,
+        #[serde(default = "default_ada")]
+        ada: u64,
+    },
+    /// cast a vote on a proposal of a vote plan of the configuration
+    SendVote {
+        #[serde(default)]
+        id: Option<String>,
+        wallet: String,
+        vote_plan: String,
+        via: NodeAlias,
+        proposal: usize,
+        choice: u8,
+    },
+    /// tally a vote plan of the configuration with a committee wallet
+    Tally {
+        #[serde(default)]
+        id: Option<String>,
+        committee: String,
+        vote_plan: String,
+        via: NodeAlias,
+    },
+    /// wait for the tip of the node to reach the given epoch, or the epoch
+    /// after the one of its tip when not set
+    WaitEpoch {
+        node: NodeAlias,
+        #[serde(default)]
+        epoch: Option<u32>,
+        /// seconds after which the step fails
+        #[serde(default = "default_wait_epoch_timeout")]
+        timeout: u64,
+    },
+    /// set the faults of the traffic sent from a node to one of its trusted
+    /// peers or the other way round, replacing the previous ones
+    LinkFaults {
+        from: NodeAlias,
+        to: NodeAlias,
+        /// apply the faults to the traffic sent from `to` to `from` as well
+        #[serde(default)]
+        both_ways: bool,
+        /// milliseconds added to every chunk of data
+        #[serde(default)]
+        latency: u64,
+        /// maximum random milliseconds added on top of the latency
+        #[serde(default)]
+        jitter: u64,
+        /// bytes per second
+        #[serde(default)]
+        bandwidth: Option<u64>,
+        /// probability for a chunk of data to be lost and retransmitted
+        #[serde(default)]
+        loss: f64,
+        /// hold the traffic, a one-way partition unless `both_ways` is set
+        #[serde(default)]
+        blocked: bool,
+    },
+    /// remove the faults of all the links
+    ClearFaults,
+}
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Spawn { .. } => "spawn",
+            Self::Kill { .. } => "kill",
+            Self::Partition { .. } => "partition",
+            Self::Heal => "heal",
+            Self::SendTx { .. } => "send_tx",
+            Self::SendVote { .. } => "send_vote",
+            Self::Tally { .. } => "tally",
+            Self::WaitEpoch { .. } => "wait_epoch",
+            Self::LinkFaults { .. } => "link_faults",
+            Self::ClearFaults => "clear_faults",
+        }
+    }
+}
+
+/// condition checked on the nodes after a step
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Assertion {
+    /// the node answers and is in the running state
+    Running { node: NodeAlias },
+    /// the tip of the node is at least at this chain length
+    MinChainLength { node: NodeAlias, length: u32 },
+    /// the nodes have the same tip
+    SameTip { nodes: Vec<NodeAlias> },
+    /// the account of the wallet holds this value on the node
+    Balance {
+        wallet: String,
+        node: NodeAlias,
+        value: u64,
+    },
+    /// the status of a fragment sent by a previous step
+    Fragment {
+        id: String,
+        node: NodeAlias,
+        status: ExpectedFragmentStatus,
+    },
+    /// the tally results of a proposal of a vote plan, one value per option
+    Tally {
+        vote_plan: String,
+        node: NodeAlias,
+        proposal: usize,
+        results: Vec<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedFragmentStatus {
+    Pending,
+    Rejected,
+    InABlock,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_ada() -> u64 {
+    DEFAULT_ADA
+}
+
+fn default_wait_epoch_timeout() -> u64 {
+    DEFAULT_WAIT_EPOCH_TIMEOUT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(yaml: &str) -> Action {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn assertion(yaml: &str) -> Assertion {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn example_scenario_is_valid() {
+        let scenario =
+            Scenario::load(concat!(env!("CARGO_MANIFEST_DIR"), "/res/scenario.yaml")).unwrap();
+
+        let actions: Vec<_> = scenario
+            .steps
+            .iter()
+            .map(|step| step.action.name())
+            .collect();
+        assert_eq!(
+            actions,
+            ["spawn", "spawn", "wait_epoch", "kill", "spawn"],
+            "unexpected steps"
+        );
+        assert_eq!(scenario.steps[4].at, Some(120));
+        assert_eq!(scenario.steps[4].wait, 20);
+        assert!(matches!(
+            &scenario.steps[4].expect[..],
+            [Assertion::SameTip { nodes }] if nodes.len() == 2
+        ));
+        assert!(!scenario.needs_fault_injection());
+    }
+
+    #[test]
+    fn step_defaults() {
+        let step: Step = serde_yaml::from_str("action: heal").unwrap();
+
+        assert_eq!(step.at, None);
+        assert_eq!(step.wait, 0);
+        assert!(step.expect.is_empty());
+    }
+
+    #[test]
+    fn spawn_and_kill_actions() {
+        assert!(matches!(
+            action("spawn:\n  alias: leader"),
+            Action::Spawn { alias, wait: true } if alias == "leader"
+        ));
+        assert!(matches!(
+            action("spawn:\n  alias: leader\n  wait: false"),
+            Action::Spawn { wait: false, .. }
+        ));
+        assert!(matches!(
+            action("kill:\n  alias: passive"),
+            Action::Kill { alias } if alias == "passive"
+        ));
+    }
+
+    #[test]
+    fn partition_and_heal_actions() {
+        match action("partition:\n  groups: [[leader], [passive, relay]]") {
+            Action::Partition { groups } => assert_eq!(
+                groups,
+                vec![
+                    vec!["leader".to_owned()],
+                    vec!["passive".to_owned(), "relay".to_owned()]
+                ]
+            ),
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(matches!(action("heal"), Action::Heal));
+    }
+
+    #[test]
+    fn send_tx_action() {
+        match action("send_tx:\n  from: alice\n  to: bob\n  via: leader") {
+            Action::SendTx {
+                id,
+                from,
+                to,
+                via,
+                ada,
+            } => {
+                assert_eq!(id, None);
+                assert_eq!((from.as_str(), to.as_str()), ("alice", "bob"));
+                assert_eq!(via, "leader");
+                assert_eq!(ada, DEFAULT_ADA);
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(matches!(
+            action("send_tx:\n  id: first\n  from: alice\n  to: bob\n  via: leader\n  ada: 7"),
+            Action::SendTx { id: Some(id), ada: 7, .. } if id == "first"
+        ));
+    }
+
+    #[test]
+    fn vote_actions() {
+        match action(
+            "send_vote:\n  wallet: alice\n  vote_plan: fund1\n  via: leader\n  proposal: 2\n  choice: 1",
+        ) {
+            Action::SendVote {
+                id,
+                wallet,
+                vote_plan,
+                via,
+                proposal,
+                choice,
+            } => {
+                assert_eq!(id, None);
+                assert_eq!(wallet, "alice");
+                assert_eq!(vote_plan, "fund1");
+                assert_eq!(via, "leader");
+                assert_eq!((proposal, choice), (2, 1));
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(matches!(
+            action("tally:\n  id: tally\n  committee: carol\n  vote_plan: fund1\n  via: leader"),
+            Action::Tally { id: Some(id), committee, .. } if id == "tally" && committee == "carol"
+        ));
+    }
+
+    #[test]
+    fn wait_epoch_action() {
+        assert!(matches!(
+            action("wait_epoch:\n  node: leader"),
+            Action::WaitEpoch {
+                epoch: None,
+                timeout: DEFAULT_WAIT_EPOCH_TIMEOUT,
+                ..
+            }
+        ));
+        assert!(matches!(
+            action("wait_epoch:\n  node: leader\n  epoch: 3\n  timeout: 30"),
+            Action::WaitEpoch {
+                epoch: Some(3),
+                timeout: 30,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn fault_actions() {
+        match action("link_faults:\n  from: passive\n  to: leader") {
+            Action::LinkFaults {
+                from,
+                to,
+                both_ways,
+                latency,
+                jitter,
+                bandwidth,
+                loss,
+                blocked,
+            } => {
+                assert_eq!((from.as_str(), to.as_str()), ("passive", "leader"));
+                assert!(!both_ways && !blocked);
+                assert_eq!((latency, jitter, bandwidth), (0, 0, None));
+                assert!(loss.abs() < f64::EPSILON);
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        match action(
+            "link_faults:\n  from: passive\n  to: leader\n  both_ways: true\n  latency: 500\n  jitter: 50\n  bandwidth: 1024\n  loss: 0.1\n  blocked: true",
+        ) {
+            Action::LinkFaults {
+                both_ways,
+                latency,
+                jitter,
+                bandwidth,
+                loss,
+                blocked,
+                ..
+            } => {
+                assert!(both_ways && blocked);
+                assert_eq!((latency, jitter, bandwidth), (500, 50, Some(1024)));
+                assert!((loss - 0.1).abs() < f64::EPSILON);
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(matches!(action("clear_faults"), Action::ClearFaults));
+
+        let scenario: Scenario = serde_yaml::from_str(
+            "steps:\n  - action: clear_faults\n  - action:\n      link_faults:\n        from: a\n        to: b",
+        )
+        .unwrap();
+        assert!(scenario.needs_fault_injection());
+    }
+
+    #[test]
+    fn assertions() {
+        assert!(matches!(
+            assertion("running:\n  node: leader"),
+            Assertion::Running { node } if node == "leader"
+        ));
+        assert!(matches!(
+            assertion("min_chain_length:\n  node: leader\n  length: 10"),
+            Assertion::MinChainLength { length: 10, .. }
+        ));
+        assert!(matches!(
+            assertion("same_tip:\n  nodes: [leader, passive]"),
+            Assertion::SameTip { nodes } if nodes == ["leader", "passive"]
+        ));
+        assert!(matches!(
+            assertion("balance:\n  wallet: bob\n  node: leader\n  value: 1000100"),
+            Assertion::Balance { wallet, value: 1000100, .. } if wallet == "bob"
+        ));
+        for (status, expected) in [
+            ("pending", ExpectedFragmentStatus::Pending),
+            ("rejected", ExpectedFragmentStatus::Rejected),
+            ("in_a_block", ExpectedFragmentStatus::InABlock),
+        ] {
+            let yaml = format!(
+                "fragment:\n  id: first\n  node: leader\n  status: {}",
+                status
+            );
+            assert!(matches!(
+                assertion(&yaml),
+                Assertion::Fragment { status, .. } if status == expected
+            ));
+        }
+        assert!(matches!(
+            assertion("tally:\n  vote_plan: fund1\n  node: leader\n  proposal: 0\n  results: [1, 0, 2]"),
+            Assertion::Tally { proposal: 0, results, .. } if results == [1, 0, 2]
+        ));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(serde_yaml::from_str::<Action>("kill:\n  alias: a\n  force: true").is_err());
+        assert!(serde_yaml::from_str::<Action>("restart:\n  alias: a").is_err());
+        assert!(serde_yaml::from_str::<Assertion>("running:\n  nodes: [a]").is_err());
+        assert!(serde_yaml::from_str::<Step>("action: heal\nafter: 10").is_err());
+    }
+}
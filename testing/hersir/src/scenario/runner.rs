@@ -0,0 +1,621 @@
+use super::{Action, Assertion, ExpectedFragmentStatus, Scenario, Step};
+use crate::{
+    config::Config,
+    controller::{Error as ControllerError, UserInteractionController},
+    error::Error,
+    style,
+};
+use chain_impl_mockchain::fragment::FragmentId;
+use jormungandr_lib::interfaces::{FragmentStatus, NodeState, Tally, TrustedPeer};
+use jormungandr_testing_utils::testing::{
+    jormungandr::{JormungandrProcess, StartupVerificationMode},
+    network::{LinkFaults, NodeAlias},
+    MemPoolCheck,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+const BOOTSTRAP_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone)]
+pub struct StepReport {
+    pub action: &'static str,
+    /// time since the start of the scenario the step started at
+    pub started_at: Duration,
+    /// the action could not be run, the scenario stops at this step
+    pub error: Option<String>,
+    /// assertions not met, empty if the step passed
+    pub failures: Vec<String>,
+}
+
+impl StepReport {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioReport {
+    pub steps: Vec<StepReport>,
+    /// number of steps of the scenario, the steps after a failed action are
+    /// not run
+    pub total_steps: usize,
+}
+
+impl ScenarioReport {
+    pub fn is_success(&self) -> bool {
+        self.steps.len() == self.total_steps && self.steps.iter().all(StepReport::is_success)
+    }
+
+    pub fn failed_steps(&self) -> usize {
+        self.steps.iter().filter(|step| !step.is_success()).count()
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            let status = if step.is_success() { "ok" } else { "failed" };
+            writeln!(
+                f,
+                "step {} ({}) at {}s: {}",
+                index + 1,
+                step.action,
+                step.started_at.as_secs(),
+                status
+            )?;
+            if let Some(error) = &step.error {
+                writeln!(f, "  - action failed: {}", error)?;
+            }
+            for failure in &step.failures {
+                writeln!(f, "  - {}", failure)?;
+            }
+        }
+        let skipped = self.total_steps - self.steps.len();
+        if skipped > 0 {
+            writeln!(f, "{} step(s) not run", skipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays a [`Scenario`] against the network of the hersir configuration.
+pub struct ScenarioRunner {
+    config: Config,
+    controller: UserInteractionController,
+    verbose: bool,
+    /// fragments sent by the steps, by the name given in the scenario
+    fragments: HashMap<String, FragmentId>,
+    /// the groups of the current partition, if any
+    partition: Option<Vec<Vec<NodeAlias>>>,
+}
+
+impl ScenarioRunner {
+    pub fn new(config: Config, controller: UserInteractionController, verbose: bool) -> Self {
+        Self {
+            config,
+            controller,
+            verbose,
+            fragments: HashMap::new(),
+            partition: None,
+        }
+    }
+
+    pub fn run(&mut self, scenario: &Scenario) -> ScenarioReport {
+        let start = Instant::now();
+        let mut report = ScenarioReport {
+            steps: Vec::new(),
+            total_steps: scenario.steps.len(),
+        };
+
+        for (index, step) in scenario.steps.iter().enumerate() {
+            if let Some(at) = step.at {
+                let at = Duration::from_secs(at);
+                if let Some(wait) = at.checked_sub(start.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+
+            println!(
+                "{}",
+                style::info.apply_to(format!("step {}: {}", index + 1, step.action.name()))
+            );
+            let step_report = self.run_step(step, start.elapsed());
+            let stop = step_report.error.is_some();
+            report.steps.push(step_report);
+            if stop {
+                break;
+            }
+        }
+
+        report
+    }
+
+    fn run_step(&mut self, step: &Step, started_at: Duration) -> StepReport {
+        let mut report = StepReport {
+            action: step.action.name(),
+            started_at,
+            error: None,
+            failures: Vec::new(),
+        };
+
+        if let Err(error) = self.run_action(&step.action) {
+            report.error = Some(error.to_string());
+            return report;
+        }
+
+        std::thread::sleep(Duration::from_secs(step.wait));
+
+        for assertion in &step.expect {
+            if let Err(failure) = self.check(assertion) {
+                report.failures.push(failure.to_string());
+            }
+        }
+        report
+    }
+
+    fn run_action(&mut self, action: &Action) -> Result<(), Error> {
+        match action {
+            Action::Spawn { alias, wait } => self.spawn(alias, *wait),
+            Action::Kill { alias } => {
+                self.take_node(alias)
+                    .ok_or_else(|| ControllerError::NodeNotFound(alias.clone()))?;
+                Ok(())
+            }
+            Action::Partition { groups } => {
+                self.partition = Some(groups.clone());
+                self.restart_all()
+            }
+            Action::Heal => {
+                self.partition = None;
+                self.restart_all()
+            }
+            Action::SendTx {
+                id,
+                from,
+                to,
+                via,
+                ada,
+            } => {
+                let check = self
+                    .controller
+                    .send_transaction(from, to, via, (*ada).into())?;
+                self.record_fragment(id, check);
+                Ok(())
+            }
+            Action::SendVote {
+                id,
+                wallet,
+                vote_plan,
+                via,
+                proposal,
+                choice,
+            } => {
+                let check = self
+                    .controller
+                    .cast_vote(wallet, vote_plan, via, *proposal, *choice)?;
+                self.record_fragment(id, check);
+                Ok(())
+            }
+            Action::Tally {
+                id,
+                committee,
+                vote_plan,
+                via,
+            } => {
+                let check = self.controller.tally_vote(committee, vote_plan, via)?;
+                self.record_fragment(id, check);
+                Ok(())
+            }
+            Action::WaitEpoch {
+                node,
+                epoch,
+                timeout,
+            } => self.wait_epoch(node, *epoch, Duration::from_secs(*timeout)),
+            Action::LinkFaults {
+                from,
+                to,
+                both_ways,
+                latency,
+                jitter,
+                bandwidth,
+                loss,
+                blocked,
+            } => {
+                let mut faults = LinkFaults::none()
+                    .with_latency(Duration::from_millis(*latency))
+                    .with_jitter(Duration::from_millis(*jitter))
+                    .with_loss(*loss);
+                faults.bandwidth = *bandwidth;
+                faults.blocked = *blocked;
+
+                let controller = self.controller.controller();
+                controller.set_link_faults(from, to, faults.clone())?;
+                if *both_ways {
+                    controller.set_link_faults(to, from, faults)?;
+                }
+                Ok(())
+            }
+            Action::ClearFaults => {
+                self.controller.controller().fault_injector()?.clear();
+                Ok(())
+            }
+        }
+    }
+
+    fn record_fragment(&mut self, id: &Option<String>, check: MemPoolCheck) {
+        println!(
+            "{}",
+            style::info.apply_to(format!("fragment '{}' sent", check.fragment_id()))
+        );
+        if let Some(id) = id {
+            self.fragments.insert(id.clone(), *check.fragment_id());
+        }
+    }
+
+    fn spawn(&mut self, alias: &str, wait: bool) -> Result<(), Error> {
+        let spawn_params = self.config.node_spawn_params(alias)?.verbose(self.verbose);
+        let trusted_peers = self.trusted_peers(alias)?;
+        let spawn_params = spawn_params
+            .skip_bootstrap(trusted_peers.is_empty())
+            .trusted_peers(trusted_peers);
+
+        let legacy = spawn_params.get_version().clone();
+        let node = match &legacy {
+            Some(version) => self.controller.spawn_legacy_node(spawn_params, version)?,
+            None => self.controller.spawn_node(spawn_params)?,
+        };
+        if wait {
+            node.wait_for_bootstrap(&StartupVerificationMode::Rest, BOOTSTRAP_TIMEOUT)
+                .map_err(ControllerError::from)?;
+        }
+        println!(
+            "{}",
+            style::info.apply_to(format!("node '{}' spawned", alias))
+        );
+
+        if legacy.is_some() {
+            self.controller.legacy_nodes_mut().push(node);
+        } else {
+            self.controller.nodes_mut().push(node);
+        }
+        Ok(())
+    }
+
+    /// the trusted peers of the node in the configuration, restricted to the
+    /// nodes of its group when the network is partitioned
+    fn trusted_peers(&self, alias: &str) -> Result<Vec<TrustedPeer>, Error> {
+        let controller = self.controller.controller();
+        let trusted_peers = controller.node_config(alias)?.p2p.trusted_peers;
+        let partition = match &self.partition {
+            Some(partition) => partition,
+            None => return Ok(trusted_peers),
+        };
+
+        let group = partition
+            .iter()
+            .find(|group| group.iter().any(|node| node == alias))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let mut addresses = Vec::new();
+        for node in group.iter().filter(|node| *node != alias) {
+            addresses.push(controller.peer_address(alias, node)?);
+        }
+        Ok(trusted_peers
+            .into_iter()
+            .filter(|peer| addresses.contains(&peer.address))
+            .collect())
+    }
+
+    /// stop the running nodes, then start them again with the trusted peers
+    /// of the current partition
+    fn restart_all(&mut self) -> Result<(), Error> {
+        let mut aliases: Vec<NodeAlias> = self
+            .controller
+            .nodes()
+            .iter()
+            .chain(self.controller.legacy_nodes().iter())
+            .map(JormungandrProcess::alias)
+            .collect();
+        for alias in aliases.iter() {
+            self.take_node(alias);
+        }
+
+        // nodes without trusted peers first, the others bootstrap from them
+        aliases.sort_by_key(|alias| !self.trusted_peers(alias).map_or(false, |p| p.is_empty()));
+        for alias in aliases.iter() {
+            self.spawn(alias, true)?;
+        }
+        Ok(())
+    }
+
+    /// remove the node from the running nodes, dropping the process kills it
+    fn take_node(&mut self, alias: &str) -> Option<JormungandrProcess> {
+        if let Some(index) = self
+            .controller
+            .nodes()
+            .iter()
+            .position(|n| n.alias() == alias)
+        {
+            return Some(self.controller.nodes_mut().remove(index));
+        }
+        let index = self
+            .controller
+            .legacy_nodes()
+            .iter()
+            .position(|n| n.alias() == alias)?;
+        Some(self.controller.legacy_nodes_mut().remove(index))
+    }
+
+    fn node(&self, alias: &str) -> Result<&JormungandrProcess, Error> {
+        self.controller
+            .nodes()
+            .iter()
+            .chain(self.controller.legacy_nodes().iter())
+            .find(|node| node.alias() == alias)
+            .ok_or_else(|| ControllerError::NodeNotFound(alias.to_owned()).into())
+    }
+
+    /// the epoch and slot of the tip of the node
+    fn tip_date(&self, alias: &str) -> Result<Option<(u32, u32)>, Error> {
+        let stats = self.node(alias)?.rest().stats()?;
+        Ok(stats
+            .stats
+            .and_then(|stats| stats.last_block_date)
+            .and_then(|date| {
+                let (epoch, slot) = date.split_once('.')?;
+                Some((epoch.parse().ok()?, slot.parse().ok()?))
+            }))
+    }
+
+    fn wait_epoch(&self, alias: &str, epoch: Option<u32>, timeout: Duration) -> Result<(), Error> {
+        let start = Instant::now();
+        let target = match epoch {
+            Some(epoch) => epoch,
+            None => self.tip_date(alias)?.map_or(1, |(epoch, _)| epoch + 1),
+        };
+        loop {
+            if let Some((epoch, _)) = self.tip_date(alias)? {
+                if epoch >= target {
+                    return Ok(());
+                }
+            }
+            if start.elapsed() > timeout {
+                return Err(Error::WaitEpochTimeout {
+                    node: alias.to_owned(),
+                    epoch: target,
+                    timeout: timeout.as_secs(),
+                });
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn check(&self, assertion: &Assertion) -> Result<(), Error> {
+        match assertion {
+            Assertion::Running { node } => {
+                let stats = self.node(node)?.rest().stats()?;
+                if stats.state != NodeState::Running {
+                    return Err(Error::AssertionFailed(format!(
+                        "node '{}' is in state {:?}",
+                        node, stats.state
+                    )));
+                }
+                Ok(())
+            }
+            Assertion::MinChainLength { node, length } => {
+                let stats = self.node(node)?.rest().stats()?;
+                let chain_length: u32 = stats
+                    .stats
+                    .and_then(|stats| stats.last_block_height)
+                    .and_then(|height| height.parse().ok())
+                    .unwrap_or(0);
+                if chain_length < *length {
+                    return Err(Error::AssertionFailed(format!(
+                        "the tip of node '{}' is at chain length {}, expected at least {}",
+                        node, chain_length, length
+                    )));
+                }
+                Ok(())
+            }
+            Assertion::SameTip { nodes } => {
+                let mut tips = Vec::new();
+                for node in nodes {
+                    tips.push((node, self.node(node)?.rest().tip()?));
+                }
+                if tips.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+                    let tips: Vec<String> = tips
+                        .iter()
+                        .map(|(node, tip)| format!("{}: {}", node, tip))
+                        .collect();
+                    return Err(Error::AssertionFailed(format!(
+                        "the nodes have different tips ({})",
+                        tips.join(", ")
+                    )));
+                }
+                Ok(())
+            }
+            Assertion::Balance {
+                wallet,
+                node,
+                value,
+            } => {
+                let state = self
+                    .node(node)?
+                    .rest()
+                    .account_state(&self.controller.wallet(wallet)?)?;
+                let actual: u64 = (*state.value()).into();
+                if actual != *value {
+                    return Err(Error::AssertionFailed(format!(
+                        "the balance of wallet '{}' on node '{}' is {}, expected {}",
+                        wallet, node, actual, value
+                    )));
+                }
+                Ok(())
+            }
+            Assertion::Fragment { id, node, status } => {
+                let fragment_id = self.fragments.get(id).ok_or_else(|| {
+                    Error::AssertionFailed(format!("no fragment was sent with id '{}'", id))
+                })?;
+                let logs = self.node(node)?.rest().fragment_logs()?;
+                let actual = logs.get(fragment_id).map(|log| log.status().clone());
+                let matches = matches!(
+                    (status, &actual),
+                    (
+                        ExpectedFragmentStatus::Pending,
+                        Some(FragmentStatus::Pending)
+                    ) | (
+                        ExpectedFragmentStatus::Rejected,
+                        Some(FragmentStatus::Rejected { .. })
+                    ) | (
+                        ExpectedFragmentStatus::InABlock,
+                        Some(FragmentStatus::InABlock { .. })
+                    )
+                );
+                if !matches {
+                    return Err(Error::AssertionFailed(format!(
+                        "fragment '{}' on node '{}' is {:?}, expected {:?}",
+                        id, node, actual, status
+                    )));
+                }
+                Ok(())
+            }
+            Assertion::Tally {
+                vote_plan,
+                node,
+                proposal,
+                results,
+            } => {
+                let vote_plan_id = self
+                    .controller
+                    .controller()
+                    .defined_vote_plan(vote_plan)?
+                    .id()
+                    .to_string();
+                let status = self
+                    .node(node)?
+                    .rest()
+                    .vote_plan_statuses()?
+                    .into_iter()
+                    .find(|status| status.id.to_string() == vote_plan_id)
+                    .ok_or_else(|| {
+                        Error::AssertionFailed(format!(
+                            "node '{}' does not know vote plan '{}'",
+                            node, vote_plan
+                        ))
+                    })?;
+                let actual = status
+                    .proposals
+                    .get(*proposal)
+                    .ok_or_else(|| {
+                        Error::AssertionFailed(format!(
+                            "vote plan '{}' has no proposal {}",
+                            vote_plan, proposal
+                        ))
+                    })?
+                    .tally
+                    .as_ref()
+                    .and_then(|tally| match tally {
+                        Tally::Public { result } => Some(result.results()),
+                        Tally::Private { .. } => None,
+                    });
+                if actual.as_ref() != Some(results) {
+                    return Err(Error::AssertionFailed(format!(
+                        "the tally of proposal {} of vote plan '{}' is {:?}, expected {:?}",
+                        proposal, vote_plan, actual, results
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(action: &'static str, error: Option<&str>, failures: &[&str]) -> StepReport {
+        StepReport {
+            action,
+            started_at: Duration::from_secs(5),
+            error: error.map(ToOwned::to_owned),
+            failures: failures.iter().map(|failure| failure.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn successful_report() {
+        let report = ScenarioReport {
+            steps: vec![step("spawn", None, &[]), step("heal", None, &[])],
+            total_steps: 2,
+        };
+
+        assert!(report.is_success());
+        assert_eq!(report.failed_steps(), 0);
+        assert_eq!(
+            report.to_string(),
+            "step 1 (spawn) at 5s: ok\nstep 2 (heal) at 5s: ok\n"
+        );
+    }
+
+    #[test]
+    fn failed_assertions() {
+        let report = ScenarioReport {
+            steps: vec![
+                step("spawn", None, &["leader is not running"]),
+                step("send_tx", None, &[]),
+            ],
+            total_steps: 2,
+        };
+
+        assert!(!report.is_success());
+        assert_eq!(report.failed_steps(), 1);
+        assert_eq!(
+            report.to_string(),
+            "step 1 (spawn) at 5s: failed\n  - leader is not running\nstep 2 (send_tx) at 5s: ok\n"
+        );
+    }
+
+    #[test]
+    fn failed_action_stops_the_scenario() {
+        let report = ScenarioReport {
+            steps: vec![
+                step("spawn", None, &[]),
+                step("kill", Some("unknown node passive"), &[]),
+            ],
+            total_steps: 5,
+        };
+
+        assert!(!report.is_success());
+        assert_eq!(report.failed_steps(), 1);
+        assert_eq!(
+            report.to_string(),
+            "step 1 (spawn) at 5s: ok\n\
+             step 2 (kill) at 5s: failed\n  - action failed: unknown node passive\n\
+             3 step(s) not run\n"
+        );
+    }
+
+    #[test]
+    fn steps_not_run_fail_the_scenario() {
+        let report = ScenarioReport {
+            steps: vec![step("spawn", None, &[])],
+            total_steps: 2,
+        };
+
+        assert!(!report.is_success());
+        assert_eq!(report.failed_steps(), 0);
+        assert!(report.to_string().ends_with("1 step(s) not run\n"));
+    }
+
+    #[test]
+    fn empty_scenario_succeeds() {
+        let report = ScenarioReport::default();
+
+        assert!(report.is_success());
+        assert_eq!(report.to_string(), "");
+    }
+}
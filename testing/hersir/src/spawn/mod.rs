@@ -1,5 +1,6 @@
 mod interactive;
 mod monitor;
+mod scenario;
 mod standard;
 
 use crate::config::{Config, SessionMode};
@@ -10,6 +11,10 @@ pub fn spawn_network(args: Args) -> Result<(), Error> {
     let config: Config = serde_yaml::from_reader(File::open(&args.config)?)?;
     let topology = config.build_topology();
 
+    if let Some(scenario) = args.scenario.clone() {
+        return scenario::spawn_network(config, topology, &args, &scenario);
+    }
+
     match &config.session.mode {
         SessionMode::Standard => standard::spawn_network(config, topology, args),
         SessionMode::Monitor => monitor::spawn_network(config, topology, args),
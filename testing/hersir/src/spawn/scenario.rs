@@ -0,0 +1,41 @@
+use crate::controller::UserInteractionController;
+use crate::scenario::{Scenario, ScenarioRunner};
+use crate::{args::Args, config::Config, error::Error};
+use jormungandr_testing_utils::testing::network::{builder::NetworkBuilder, Topology};
+use std::path::Path;
+
+pub fn spawn_network(
+    config: Config,
+    topology: Topology,
+    args: &Args,
+    scenario: &Path,
+) -> Result<(), Error> {
+    let scenario = Scenario::load(scenario)?;
+
+    println!("Building network...");
+    let mut builder = NetworkBuilder::default()
+        .topology(topology)
+        .testing_directory(config.testing_directory())
+        .blockchain_config(config.blockchain.clone());
+    if scenario.needs_fault_injection() {
+        builder = builder.fault_injection();
+    }
+    let controller = builder.build()?;
+
+    let mut runner = ScenarioRunner::new(
+        config,
+        UserInteractionController::new(controller),
+        args.verbose,
+    );
+    let report = runner.run(&scenario);
+    print!("{}", report);
+
+    if report.is_success() {
+        Ok(())
+    } else {
+        Err(Error::ScenarioFailed {
+            failed: report.failed_steps(),
+            total: report.total_steps,
+        })
+    }
+}
@@ -0,0 +1,38 @@
+use jormungandr_testing_utils::testing::jcli::JCli;
+
+#[test]
+pub fn test_key_encrypt_decrypt() {
+    let jcli: JCli = Default::default();
+    let private_key = jcli.key().generate("Ed25519Extended");
+    let encrypted_key = jcli.key().encrypt(&private_key, "passphrase");
+    assert!(
+        !encrypted_key.contains(&private_key),
+        "encrypted key file holds the key in clear"
+    );
+    let decrypted_key = jcli.key().decrypt(&encrypted_key, "passphrase");
+    assert_eq!(private_key, decrypted_key);
+}
+
+#[test]
+pub fn test_key_decrypt_wrong_passphrase() {
+    let jcli: JCli = Default::default();
+    let private_key = jcli.key().generate("Ed25519Extended");
+    let encrypted_key = jcli.key().encrypt(&private_key, "passphrase");
+    jcli.key().decrypt_expect_fail(
+        &encrypted_key,
+        "wrong passphrase",
+        "could not encrypt or decrypt the secret key",
+    );
+}
+
+#[test]
+pub fn test_encrypted_key_to_public() {
+    let jcli: JCli = Default::default();
+    let private_key = jcli.key().generate("Ed25519Extended");
+    let public_key = jcli.key().convert_to_public_string(&private_key);
+    let encrypted_key = jcli.key().encrypt(&private_key, "passphrase");
+    let public_key_from_encrypted = jcli
+        .key()
+        .convert_to_public_string_with_passphrase(&encrypted_key, "passphrase");
+    assert_eq!(public_key, public_key_from_encrypted);
+}
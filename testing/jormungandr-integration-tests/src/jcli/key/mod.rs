@@ -1,3 +1,4 @@
+pub mod encrypt;
 pub mod from_bytes;
 pub mod generate;
 pub mod to_bytes;
@@ -2,3 +2,4 @@ pub mod e2e;
 pub mod finalize;
 pub mod input;
 pub mod simplified;
+pub mod simulate;
@@ -0,0 +1,108 @@
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{BlockDate, FragmentExpiry, InitialUTxO},
+};
+use jormungandr_testing_utils::testing::{
+    jcli::JCli,
+    jormungandr::{ConfigurationBuilder, Starter},
+    startup,
+};
+
+use assert_fs::TempDir;
+
+#[test]
+pub fn test_simulated_transaction_is_not_sent_to_node() {
+    let temp_dir = TempDir::new().unwrap();
+    let jcli: JCli = Default::default();
+
+    let sender = startup::create_new_utxo_address();
+    let receiver = startup::create_new_utxo_address();
+
+    let config = ConfigurationBuilder::new()
+        .with_funds(vec![InitialUTxO {
+            address: sender.address(),
+            value: 100.into(),
+        }])
+        .build(&temp_dir);
+
+    let jormungandr = Starter::new()
+        .temp_dir(temp_dir)
+        .config(config.clone())
+        .start()
+        .unwrap();
+
+    let utxo = config.block0_utxo_for_address(&sender);
+    let block0_hash = Hash::from_hex(config.genesis_block_hash()).unwrap();
+    let mut transaction_builder = jcli.transaction_builder(block0_hash);
+    transaction_builder
+        .new_transaction()
+        .add_input_from_utxo(&utxo)
+        .add_output(&receiver.address().to_string(), *utxo.associated_fund())
+        .set_expiry_date(BlockDate::new(1, 0))
+        .finalize()
+        .seal_with_witness_for_address(&sender);
+
+    let simulation = jcli.transaction().simulate(
+        jormungandr.rest_uri(),
+        transaction_builder.staging_file_path(),
+    );
+
+    assert!(simulation.is_accepted());
+    let fragment = &simulation.fragments[0];
+    assert_eq!(
+        Hash::from(fragment.fragment_id),
+        transaction_builder.fragment_id()
+    );
+    assert_eq!(fragment.expiry, FragmentExpiry::Valid);
+    assert_eq!(fragment.fee, Some(0.into()));
+    let spent = fragment
+        .balance_changes
+        .iter()
+        .find(|change| change.address.1 == sender.address().1)
+        .expect("the sender balance change is missing");
+    assert_eq!(spent.spent, *utxo.associated_fund());
+
+    assert!(jormungandr.rest().fragment_logs().unwrap().is_empty());
+}
+
+#[test]
+pub fn test_simulated_transaction_spending_unknown_utxo_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let jcli: JCli = Default::default();
+
+    let sender = startup::create_new_utxo_address();
+    let receiver = startup::create_new_utxo_address();
+
+    let config = ConfigurationBuilder::new()
+        .with_funds(vec![InitialUTxO {
+            address: receiver.address(),
+            value: 100.into(),
+        }])
+        .build(&temp_dir);
+
+    let jormungandr = Starter::new()
+        .temp_dir(temp_dir)
+        .config(config.clone())
+        .start()
+        .unwrap();
+
+    let block0_hash = Hash::from_hex(config.genesis_block_hash()).unwrap();
+    let mut transaction_builder = jcli.transaction_builder(block0_hash);
+    transaction_builder
+        .new_transaction()
+        .add_input(&block0_hash, 0, "100")
+        .add_output(&receiver.address().to_string(), 100.into())
+        .set_expiry_date(BlockDate::new(1, 0))
+        .finalize()
+        .seal_with_witness_for_address(&sender);
+
+    let simulation = jcli.transaction().simulate(
+        jormungandr.rest_uri(),
+        transaction_builder.staging_file_path(),
+    );
+
+    assert!(!simulation.is_accepted());
+    assert!(simulation.fragments[0].error.is_some());
+    assert!(simulation.fragments[0].balance_changes.is_empty());
+    assert!(jormungandr.rest().fragment_logs().unwrap().is_empty());
+}
@@ -6,7 +6,11 @@ use chain_impl_mockchain::{
 };
 use jormungandr_lib::interfaces::SlotDuration;
 use jormungandr_testing_utils::testing::{
-    adversary::{block::BlockBuilder, process::AdversaryNodeBuilder},
+    adversary::{
+        block::BlockBuilder,
+        process::AdversaryNodeBuilder,
+        scenario::{Scenario, ScenarioRunner},
+    },
     jormungandr::{ConfigurationBuilder, Starter},
     network::{builder::NetworkBuilder, Blockchain, Node, SpawnParams, Topology},
     startup, FragmentBuilder,
@@ -202,3 +206,110 @@ fn block_with_invalid_fragment() {
         .send_block_to_peer(jormungandr.address(), block)
         .is_err());
 }
+
+#[test]
+/// Ensures that a BFT leader node keeps running on its own chain when served
+/// mismatching or withheld block bodies
+fn scripted_body_attacks_are_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let keys = startup::create_new_key_pair();
+
+    let node_params = ConfigurationBuilder::default()
+        .with_block0_consensus(ConsensusType::Bft)
+        .with_slot_duration(10)
+        .with_leader_key_pair(keys.clone())
+        .build(&temp_dir);
+
+    let block0 = node_params.block0_configuration().to_block();
+
+    let jormungandr = Starter::default().config(node_params).start().unwrap();
+
+    let scenario: Scenario = serde_yaml::from_str(&format!(
+        "
+victim:
+  p2p: {}
+  rest: {}
+steps:
+  - action:
+      header_body_mismatch: {{}}
+    wait: 2
+    expect:
+      - node_running
+      - tip_not_adversarial
+  - action:
+      withheld_body:
+        parent: block0
+    wait: 2
+    expect:
+      - node_running
+      - tip_not_adversarial
+",
+        jormungandr.address(),
+        jormungandr.rest_address()
+    ))
+    .unwrap();
+
+    let report = ScenarioRunner::new(AdversaryNodeBuilder::new(block0).build())
+        .signing_key(keys.signing_key())
+        .run(&scenario)
+        .unwrap();
+
+    assert!(report.is_success(), "{}", report);
+}
+
+#[test]
+/// Ensures that a BFT leader node keeps running when flooded with gossip and
+/// served equivocating blocks, and that it switches to a longer fork pulled
+/// from a peer holding a leader key
+fn scripted_fork_and_flood_attacks() {
+    let temp_dir = TempDir::new().unwrap();
+    let keys = startup::create_new_key_pair();
+
+    let node_params = ConfigurationBuilder::default()
+        .with_block0_consensus(ConsensusType::Bft)
+        .with_slot_duration(10)
+        .with_leader_key_pair(keys.clone())
+        .build(&temp_dir);
+
+    let block0 = node_params.block0_configuration().to_block();
+
+    let jormungandr = Starter::default().config(node_params).start().unwrap();
+
+    let scenario: Scenario = serde_yaml::from_str(&format!(
+        "
+victim:
+  p2p: {}
+  rest: {}
+steps:
+  - action:
+      gossip_flood:
+        count: 200
+    wait: 2
+    expect:
+      - node_running
+  - action:
+      equivocating_blocks:
+        count: 3
+    wait: 2
+    expect:
+      - node_running
+  - action:
+      private_fork:
+        length: 10
+    wait: 5
+    expect:
+      - node_running
+      - tip_adversarial
+",
+        jormungandr.address(),
+        jormungandr.rest_address()
+    ))
+    .unwrap();
+
+    let report = ScenarioRunner::new(AdversaryNodeBuilder::new(block0).build())
+        .signing_key(keys.signing_key())
+        .run(&scenario)
+        .unwrap();
+
+    assert!(report.is_success(), "{}", report);
+}
@@ -1,13 +1,32 @@
+use assert_fs::{
+    fixture::{FileWriteStr, PathChild},
+    TempDir,
+};
+use chain_addr::Discrimination;
+use chain_crypto::bech32::Bech32;
 use chain_impl_mockchain::block::BlockDate;
 use chain_impl_mockchain::fragment::FragmentId;
 use chain_impl_mockchain::key::Hash;
-use jormungandr_lib::interfaces::ActiveSlotCoefficient;
+use chain_impl_mockchain::value::Value;
+use jormungandr_lib::interfaces::{
+    ActiveSlotCoefficient, BlockContentMaxSize, ConfigParam, ConfigParams, ConsensusLeaderId,
+};
 use jormungandr_testing_utils::stake_pool::StakePool;
-use jormungandr_testing_utils::testing::node::Explorer;
+use jormungandr_testing_utils::testing::node::explorer::{config_updates, update_proposal};
+use jormungandr_testing_utils::testing::node::{
+    time::{get_current_date, wait_for_epoch},
+    Explorer,
+};
 use jormungandr_testing_utils::testing::{
-    jcli::JCli, jormungandr::ConfigurationBuilder, startup, transaction_utils::TransactionHash,
+    jcli::JCli,
+    jormungandr::{ConfigurationBuilder, JormungandrProcess, Starter},
+    startup,
+    transaction_utils::TransactionHash,
 };
+use jormungandr_testing_utils::wallet::Wallet;
 use jortestkit::process::Wait;
+use rand_core::OsRng;
+use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -181,3 +200,312 @@ fn epoch(explorer: &Explorer) {
 
     assert_eq!(epoch.data.unwrap().epoch.id, "1", "can't find epoch");
 }
+
+#[test]
+pub fn explorer_balances_test() {
+    let jcli: JCli = Default::default();
+    let mut faucet = startup::create_new_account_address();
+    let receiver = startup::create_new_utxo_address();
+    let initial_funds = 1_000_000_000;
+    let value = 1_000;
+
+    let jormungandr = startup::start_bft(vec![&faucet], &mut ConfigurationBuilder::new()).unwrap();
+
+    let transaction = faucet
+        .transaction_to(
+            &jormungandr.genesis_block_hash(),
+            &jormungandr.fees(),
+            BlockDate::first().next_epoch(),
+            receiver.address(),
+            value.into(),
+        )
+        .unwrap()
+        .encode();
+
+    let wait = Wait::new(Duration::from_secs(3), 20);
+    let fragment_id = jcli
+        .fragment_sender(&jormungandr)
+        .send(&transaction)
+        .assert_in_block_with_wait(&wait);
+
+    let explorer = jormungandr.explorer();
+
+    let receiver_address = explorer
+        .address_balance(receiver.address().to_string(), None)
+        .unwrap()
+        .data
+        .unwrap()
+        .address;
+    assert_eq!(receiver_address.balance, value.to_string());
+    assert_eq!(receiver_address.balance_history.len(), 1);
+    let change = &receiver_address.balance_history[0];
+    assert_eq!(change.balance, value.to_string());
+    assert_eq!(receiver_address.unspent_outputs.len(), 1);
+    let utxo = &receiver_address.unspent_outputs[0];
+    assert_eq!(utxo.transaction.id, fragment_id.to_string());
+    assert_eq!(utxo.index, 0);
+    assert_eq!(utxo.amount, value.to_string());
+
+    let block = Hash::from_str(&change.block.id).unwrap();
+    let balance_at_block = explorer
+        .address_balance(receiver.address().to_string(), Some(block.into()))
+        .unwrap()
+        .data
+        .unwrap()
+        .address
+        .balance;
+    assert_eq!(balance_at_block, value.to_string());
+    let balance_at_block0 = explorer
+        .address_balance(
+            receiver.address().to_string(),
+            Some(jormungandr.genesis_block_hash().into()),
+        )
+        .unwrap()
+        .data
+        .unwrap()
+        .address
+        .balance;
+    assert_eq!(balance_at_block0, "0");
+
+    let faucet_address = explorer
+        .address_balance(faucet.address().to_string(), None)
+        .unwrap()
+        .data
+        .unwrap()
+        .address;
+    assert!(faucet_address.unspent_outputs.is_empty());
+    let history: Vec<u64> = faucet_address
+        .balance_history
+        .iter()
+        .map(|change| change.balance.parse().unwrap())
+        .collect();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0], initial_funds);
+    assert!(history[1] <= initial_funds - value);
+    assert_eq!(faucet_address.balance.parse::<u64>().unwrap(), history[1]);
+    assert_eq!(
+        faucet_address.balance_history[1].block.id, change.block.id,
+        "the faucet and the receiver balances changed in different blocks"
+    );
+}
+
+#[test]
+pub fn explorer_subscriptions_test() {
+    let jcli: JCli = Default::default();
+    let mut faucet = startup::create_new_account_address();
+    let receiver = startup::create_new_account_address();
+
+    let jormungandr = startup::start_bft(vec![&faucet], &mut ConfigurationBuilder::new()).unwrap();
+
+    let explorer = jormungandr.explorer();
+    let timeout = Duration::from_secs(60);
+    let mut blocks = explorer.subscribe_blocks(timeout).unwrap();
+    let mut transactions = explorer
+        .subscribe_transactions_by_address(receiver.address().to_string(), timeout)
+        .unwrap();
+
+    let transaction = faucet
+        .transaction_to(
+            &jormungandr.genesis_block_hash(),
+            &jormungandr.fees(),
+            BlockDate::first().next_epoch(),
+            receiver.address(),
+            1_000.into(),
+        )
+        .unwrap()
+        .encode();
+
+    let wait = Wait::new(Duration::from_secs(3), 20);
+    let fragment_id = jcli
+        .fragment_sender(&jormungandr)
+        .send(&transaction)
+        .assert_in_block_with_wait(&wait);
+
+    let event = transactions.next_event().unwrap();
+    assert_eq!(
+        event["transactionsByAddress"]["id"],
+        fragment_id.to_string(),
+        "unexpected transaction event"
+    );
+
+    let chain_lengths: Vec<u32> = (0..2)
+        .map(|_| {
+            let event = blocks.next_event().unwrap();
+            assert!(event["blocks"]["id"].is_string(), "block event without id");
+            event["blocks"]["chainLength"]
+                .as_str()
+                .unwrap()
+                .parse()
+                .unwrap()
+        })
+        .collect();
+    assert!(
+        chain_lengths[0] < chain_lengths[1],
+        "blocks are not notified in order: {:?}",
+        chain_lengths
+    );
+}
+
+#[test]
+pub fn explorer_update_proposals_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let jcli: JCli = Default::default();
+    let mut rng = OsRng;
+
+    let mut alice = Wallet::new_account_with_discrimination(&mut rng, Discrimination::Test);
+    let alice_pk = alice.address().1.public_key().unwrap().to_bech32_str();
+    let alice_sk = temp_dir.child("alice_sk");
+    alice.save_to_path(alice_sk.path()).unwrap();
+
+    let mut bob = Wallet::new_account_with_discrimination(&mut rng, Discrimination::Test);
+    let bob_pk = bob.address().1.public_key().unwrap().to_bech32_str();
+    let bob_sk = temp_dir.child("bob_sk");
+    bob.save_to_path(bob_sk.path()).unwrap();
+
+    let config = ConfigurationBuilder::new()
+        .with_funds(vec![
+            alice.to_initial_fund(1_000_000),
+            bob.to_initial_fund(1_000_000),
+        ])
+        .with_consensus_leaders_ids(vec![
+            ConsensusLeaderId::from(alice.address().1.public_key().unwrap().clone()),
+            ConsensusLeaderId::from(bob.address().1.public_key().unwrap().clone()),
+        ])
+        .with_explorer()
+        .build(&temp_dir);
+
+    let new_block_content_max_size = 1000;
+    let change_params = ConfigParams::new(vec![ConfigParam::BlockContentMaxSize(
+        BlockContentMaxSize::from(new_block_content_max_size),
+    )]);
+    let change_params_path = temp_dir.child("change_params.yaml");
+    change_params_path
+        .write_str(&serde_yaml::to_string(&change_params).unwrap())
+        .unwrap();
+
+    let jormungandr = Starter::new()
+        .temp_dir(temp_dir)
+        .config(config)
+        .start()
+        .unwrap();
+    let explorer = jormungandr.explorer();
+
+    let update_proposal_cert = jcli
+        .certificate()
+        .new_update_proposal(&alice_pk, change_params_path.path());
+    let proposal_id = send_certificate(
+        &jcli,
+        &jormungandr,
+        &mut alice,
+        alice_sk.path(),
+        &update_proposal_cert,
+    );
+
+    let proposal = explorer
+        .update_proposal(proposal_id.into())
+        .unwrap()
+        .data
+        .unwrap()
+        .update_proposal;
+    assert_eq!(proposal.id, proposal_id.to_string());
+    assert_eq!(proposal.proposer.id, alice_pk);
+    assert_eq!(
+        proposal.state,
+        update_proposal::UpdateProposalState::PENDING
+    );
+    assert!(proposal.votes.is_empty());
+    assert!(proposal.settled_in.is_none());
+
+    for (wallet, sk, pk) in [
+        (&mut alice, &alice_sk, &alice_pk),
+        (&mut bob, &bob_sk, &bob_pk),
+    ] {
+        let update_vote_cert = jcli
+            .certificate()
+            .new_update_vote(&proposal_id.to_string(), pk);
+        send_certificate(&jcli, &jormungandr, wallet, sk.path(), &update_vote_cert);
+    }
+
+    let current_epoch = get_current_date(&mut jormungandr.rest()).epoch();
+    wait_for_epoch(current_epoch + 1, jormungandr.rest());
+
+    let proposal = explorer
+        .update_proposal(proposal_id.into())
+        .unwrap()
+        .data
+        .unwrap()
+        .update_proposal;
+    assert_eq!(
+        proposal.state,
+        update_proposal::UpdateProposalState::ACCEPTED
+    );
+    let mut voters: Vec<_> = proposal
+        .votes
+        .into_iter()
+        .map(|vote| vote.voter.id)
+        .collect();
+    voters.sort();
+    let mut leaders = vec![alice_pk, bob_pk];
+    leaders.sort();
+    assert_eq!(voters, leaders);
+    let settled_in = proposal
+        .settled_in
+        .expect("accepted proposal without an epoch")
+        .id;
+
+    let branch = explorer
+        .config_updates(settled_in.parse().unwrap())
+        .unwrap()
+        .data
+        .unwrap()
+        .tip;
+    assert!(branch.update_proposals.iter().any(|proposal| {
+        proposal.id == proposal_id.to_string()
+            && proposal.state == config_updates::UpdateProposalState::ACCEPTED
+    }));
+    assert_eq!(branch.config_updates.len(), 1);
+    assert_eq!(branch.config_updates[0].id, proposal_id.to_string());
+    assert_eq!(
+        branch.config_updates[0].changes.config_params.len(),
+        1,
+        "unexpected changes"
+    );
+    assert!(matches!(
+        &branch.config_updates[0].changes.config_params[0].on,
+        config_updates::ConfigUpdatesTipConfigUpdatesChangesConfigParamsOn::BlockContentMaxSize(
+            param
+        ) if param.block_content_max_size == new_block_content_max_size as i64
+    ));
+
+    let previous_epoch = settled_in.parse::<u32>().unwrap() - 1;
+    let branch = explorer
+        .config_updates(previous_epoch)
+        .unwrap()
+        .data
+        .unwrap()
+        .tip;
+    assert!(branch.config_updates.is_empty());
+}
+
+fn send_certificate(
+    jcli: &JCli,
+    jormungandr: &JormungandrProcess,
+    wallet: &mut Wallet,
+    secret_key: &Path,
+    certificate: &str,
+) -> FragmentId {
+    let tx = jcli
+        .transaction_builder(jormungandr.genesis_block_hash())
+        .new_transaction()
+        .add_account(&wallet.address().to_string(), &Value::zero().into())
+        .add_certificate(certificate)
+        .set_expiry_date(BlockDate::first().next_epoch().next_epoch().into())
+        .finalize()
+        .seal_with_witness_for_address(wallet)
+        .add_auth(secret_key)
+        .to_message();
+    wallet.confirm_transaction();
+    jcli.fragment_sender(jormungandr)
+        .send(tx.as_str())
+        .assert_in_block_with_wait(&Wait::new(Duration::from_secs(5), 10))
+}
@@ -1,3 +1,4 @@
 pub mod client_tests;
+pub mod replay_tests;
 pub mod server_tests;
 pub mod setup;
@@ -0,0 +1,77 @@
+use super::setup;
+use assert_fs::{fixture::PathChild, TempDir};
+use jormungandr_lib::interfaces::TrustedPeer;
+use jormungandr_testing_utils::testing::{
+    configuration,
+    jormungandr::{ConfigurationBuilder, Starter},
+    node::grpc::{
+        server::MockBuilder,
+        traffic::{ReplayTiming, TrafficCapture, TrafficRecorder, TrafficReplayer},
+    },
+};
+use std::time::Duration;
+
+const RECORDED_CHAIN_LENGTH: u32 = 5;
+
+#[test]
+pub fn replayed_traffic_syncs_passive_node() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // record the traffic of a leader node connected to the mock
+    let setup = setup::server::default();
+    let block0 = setup.server.block0_configuration().to_block();
+    let block0_hash = block0.header().hash();
+    let recorder = TrafficRecorder::new(block0_hash);
+    let mock_controller = MockBuilder::default()
+        .with_port(setup.mock_port)
+        .with_genesis_block(block0.clone())
+        .with_traffic_recorder(recorder.clone())
+        .build();
+    setup.wait_server_online();
+
+    let client = setup::Config::attach_to_local_node(setup.config.get_p2p_listen_port()).client();
+    client.wait_for_chain_length(RECORDED_CHAIN_LENGTH.into(), Duration::from_secs(60));
+    let tip = client.tip().hash();
+    recorder
+        .record_pull_blocks(&client, &[block0_hash], tip)
+        .unwrap();
+
+    mock_controller.stop();
+    setup.server.shutdown();
+
+    let capture_file = temp_dir.child("capture.json");
+    recorder.save(capture_file.path()).unwrap();
+    let capture = TrafficCapture::load(capture_file.path()).unwrap();
+    assert_eq!(capture.events.len(), recorder.events_count());
+
+    // replay it against a passive node which only knows the block0
+    let mock_port = configuration::get_available_port();
+    let seed_controller = MockBuilder::default()
+        .with_port(mock_port)
+        .with_genesis_block(block0)
+        .build();
+    let passive_dir = temp_dir.child("passive");
+    let passive = Starter::new()
+        .config(
+            ConfigurationBuilder::new()
+                .with_trusted_peers(vec![TrustedPeer {
+                    address: format!("/ip4/127.0.0.1/tcp/{}", mock_port).parse().unwrap(),
+                    id: None,
+                }])
+                .with_block_hash(block0_hash.to_string())
+                .build(&passive_dir),
+        )
+        .passive()
+        .start()
+        .unwrap();
+
+    let report = TrafficReplayer::new(passive.address())
+        .with_timing(ReplayTiming::Immediate)
+        .with_linger(Duration::from_secs(5))
+        .replay(&capture)
+        .unwrap();
+
+    seed_controller.stop();
+    assert!(report.unknown_blocks.is_empty());
+    assert_eq!(passive.rest().tip().unwrap().into_hash(), tip);
+}
@@ -1,2 +1,3 @@
 pub mod fail_fast;
+pub mod simulate;
 pub mod statuses;
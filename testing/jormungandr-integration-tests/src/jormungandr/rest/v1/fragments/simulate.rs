@@ -0,0 +1,133 @@
+use chain_impl_mockchain::{block::BlockDate, fragment::Fragment};
+use jormungandr_lib::interfaces::FragmentExpiry;
+use jormungandr_testing_utils::testing::fragments::FaultyTransactionBuilder;
+use jormungandr_testing_utils::testing::jormungandr::JormungandrProcess;
+use jormungandr_testing_utils::testing::{jormungandr::ConfigurationBuilder, startup};
+use jormungandr_testing_utils::wallet::Wallet;
+use rstest::*;
+
+#[fixture]
+fn world() -> (JormungandrProcess, Wallet, Fragment, Fragment, Fragment) {
+    let mut alice = startup::create_new_account_address();
+    let bob = startup::create_new_account_address();
+    let mut clarice = startup::create_new_account_address();
+
+    let (jormungandr, _stake_pools) = startup::start_stake_pool(
+        &[alice.clone()],
+        &[bob.clone()],
+        &mut ConfigurationBuilder::new(),
+    )
+    .unwrap();
+
+    let valid_fragment = alice
+        .transaction_to(
+            &jormungandr.genesis_block_hash(),
+            &jormungandr.fees(),
+            BlockDate::first().next_epoch(),
+            bob.address(),
+            100.into(),
+        )
+        .unwrap();
+
+    // clarice has no funds
+    let late_invalid_fragment = clarice
+        .transaction_to(
+            &jormungandr.genesis_block_hash(),
+            &jormungandr.fees(),
+            BlockDate::first().next_epoch(),
+            alice.address(),
+            100.into(),
+        )
+        .unwrap();
+
+    let faulty_tx_builder = FaultyTransactionBuilder::new(
+        jormungandr.genesis_block_hash(),
+        jormungandr.fees(),
+        BlockDate::first().next_epoch().into(),
+    );
+    let early_invalid_fragment = faulty_tx_builder.unbalanced(&alice, &bob);
+
+    (
+        jormungandr,
+        bob,
+        valid_fragment,
+        early_invalid_fragment,
+        late_invalid_fragment,
+    )
+}
+
+#[rstest]
+pub fn simulate_does_not_add_fragments_to_mempool(
+    world: (JormungandrProcess, Wallet, Fragment, Fragment, Fragment),
+) {
+    let (jormungandr, bob, valid_fragment, _, _) = world;
+    let fragment_id = valid_fragment.id();
+
+    let simulation = jormungandr
+        .rest()
+        .simulate_fragments(vec![valid_fragment], false)
+        .unwrap();
+
+    assert!(simulation.is_accepted());
+    let fragment = &simulation.fragments[0];
+    assert_eq!(fragment.fragment_id, fragment_id);
+    assert_eq!(fragment.expiry, FragmentExpiry::Valid);
+    assert_eq!(fragment.fee, fragment.expected_fee);
+    let received = fragment
+        .balance_changes
+        .iter()
+        .find(|change| change.address.1 == bob.address().1)
+        .expect("the receiver balance change is missing");
+    assert_eq!(received.received, 100.into());
+
+    assert!(jormungandr
+        .rest()
+        .fragment_logs()
+        .unwrap()
+        .get(&fragment_id)
+        .is_none());
+}
+
+#[rstest]
+pub fn simulate_applies_the_fragments_following_a_rejected_one(
+    world: (JormungandrProcess, Wallet, Fragment, Fragment, Fragment),
+) {
+    let (jormungandr, _, valid_fragment, early_invalid_fragment, late_invalid_fragment) = world;
+
+    let simulation = jormungandr
+        .rest()
+        .simulate_fragments(
+            vec![
+                early_invalid_fragment,
+                valid_fragment,
+                late_invalid_fragment,
+            ],
+            false,
+        )
+        .unwrap();
+
+    assert!(!simulation.is_accepted());
+    assert!(simulation.fragments[0].error.is_some());
+    assert!(simulation.fragments[0].balance_changes.is_empty());
+    assert!(simulation.fragments[1].is_accepted());
+    assert!(simulation.fragments[2].error.is_some());
+}
+
+#[rstest]
+pub fn simulate_fail_fast_stops_at_the_first_rejected_fragment(
+    world: (JormungandrProcess, Wallet, Fragment, Fragment, Fragment),
+) {
+    let (jormungandr, _, valid_fragment, early_invalid_fragment, _) = world;
+
+    let simulation = jormungandr
+        .rest()
+        .simulate_fragments(vec![early_invalid_fragment, valid_fragment], true)
+        .unwrap();
+
+    assert!(simulation.fragments[0].error.is_some());
+    assert!(simulation.fragments[1]
+        .error
+        .as_ref()
+        .unwrap()
+        .contains("not applied"));
+}
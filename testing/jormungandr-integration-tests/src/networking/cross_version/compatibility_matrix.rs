@@ -0,0 +1,61 @@
+use hersir::controller::Context;
+use jormungandr_testing_utils::testing::network::CompatibilityMatrix;
+use jormungandr_testing_utils::{
+    testing::node::{cached_releases, download_last_n_releases, get_cached_jormungandr_bin},
+    Version,
+};
+use std::env;
+use std::path::PathBuf;
+
+/// released versions to check, comma separated (e.g. `0.12.0,0.13.0`).
+/// Defaults to the releases found in the cache, or the last two releases if
+/// the cache is empty.
+const VERSIONS: &str = "JORMUNGANDR_COMPATIBILITY_VERSIONS";
+/// directory of the release binaries, a missing release is downloaded there
+const RELEASES_CACHE: &str = "JORMUNGANDR_RELEASES_CACHE";
+/// file to write the JSON report to
+const REPORT: &str = "JORMUNGANDR_COMPATIBILITY_REPORT";
+const DEFAULT_RELEASES_COUNT: u32 = 2;
+
+#[test]
+pub fn compatibility_matrix() {
+    let context = Context::default();
+    let cache_dir = env::var(RELEASES_CACHE)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| context.child_directory("releases").path().to_path_buf());
+
+    let versions: Vec<Version> = match env::var(VERSIONS) {
+        Ok(versions) => versions
+            .split(',')
+            .map(|version| Version::parse(version.trim()).expect("invalid version"))
+            .collect(),
+        Err(_) => {
+            let cached: Vec<Version> = cached_releases(&cache_dir)
+                .into_iter()
+                .map(|(version, _)| version)
+                .collect();
+            if cached.is_empty() {
+                download_last_n_releases(DEFAULT_RELEASES_COUNT)
+                    .iter()
+                    .map(|release| release.version())
+                    .collect()
+            } else {
+                cached
+            }
+        }
+    };
+
+    let matrix = versions
+        .into_iter()
+        .fold(CompatibilityMatrix::new(), |matrix, version| {
+            let jormungandr = get_cached_jormungandr_bin(&version, &cache_dir);
+            matrix.release(version, jormungandr)
+        });
+    let report = matrix.run();
+    println!("{}", report);
+
+    if let Ok(path) = env::var(REPORT) {
+        std::fs::write(&path, report.to_json().unwrap()).unwrap();
+    }
+    assert!(report.is_compatible(), "some versions are not compatible");
+}
@@ -1,3 +1,4 @@
+pub mod compatibility_matrix;
 pub mod disruption;
 pub mod fragment_propagation;
 
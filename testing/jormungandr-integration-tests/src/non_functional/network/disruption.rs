@@ -2,6 +2,7 @@ use crate::networking::utils;
 use crate::non_functional::network::*;
 use jormungandr_testing_utils::testing::network::builder::NetworkBuilder;
 use jormungandr_testing_utils::testing::network::wallet::template::builder::WalletTemplateBuilder;
+use jormungandr_testing_utils::testing::network::LinkFaults;
 use jormungandr_testing_utils::testing::network::Node;
 use jormungandr_testing_utils::testing::network::SpawnParams;
 use jormungandr_testing_utils::testing::network::Topology;
@@ -9,6 +10,7 @@ use jormungandr_testing_utils::testing::network::{LeadershipMode, PersistenceMod
 use jormungandr_testing_utils::testing::sync::MeasurementReportInterval;
 use jormungandr_testing_utils::testing::FragmentSender;
 use jormungandr_testing_utils::testing::SyncWaitParams;
+use std::time::Duration;
 #[test]
 pub fn passive_leader_disruption_no_overlap() {
     let mut controller = NetworkBuilder::default()
@@ -492,3 +494,79 @@ pub fn mesh_disruption() {
     )
     .unwrap();
 }
+
+#[test]
+pub fn passive_leader_disruption_link_faults() {
+    let mut controller = NetworkBuilder::default()
+        .topology(
+            Topology::default()
+                .with_node(Node::new(LEADER))
+                .with_node(Node::new(PASSIVE).with_trusted_peer(LEADER)),
+        )
+        .wallet_template(
+            WalletTemplateBuilder::new(ALICE)
+                .with(2_500_000_000)
+                .build(),
+        )
+        .wallet_template(
+            WalletTemplateBuilder::new(BOB)
+                .with(2_000_000_000)
+                .delegated_to(LEADER)
+                .build(),
+        )
+        .fault_injection()
+        .build()
+        .unwrap();
+
+    let leader = controller.spawn(SpawnParams::new(LEADER)).unwrap();
+    let passive = controller
+        .spawn(SpawnParams::new(PASSIVE).passive())
+        .unwrap();
+
+    // 1. both nodes are up, on a slow and lossy link
+    let slow_link = LinkFaults::none()
+        .with_latency(Duration::from_millis(300))
+        .with_jitter(Duration::from_millis(100))
+        .with_loss(0.05)
+        .with_bandwidth(64 * 1024);
+    controller
+        .set_link_faults(PASSIVE, LEADER, slow_link.clone())
+        .unwrap();
+    controller
+        .set_link_faults(LEADER, PASSIVE, slow_link)
+        .unwrap();
+    utils::wait(20);
+
+    // 2. the leader cannot send anything to the passive node
+    controller
+        .fault_injector()
+        .unwrap()
+        .partition_one_way(LEADER, PASSIVE)
+        .unwrap();
+    // let the data sent before the partition arrive
+    utils::wait(5);
+    let leader_tip = leader.rest().tip().unwrap();
+    let passive_tip = passive.rest().tip().unwrap();
+    utils::wait(25);
+    assert_ne!(
+        leader.rest().tip().unwrap(),
+        leader_tip,
+        "the leader did not create any block during the partition"
+    );
+    assert_eq!(
+        passive.rest().tip().unwrap(),
+        passive_tip,
+        "the passive node received blocks through the partition"
+    );
+
+    // 3. the link is healed
+    controller.fault_injector().unwrap().clear();
+
+    utils::measure_and_log_sync_time(
+        &[&leader, &passive],
+        SyncWaitParams::nodes_restart(5).into(),
+        "passive_leader_disruption_link_faults",
+        MeasurementReportInterval::Standard,
+    )
+    .unwrap();
+}
@@ -1,5 +1,34 @@
 pub mod block;
 pub mod process;
 pub mod rest;
+pub mod scenario;
 
 pub use process::AdversaryNode;
+
+use chain_impl_mockchain::{block::Block, fragment::Fragment, testing::data::StakePool};
+use jormungandr_lib::interfaces::GenesisPraos;
+
+/// The stake pool of the Genesis Praos secret of a node, to sign blocks the
+/// way the node would. The registration of the pool is looked up in the
+/// block0, `None` if the pool is not registered there.
+pub fn stake_pool_from_secret(block0: &Block, secret: &GenesisPraos) -> Option<StakePool> {
+    let node_id = secret.node_id.to_string();
+    block0
+        .contents()
+        .iter()
+        .filter_map(|fragment| match fragment {
+            Fragment::PoolRegistration(tx) => Some(tx.as_slice().payload().into_payload()),
+            _ => None,
+        })
+        .find(|registration| registration.to_id().to_string() == node_id)
+        .map(|registration| {
+            StakePool::new(
+                &node_id,
+                registration.to_id(),
+                chain_crypto::KeyPair::from(secret.vrf_key.clone().into_secret_key()),
+                chain_crypto::KeyPair::from(secret.sig_key.clone().into_secret_key()),
+                registration,
+                None,
+            )
+        })
+}
@@ -0,0 +1,153 @@
+//! Scripted adversarial scenarios.
+//!
+//! A scenario is a YAML file listing the steps an [`AdversaryNode`] plays
+//! against a victim node, and the reactions expected from the victim after
+//! each step, checked through its REST API:
+//!
+//! ```yaml
+//! victim:
+//!   p2p: 127.0.0.1:3000
+//!   rest: 127.0.0.1:8080
+//! steps:
+//!   - action:
+//!       equivocating_blocks:
+//!         count: 2
+//!     expect:
+//!       - node_running
+//!   - action:
+//!       private_fork:
+//!         parent: block0
+//!         length: 10
+//!     wait: 10
+//!     expect:
+//!       - tip_adversarial
+//! ```
+//!
+//! [`AdversaryNode`]: super::AdversaryNode
+
+mod runner;
+
+pub use runner::{ScenarioError, ScenarioReport, ScenarioRunner, StepReport};
+
+use jormungandr_lib::crypto::hash::Hash;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+
+const DEFAULT_STEP_WAIT: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub victim: Victim,
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ScenarioError> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(serde_yaml::from_reader(file)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Victim {
+    /// node-to-node gRPC address of the victim
+    pub p2p: SocketAddr,
+    /// REST address of the victim
+    pub rest: SocketAddr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub action: Action,
+    /// seconds to wait after the action before checking the expectations
+    #[serde(default = "default_step_wait")]
+    pub wait: u64,
+    #[serde(default)]
+    pub expect: Vec<Expectation>,
+}
+
+fn default_step_wait() -> u64 {
+    DEFAULT_STEP_WAIT
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Action {
+    /// several different blocks for the same slot on top of the same parent
+    EquivocatingBlocks {
+        #[serde(default)]
+        parent: Parent,
+        count: usize,
+    },
+    /// a chain of blocks kept private and released at once: only the
+    /// header of the last block is announced, the victim has to pull the
+    /// rest of the fork
+    PrivateFork {
+        #[serde(default)]
+        parent: Parent,
+        length: usize,
+    },
+    /// a header announced for a block whose body, once solicited, does not
+    /// match it
+    HeaderBodyMismatch {
+        #[serde(default)]
+        parent: Parent,
+    },
+    /// gossip about nodes with made up node IDs and addresses
+    GossipFlood { count: usize },
+    /// a header announced for a block whose body is never served
+    WithheldBody {
+        #[serde(default)]
+        parent: Parent,
+    },
+}
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EquivocatingBlocks { .. } => "equivocating_blocks",
+            Self::PrivateFork { .. } => "private_fork",
+            Self::HeaderBodyMismatch { .. } => "header_body_mismatch",
+            Self::GossipFlood { .. } => "gossip_flood",
+            Self::WithheldBody { .. } => "withheld_body",
+        }
+    }
+}
+
+/// the block the adversarial blocks are built on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Parent {
+    /// the tip of the victim when the step starts
+    VictimTip,
+    Block0,
+    Hash(Hash),
+}
+
+impl Default for Parent {
+    fn default() -> Self {
+        Self::VictimTip
+    }
+}
+
+/// reaction of the victim checked after a step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expectation {
+    /// the victim still answers and is in the running state
+    NodeRunning,
+    /// the tip of the victim did not move during the step
+    TipUnchanged,
+    /// the tip of the victim is none of the blocks made by the step
+    TipNotAdversarial,
+    /// the tip of the victim is the last block made by the step
+    TipAdversarial,
+    /// the victim knows at most this number of available peers
+    MaxAvailablePeers(usize),
+}
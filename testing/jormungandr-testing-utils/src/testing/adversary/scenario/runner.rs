@@ -0,0 +1,381 @@
+use super::{Action, Expectation, Parent, Scenario, Step};
+use crate::testing::{
+    adversary::{block::BlockBuilder, AdversaryNode},
+    node::{
+        grpc::traffic::{
+            CapturedEvent, Payload, ReplayReport, ReplayTiming, TrafficCapture, TrafficError,
+            TrafficEvent, TrafficReplayer,
+        },
+        uri_from_socket_addr, JormungandrRest, RestError,
+    },
+    startup, FragmentBuilder,
+};
+use bincode::Options;
+use chain_core::property::Serialize;
+use chain_crypto::Ed25519;
+use chain_impl_mockchain::{
+    block::{Block, BlockDate, Contents, ContentsBuilder, Header},
+    chaintypes::ConsensusType,
+    testing::data::StakePool,
+};
+use jormungandr_lib::{
+    crypto::{hash::Hash, key::SigningKey},
+    interfaces::NodeState,
+};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use thiserror::Error;
+
+const FAKE_GOSSIP_PORT: u16 = 3000;
+
+#[derive(Debug, Error)]
+pub enum ScenarioError {
+    #[error("cannot read the scenario file")]
+    Io(#[from] std::io::Error),
+    #[error("malformed scenario file")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("request to the victim REST API failed")]
+    Rest(#[from] RestError),
+    #[error("cannot send the adversarial traffic to the victim")]
+    Traffic(#[from] TrafficError),
+    #[error("no signing key available to sign BFT blocks")]
+    NoSigningKey,
+    #[error("no stake pool available to sign Genesis Praos blocks")]
+    NoStakePool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepReport {
+    pub action: &'static str,
+    pub replay: ReplayReport,
+    /// expectations not met by the victim, empty if the step passed
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioReport {
+    pub steps: Vec<StepReport>,
+}
+
+impl ScenarioReport {
+    pub fn is_success(&self) -> bool {
+        self.steps.iter().all(|step| step.failures.is_empty())
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if step.failures.is_empty() {
+                writeln!(f, "step {} ({}): ok", index + 1, step.action)?;
+            } else {
+                writeln!(f, "step {} ({}): failed", index + 1, step.action)?;
+                for failure in &step.failures {
+                    writeln!(f, "  - {}", failure)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Plays a [`Scenario`] with an [`AdversaryNode`] against a victim node.
+///
+/// Each step is sent to the victim as a [`TrafficCapture`] replayed with a
+/// [`TrafficReplayer`]: the blocks the victim is allowed to download are
+/// served on its solicitations, withheld blocks are never served.
+pub struct ScenarioRunner {
+    adversary: AdversaryNode,
+    signing_key: Option<SigningKey<Ed25519>>,
+    stake_pool: Option<StakePool>,
+}
+
+impl ScenarioRunner {
+    pub fn new(adversary: AdversaryNode) -> Self {
+        Self {
+            adversary,
+            signing_key: None,
+            stake_pool: None,
+        }
+    }
+
+    pub fn signing_key(self, key: SigningKey<Ed25519>) -> Self {
+        Self {
+            signing_key: Some(key),
+            ..self
+        }
+    }
+
+    pub fn stake_pool(self, stake_pool: StakePool) -> Self {
+        Self {
+            stake_pool: Some(stake_pool),
+            ..self
+        }
+    }
+
+    pub fn run(&self, scenario: &Scenario) -> Result<ScenarioReport, ScenarioError> {
+        let rest = JormungandrRest::new(uri_from_socket_addr(scenario.victim.rest));
+        let mut report = ScenarioReport::default();
+
+        for step in &scenario.steps {
+            let tip_before = rest.tip()?;
+            let (capture, blocks) = self.capture(&step.action, &rest, tip_before)?;
+            let replay = TrafficReplayer::new(scenario.victim.p2p)
+                .with_timing(ReplayTiming::Immediate)
+                .with_linger(Duration::from_secs(step.wait))
+                .replay(&capture)?;
+
+            report.steps.push(StepReport {
+                action: step.action.name(),
+                replay,
+                failures: check(step, &rest, tip_before, &blocks),
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// build the traffic of an action, together with the ids of the blocks
+    /// made for it
+    fn capture(
+        &self,
+        action: &Action,
+        rest: &JormungandrRest,
+        victim_tip: Hash,
+    ) -> Result<(TrafficCapture, Vec<Hash>), ScenarioError> {
+        let mut capture = TrafficCapture::new(self.adversary.genesis_block_hash().into_hash());
+        let mut events = Vec::new();
+        let mut blocks = Vec::new();
+
+        match action {
+            Action::EquivocatingBlocks { parent, count } => {
+                let parent = self.parent_header(parent, rest, victim_tip)?;
+                let mut uploaded = Vec::new();
+                for index in 0..*count {
+                    let block = self.block(&parent, self.distinct_contents(index))?;
+                    blocks.push(block.header().id().into());
+                    uploaded.push(payload(&block));
+                }
+                events.push(TrafficEvent::UploadedBlocks { blocks: uploaded });
+            }
+            Action::PrivateFork { parent, length } => {
+                let fork_parent = self.parent_header(parent, rest, victim_tip)?;
+                let mut parent = fork_parent.clone();
+                let mut fork = Vec::new();
+                for _ in 0..*length {
+                    let block = self.block(&parent, Contents::empty())?;
+                    parent = block.header().clone();
+                    blocks.push(parent.id().into());
+                    fork.push(payload(&block));
+                }
+                events.push(TrafficEvent::PullBlocksResponse {
+                    from: vec![fork_parent.id().as_ref().to_vec().into()],
+                    to: parent.id().as_ref().to_vec().into(),
+                    blocks: fork,
+                });
+                events.push(announcement(&parent));
+            }
+            Action::HeaderBodyMismatch { parent } => {
+                let parent = self.parent_header(parent, rest, victim_tip)?;
+                let announced = self.block(&parent, Contents::empty())?;
+                let other = self.block(&parent, self.distinct_contents(1))?;
+                // the announced block has no contents, so its serialization
+                // is only its header, which has the same size as the other one
+                let mut mismatched = announced.serialize_as_vec().unwrap();
+                let header_len = mismatched.len();
+                mismatched.extend_from_slice(&other.serialize_as_vec().unwrap()[header_len..]);
+
+                blocks.push(announced.header().id().into());
+                events.push(TrafficEvent::PullBlocksResponse {
+                    from: vec![parent.id().as_ref().to_vec().into()],
+                    to: announced.header().id().as_ref().to_vec().into(),
+                    blocks: vec![mismatched.into()],
+                });
+                events.push(announcement(announced.header()));
+            }
+            Action::GossipFlood { count } => {
+                events.push(TrafficEvent::Gossip {
+                    nodes: fake_gossip(*count),
+                });
+            }
+            Action::WithheldBody { parent } => {
+                let parent = self.parent_header(parent, rest, victim_tip)?;
+                let block = self.block(&parent, Contents::empty())?;
+                blocks.push(block.header().id().into());
+                events.push(announcement(block.header()));
+            }
+        }
+
+        capture.events = events
+            .into_iter()
+            .map(|event| CapturedEvent { offset: 0, event })
+            .collect();
+        Ok((capture, blocks))
+    }
+
+    fn parent_header(
+        &self,
+        parent: &Parent,
+        rest: &JormungandrRest,
+        victim_tip: Hash,
+    ) -> Result<Header, ScenarioError> {
+        let id = match parent {
+            Parent::Block0 => self.adversary.genesis_block_hash(),
+            Parent::VictimTip => victim_tip,
+            Parent::Hash(hash) => *hash,
+        };
+        Ok(rest.block(&id.into_hash())?.header().clone())
+    }
+
+    /// a block for the slot following the parent, signed with the key
+    /// matching the consensus of the block0
+    fn block(&self, parent: &Header, contents: Contents) -> Result<Block, ScenarioError> {
+        let block0_config = self
+            .adversary
+            .block0_configuration()
+            .blockchain_configuration;
+
+        let parent_block_date = parent.block_date();
+        let slots_per_epoch: u32 = block0_config.slots_per_epoch.into();
+        let block_date = BlockDate {
+            epoch: parent_block_date.epoch + (parent_block_date.slot_id + 1) / slots_per_epoch,
+            slot_id: (parent_block_date.slot_id + 1) % slots_per_epoch,
+        };
+
+        match block0_config.block0_consensus {
+            ConsensusType::Bft => {
+                let key = self
+                    .signing_key
+                    .clone()
+                    .ok_or(ScenarioError::NoSigningKey)?;
+                Ok(BlockBuilder::bft(block_date, parent.clone())
+                    .contents(contents)
+                    .signing_key(key)
+                    .build())
+            }
+            ConsensusType::GenesisPraos => {
+                let stake_pool = self.stake_pool.clone().ok_or(ScenarioError::NoStakePool)?;
+                Ok(BlockBuilder::genesis_praos(block_date, parent.clone())
+                    .contents(contents)
+                    .stake_pool(stake_pool)
+                    .build())
+            }
+        }
+    }
+
+    /// Contents making blocks for the same slot differ. Apart from the first
+    /// one, they carry a transaction from an account without funds, which the
+    /// victim rejects if it gets to validate the contents.
+    fn distinct_contents(&self, index: usize) -> Contents {
+        if index == 0 {
+            return Contents::empty();
+        }
+        let block0_config = self
+            .adversary
+            .block0_configuration()
+            .blockchain_configuration;
+        let mut contents_builder = ContentsBuilder::default();
+        contents_builder.push(
+            FragmentBuilder::new(
+                &self.adversary.genesis_block_hash(),
+                &block0_config.linear_fees,
+                BlockDate::first().next_epoch(),
+            )
+            .transaction(
+                &startup::create_new_account_address(),
+                startup::create_new_account_address().address(),
+                (index as u64).into(),
+            )
+            .unwrap(),
+        );
+        contents_builder.into()
+    }
+}
+
+fn check(step: &Step, rest: &JormungandrRest, tip_before: Hash, blocks: &[Hash]) -> Vec<String> {
+    step.expect
+        .iter()
+        .filter_map(
+            |expectation| match check_expectation(expectation, rest, tip_before, blocks) {
+                Ok(None) => None,
+                Ok(Some(failure)) => Some(failure),
+                Err(e) => Some(format!(
+                    "{:?}: victim REST request failed: {}",
+                    expectation, e
+                )),
+            },
+        )
+        .collect()
+}
+
+fn check_expectation(
+    expectation: &Expectation,
+    rest: &JormungandrRest,
+    tip_before: Hash,
+    blocks: &[Hash],
+) -> Result<Option<String>, RestError> {
+    let failure = match expectation {
+        Expectation::NodeRunning => {
+            let state = rest.stats()?.state;
+            (state != NodeState::Running)
+                .then(|| format!("victim is in state {:?} instead of running", state))
+        }
+        Expectation::TipUnchanged => {
+            let tip = rest.tip()?;
+            (tip != tip_before).then(|| format!("victim tip moved from {} to {}", tip_before, tip))
+        }
+        Expectation::TipNotAdversarial => {
+            let tip = rest.tip()?;
+            blocks
+                .contains(&tip)
+                .then(|| format!("victim adopted the adversarial block {}", tip))
+        }
+        Expectation::TipAdversarial => {
+            let tip = rest.tip()?;
+            (!blocks.contains(&tip))
+                .then(|| format!("victim tip {} is not an adversarial block", tip))
+        }
+        Expectation::MaxAvailablePeers(max) => {
+            let available = rest.p2p_available()?.len();
+            (available > *max).then(|| {
+                format!(
+                    "victim has {} available peers, expected at most {}",
+                    available, max
+                )
+            })
+        }
+    };
+    Ok(failure)
+}
+
+fn payload(block: &Block) -> Payload {
+    block.serialize_as_vec().unwrap().into()
+}
+
+fn announcement(header: &Header) -> TrafficEvent {
+    TrafficEvent::BlockAnnouncement {
+        header: header.serialize_as_vec().unwrap().into(),
+    }
+}
+
+/// gossip about nodes with random node IDs, with addresses in the
+/// TEST-NET-1 documentation range so the victim cannot reach them
+fn fake_gossip(count: usize) -> Vec<Payload> {
+    (0..count)
+        .map(|index| {
+            let key = keynesis::key::ed25519::SecretKey::new(&mut rand::thread_rng());
+            let address = SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, (index % 254 + 1) as u8)),
+                FAKE_GOSSIP_PORT + (index / 254) as u16,
+            );
+            let profile = poldercast::Profile::new(address, &key);
+            let mut gossip = Vec::new();
+            bincode::options()
+                .with_limit(512)
+                .serialize_into(&mut gossip, profile.gossip().as_ref())
+                .unwrap();
+            gossip.into()
+        })
+        .collect()
+}
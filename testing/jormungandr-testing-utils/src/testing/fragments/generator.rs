@@ -22,6 +22,10 @@ use std::iter;
 use std::time::Duration;
 use std::time::Instant;
 
+/// votes cast on each vote plan registered for the vote casts, the vote cast
+/// counter holds the number of proposals of a vote plan in a `u8`
+pub const VOTE_CASTS_PER_VOTE_PLAN: usize = u8::MAX as usize;
+
 pub struct FragmentGenerator<'a, S: SyncNode + Send> {
     sender: Wallet,
     receiver: Wallet,
@@ -89,7 +93,7 @@ impl<'a, S: SyncNode + Send> FragmentGenerator<'a, S> {
         let votes_plan_for_casting: Vec<VotePlan> = iter::from_fn(|| {
             Some(
                 VotePlanBuilder::new()
-                    .proposals_count(256)
+                    .proposals_count(VOTE_CASTS_PER_VOTE_PLAN)
                     .with_vote_start(start_block_date.shift_slot(5, &time_era).into())
                     .with_tally_start(start_block_date.shift_epoch(5).into())
                     .with_tally_end(start_block_date.shift_epoch(6).into())
@@ -181,6 +185,45 @@ impl<'a, S: SyncNode + Send> FragmentGenerator<'a, S> {
         Ok(checks)
     }
 
+    /// delegate the whole stake of the sender to one of the active stake
+    /// pools
+    pub fn send_full_delegation(&mut self) -> Result<MemPoolCheck, FragmentSenderError> {
+        let index = self.rand.next_u32() as usize % self.active_stake_pools.len();
+        let stake_pool = self.active_stake_pools.get(index).unwrap();
+
+        self.fragment_sender
+            .send_full_delegation(&mut self.sender, stake_pool, &self.node)
+    }
+
+    /// cast the next vote on the vote plans registered by `prepare`, each of
+    /// them takes [`VOTE_CASTS_PER_VOTE_PLAN`] votes
+    pub fn send_vote_cast(&mut self) -> Result<MemPoolCheck, FragmentSenderError> {
+        let vote_cast_register = self
+            .vote_cast_register
+            .as_mut()
+            .expect("please run 'prepare' method before running load");
+
+        // wallet_idx is always 0 because we are using only one wallet
+        let wallet_idx = 0;
+        let wallet_votes_to_cast = vote_cast_register.advance_single(wallet_idx).unwrap();
+        let votes_to_cast = wallet_votes_to_cast
+            .get(0)
+            .expect("all the votes of the vote plans are cast");
+        let vote_plan = self
+            .vote_plans_for_casting
+            .iter()
+            .find(|x| x.to_id() == votes_to_cast.id())
+            .unwrap();
+
+        self.fragment_sender.send_vote_cast(
+            &mut self.sender,
+            vote_plan,
+            votes_to_cast.range().start as u8,
+            &Choice::new(1),
+            &self.node,
+        )
+    }
+
     pub fn send_one(&mut self, option: u8) -> Result<MemPoolCheck, FragmentSenderError> {
         match option % 10 {
             0 => self.fragment_sender.send_transaction(
@@ -189,13 +232,7 @@ impl<'a, S: SyncNode + Send> FragmentGenerator<'a, S> {
                 &self.node,
                 1.into(),
             ),
-            1 => {
-                let index = self.rand.next_u32() as usize % self.active_stake_pools.len();
-                let stake_pool = self.active_stake_pools.get(index).unwrap();
-
-                self.fragment_sender
-                    .send_full_delegation(&mut self.sender, stake_pool, &self.node)
-            }
+            1 => self.send_full_delegation(),
             2 => {
                 let (left, right) = self.active_stake_pools.split_first().unwrap();
 
@@ -265,30 +302,7 @@ impl<'a, S: SyncNode + Send> FragmentGenerator<'a, S> {
                 self.fragment_sender
                     .send_vote_plan(&mut self.sender, &vote_plan, &self.node)
             }
-            8 => {
-                let vote_cast_register = self
-                    .vote_cast_register
-                    .as_mut()
-                    .expect("please run 'prepare' method before running load");
-
-                // wallet_idx is always 0 because we are using only one wallet
-                let wallet_idx = 0;
-                let wallet_votes_to_cast = vote_cast_register.advance_single(wallet_idx).unwrap();
-                let votes_to_cast = wallet_votes_to_cast.get(0).unwrap();
-                let vote_plan = self
-                    .vote_plans_for_casting
-                    .iter()
-                    .find(|x| x.to_id() == votes_to_cast.id())
-                    .unwrap();
-
-                self.fragment_sender.send_vote_cast(
-                    &mut self.sender,
-                    vote_plan,
-                    votes_to_cast.range().start as u8,
-                    &Choice::new(1),
-                    &self.node,
-                )
-            }
+            8 => self.send_vote_cast(),
             9 => {
                 let index = self.rand.next_u32() as usize % self.vote_plans_for_tally.len();
                 let vote_plan = self.vote_plans_for_tally.get(index).unwrap();
@@ -5,7 +5,7 @@ pub use self::{
     },
     chain_sender::FragmentChainSender,
     export::{FragmentExporter, FragmentExporterError},
-    generator::FragmentGenerator,
+    generator::{FragmentGenerator, VOTE_CASTS_PER_VOTE_PLAN},
     initial_certificates::{signed_delegation_cert, signed_stake_pool_cert, vote_plan_cert},
     node::{FragmentNode, FragmentNodeError, MemPoolCheck},
     persistent_log::{write_into_persistent_log, PersistentLogViewer},
@@ -162,4 +162,79 @@ impl Key {
             .failure()
             .stderr(predicates::str::contains(expected_msg_path));
     }
+
+    pub fn convert_to_public_string_with_passphrase<S: Into<String>>(
+        self,
+        encrypted_key: S,
+        passphrase: &str,
+    ) -> String {
+        let input_file = NamedTempFile::new("key_to_public.input").unwrap();
+        input_file.write_str(&encrypted_key.into()).unwrap();
+
+        self.key_command
+            .to_public()
+            .input(input_file.path())
+            .passphrase(passphrase)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_single_line()
+    }
+
+    pub fn encrypt<S: Into<String>>(self, private_key: S, passphrase: &str) -> String {
+        let input_file = NamedTempFile::new("key_encrypt.input").unwrap();
+        input_file.write_str(&private_key.into()).unwrap();
+        let passphrase_file = NamedTempFile::new("key_encrypt.passphrase").unwrap();
+        passphrase_file.write_str(passphrase).unwrap();
+
+        self.key_command
+            .encrypt()
+            .input(input_file.path())
+            .passphrase_file(passphrase_file.path())
+            .kdf_iterations(1000)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_lossy_string()
+    }
+
+    pub fn decrypt<S: Into<String>>(self, encrypted_key: S, passphrase: &str) -> String {
+        let input_file = NamedTempFile::new("key_decrypt.input").unwrap();
+        input_file.write_str(&encrypted_key.into()).unwrap();
+        let passphrase_file = NamedTempFile::new("key_decrypt.passphrase").unwrap();
+        passphrase_file.write_str(passphrase).unwrap();
+
+        self.key_command
+            .decrypt()
+            .input(input_file.path())
+            .passphrase_file(passphrase_file.path())
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_single_line()
+    }
+
+    pub fn decrypt_expect_fail<S: Into<String>>(
+        self,
+        encrypted_key: S,
+        passphrase: &str,
+        expected_msg_path: &str,
+    ) {
+        let input_file = NamedTempFile::new("key_decrypt.input").unwrap();
+        input_file.write_str(&encrypted_key.into()).unwrap();
+        let passphrase_file = NamedTempFile::new("key_decrypt.passphrase").unwrap();
+        passphrase_file.write_str(passphrase).unwrap();
+
+        self.key_command
+            .decrypt()
+            .input(input_file.path())
+            .passphrase_file(passphrase_file.path())
+            .build()
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(expected_msg_path));
+    }
 }
@@ -7,7 +7,7 @@ use chain_core::property::Deserialize;
 use chain_impl_mockchain::{account::SpendingCounter, fee::LinearFee, fragment::Fragment};
 use jormungandr_lib::{
     crypto::hash::Hash,
-    interfaces::{BlockDate, LegacyUTxO, UTxOInfo, Value},
+    interfaces::{BlockDate, FragmentsSimulation, LegacyUTxO, UTxOInfo, Value},
 };
 use jortestkit::process::output_extensions::ProcessOutput as _;
 use std::path::Path;
@@ -368,4 +368,20 @@ impl Transaction {
             .assert()
             .success();
     }
+
+    pub fn simulate<S: Into<String>, P: AsRef<Path>>(
+        self,
+        host: S,
+        staging_file: P,
+    ) -> FragmentsSimulation {
+        let content = self
+            .command
+            .simulate(host, staging_file)
+            .build()
+            .assert()
+            .success()
+            .get_output()
+            .as_lossy_string();
+        serde_json::from_str(&content).expect("JCLI returned malformed fragments simulation")
+    }
 }
@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::process::Command;
+pub struct KeyDecryptCommand {
+    command: Command,
+}
+
+impl KeyDecryptCommand {
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+
+    pub fn input<P: AsRef<Path>>(mut self, input: P) -> Self {
+        self.command.arg("--input").arg(input.as_ref());
+        self
+    }
+
+    pub fn passphrase_file<P: AsRef<Path>>(mut self, passphrase_file: P) -> Self {
+        self.command
+            .arg("--passphrase-file")
+            .arg(passphrase_file.as_ref());
+        self
+    }
+
+    pub fn output<P: AsRef<Path>>(mut self, output: P) -> Self {
+        self.command.arg(output.as_ref());
+        self
+    }
+
+    pub fn build(self) -> Command {
+        self.command
+    }
+}
@@ -1,8 +1,12 @@
+mod decrypt;
+mod encrypt;
 mod from_bytes;
 mod generate;
 mod to_bytes;
 mod to_public;
 
+pub use decrypt::KeyDecryptCommand;
+pub use encrypt::KeyEncryptCommand;
 pub use from_bytes::KeyFromBytesCommand;
 pub use generate::KeyGenerateCommand;
 pub use to_bytes::KeyToBytesCommand;
@@ -39,4 +43,14 @@ impl KeyCommand {
         self.command.arg("to-public");
         KeyToPublicCommand::new(self.command)
     }
+
+    pub fn encrypt(mut self) -> KeyEncryptCommand {
+        self.command.arg("encrypt");
+        KeyEncryptCommand::new(self.command)
+    }
+
+    pub fn decrypt(mut self) -> KeyDecryptCommand {
+        self.command.arg("decrypt");
+        KeyDecryptCommand::new(self.command)
+    }
 }
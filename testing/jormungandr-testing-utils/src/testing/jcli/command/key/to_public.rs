@@ -14,6 +14,11 @@ impl KeyToPublicCommand {
         self
     }
 
+    pub fn passphrase<S: AsRef<str>>(mut self, passphrase: S) -> Self {
+        self.command.env("JCLI_PASSPHRASE", passphrase.as_ref());
+        self
+    }
+
     pub fn output<P: AsRef<Path>>(mut self, output: P) -> Self {
         self.command.arg(output.as_ref());
         self
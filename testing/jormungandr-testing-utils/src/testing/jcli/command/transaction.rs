@@ -234,6 +234,18 @@ impl TransactionCommand {
         self
     }
 
+    pub fn simulate<S: Into<String>, P: AsRef<Path>>(mut self, host: S, staging_file: P) -> Self {
+        self.command
+            .arg("simulate")
+            .arg("--host")
+            .arg(host.into())
+            .arg("--output-format")
+            .arg("json")
+            .arg("--staging")
+            .arg(staging_file.as_ref());
+        self
+    }
+
     pub fn build(self) -> Command {
         println!("{:?}", self.command);
         self.command
@@ -20,6 +20,7 @@ pub struct NetworkBuilder {
     wallet_templates: Vec<WalletTemplate>,
     testing_directory: TestingDirectory,
     observers: Vec<Weak<dyn Observer>>,
+    fault_injection: bool,
 }
 
 impl Observable for NetworkBuilder {
@@ -66,6 +67,13 @@ impl NetworkBuilder {
         self
     }
 
+    /// route the links of the topology through proxies able to inject
+    /// faults, see [`Controller::set_link_faults`]
+    pub fn fault_injection(mut self) -> Self {
+        self.fault_injection = true;
+        self
+    }
+
     pub fn build(mut self) -> Result<Controller, ControllerError> {
         self.notify_all(Event::new("building topology..."));
         let nodes: HashMap<NodeAlias, NodeSetting> = self
@@ -106,6 +114,10 @@ impl NetworkBuilder {
         let settings = Settings::new(nodes, self.blockchain.clone(), &mut random);
 
         self.finish_all();
-        Controller::new(settings, self.testing_directory)
+        let mut controller = Controller::new(settings, self.testing_directory)?;
+        if self.fault_injection {
+            controller.enable_fault_injection()?;
+        }
+        Ok(controller)
     }
 }
@@ -0,0 +1,558 @@
+//! Compatibility matrix of node versions.
+//!
+//! Every ordered pair of versions gets a network of its own: a leader
+//! running the first version and a passive node running the second one,
+//! with the leader as its only trusted peer. The pair is compatible when the
+//! passive node syncs the blocks of the leader, a transaction sent to the
+//! passive node reaches a block of the leader and both nodes end up on the
+//! same tip.
+//!
+//! Only these two-node networks are checked: a network mixing more versions,
+//! or a release relaying blocks between two other nodes, is out of the scope
+//! of the matrix.
+//!
+//! ```ignore
+//! let report = CompatibilityMatrix::new()
+//!     .release(version_0_12_0(), cached_bin_0_12)
+//!     .release(version_0_13_0(), cached_bin_0_13)
+//!     .run();
+//! println!("{}", report);
+//! assert!(report.is_compatible());
+//! ```
+
+use crate::testing::{
+    jormungandr::JormungandrProcess,
+    network::{
+        builder::NetworkBuilder,
+        controller::{Controller, ControllerError},
+        wallet::template::builder::WalletTemplateBuilder,
+        Node, SpawnParams, Topology,
+    },
+    FragmentSender, SyncNode,
+};
+use crate::Version;
+use serde::Serialize;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const LEADER: &str = "Leader";
+const FOLLOWER: &str = "Follower";
+const SENDER: &str = "Sender";
+const RECEIVER: &str = "Receiver";
+
+const DEFAULT_BLOCKS: u32 = 5;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Version of the node running in a network of the matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeVersion {
+    /// the node under test, started like the other nodes of the controller
+    Current,
+    /// a released node, started from its binary with a legacy configuration
+    Release {
+        version: Version,
+        jormungandr: PathBuf,
+    },
+}
+
+impl fmt::Display for NodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeVersion::Current => write!(f, "current"),
+            NodeVersion::Release { version, .. } => write!(f, "{}", version),
+        }
+    }
+}
+
+pub struct CompatibilityMatrix {
+    versions: Vec<NodeVersion>,
+    blocks: u32,
+    timeout: Duration,
+}
+
+impl Default for CompatibilityMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompatibilityMatrix {
+    /// a matrix with only the current node, add the releases to check it
+    /// against with [`release`](Self::release)
+    pub fn new() -> Self {
+        Self {
+            versions: vec![NodeVersion::Current],
+            blocks: DEFAULT_BLOCKS,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// add a released version, `jormungandr` being its binary
+    pub fn release(mut self, version: Version, jormungandr: PathBuf) -> Self {
+        // the releases come first, oldest first, and the current node last
+        let position = self
+            .versions
+            .iter()
+            .position(|other| match other {
+                NodeVersion::Current => true,
+                NodeVersion::Release { version: other, .. } => *other > version,
+            })
+            .unwrap_or_else(|| self.versions.len());
+        self.versions.insert(
+            position,
+            NodeVersion::Release {
+                version,
+                jormungandr,
+            },
+        );
+        self
+    }
+
+    /// number of blocks the leader produces before the passive node is
+    /// expected to be in sync
+    pub fn blocks(mut self, blocks: u32) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// time given to each check
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn versions(&self) -> &[NodeVersion] {
+        &self.versions
+    }
+
+    /// check every ordered pair of versions, one network after the other
+    pub fn run(&self) -> CompatibilityReport {
+        let mut pairs = Vec::new();
+        for leader in &self.versions {
+            for follower in &self.versions {
+                println!("checking leader {} with follower {}...", leader, follower);
+                let pair = self.run_pair(leader, follower);
+                println!("{}", pair);
+                pairs.push(pair);
+            }
+        }
+        CompatibilityReport {
+            versions: self.versions.iter().map(ToString::to_string).collect(),
+            pairs,
+        }
+    }
+
+    fn run_pair(&self, leader: &NodeVersion, follower: &NodeVersion) -> PairReport {
+        let mut report = PairReport {
+            leader: leader.to_string(),
+            follower: follower.to_string(),
+            sync: CheckOutcome::Skipped,
+            fragments: CheckOutcome::Skipped,
+            tip: CheckOutcome::Skipped,
+        };
+
+        let (mut controller, leader_node, follower_node) = match start_network(leader, follower) {
+            Ok(network) => network,
+            Err(error) => {
+                report.sync = CheckOutcome::failed(format!("cannot start the network: {}", error));
+                return report;
+            }
+        };
+
+        report.sync = check(|| self.check_sync(&leader_node, &follower_node));
+        report.fragments = check(|| check_fragments(&mut controller, &follower_node));
+        report.tip = check(|| self.check_tip(&leader_node, &follower_node));
+        report
+    }
+
+    fn check_sync(
+        &self,
+        leader: &JormungandrProcess,
+        follower: &JormungandrProcess,
+    ) -> Result<(), String> {
+        if !wait_until(self.timeout, || leader.last_block_height() >= self.blocks) {
+            return Err(format!(
+                "the leader produced {} blocks out of {}",
+                leader.last_block_height(),
+                self.blocks
+            ));
+        }
+        let height = leader.last_block_height();
+        if !wait_until(self.timeout, || follower.last_block_height() >= height) {
+            return Err(format!(
+                "the follower is at height {}, the leader at {}",
+                follower.last_block_height(),
+                height
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_tip(
+        &self,
+        leader: &JormungandrProcess,
+        follower: &JormungandrProcess,
+    ) -> Result<(), String> {
+        // the tips are compared between two blocks, a block arriving in the
+        // meantime only delays the agreement
+        if wait_until(self.timeout, || leader.tip() == follower.tip()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "tips differ: leader {} at height {}, follower {} at height {}",
+                leader.tip(),
+                leader.last_block_height(),
+                follower.tip(),
+                follower.last_block_height()
+            ))
+        }
+    }
+}
+
+fn start_network(
+    leader: &NodeVersion,
+    follower: &NodeVersion,
+) -> Result<(Controller, JormungandrProcess, JormungandrProcess), ControllerError> {
+    let mut controller = NetworkBuilder::default()
+        .topology(
+            Topology::default()
+                .with_node(Node::new(LEADER))
+                .with_node(Node::new(FOLLOWER).with_trusted_peer(LEADER)),
+        )
+        .wallet_template(
+            WalletTemplateBuilder::new(SENDER)
+                .with(2_000_000_000)
+                .delegated_to(LEADER)
+                .build(),
+        )
+        .wallet_template(
+            WalletTemplateBuilder::new(RECEIVER)
+                .with(2_000_000_000)
+                .build(),
+        )
+        .build()?;
+
+    let leader = spawn(
+        &mut controller,
+        SpawnParams::new(LEADER).in_memory(),
+        leader,
+    )?;
+    let follower = spawn(
+        &mut controller,
+        SpawnParams::new(FOLLOWER).in_memory().passive(),
+        follower,
+    )?;
+    Ok((controller, leader, follower))
+}
+
+fn spawn(
+    controller: &mut Controller,
+    params: SpawnParams,
+    version: &NodeVersion,
+) -> Result<JormungandrProcess, ControllerError> {
+    match version {
+        NodeVersion::Current => controller.spawn(params),
+        NodeVersion::Release {
+            version,
+            jormungandr,
+        } => controller
+            .spawn_legacy(params.jormungandr(jormungandr.clone()), version)
+            .map(|(process, _)| process),
+    }
+}
+
+/// send a transaction to the follower, only the leader can put it in a block
+fn check_fragments(
+    controller: &mut Controller,
+    follower: &JormungandrProcess,
+) -> Result<(), String> {
+    let mut sender = controller.wallet(SENDER).map_err(|e| e.to_string())?;
+    let receiver = controller.wallet(RECEIVER).map_err(|e| e.to_string())?;
+    FragmentSender::from(&*controller)
+        .send_transaction(&mut sender, &receiver, follower, 1_000.into())
+        .map(|_| ())
+        .map_err(|e| format!("the transaction did not make it to a block: {}", e))
+}
+
+fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let started = Instant::now();
+    loop {
+        if condition() {
+            return true;
+        }
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// run a check, a node that stops answering makes the helpers panic
+fn check(check: impl FnOnce() -> Result<(), String>) -> CheckOutcome {
+    match panic::catch_unwind(AssertUnwindSafe(check)) {
+        Ok(Ok(())) => CheckOutcome::Passed,
+        Ok(Err(reason)) => CheckOutcome::Failed { reason },
+        Err(panic) => {
+            let reason = panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_else(|| "the check panicked".to_owned());
+            CheckOutcome::Failed { reason }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Passed,
+    Failed {
+        reason: String,
+    },
+    /// not run because the network could not be started
+    Skipped,
+}
+
+impl CheckOutcome {
+    fn failed(reason: String) -> Self {
+        CheckOutcome::Failed { reason }
+    }
+
+    pub fn is_passed(&self) -> bool {
+        matches!(self, CheckOutcome::Passed)
+    }
+}
+
+impl fmt::Display for CheckOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckOutcome::Passed => write!(f, "passed"),
+            CheckOutcome::Failed { reason } => write!(f, "failed: {}", reason),
+            CheckOutcome::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Results of the network of a leader and a follower.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairReport {
+    pub leader: String,
+    pub follower: String,
+    pub sync: CheckOutcome,
+    pub fragments: CheckOutcome,
+    pub tip: CheckOutcome,
+}
+
+impl PairReport {
+    fn checks(&self) -> [(&'static str, &CheckOutcome); 3] {
+        [
+            ("sync", &self.sync),
+            ("fragments", &self.fragments),
+            ("tip", &self.tip),
+        ]
+    }
+
+    pub fn is_compatible(&self) -> bool {
+        self.checks().iter().all(|(_, outcome)| outcome.is_passed())
+    }
+
+    /// short form for the matrix: `ok` or the names of the failed checks
+    fn summary(&self) -> String {
+        if self.is_compatible() {
+            return "ok".to_owned();
+        }
+        self.checks()
+            .iter()
+            .filter(|(_, outcome)| !outcome.is_passed())
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for PairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "leader {} / follower {}:", self.leader, self.follower)?;
+        for (name, outcome) in self.checks().iter() {
+            writeln!(f, "  {}: {}", name, outcome)?;
+        }
+        Ok(())
+    }
+}
+
+/// Results of all the pairs of versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityReport {
+    pub versions: Vec<String>,
+    pub pairs: Vec<PairReport>,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.pairs.iter().all(PairReport::is_compatible)
+    }
+
+    pub fn pair(&self, leader: &str, follower: &str) -> Option<&PairReport> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.leader == leader && pair.follower == follower)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    /// the matrix, with a row per leader version and a column per follower
+    /// version, followed by the reasons of the failures
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = "leader \\ follower";
+        let cells: Vec<Vec<String>> = self
+            .versions
+            .iter()
+            .map(|leader| {
+                self.versions
+                    .iter()
+                    .map(|follower| {
+                        self.pair(leader, follower)
+                            .map(PairReport::summary)
+                            .unwrap_or_else(|| "-".to_owned())
+                    })
+                    .collect()
+            })
+            .collect();
+        let first_width = self
+            .versions
+            .iter()
+            .map(String::len)
+            .chain(std::iter::once(header.len()))
+            .max()
+            .unwrap_or_default();
+        let widths: Vec<usize> = self
+            .versions
+            .iter()
+            .enumerate()
+            .map(|(column, version)| {
+                cells
+                    .iter()
+                    .map(|row| row[column].len())
+                    .chain(std::iter::once(version.len()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        write!(f, "{:<width$}", header, width = first_width)?;
+        for (version, width) in self.versions.iter().zip(&widths) {
+            write!(f, " | {:<width$}", version, width = width)?;
+        }
+        writeln!(f)?;
+        for (leader, row) in self.versions.iter().zip(&cells) {
+            write!(f, "{:<width$}", leader, width = first_width)?;
+            for (cell, width) in row.iter().zip(&widths) {
+                write!(f, " | {:<width$}", cell, width = width)?;
+            }
+            writeln!(f)?;
+        }
+
+        for pair in self.pairs.iter().filter(|pair| !pair.is_compatible()) {
+            writeln!(f)?;
+            write!(f, "{}", pair)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(leader: &str, follower: &str, fragments: CheckOutcome) -> PairReport {
+        PairReport {
+            leader: leader.to_owned(),
+            follower: follower.to_owned(),
+            sync: CheckOutcome::Passed,
+            fragments,
+            tip: CheckOutcome::Passed,
+        }
+    }
+
+    #[test]
+    fn releases_are_ordered_before_the_current_node() {
+        let matrix = CompatibilityMatrix::new()
+            .release(Version::new(0, 13, 0), PathBuf::from("0.13.0"))
+            .release(Version::new(0, 8, 19), PathBuf::from("0.8.19"))
+            .release(Version::new(0, 12, 0), PathBuf::from("0.12.0"));
+        let versions: Vec<String> = matrix.versions().iter().map(ToString::to_string).collect();
+        assert_eq!(versions, vec!["0.8.19", "0.12.0", "0.13.0", "current"]);
+        assert_eq!(
+            matrix.versions()[1],
+            NodeVersion::Release {
+                version: Version::new(0, 12, 0),
+                jormungandr: PathBuf::from("0.12.0"),
+            }
+        );
+    }
+
+    #[test]
+    fn pair_summary_lists_the_failed_checks() {
+        let passed = pair("current", "current", CheckOutcome::Passed);
+        assert!(passed.is_compatible());
+        assert_eq!(passed.summary(), "ok");
+
+        let failed = PairReport {
+            sync: CheckOutcome::failed("no blocks".to_owned()),
+            tip: CheckOutcome::Skipped,
+            ..passed
+        };
+        assert!(!failed.is_compatible());
+        assert_eq!(failed.summary(), "sync,tip");
+    }
+
+    #[test]
+    fn report_renders_the_matrix_and_the_failures() {
+        let report = CompatibilityReport {
+            versions: vec!["0.12.0".to_owned(), "current".to_owned()],
+            pairs: vec![
+                pair("0.12.0", "0.12.0", CheckOutcome::Passed),
+                pair("0.12.0", "current", CheckOutcome::failed("lost".to_owned())),
+                pair("current", "0.12.0", CheckOutcome::Passed),
+                pair("current", "current", CheckOutcome::Passed),
+            ],
+        };
+        assert!(!report.is_compatible());
+        assert_eq!(
+            report.to_string(),
+            [
+                "leader \\ follower | 0.12.0 | current  ",
+                "0.12.0            | ok     | fragments",
+                "current           | ok     | ok       ",
+                "",
+                "leader 0.12.0 / follower current:",
+                "  sync: passed",
+                "  fragments: failed: lost",
+                "  tip: passed",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn missing_pairs_are_rendered_as_dashes() {
+        let report = CompatibilityReport {
+            versions: vec!["current".to_owned()],
+            pairs: Vec::new(),
+        };
+        assert!(report.is_compatible());
+        assert_eq!(
+            report.to_string(),
+            "leader \\ follower | current\ncurrent           | -      \n"
+        );
+    }
+}
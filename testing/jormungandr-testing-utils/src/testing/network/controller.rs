@@ -3,6 +3,7 @@ use crate::testing::jormungandr::ConfiguredStarter;
 use crate::testing::jormungandr::TestingDirectory;
 use crate::testing::network::VotePlanKey;
 use crate::testing::network::WalletAlias;
+use crate::testing::network::{FaultInjectionError, FaultInjector, LinkFaults};
 use crate::testing::node::configuration::legacy::NodeConfig as LegacyNodeConfig;
 use crate::testing::LegacyConfigConverterError;
 use crate::testing::LegacyNodeConfigConverter;
@@ -32,6 +33,7 @@ use chain_impl_mockchain::testing::scenario::template::{
     ProposalDefBuilder, VotePlanDef, VotePlanDefBuilder,
 };
 use jormungandr_lib::interfaces::{Log, LogEntry, LogOutput, NodeConfig};
+use multiaddr::Multiaddr;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -59,12 +61,17 @@ pub enum ControllerError {
     VotePlanNotFound(String),
     #[error(transparent)]
     LegacyConfigConverter(#[from] LegacyConfigConverterError),
+    #[error("fault injection is not enabled for this network")]
+    FaultInjectionDisabled,
+    #[error(transparent)]
+    FaultInjection(#[from] FaultInjectionError),
 }
 
 pub struct Controller {
     settings: Settings,
     working_directory: TestingDirectory,
     block0_file: PathBuf,
+    fault_injector: Option<FaultInjector>,
 }
 
 impl Controller {
@@ -82,9 +89,72 @@ impl Controller {
             settings,
             working_directory,
             block0_file,
+            fault_injector: None,
         })
     }
 
+    /// route every link of the topology through a proxy able to inject
+    /// faults, the nodes have to be spawned after this call to use it.
+    ///
+    /// The nodes only connect to their trusted peers: the local addresses
+    /// learned through gossip are ignored, a node would otherwise dial
+    /// them directly and bypass the proxies.
+    pub fn enable_fault_injection(&mut self) -> Result<(), ControllerError> {
+        if self.fault_injector.is_some() {
+            return Ok(());
+        }
+
+        let mut fault_injector = FaultInjector::default();
+        let addresses: Vec<(NodeAlias, Multiaddr)> = self
+            .settings
+            .nodes
+            .iter()
+            .map(|(alias, node)| (alias.clone(), node.config.p2p.public_address.clone()))
+            .collect();
+        for (alias, node) in self.settings.nodes.iter_mut() {
+            node.config.p2p.allow_private_addresses = false;
+            for peer in node.config.p2p.trusted_peers.iter_mut() {
+                if let Some((peer_alias, _)) = addresses
+                    .iter()
+                    .find(|(_, address)| *address == peer.address)
+                {
+                    peer.address = fault_injector.add_link(alias, peer_alias, &peer.address)?;
+                }
+            }
+        }
+        self.fault_injector = Some(fault_injector);
+        Ok(())
+    }
+
+    pub fn fault_injector(&self) -> Result<&FaultInjector, ControllerError> {
+        self.fault_injector
+            .as_ref()
+            .ok_or(ControllerError::FaultInjectionDisabled)
+    }
+
+    /// set the faults of the traffic sent from node `from` to node `to`
+    pub fn set_link_faults(
+        &self,
+        from: &str,
+        to: &str,
+        faults: LinkFaults,
+    ) -> Result<(), ControllerError> {
+        Ok(self.fault_injector()?.set_link_faults(from, to, faults)?)
+    }
+
+    /// address node `from` uses to reach node `to`, the one of the proxy of
+    /// their link when fault injection is enabled
+    pub fn peer_address(&self, from: &str, to: &str) -> Result<Multiaddr, ControllerError> {
+        if let Some(address) = self
+            .fault_injector
+            .as_ref()
+            .and_then(|fault_injector| fault_injector.proxy_address(from, to))
+        {
+            return Ok(address.clone());
+        }
+        Ok(self.node_settings(to)?.config.p2p.public_address.clone())
+    }
+
     pub fn wallet(&mut self, wallet: &str) -> Result<Wallet, ControllerError> {
         if let Some(wallet) = self.settings.wallets.remove(wallet) {
             Ok(wallet.into())
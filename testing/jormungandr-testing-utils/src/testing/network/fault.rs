@@ -0,0 +1,435 @@
+//! Fault injection between the nodes of a network.
+//!
+//! Every link of the [`Topology`](super::Topology) gets a local TCP proxy: the
+//! node dials the proxy instead of its trusted peer and the proxy forwards the
+//! traffic to the peer. Faults are set per direction, for the traffic sent
+//! from one node to another, and can be changed while the nodes are running.
+//!
+//! Only the connections opened to trusted peers go through the proxies, so
+//! the nodes of a network with fault injection enabled ignore the local
+//! addresses learned through gossip and never dial them directly.
+
+use super::NodeAlias;
+use jormungandr_lib::multiaddr::to_tcp_socket_addr;
+use multiaddr::{Multiaddr, Protocol};
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+const CHUNK_SIZE: usize = 16 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// extra delay of a lost chunk, TCP retransmits lost segments so the data
+/// arrives late rather than never
+const RETRANSMISSION_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Error, Debug)]
+pub enum FaultInjectionError {
+    #[error("no link between nodes '{from}' and '{to}'")]
+    LinkNotFound { from: NodeAlias, to: NodeAlias },
+    #[error("address {0} is not a TCP address")]
+    InvalidAddress(Multiaddr),
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+/// Faults applied to the traffic sent from a node to another.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkFaults {
+    /// delay added to every chunk of data
+    pub latency: Duration,
+    /// random delay between zero and this value added on top of the latency
+    pub jitter: Duration,
+    /// maximum throughput in bytes per second
+    pub bandwidth: Option<u64>,
+    /// probability, between 0 and 1, for a chunk of data to be lost and
+    /// retransmitted
+    pub loss: f64,
+    /// hold all the traffic until unblocked
+    pub blocked: bool,
+}
+
+impl LinkFaults {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn blocked() -> Self {
+        Self {
+            blocked: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_bandwidth(mut self, bytes_per_second: u64) -> Self {
+        self.bandwidth = Some(bytes_per_second);
+        self
+    }
+
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss.max(0.0).min(1.0);
+        self
+    }
+
+    fn delay<R: Rng>(&self, rng: &mut R) -> Duration {
+        let mut delay = self.latency;
+        if self.jitter > Duration::from_secs(0) {
+            delay += self.jitter.mul_f64(rng.gen::<f64>());
+        }
+        if self.loss > 0.0 && rng.gen_bool(self.loss.min(1.0)) {
+            delay += RETRANSMISSION_DELAY;
+        }
+        delay
+    }
+}
+
+type SharedFaults = Arc<RwLock<LinkFaults>>;
+
+/// Proxy for the connections a node opens to one of its trusted peers.
+struct LinkProxy {
+    address: Multiaddr,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl LinkProxy {
+    fn start(
+        target: SocketAddr,
+        upstream: SharedFaults,
+        downstream: SharedFaults,
+    ) -> Result<Self, FaultInjectionError> {
+        let listener = TcpListener::bind((target.ip(), 0))?;
+        listener.set_nonblocking(true)?;
+        let local = listener.local_addr()?;
+        let address = Multiaddr::from(local.ip()).with(Protocol::Tcp(local.port()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = Arc::clone(&shutdown);
+        thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((client, _)) => {
+                        if let Ok(server) = TcpStream::connect(target) {
+                            let _ = forward(
+                                client,
+                                server,
+                                Arc::clone(&upstream),
+                                Arc::clone(&downstream),
+                                Arc::clone(&stop),
+                            );
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(Self { address, shutdown })
+    }
+}
+
+impl Drop for LinkProxy {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+fn forward(
+    client: TcpStream,
+    server: TcpStream,
+    upstream: SharedFaults,
+    downstream: SharedFaults,
+    shutdown: Arc<AtomicBool>,
+) -> io::Result<()> {
+    client.set_nonblocking(false)?;
+    for stream in &[&client, &server] {
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        stream.set_nodelay(true)?;
+    }
+    pipe(
+        client.try_clone()?,
+        server.try_clone()?,
+        upstream,
+        Arc::clone(&shutdown),
+    );
+    pipe(server, client, downstream, shutdown);
+    Ok(())
+}
+
+struct Chunk {
+    data: Vec<u8>,
+    deliver_at: Instant,
+    bandwidth: Option<u64>,
+}
+
+/// copy the data read from `from` to `to`, delaying it according to the
+/// faults. The reading and the writing run on their own threads so the
+/// latency does not limit the throughput.
+fn pipe(from: TcpStream, to: TcpStream, faults: SharedFaults, shutdown: Arc<AtomicBool>) {
+    let (sender, receiver) = mpsc::channel();
+    let read_shutdown = Arc::clone(&shutdown);
+    thread::spawn(move || read_chunks(from, sender, faults, read_shutdown));
+    thread::spawn(move || write_chunks(to, receiver, shutdown));
+}
+
+fn read_chunks(
+    mut from: TcpStream,
+    sender: Sender<Chunk>,
+    faults: SharedFaults,
+    shutdown: Arc<AtomicBool>,
+) {
+    let mut rng = rand::thread_rng();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut last_delivery = Instant::now();
+    while !shutdown.load(Ordering::SeqCst) {
+        let read = match from.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(_) => break,
+        };
+
+        // a blocked link holds the data, as TCP would keep retransmitting it
+        let faults = loop {
+            let faults = faults.read().unwrap().clone();
+            if !faults.blocked || shutdown.load(Ordering::SeqCst) {
+                break faults;
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        // chunks are delivered in order, whatever their own delay
+        last_delivery = last_delivery.max(Instant::now() + faults.delay(&mut rng));
+        let chunk = Chunk {
+            data: buffer[..read].to_vec(),
+            deliver_at: last_delivery,
+            bandwidth: faults.bandwidth,
+        };
+        if sender.send(chunk).is_err() {
+            break;
+        }
+    }
+    let _ = from.shutdown(Shutdown::Read);
+}
+
+fn write_chunks(mut to: TcpStream, receiver: Receiver<Chunk>, shutdown: Arc<AtomicBool>) {
+    while !shutdown.load(Ordering::SeqCst) {
+        let chunk = match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(chunk) => chunk,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let now = Instant::now();
+        if chunk.deliver_at > now {
+            thread::sleep(chunk.deliver_at - now);
+        }
+        if to.write_all(&chunk.data).is_err() {
+            break;
+        }
+        if let Some(bandwidth) = chunk.bandwidth.filter(|bandwidth| *bandwidth > 0) {
+            thread::sleep(Duration::from_secs_f64(
+                chunk.data.len() as f64 / bandwidth as f64,
+            ));
+        }
+    }
+    let _ = to.shutdown(Shutdown::Write);
+}
+
+/// Proxies of all the links of a network and the faults applied to them.
+#[derive(Default)]
+pub struct FaultInjector {
+    /// proxy used by the first node to reach the second one
+    proxies: HashMap<(NodeAlias, NodeAlias), LinkProxy>,
+    /// faults of the traffic sent from the first node to the second one
+    faults: HashMap<(NodeAlias, NodeAlias), SharedFaults>,
+}
+
+impl FaultInjector {
+    /// start a proxy for the connections `from` opens to `to`, listening at
+    /// `to_address`. Returns the address `from` has to dial.
+    pub fn add_link(
+        &mut self,
+        from: &str,
+        to: &str,
+        to_address: &Multiaddr,
+    ) -> Result<Multiaddr, FaultInjectionError> {
+        let target = to_tcp_socket_addr(to_address)
+            .ok_or_else(|| FaultInjectionError::InvalidAddress(to_address.clone()))?;
+        let upstream = self.shared_faults(from, to);
+        let downstream = self.shared_faults(to, from);
+        let proxy = LinkProxy::start(target, upstream, downstream)?;
+        let address = proxy.address.clone();
+        self.proxies.insert((from.to_owned(), to.to_owned()), proxy);
+        Ok(address)
+    }
+
+    fn shared_faults(&mut self, from: &str, to: &str) -> SharedFaults {
+        Arc::clone(
+            self.faults
+                .entry((from.to_owned(), to.to_owned()))
+                .or_default(),
+        )
+    }
+
+    /// address of the proxy `from` dials to reach `to`, if they are linked
+    pub fn proxy_address(&self, from: &str, to: &str) -> Option<&Multiaddr> {
+        self.proxies
+            .get(&(from.to_owned(), to.to_owned()))
+            .map(|proxy| &proxy.address)
+    }
+
+    fn faults(&self, from: &str, to: &str) -> Result<&SharedFaults, FaultInjectionError> {
+        self.faults
+            .get(&(from.to_owned(), to.to_owned()))
+            .ok_or_else(|| FaultInjectionError::LinkNotFound {
+                from: from.to_owned(),
+                to: to.to_owned(),
+            })
+    }
+
+    pub fn link_faults(&self, from: &str, to: &str) -> Result<LinkFaults, FaultInjectionError> {
+        Ok(self.faults(from, to)?.read().unwrap().clone())
+    }
+
+    /// set the faults of the traffic sent from `from` to `to`, the traffic
+    /// in the other direction is left as is
+    pub fn set_link_faults(
+        &self,
+        from: &str,
+        to: &str,
+        faults: LinkFaults,
+    ) -> Result<(), FaultInjectionError> {
+        *self.faults(from, to)?.write().unwrap() = faults;
+        Ok(())
+    }
+
+    /// block the traffic sent from `from` to `to` while `to` can still send
+    /// data to `from`
+    pub fn partition_one_way(&self, from: &str, to: &str) -> Result<(), FaultInjectionError> {
+        self.set_link_faults(from, to, LinkFaults::blocked())
+    }
+
+    /// remove the faults of all the links
+    pub fn clear(&self) {
+        for faults in self.faults.values() {
+            *faults.write().unwrap() = LinkFaults::none();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// start a server sending back everything it receives
+    fn echo_server() -> Multiaddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let local = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                thread::spawn(move || {
+                    let mut buffer = [0; CHUNK_SIZE];
+                    while let Ok(read) = stream.read(&mut buffer) {
+                        if read == 0 || stream.write_all(&buffer[..read]).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        Multiaddr::from(local.ip()).with(Protocol::Tcp(local.port()))
+    }
+
+    fn connect(injector: &FaultInjector) -> TcpStream {
+        let address = injector.proxy_address("client", "server").unwrap();
+        let stream = TcpStream::connect(to_tcp_socket_addr(address).unwrap()).unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        stream
+    }
+
+    /// send the data through the proxy and wait for it to come back
+    fn round_trip(stream: &mut TcpStream, data: &[u8]) -> Duration {
+        let start = Instant::now();
+        stream.write_all(data).unwrap();
+        let mut echo = vec![0; data.len()];
+        stream.read_exact(&mut echo).unwrap();
+        assert_eq!(echo, data);
+        start.elapsed()
+    }
+
+    #[test]
+    fn proxy_applies_and_clears_the_faults() {
+        let mut injector = FaultInjector::default();
+        injector
+            .add_link("client", "server", &echo_server())
+            .unwrap();
+        let mut stream = connect(&injector);
+        assert!(round_trip(&mut stream, b"no faults") < Duration::from_millis(500));
+
+        let latency = Duration::from_millis(300);
+        injector
+            .set_link_faults("client", "server", LinkFaults::none().with_latency(latency))
+            .unwrap();
+        assert_eq!(
+            injector.link_faults("client", "server").unwrap().latency,
+            latency
+        );
+        assert!(round_trip(&mut stream, b"upstream latency") >= latency);
+
+        // the data sent by the client is held until the link is unblocked
+        injector.partition_one_way("client", "server").unwrap();
+        stream.write_all(b"blocked").unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        let mut buffer = [0; 16];
+        let error = stream.read(&mut buffer).unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::WouldBlock | ErrorKind::TimedOut
+        ));
+
+        injector.clear();
+        assert_eq!(
+            injector.link_faults("client", "server").unwrap(),
+            LinkFaults::none()
+        );
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        let mut echo = [0; 7];
+        stream.read_exact(&mut echo).unwrap();
+        assert_eq!(&echo, b"blocked");
+        assert!(round_trip(&mut stream, b"cleared") < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn faults_of_unknown_links_are_rejected() {
+        let mut injector = FaultInjector::default();
+        injector
+            .add_link("client", "server", &echo_server())
+            .unwrap();
+        assert!(injector.link_faults("server", "client").is_ok());
+        assert!(matches!(
+            injector.set_link_faults("client", "other", LinkFaults::blocked()),
+            Err(FaultInjectionError::LinkNotFound { .. })
+        ));
+        assert!(injector.proxy_address("server", "client").is_none());
+    }
+}
@@ -1,6 +1,8 @@
 pub mod blockchain;
 pub mod builder;
+pub mod compatibility;
 pub mod controller;
+pub mod fault;
 pub mod rng;
 pub mod settings;
 pub mod spawn_params;
@@ -11,6 +13,10 @@ pub mod wallet;
 
 pub use blockchain::Blockchain;
 use chain_impl_mockchain::header::HeaderId;
+pub use compatibility::{
+    CheckOutcome, CompatibilityMatrix, CompatibilityReport, NodeVersion, PairReport,
+};
+pub use fault::{FaultInjectionError, FaultInjector, LinkFaults};
 pub use rng::{Random, Seed};
 use serde::Deserialize;
 pub use settings::{NodeSetting, Settings};
@@ -5,6 +5,7 @@ pub type PoolId = String;
 pub type Value = String;
 pub type NonZero = String;
 pub type VotePlanId = String;
+pub type PublicKey = String;
 
 use graphql_client::GraphQLQuery;
 
@@ -16,6 +17,14 @@ use graphql_client::GraphQLQuery;
 )]
 pub struct Address;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/address_balance.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct AddressBalance;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "resources/explorer/graphql/allblocks.graphql",
@@ -40,6 +49,14 @@ pub struct AllStakePools;
 )]
 pub struct BlocksByChainLength;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/config_updates.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug,PartialEq"
+)]
+pub struct ConfigUpdates;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "resources/explorer/graphql/epoch.graphql",
@@ -80,6 +97,14 @@ pub struct Settings;
 )]
 pub struct TransactionById;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/update_proposal.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug,PartialEq"
+)]
+pub struct UpdateProposal;
+
 #[derive(GraphQLQuery)]
 #[allow(clippy::upper_case_acronyms)]
 #[graphql(
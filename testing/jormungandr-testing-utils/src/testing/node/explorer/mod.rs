@@ -1,9 +1,11 @@
 use self::{
     client::GraphQlClient,
     data::{
-        address, all_blocks, all_stake_pools, all_vote_plans, blocks_by_chain_length, epoch,
-        last_block, settings, stake_pool, transaction_by_id, Address, AllBlocks, AllStakePools,
-        AllVotePlans, BlocksByChainLength, Epoch, LastBlock, Settings, StakePool, TransactionById,
+        address, address_balance, all_blocks, all_stake_pools, all_vote_plans,
+        blocks_by_chain_length, config_updates, epoch, last_block, settings, stake_pool,
+        transaction_by_id, update_proposal, Address, AddressBalance, AllBlocks, AllStakePools,
+        AllVotePlans, BlocksByChainLength, ConfigUpdates, Epoch, LastBlock, Settings, StakePool,
+        TransactionById, UpdateProposal,
     },
 };
 use graphql_client::GraphQLQuery;
@@ -16,8 +18,11 @@ mod client;
 // do not respect the naming convention
 #[allow(clippy::upper_case_acronyms)]
 mod data;
+mod subscription;
 mod wrappers;
 
+pub use data::{config_updates, update_proposal};
+pub use subscription::{ExplorerSubscription, SubscriptionError};
 pub use wrappers::LastBlockResponse;
 
 pub mod load;
@@ -26,6 +31,7 @@ use jortestkit::file;
 use serde::Serialize;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -40,16 +46,19 @@ pub enum ExplorerError {
 
 #[derive(Clone)]
 pub struct Explorer {
+    address: String,
     client: GraphQlClient,
     print_log: bool,
 }
 
 impl Explorer {
     pub fn new<S: Into<String>>(address: S) -> Explorer {
+        let address = address.into();
         let print_log = true;
 
         Explorer {
-            client: GraphQlClient::new(address),
+            client: GraphQlClient::new(address.clone()),
+            address,
             print_log,
         }
     }
@@ -90,6 +99,22 @@ impl Explorer {
         Ok(response_body)
     }
 
+    pub fn address_balance<S: Into<String>>(
+        &self,
+        bech32_address: S,
+        block: Option<Hash>,
+    ) -> Result<Response<address_balance::ResponseData>, ExplorerError> {
+        let query = AddressBalance::build_query(address_balance::Variables {
+            bech32: bech32_address.into(),
+            block: block.map(|hash| hash.to_string()),
+        });
+        self.print_request(&query);
+        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
+        let response_body = response.json()?;
+        self.print_log(&response_body);
+        Ok(response_body)
+    }
+
     pub fn stake_pools(
         &self,
         limit: i64,
@@ -198,6 +223,59 @@ impl Explorer {
         Ok(response_body)
     }
 
+    pub fn update_proposal(
+        &self,
+        id: Hash,
+    ) -> Result<Response<update_proposal::ResponseData>, ExplorerError> {
+        let query = UpdateProposal::build_query(update_proposal::Variables { id: id.to_string() });
+        self.print_request(&query);
+        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
+        let response_body = response.json()?;
+        self.print_log(&response_body);
+        Ok(response_body)
+    }
+
+    pub fn config_updates(
+        &self,
+        epoch_number: u32,
+    ) -> Result<Response<config_updates::ResponseData>, ExplorerError> {
+        let query = ConfigUpdates::build_query(config_updates::Variables {
+            epoch: epoch_number.to_string(),
+        });
+        self.print_request(&query);
+        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
+        let response_body = response.json()?;
+        self.print_log(&response_body);
+        Ok(response_body)
+    }
+
+    pub fn subscribe_blocks(
+        &self,
+        timeout: Duration,
+    ) -> Result<ExplorerSubscription, SubscriptionError> {
+        ExplorerSubscription::start(
+            &self.address,
+            include_str!("../../../../resources/explorer/graphql/subscription_blocks.graphql"),
+            serde_json::Value::Null,
+            timeout,
+        )
+    }
+
+    pub fn subscribe_transactions_by_address<S: Into<String>>(
+        &self,
+        bech32_address: S,
+        timeout: Duration,
+    ) -> Result<ExplorerSubscription, SubscriptionError> {
+        ExplorerSubscription::start(
+            &self.address,
+            include_str!(
+                "../../../../resources/explorer/graphql/subscription_transactions_by_address.graphql"
+            ),
+            serde_json::json!({ "bech32": bech32_address.into() }),
+            timeout,
+        )
+    }
+
     pub fn current_time(&self) -> BlockDate {
         self.last_block().unwrap().block_date()
     }
@@ -0,0 +1,105 @@
+use serde_json::{json, Value};
+use std::net::TcpStream;
+use std::time::Duration;
+use thiserror::Error;
+use tungstenite::{client::IntoClientRequest, http::HeaderValue, Message, WebSocket};
+
+const SUBSCRIPTION_ID: &str = "1";
+
+#[derive(Error, Debug)]
+pub enum SubscriptionError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+    #[error("websocket error")]
+    WebSocket(#[from] tungstenite::Error),
+    #[error("websocket handshake failed: {0}")]
+    Handshake(String),
+    #[error("json serializiation error")]
+    Serialization(#[from] serde_json::Error),
+    #[error("unexpected message: {0}")]
+    UnexpectedMessage(String),
+    #[error("subscription returned errors: {0}")]
+    Errors(Value),
+}
+
+/// A GraphQL subscription to the explorer, over the `graphql-ws` websocket
+/// protocol
+pub struct ExplorerSubscription {
+    socket: WebSocket<TcpStream>,
+}
+
+impl ExplorerSubscription {
+    /// starts the subscription, reading an event fails once no message
+    /// came in for `timeout`
+    pub fn start(
+        address: &str,
+        query: &str,
+        variables: Value,
+        timeout: Duration,
+    ) -> Result<Self, SubscriptionError> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(timeout))?;
+
+        let mut request =
+            format!("ws://{}/explorer/subscription", address).into_client_request()?;
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            HeaderValue::from_static("graphql-ws"),
+        );
+        let (socket, _response) = tungstenite::client(request, stream)
+            .map_err(|error| SubscriptionError::Handshake(error.to_string()))?;
+
+        let mut subscription = ExplorerSubscription { socket };
+        subscription.send(json!({ "type": "connection_init", "payload": {} }))?;
+        let message = subscription.read()?;
+        if message["type"] != "connection_ack" {
+            return Err(SubscriptionError::UnexpectedMessage(message.to_string()));
+        }
+        subscription.send(json!({
+            "id": SUBSCRIPTION_ID,
+            "type": "start",
+            "payload": { "query": query, "variables": variables },
+        }))?;
+
+        Ok(subscription)
+    }
+
+    /// the data of the next event, waiting for it to come
+    pub fn next_event(&mut self) -> Result<Value, SubscriptionError> {
+        loop {
+            let message = self.read()?;
+            match message["type"].as_str() {
+                Some("data") => {
+                    let payload = &message["payload"];
+                    if let Some(errors) = payload.get("errors") {
+                        return Err(SubscriptionError::Errors(errors.clone()));
+                    }
+                    return Ok(payload["data"].clone());
+                }
+                Some("ka") => continue,
+                _ => return Err(SubscriptionError::UnexpectedMessage(message.to_string())),
+            }
+        }
+    }
+
+    fn send(&mut self, message: Value) -> Result<(), SubscriptionError> {
+        self.socket
+            .write_message(Message::Text(message.to_string()))
+            .map_err(Into::into)
+    }
+
+    fn read(&mut self) -> Result<Value, SubscriptionError> {
+        loop {
+            match self.socket.read_message()? {
+                Message::Text(text) => return Ok(serde_json::from_str(&text)?),
+                Message::Ping(_) | Message::Pong(_) => continue,
+                message => {
+                    return Err(SubscriptionError::UnexpectedMessage(format!(
+                        "{:?}",
+                        message
+                    )))
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod client;
 pub mod server;
+pub mod traffic;
 
 pub use client::JormungandrClient;
 pub use server::JormungandrServerImpl;
@@ -13,12 +13,14 @@ use tonic::transport::Server;
 
 use crate::testing::configuration::get_available_port;
 use crate::testing::node::grpc::server::NodeServer;
+use crate::testing::node::grpc::traffic::TrafficRecorder;
 
 pub struct MockBuilder {
     mock_port: Option<u16>,
     genesis_block: Option<Block>,
     protocol_version: ProtocolVersion,
     invalid_block0_hash: bool,
+    traffic_recorder: Option<TrafficRecorder>,
 }
 
 impl Default for MockBuilder {
@@ -28,6 +30,7 @@ impl Default for MockBuilder {
             genesis_block: None,
             protocol_version: ProtocolVersion::GenesisPraos,
             invalid_block0_hash: false,
+            traffic_recorder: None,
         }
     }
 }
@@ -53,6 +56,11 @@ impl MockBuilder {
         self
     }
 
+    pub fn with_traffic_recorder(&mut self, recorder: TrafficRecorder) -> &mut Self {
+        self.traffic_recorder = Some(recorder);
+        self
+    }
+
     pub fn build_data(&self) -> Arc<RwLock<MockServerData>> {
         let storage = BlockStore::memory(Hash::zero_hash().as_bytes().to_owned()).unwrap();
         let block0 = if let Some(block) = self.genesis_block.clone().take() {
@@ -63,7 +71,7 @@ impl MockBuilder {
             super::data::block0()
         };
 
-        let data = MockServerData::new(
+        let mut data = MockServerData::new(
             block0.header().hash(),
             self.protocol_version.clone(),
             format!(
@@ -76,6 +84,10 @@ impl MockBuilder {
             self.invalid_block0_hash,
         );
 
+        if let Some(recorder) = self.traffic_recorder.clone() {
+            data.set_traffic_recorder(recorder);
+        }
+
         data.put_block(&block0).unwrap();
         data.set_tip(block0.header().hash().serialize_as_vec().unwrap().as_ref())
             .unwrap();
@@ -1,4 +1,5 @@
 use super::ProtocolVersion;
+use crate::testing::node::grpc::traffic::TrafficRecorder;
 use chain_core::{
     mempack::{ReadBuf, ReadError, Readable},
     property::Serialize,
@@ -23,6 +24,7 @@ pub struct MockServerData {
     auth_nonce: [u8; AUTH_NONCE_LEN],
     storage: BlockStore,
     invalid_block0_hash: bool,
+    traffic_recorder: Option<TrafficRecorder>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -55,6 +57,7 @@ impl MockServerData {
             auth_nonce: [0; AUTH_NONCE_LEN],
             storage,
             invalid_block0_hash: invalid_get_blocks_hash,
+            traffic_recorder: None,
         }
     }
 
@@ -153,6 +156,15 @@ impl MockServerData {
     pub fn invalid_block0_hash(&self) -> bool {
         self.invalid_block0_hash
     }
+
+    pub fn traffic_recorder(&self) -> Option<&TrafficRecorder> {
+        self.traffic_recorder.as_ref()
+    }
+
+    /// record the traffic pushed by the peers connected to the mock
+    pub fn set_traffic_recorder(&mut self, recorder: TrafficRecorder) {
+        self.traffic_recorder = Some(recorder);
+    }
 }
 
 pub fn block0() -> Block {
@@ -8,6 +8,7 @@ use crate::testing::{
             PushHeadersResponse, TipRequest, TipResponse, UploadBlocksResponse,
         },
     },
+    node::grpc::traffic::TrafficEvent,
     Block0ConfigurationBuilder,
 };
 use chain_core::{
@@ -296,57 +297,97 @@ impl Node for JormungandrServerImpl {
     }
     async fn push_headers(
         &self,
-        _request: tonic::Request<tonic::Streaming<Header>>,
+        request: tonic::Request<tonic::Streaming<Header>>,
     ) -> Result<tonic::Response<PushHeadersResponse>, tonic::Status> {
         info!(
             method = %MethodType::PushHeaders,
             "Push headers method received",
         );
+        let recorder = self.data.read().unwrap().traffic_recorder().cloned();
+        if let Some(recorder) = recorder {
+            let mut stream = request.into_inner();
+            let mut headers = Vec::new();
+            while let Some(header) = stream.message().await? {
+                headers.push(header.content.into());
+            }
+            recorder.record(TrafficEvent::PushedHeaders { headers });
+        }
         Ok(Response::new(PushHeadersResponse::default()))
     }
     async fn upload_blocks(
         &self,
-        _request: tonic::Request<tonic::Streaming<Block>>,
+        request: tonic::Request<tonic::Streaming<Block>>,
     ) -> Result<tonic::Response<UploadBlocksResponse>, tonic::Status> {
         info!(
             method = %MethodType::UploadBlocks,
             "Upload blocks method received",
         );
+        let recorder = self.data.read().unwrap().traffic_recorder().cloned();
+        if let Some(recorder) = recorder {
+            let mut stream = request.into_inner();
+            let mut blocks = Vec::new();
+            while let Some(block) = stream.message().await? {
+                blocks.push(block.content.into());
+            }
+            recorder.record(TrafficEvent::UploadedBlocks { blocks });
+        }
         Ok(Response::new(UploadBlocksResponse::default()))
     }
 
     async fn block_subscription(
         &self,
-        _request: tonic::Request<tonic::Streaming<Header>>,
+        request: tonic::Request<tonic::Streaming<Header>>,
     ) -> Result<tonic::Response<Self::BlockSubscriptionStream>, tonic::Status> {
         info!(
             method = %MethodType::BlockSubscription,
             "Block subscription event received",
         );
-        let (_tx, rx) = mpsc::channel(1);
+        let (tx, rx) = mpsc::channel(1);
+        let recorder = self.data.read().unwrap().traffic_recorder().cloned();
+        if let Some(recorder) = recorder {
+            recorder.spawn_subscription(request.into_inner(), tx, |header| {
+                TrafficEvent::BlockAnnouncement {
+                    header: header.content.into(),
+                }
+            });
+        }
         Ok(Response::new(ReceiverStream::new(rx)))
     }
 
     async fn fragment_subscription(
         &self,
-        _request: tonic::Request<tonic::Streaming<Fragment>>,
+        request: tonic::Request<tonic::Streaming<Fragment>>,
     ) -> Result<tonic::Response<Self::FragmentSubscriptionStream>, tonic::Status> {
         info!(
             method = %MethodType::FragmentSubscription,
             "Fragment subscription event received",
         );
-        let (_tx, rx) = mpsc::channel(1);
+        let (tx, rx) = mpsc::channel(1);
+        let recorder = self.data.read().unwrap().traffic_recorder().cloned();
+        if let Some(recorder) = recorder {
+            recorder.spawn_subscription(request.into_inner(), tx, |fragment| {
+                TrafficEvent::Fragment {
+                    fragment: fragment.content.into(),
+                }
+            });
+        }
         Ok(Response::new(ReceiverStream::new(rx)))
     }
     async fn gossip_subscription(
         &self,
-        _request: tonic::Request<tonic::Streaming<Gossip>>,
+        request: tonic::Request<tonic::Streaming<Gossip>>,
     ) -> Result<tonic::Response<Self::GossipSubscriptionStream>, tonic::Status> {
         info!(
             method = %MethodType::GossipSubscription,
             "Gossip subscription event received",
         );
-        let (_tx, rx) = mpsc::channel(1);
+        let (tx, rx) = mpsc::channel(1);
+        let recorder = self.data.read().unwrap().traffic_recorder().cloned();
+        if let Some(recorder) = recorder {
+            recorder.spawn_subscription(request.into_inner(), tx, |gossip| TrafficEvent::Gossip {
+                nodes: gossip.nodes.into_iter().map(Into::into).collect(),
+            });
+        }
         Ok(Response::new(ReceiverStream::new(rx)))
     }
 }
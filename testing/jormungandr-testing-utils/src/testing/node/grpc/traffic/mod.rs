@@ -0,0 +1,226 @@
+//! Capture of the gRPC traffic exchanged with a node, so that it can be
+//! replayed later against a node under test.
+//!
+//! A [`TrafficRecorder`] is attached to the mock server (see
+//! [`MockBuilder::with_traffic_recorder`](super::server::MockBuilder::with_traffic_recorder))
+//! and records everything a real node pushes to it: block announcements,
+//! fragments, gossip, pushed headers and uploaded blocks. Responses to pull
+//! requests made with a [`JormungandrClient`](super::JormungandrClient) can be
+//! recorded as well. The resulting [`TrafficCapture`] is saved as a json file
+//! and replayed with a [`TrafficReplayer`].
+
+mod recorder;
+mod replayer;
+
+pub use recorder::TrafficRecorder;
+pub use replayer::{ReplayReport, ReplayTiming, ReplayTimingError, TrafficReplayer};
+
+use chain_core::mempack::{ReadBuf, ReadError, Readable};
+use chain_impl_mockchain::{
+    block::{Block, Header},
+    key::Hash,
+};
+use jormungandr_lib::crypto::hash::Hash as LibHash;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+pub const TRAFFIC_CAPTURE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum TrafficError {
+    #[error("cannot access the capture file")]
+    Io(#[from] std::io::Error),
+    #[error("malformed capture file")]
+    Serialization(#[from] serde_json::Error),
+    #[error("unsupported capture version {0}")]
+    UnsupportedVersion(u32),
+    #[error("captured message cannot be decoded")]
+    MalformedMessage(#[from] ReadError),
+    #[error("cannot connect to the node")]
+    Transport(#[from] tonic::transport::Error),
+    #[error("request to the node failed: {}", .0.message())]
+    Request(#[from] tonic::Status),
+    #[error("capture was recorded on block0 {capture}, the node runs block0 {node}")]
+    Block0Mismatch { capture: LibHash, node: LibHash },
+    #[error(transparent)]
+    Timing(#[from] ReplayTimingError),
+}
+
+/// raw bytes of a captured message, stored as an hexadecimal string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payload(Vec<u8>);
+
+impl Payload {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn decode<T: Readable>(&self) -> Result<T, ReadError> {
+        T::read(&mut ReadBuf::from(&self.0[..]))
+    }
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Payload> for Vec<u8> {
+    fn from(payload: Payload) -> Self {
+        payload.0
+    }
+}
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(s).map(Self).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrafficEvent {
+    /// header received on the block subscription
+    BlockAnnouncement { header: Payload },
+    /// fragment received on the fragment subscription
+    Fragment { fragment: Payload },
+    /// node descriptions received on the gossip subscription
+    Gossip { nodes: Vec<Payload> },
+    /// headers received through a PushHeaders call
+    PushedHeaders { headers: Vec<Payload> },
+    /// blocks received through an UploadBlocks call
+    UploadedBlocks { blocks: Vec<Payload> },
+    /// blocks returned by the node for a PullBlocks request
+    PullBlocksResponse {
+        from: Vec<Payload>,
+        to: Payload,
+        blocks: Vec<Payload>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedEvent {
+    /// time elapsed since the start of the recording, in milliseconds
+    pub offset: u64,
+    #[serde(flatten)]
+    pub event: TrafficEvent,
+}
+
+impl CapturedEvent {
+    pub fn offset(&self) -> Duration {
+        Duration::from_millis(self.offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficCapture {
+    pub version: u32,
+    pub block0: LibHash,
+    pub events: Vec<CapturedEvent>,
+}
+
+impl TrafficCapture {
+    pub fn new(block0: Hash) -> Self {
+        Self {
+            version: TRAFFIC_CAPTURE_VERSION,
+            block0: block0.into(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TrafficError> {
+        let file = BufReader::new(File::open(path)?);
+        let capture: Self = serde_json::from_reader(file)?;
+        if capture.version != TRAFFIC_CAPTURE_VERSION {
+            return Err(TrafficError::UnsupportedVersion(capture.version));
+        }
+        Ok(capture)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TrafficError> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(serde_json::to_writer_pretty(file, self)?)
+    }
+
+    /// index every header and block carried by the captured events
+    pub fn chain_index(&self) -> Result<ChainIndex, TrafficError> {
+        let mut index = ChainIndex::default();
+        for captured in &self.events {
+            match &captured.event {
+                TrafficEvent::BlockAnnouncement { header } => {
+                    index.add_header(header.decode()?);
+                }
+                TrafficEvent::PushedHeaders { headers } => {
+                    for header in headers {
+                        index.add_header(header.decode()?);
+                    }
+                }
+                TrafficEvent::UploadedBlocks { blocks }
+                | TrafficEvent::PullBlocksResponse { blocks, .. } => {
+                    for block in blocks {
+                        index.add_block(block.decode()?);
+                    }
+                }
+                TrafficEvent::Fragment { .. } | TrafficEvent::Gossip { .. } => {}
+            }
+        }
+        Ok(index)
+    }
+}
+
+/// headers and blocks of a capture, used by the replayer to answer the
+/// solicitations of the node under test
+#[derive(Debug, Default)]
+pub struct ChainIndex {
+    headers: HashMap<Hash, Header>,
+    blocks: HashMap<Hash, Block>,
+}
+
+impl ChainIndex {
+    fn add_header(&mut self, header: Header) {
+        self.headers.insert(header.hash(), header);
+    }
+
+    fn add_block(&mut self, block: Block) {
+        let header = block.header().clone();
+        self.blocks.insert(header.hash(), block);
+        self.add_header(header);
+    }
+
+    pub fn header(&self, id: &Hash) -> Option<&Header> {
+        self.headers.get(id)
+    }
+
+    pub fn block(&self, id: &Hash) -> Option<&Block> {
+        self.blocks.get(id)
+    }
+
+    /// headers from the first known ancestor of `to` following one of `from`
+    /// up to `to`, in chain order
+    pub fn headers_between(&self, from: &[Hash], to: &Hash) -> Vec<Header> {
+        let mut headers = Vec::new();
+        let mut current = self.headers.get(to);
+        while let Some(header) = current {
+            if from.contains(&header.hash()) {
+                break;
+            }
+            headers.push(header.clone());
+            current = self.headers.get(&header.block_parent_hash());
+        }
+        headers.reverse();
+        headers
+    }
+}
@@ -0,0 +1,91 @@
+use super::{CapturedEvent, TrafficCapture, TrafficError, TrafficEvent};
+use crate::testing::node::grpc::{client::MockClientError, JormungandrClient};
+use chain_core::property::Serialize;
+use chain_impl_mockchain::{block::Block, key::Hash};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tonic::{Status, Streaming};
+
+/// Shared handle recording traffic events in the order they are received.
+///
+/// Clones of the recorder append to the same capture.
+#[derive(Clone)]
+pub struct TrafficRecorder {
+    started: Instant,
+    capture: Arc<Mutex<TrafficCapture>>,
+}
+
+impl TrafficRecorder {
+    pub fn new(block0: Hash) -> Self {
+        Self {
+            started: Instant::now(),
+            capture: Arc::new(Mutex::new(TrafficCapture::new(block0))),
+        }
+    }
+
+    pub fn record(&self, event: TrafficEvent) {
+        let offset = self.started.elapsed().as_millis() as u64;
+        self.capture
+            .lock()
+            .unwrap()
+            .events
+            .push(CapturedEvent { offset, event });
+    }
+
+    /// pull blocks from the node and record its response
+    pub fn record_pull_blocks(
+        &self,
+        client: &JormungandrClient,
+        from: &[Hash],
+        to: Hash,
+    ) -> Result<Vec<Block>, MockClientError> {
+        let blocks = client.pull_blocks(from, to)?;
+        self.record(TrafficEvent::PullBlocksResponse {
+            from: from.iter().map(|id| id.as_ref().to_vec().into()).collect(),
+            to: to.as_ref().to_vec().into(),
+            blocks: blocks
+                .iter()
+                .map(|block| block.serialize_as_vec().unwrap().into())
+                .collect(),
+        });
+        Ok(blocks)
+    }
+
+    pub fn events_count(&self) -> usize {
+        self.capture.lock().unwrap().events.len()
+    }
+
+    pub fn capture(&self) -> TrafficCapture {
+        self.capture.lock().unwrap().clone()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), TrafficError> {
+        self.capture().save(path)
+    }
+
+    /// Record every message of an inbound subscription stream.
+    ///
+    /// `outbound` is the sending half of the subscription response, it is
+    /// kept open until the inbound stream ends so the peer does not consider
+    /// the subscription closed.
+    pub(crate) fn spawn_subscription<T, R, F>(
+        &self,
+        mut inbound: Streaming<T>,
+        outbound: mpsc::Sender<Result<R, Status>>,
+        to_event: F,
+    ) where
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> TrafficEvent + Send + 'static,
+    {
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Ok(Some(message)) = inbound.message().await {
+                recorder.record(to_event(message));
+            }
+            drop(outbound);
+        });
+    }
+}
@@ -0,0 +1,399 @@
+use super::{ChainIndex, TrafficCapture, TrafficError, TrafficEvent};
+use crate::testing::node::grpc::proto::{
+    block_event, node_client::NodeClient, Block, BlockEvent, ClientAuthRequest, Fragment, Gossip,
+    HandshakeRequest, Header, PullHeadersRequest,
+};
+use chain_core::{
+    mempack::{ReadBuf, Readable},
+    property::Serialize,
+};
+use chain_crypto::{Ed25519, KeyPair};
+use chain_impl_mockchain::key::Hash;
+use futures::stream;
+use rand::Rng;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Builder;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Channel, Request, Streaming};
+
+const SUBSCRIPTION_BUFFER: usize = 64;
+const DEFAULT_LINGER: Duration = Duration::from_secs(10);
+
+/// pace at which the captured events are sent to the node under test
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayTiming {
+    /// keep the delays between events as recorded
+    Recorded,
+    /// divide the recorded delays by the given factor, which must be greater
+    /// than 0, see [`ReplayTiming::accelerated`]
+    Accelerated(f64),
+    /// send the events back to back
+    Immediate,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ReplayTimingError {
+    #[error("acceleration factor {0} is not a number greater than 0")]
+    InvalidFactor(f64),
+    #[error(
+        "unknown replay timing '{0}', expected 'recorded', 'immediate' or an acceleration factor"
+    )]
+    Unknown(String),
+}
+
+impl ReplayTiming {
+    /// divide the recorded delays by `factor`, rejecting the factors that
+    /// would not give a delay
+    pub fn accelerated(factor: f64) -> Result<Self, ReplayTimingError> {
+        if factor.is_finite() && factor > 0.0 {
+            Ok(Self::Accelerated(factor))
+        } else {
+            Err(ReplayTimingError::InvalidFactor(factor))
+        }
+    }
+
+    fn validate(&self) -> Result<(), ReplayTimingError> {
+        match self {
+            Self::Accelerated(factor) => Self::accelerated(*factor).map(|_| ()),
+            Self::Recorded | Self::Immediate => Ok(()),
+        }
+    }
+
+    fn scale(&self, offset: Duration) -> Duration {
+        match self {
+            Self::Recorded => offset,
+            Self::Accelerated(factor) => offset.div_f64(*factor),
+            Self::Immediate => Duration::from_secs(0),
+        }
+    }
+}
+
+/// parses `recorded`, `immediate` or an acceleration factor such as `4` or `x4`
+impl FromStr for ReplayTiming {
+    type Err = ReplayTimingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recorded" => Ok(Self::Recorded),
+            "immediate" => Ok(Self::Immediate),
+            _ => {
+                let factor = s.strip_prefix('x').unwrap_or(s);
+                factor
+                    .parse()
+                    .map_err(|_| ReplayTimingError::Unknown(s.to_owned()))
+                    .and_then(Self::accelerated)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub events_replayed: usize,
+    /// blocks uploaded in answer to the solicitations of the node
+    pub blocks_served: usize,
+    /// headers pushed in answer to the solicitations of the node
+    pub headers_served: usize,
+    /// blocks solicited by the node which are not part of the capture
+    pub unknown_blocks: Vec<Hash>,
+}
+
+/// Replays a [`TrafficCapture`] against a node, acting as a peer.
+///
+/// Subscription events are sent on the block, fragment and gossip
+/// subscriptions opened with the node, pushed headers and uploaded blocks
+/// are sent with the corresponding calls. Pull responses are not sent on
+/// their own, the blocks they carry are used, together with all the other
+/// captured blocks and headers, to answer the solicitations the node sends
+/// on the block subscription.
+pub struct TrafficReplayer {
+    addr: SocketAddr,
+    timing: ReplayTiming,
+    linger: Duration,
+    keypair: KeyPair<Ed25519>,
+}
+
+impl TrafficReplayer {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            timing: ReplayTiming::Recorded,
+            linger: DEFAULT_LINGER,
+            keypair: KeyPair::generate(&mut rand::thread_rng()),
+        }
+    }
+
+    pub fn with_timing(&mut self, timing: ReplayTiming) -> &mut Self {
+        self.timing = timing;
+        self
+    }
+
+    /// time the subscriptions are kept open after the last event, so the
+    /// node can finish soliciting the blocks it is missing
+    pub fn with_linger(&mut self, linger: Duration) -> &mut Self {
+        self.linger = linger;
+        self
+    }
+
+    pub fn replay(&self, capture: &TrafficCapture) -> Result<ReplayReport, TrafficError> {
+        self.timing.validate()?;
+        let index = Arc::new(capture.chain_index()?);
+        let rt = Builder::new_current_thread().enable_all().build()?;
+        rt.block_on(self.replay_async(capture, index))
+    }
+
+    async fn replay_async(
+        &self,
+        capture: &TrafficCapture,
+        index: Arc<ChainIndex>,
+    ) -> Result<ReplayReport, TrafficError> {
+        let mut client = NodeClient::connect(format!("http://{}", self.addr)).await?;
+        self.authenticate(&mut client, capture).await?;
+
+        let report = Arc::new(Mutex::new(ReplayReport::default()));
+
+        let (headers_tx, headers_rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let (fragments_tx, fragments_rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let (gossip_tx, gossip_rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+
+        let block_events = client
+            .block_subscription(Request::new(ReceiverStream::new(headers_rx)))
+            .await?
+            .into_inner();
+        let fragment_events = client
+            .fragment_subscription(Request::new(ReceiverStream::new(fragments_rx)))
+            .await?
+            .into_inner();
+        let gossip_events = client
+            .gossip_subscription(Request::new(ReceiverStream::new(gossip_rx)))
+            .await?
+            .into_inner();
+
+        tokio::spawn(serve_solicitations(
+            client.clone(),
+            block_events,
+            index,
+            report.clone(),
+        ));
+        tokio::spawn(drain(fragment_events));
+        tokio::spawn(drain(gossip_events));
+
+        let started = Instant::now();
+        for captured in &capture.events {
+            let due = self.timing.scale(captured.offset());
+            if let Some(delay) = due.checked_sub(started.elapsed()) {
+                tokio::time::sleep(delay).await;
+            }
+
+            match captured.event.clone() {
+                TrafficEvent::BlockAnnouncement { header } => {
+                    if headers_tx
+                        .send(Header {
+                            content: header.into(),
+                        })
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                TrafficEvent::Fragment { fragment } => {
+                    if fragments_tx
+                        .send(Fragment {
+                            content: fragment.into(),
+                        })
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                TrafficEvent::Gossip { nodes } => {
+                    if gossip_tx
+                        .send(Gossip {
+                            nodes: nodes.into_iter().map(Into::into).collect(),
+                        })
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                TrafficEvent::PushedHeaders { headers } => {
+                    let headers: Vec<Header> = headers
+                        .into_iter()
+                        .map(|header| Header {
+                            content: header.into(),
+                        })
+                        .collect();
+                    client
+                        .push_headers(Request::new(stream::iter(headers)))
+                        .await?;
+                }
+                TrafficEvent::UploadedBlocks { blocks } => {
+                    let blocks: Vec<Block> = blocks
+                        .into_iter()
+                        .map(|block| Block {
+                            content: block.into(),
+                        })
+                        .collect();
+                    client
+                        .upload_blocks(Request::new(stream::iter(blocks)))
+                        .await?;
+                }
+                TrafficEvent::PullBlocksResponse { .. } => continue,
+            }
+            report.lock().unwrap().events_replayed += 1;
+        }
+
+        tokio::time::sleep(self.linger).await;
+
+        let report = report.lock().unwrap().clone();
+        Ok(report)
+    }
+
+    async fn authenticate(
+        &self,
+        client: &mut NodeClient<Channel>,
+        capture: &TrafficCapture,
+    ) -> Result<(), TrafficError> {
+        let mut nonce = [0u8; 32];
+        rand::thread_rng().fill(&mut nonce[..]);
+        let handshake = client
+            .handshake(Request::new(HandshakeRequest {
+                nonce: nonce.to_vec(),
+            }))
+            .await?
+            .into_inner();
+
+        let node_block0 = Hash::read(&mut ReadBuf::from(&handshake.block0[..]))?;
+        if node_block0 != capture.block0.into_hash() {
+            return Err(TrafficError::Block0Mismatch {
+                capture: capture.block0,
+                node: node_block0.into(),
+            });
+        }
+
+        let signature = self.keypair.private_key().sign(&handshake.nonce);
+        client
+            .client_auth(Request::new(ClientAuthRequest {
+                node_id: self.keypair.public_key().as_ref().to_vec(),
+                signature: signature.as_ref().to_vec(),
+            }))
+            .await?;
+        Ok(())
+    }
+}
+
+/// answer the solicitations sent by the node with the captured chain
+async fn serve_solicitations(
+    mut client: NodeClient<Channel>,
+    mut events: Streaming<BlockEvent>,
+    index: Arc<ChainIndex>,
+    report: Arc<Mutex<ReplayReport>>,
+) {
+    while let Ok(Some(event)) = events.message().await {
+        match event.item {
+            Some(block_event::Item::Solicit(ids)) => {
+                let mut blocks = Vec::new();
+                for id in ids.ids {
+                    let id = match Hash::read(&mut ReadBuf::from(&id[..])) {
+                        Ok(id) => id,
+                        Err(_) => continue,
+                    };
+                    match index.block(&id) {
+                        Some(block) => blocks.push(Block {
+                            content: block.serialize_as_vec().unwrap(),
+                        }),
+                        None => report.lock().unwrap().unknown_blocks.push(id),
+                    }
+                }
+                let served = blocks.len();
+                if client
+                    .upload_blocks(Request::new(stream::iter(blocks)))
+                    .await
+                    .is_ok()
+                {
+                    report.lock().unwrap().blocks_served += served;
+                }
+            }
+            Some(block_event::Item::Missing(PullHeadersRequest { from, to })) => {
+                let from: Vec<Hash> = from
+                    .iter()
+                    .filter_map(|id| Hash::read(&mut ReadBuf::from(&id[..])).ok())
+                    .collect();
+                let to = match Hash::read(&mut ReadBuf::from(&to[..])) {
+                    Ok(to) => to,
+                    Err(_) => continue,
+                };
+                let headers: Vec<Header> = index
+                    .headers_between(&from, &to)
+                    .into_iter()
+                    .map(|header| Header {
+                        content: header.serialize_as_vec().unwrap(),
+                    })
+                    .collect();
+                let served = headers.len();
+                if client
+                    .push_headers(Request::new(stream::iter(headers)))
+                    .await
+                    .is_ok()
+                {
+                    report.lock().unwrap().headers_served += served;
+                }
+            }
+            Some(block_event::Item::Announce(_)) | None => {}
+        }
+    }
+}
+
+async fn drain<T>(mut events: Streaming<T>) {
+    while let Ok(Some(_)) = events.message().await {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timing() {
+        assert_eq!("recorded".parse(), Ok(ReplayTiming::Recorded));
+        assert_eq!("immediate".parse(), Ok(ReplayTiming::Immediate));
+        assert_eq!("4".parse(), Ok(ReplayTiming::Accelerated(4.0)));
+        assert_eq!("x0.5".parse(), Ok(ReplayTiming::Accelerated(0.5)));
+        assert_eq!(
+            "fast".parse::<ReplayTiming>(),
+            Err(ReplayTimingError::Unknown("fast".to_owned()))
+        );
+    }
+
+    #[test]
+    fn reject_factors_not_greater_than_zero() {
+        for factor in &["0", "x0.0", "-2", "NaN", "inf"] {
+            assert!(
+                matches!(
+                    factor.parse::<ReplayTiming>(),
+                    Err(ReplayTimingError::InvalidFactor(_))
+                ),
+                "factor {} accepted",
+                factor
+            );
+        }
+        assert!(ReplayTiming::Accelerated(0.0).validate().is_err());
+    }
+
+    #[test]
+    fn scale_offsets() {
+        let offset = Duration::from_secs(4);
+        assert_eq!(ReplayTiming::Recorded.scale(offset), offset);
+        assert_eq!(
+            ReplayTiming::accelerated(2.0).unwrap().scale(offset),
+            Duration::from_secs(2)
+        );
+        assert_eq!(ReplayTiming::Immediate.scale(offset), Duration::default());
+    }
+}
@@ -10,10 +10,10 @@ pub use jormungandr_lib::interfaces::{
 };
 use jortestkit::file;
 
-use assert_fs::fixture::PathChild;
+use assert_fs::fixture::{ChildPath, PathChild};
 use assert_fs::prelude::*;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub use rest::BackwardCompatibleRest;
 
@@ -47,8 +47,66 @@ pub fn get_jormungandr_bin(release: &Release, temp_dir: &impl PathChild) -> Path
     asset
         .download_to(output.path())
         .expect("cannot download file");
-    let release_dir = temp_dir.child(format!("release-{}", release.version()));
+    let release_dir = temp_dir.child(release_dir_name(&release.version()));
     release_dir.create_dir_all().unwrap();
     decompress(output.path(), release_dir.path()).unwrap();
     file::find_file(release_dir.path(), "jormungandr").unwrap()
 }
+
+fn release_dir_name(version: &Version) -> String {
+    format!("release-{}", version)
+}
+
+/// the jormungandr binary of `version` extracted in `cache_dir` by
+/// [`get_jormungandr_bin`], if any
+pub fn find_cached_jormungandr_bin(version: &Version, cache_dir: &Path) -> Option<PathBuf> {
+    find_jormungandr_bin(&cache_dir.join(release_dir_name(version)))
+}
+
+fn find_jormungandr_bin(dir: &Path) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(bin) = find_jormungandr_bin(&path) {
+                return Some(bin);
+            }
+        } else if path.file_stem().map_or(false, |stem| stem == "jormungandr") {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// the releases extracted in `cache_dir` by [`get_jormungandr_bin`] with
+/// their jormungandr binary, oldest first
+pub fn cached_releases(cache_dir: &Path) -> Vec<(Version, PathBuf)> {
+    let entries = match std::fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut releases: Vec<(Version, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let version = Version::parse(name.strip_prefix("release-")?).ok()?;
+            let bin = find_jormungandr_bin(&entry.path())?;
+            Some((version, bin))
+        })
+        .collect();
+    releases.sort_by(|(left, _), (right, _)| left.cmp(right));
+    releases
+}
+
+/// the jormungandr binary of `version` from `cache_dir`, the release is
+/// downloaded and extracted there first if it is not cached yet
+pub fn get_cached_jormungandr_bin(version: &Version, cache_dir: &Path) -> PathBuf {
+    if let Some(bin) = find_cached_jormungandr_bin(version, cache_dir) {
+        return bin;
+    }
+    let release = RELEASES
+        .into_iter()
+        .find(|release| release.version() == *version)
+        .unwrap_or_else(|| panic!("no release {} found", version))
+        .clone();
+    get_jormungandr_bin(&release, &ChildPath::new(cache_dir))
+}
@@ -11,8 +11,9 @@ mod verifier;
 pub use benchmark::*;
 pub use explorer::{Explorer, ExplorerError};
 pub use legacy::{
-    download_last_n_releases, get_jormungandr_bin, version_0_12_0, version_0_13_0, version_0_8_19,
-    BackwardCompatibleRest, Version,
+    cached_releases, download_last_n_releases, find_cached_jormungandr_bin,
+    get_cached_jormungandr_bin, get_jormungandr_bin, version_0_12_0, version_0_13_0,
+    version_0_8_19, BackwardCompatibleRest, Version,
 };
 pub use logger::{JormungandrLogger, Level as LogLevel, LogEntry};
 pub use rest::{
@@ -7,7 +7,8 @@ use chain_impl_mockchain::block::Block;
 use chain_impl_mockchain::fragment::{Fragment, FragmentId};
 use chain_impl_mockchain::header::HeaderId;
 use jormungandr_lib::interfaces::{
-    AccountVotes, Address, FragmentStatus, FragmentsProcessingSummary, Value, VotePlanId,
+    AccountVotes, Address, FragmentStatus, FragmentsProcessingSummary, FragmentsSimulation, Value,
+    VotePlanId,
 };
 use jormungandr_lib::{
     crypto::hash::Hash,
@@ -253,6 +254,22 @@ impl JormungandrRest {
             .map_err(Into::into)
     }
 
+    pub fn simulate_fragments(
+        &self,
+        fragments: Vec<Fragment>,
+        fail_fast: bool,
+    ) -> Result<FragmentsSimulation, RestError> {
+        let response = self.raw().simulate_fragments(fragments, fail_fast)?;
+        if !response.status().is_success() {
+            return Err(RestError::NonSuccessErrorCode {
+                status: response.status(),
+                response: response.text()?,
+                checks: Vec::new(),
+            });
+        }
+        serde_json::from_str(&response.text()?).map_err(RestError::CannotDeserialize)
+    }
+
     pub fn vote_plan_statuses(&self) -> Result<Vec<VotePlanStatus>, RestError> {
         serde_json::from_str(&self.inner.vote_plan_statuses()?)
             .map_err(RestError::CannotDeserialize)
@@ -304,6 +304,21 @@ impl RawRest {
             .send()
     }
 
+    pub fn simulate_fragments(
+        &self,
+        fragments: Vec<Fragment>,
+        fail_fast: bool,
+    ) -> Result<Response, reqwest::Error> {
+        self.client
+            .post(&self.path(ApiVersion::V1, "fragments/simulate"))
+            .headers(self.construct_headers())
+            .json(&FragmentsBatch {
+                fail_fast,
+                fragments,
+            })
+            .send()
+    }
+
     pub fn vote_plan_statuses(&self) -> Result<Response, reqwest::Error> {
         self.get("vote/active/plans")
     }
@@ -8,11 +8,18 @@ pub struct Args {
     #[structopt(long, short, parse(try_from_str))]
     pub genesis_block: PathBuf,
 
-    /// Set the secret node config (in YAML format).
+    /// Set the secret node config (in YAML format). Adversarial blocks are
+    /// signed with its BFT signing key, or as its Genesis Praos stake pool,
+    /// which has to be registered in the block0.
     #[structopt(long, short, parse(from_os_str))]
     pub secret: Option<PathBuf>,
 
     /// Specifies the address the node will listen.
     #[structopt(short = "a", long = "listen-address")]
     pub listen_address: Option<SocketAddr>,
+
+    /// Play the adversarial scenario (in YAML format) against its victim node
+    /// instead of starting the REST interface.
+    #[structopt(long, parse(from_os_str))]
+    pub scenario: Option<PathBuf>,
 }
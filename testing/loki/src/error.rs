@@ -1,4 +1,5 @@
 use jormungandr_lib::interfaces::Block0ConfigurationError;
+use jormungandr_testing_utils::testing::adversary::scenario::ScenarioError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,4 +10,10 @@ pub enum Error {
     Yaml(#[from] serde_yaml::Error),
     #[error("Block0 error: {0}")]
     Block0(#[from] Block0ConfigurationError),
+    #[error("Scenario error: {0}")]
+    Scenario(#[from] ScenarioError),
+    #[error("The victim node did not react as expected to the scenario")]
+    ScenarioFailed,
+    #[error("Stake pool {0} of the secret is not registered in the block0")]
+    UnregisteredStakePool(String),
 }
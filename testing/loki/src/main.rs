@@ -7,7 +7,10 @@ use chain_impl_mockchain::block::Block;
 use error::Error;
 use jormungandr_lib::interfaces::NodeSecret;
 use jormungandr_testing_utils::testing::adversary::{
-    process::AdversaryNodeBuilder, rest::AdversaryRest,
+    process::AdversaryNodeBuilder,
+    rest::AdversaryRest,
+    scenario::{Scenario, ScenarioRunner},
+    stake_pool_from_secret,
 };
 use std::{fs::File, io::BufReader};
 use structopt::StructOpt;
@@ -24,14 +27,55 @@ fn main() {
 fn launch(args: &Args) -> Result<(), Error> {
     let block0 = Block::deserialize(BufReader::new(File::open(&args.genesis_block)?))?;
 
-    let mut rest = AdversaryRest::new(AdversaryNodeBuilder::new(block0).build());
+    let secret: Option<NodeSecret> = match args.secret.as_ref() {
+        Some(secret_file) => Some(serde_yaml::from_reader(BufReader::new(File::open(
+            secret_file,
+        )?))?),
+        None => None,
+    };
+
+    let signing_key = secret
+        .as_ref()
+        .and_then(|secret| secret.bft.as_ref())
+        .map(|bft| bft.signing_key.clone());
+
+    let stake_pool = secret
+        .as_ref()
+        .and_then(|secret| secret.genesis.as_ref())
+        .map(|genesis| {
+            stake_pool_from_secret(&block0, genesis)
+                .ok_or_else(|| Error::UnregisteredStakePool(genesis.node_id.to_string()))
+        })
+        .transpose()?;
 
-    if let Some(secret_file) = args.secret.as_ref() {
-        let secret: NodeSecret = serde_yaml::from_reader(BufReader::new(File::open(secret_file)?))?;
+    if let Some(scenario_file) = args.scenario.as_ref() {
+        let scenario = Scenario::load(scenario_file)?;
 
-        if let Some(bft) = secret.bft {
-            rest = rest.signing_key(bft.signing_key);
+        let mut runner = ScenarioRunner::new(AdversaryNodeBuilder::new(block0).build());
+        if let Some(signing_key) = signing_key {
+            runner = runner.signing_key(signing_key);
         }
+        if let Some(stake_pool) = stake_pool {
+            runner = runner.stake_pool(stake_pool);
+        }
+
+        let report = runner.run(&scenario)?;
+        print!("{}", report);
+        return if report.is_success() {
+            Ok(())
+        } else {
+            Err(Error::ScenarioFailed)
+        };
+    }
+
+    let mut rest = AdversaryRest::new(AdversaryNodeBuilder::new(block0).build());
+
+    if let Some(signing_key) = signing_key {
+        rest = rest.signing_key(signing_key);
+    }
+
+    if let Some(stake_pool) = stake_pool {
+        rest = rest.stake_pool(stake_pool);
     }
 
     if let Some(address) = args.listen_address {
@@ -19,4 +19,14 @@ pub enum MjolnirError {
     PaceTooLow(u64),
     #[error("get block0 error")]
     GetBlock0Error(#[from] GetBlock0Error),
+    #[error("arrival rate must be positive ({0})")]
+    InvalidRate(f64),
+    #[error("empty workload, at least one kind of fragment needs a positive weight")]
+    EmptyWorkload,
+    #[error("cannot read or write report")]
+    Io(#[from] std::io::Error),
+    #[error("invalid report")]
+    Report(#[from] serde_json::Error),
+    #[error("{0} metric(s) worse than the baseline")]
+    BaselineRegression(usize),
 }
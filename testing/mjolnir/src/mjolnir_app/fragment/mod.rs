@@ -3,6 +3,7 @@ use structopt::StructOpt;
 use thiserror::Error;
 
 mod batch;
+mod open_loop;
 mod standard;
 
 #[derive(StructOpt, Debug)]
@@ -11,6 +12,9 @@ pub enum FragmentLoadCommand {
     Batch(batch::Batch),
     /// sends fragments in single manner
     Standard(standard::Standard),
+    /// sends fragments at a constant arrival rate and measures the time
+    /// they take to be in a block
+    OpenLoop(open_loop::OpenLoop),
 }
 
 #[derive(Error, Debug)]
@@ -24,6 +28,7 @@ impl FragmentLoadCommand {
         match self {
             FragmentLoadCommand::Batch(batch) => batch.exec(),
             FragmentLoadCommand::Standard(standard) => standard.exec(),
+            FragmentLoadCommand::OpenLoop(open_loop) => open_loop.exec(),
         }
     }
 }
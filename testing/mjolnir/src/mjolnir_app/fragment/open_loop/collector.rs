@@ -0,0 +1,91 @@
+use super::Outcome;
+use jormungandr_lib::interfaces::FragmentStatus;
+use jormungandr_testing_utils::testing::RemoteJormungandrBuilder;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Polls the fragment logs of the node on its own thread and records when
+/// the tracked fragments end in a block or are rejected.
+pub struct StatusCollector {
+    /// fragments without a final status yet, with the time they were
+    /// scheduled to be sent at
+    pending: Arc<Mutex<HashMap<String, Instant>>>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<HashMap<String, Outcome>>,
+}
+
+impl StatusCollector {
+    pub fn start(endpoint: SocketAddr, poll_interval: Duration) -> Self {
+        let pending: Arc<Mutex<HashMap<String, Instant>>> = Default::default();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_pending = Arc::clone(&pending);
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut builder = RemoteJormungandrBuilder::new("node".to_owned());
+            builder.with_rest(endpoint);
+            let node = builder.build();
+            let mut outcomes = HashMap::new();
+
+            loop {
+                let stopping = thread_stop.load(Ordering::SeqCst);
+                // logs that cannot be fetched are retried at the next poll
+                if let Ok(logs) = node.rest().fragment_logs() {
+                    let now = Instant::now();
+                    let mut pending = thread_pending.lock().unwrap();
+                    for (id, log) in logs.iter() {
+                        let id = id.to_string();
+                        let scheduled_at = match pending.get(&id) {
+                            Some(scheduled_at) => *scheduled_at,
+                            None => continue,
+                        };
+                        let outcome = match log.status() {
+                            FragmentStatus::Pending => continue,
+                            FragmentStatus::Rejected { .. } => Outcome::Rejected,
+                            FragmentStatus::InABlock { .. } => {
+                                Outcome::InABlock(now.duration_since(scheduled_at))
+                            }
+                        };
+                        pending.remove(&id);
+                        outcomes.insert(id, outcome);
+                    }
+                }
+                // one last poll is done after the stop request
+                if stopping {
+                    break;
+                }
+                thread::sleep(poll_interval);
+            }
+
+            for id in thread_pending.lock().unwrap().keys() {
+                outcomes.insert(id.clone(), Outcome::Pending);
+            }
+            outcomes
+        });
+
+        Self {
+            pending,
+            stop,
+            handle,
+        }
+    }
+
+    pub fn track(&self, id: String, scheduled_at: Instant) {
+        self.pending.lock().unwrap().insert(id, scheduled_at);
+    }
+
+    /// wait for the tracked fragments to get a final status for at most
+    /// `drain`, the ones still pending after it are reported as such
+    pub fn finish(self, drain: Duration) -> HashMap<String, Outcome> {
+        let deadline = Instant::now() + drain;
+        while Instant::now() < deadline && !self.pending.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(100));
+        }
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.join().expect("status collector panicked")
+    }
+}
@@ -0,0 +1,353 @@
+//! Open-loop load: fragments are sent at a constant arrival rate whatever
+//! the time the node takes to answer, and followed until they are in a block.
+
+mod collector;
+mod report;
+mod schedule;
+mod workload;
+
+use crate::mjolnir_app::{args::parse_shift, MjolnirError};
+use chain_impl_mockchain::block::BlockDate;
+use collector::StatusCollector;
+use jormungandr_lib::crypto::hash::Hash;
+use jormungandr_testing_utils::{
+    testing::{
+        fragments::{BlockDateGenerator, VOTE_CASTS_PER_VOTE_PLAN},
+        node::time,
+        startup, DummySyncNode, FragmentGenerator, FragmentSender, FragmentSenderSetup,
+        RemoteJormungandrBuilder, TransactionGenerator,
+    },
+    wallet::Wallet,
+};
+use report::{LoadReport, Outcome, PhaseReport, Sample};
+use schedule::{ArrivalSchedule, Phase};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use std::{path::PathBuf, str::FromStr};
+use structopt::StructOpt;
+use workload::{FragmentKind, Workload, WorkloadMix};
+
+/// stake pools and vote plans registered for the delegations and vote casts
+const STAKE_POOLS_COUNT: usize = 2;
+const VOTE_PLANS_FOR_TALLY_COUNT: usize = 2;
+/// slots after the registration of the vote plans at which the vote starts
+const VOTE_START_SLOTS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ReportFormat::Json),
+            "csv" => Ok(ReportFormat::Csv),
+            other => Err(format!("unknown report format '{}'", other)),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub struct OpenLoop {
+    /// address in format:
+    /// 127.0.0.1:8002
+    #[structopt(short = "a", long = "address")]
+    pub endpoint: SocketAddr,
+
+    /// fragments sent per second in the steady phase
+    #[structopt(short = "r", long = "rate")]
+    pub rate: f64,
+
+    /// fragments sent per second during the warmup and at the start of the ramp
+    #[structopt(long = "start-rate", default_value = "1")]
+    pub start_rate: f64,
+
+    /// warmup duration [seconds], its fragments are not measured
+    #[structopt(long = "warmup", default_value = "0")]
+    pub warmup: u64,
+
+    /// duration [seconds] of the ramp from the start rate to the rate
+    #[structopt(long = "ramp", default_value = "0")]
+    pub ramp: u64,
+
+    /// duration [seconds] of the steady phase
+    #[structopt(short = "d", long = "duration")]
+    pub duration: u64,
+
+    /// weight of the transfers in the workload
+    #[structopt(long = "transfers", default_value = "1")]
+    pub transfers: u32,
+
+    /// weight of the delegations in the workload
+    #[structopt(long = "delegations", default_value = "0")]
+    pub delegations: u32,
+
+    /// weight of the vote casts in the workload
+    #[structopt(long = "vote-casts", default_value = "0")]
+    pub vote_casts: u32,
+
+    /// minimum number of vote plans registered for the vote casts, more are
+    /// registered when the run casts more than 255 votes per vote plan
+    #[structopt(long = "vote-plans", default_value = "4")]
+    pub vote_plans: usize,
+
+    /// amount of delay [milliseconds] between fragment status polls,
+    /// the resolution of the latencies
+    #[structopt(long = "poll-interval", default_value = "200")]
+    pub poll_interval: u64,
+
+    /// time [seconds] to wait for pending fragments after the last one is sent
+    #[structopt(long = "drain", default_value = "60")]
+    pub drain: u64,
+
+    #[structopt(long = "key", short = "k")]
+    faucet_key_file: PathBuf,
+
+    #[structopt(long = "spending-counter", short = "s")]
+    faucet_spending_counter: u32,
+
+    /// Transaction validity deadline (inclusive)
+    #[structopt(short = "v", long = "valid-until", conflicts_with = "ttl")]
+    valid_until: Option<BlockDate>,
+
+    /// Transaction time to live (can be negative e.g. ~4.2)
+    #[structopt(short = "t", long= "ttl", default_value = "1.0", parse(try_from_str = parse_shift))]
+    ttl: (BlockDate, bool),
+
+    /// file to write the report to
+    #[structopt(long = "report")]
+    pub report: Option<PathBuf>,
+
+    /// format of the report file: json or csv
+    #[structopt(long = "format", default_value = "json")]
+    pub format: ReportFormat,
+
+    /// JSON report of a previous run to compare the steady phase with
+    #[structopt(long = "baseline")]
+    pub baseline: Option<PathBuf>,
+
+    /// percent by which a latency or the throughput can be worse than the
+    /// baseline
+    #[structopt(long = "tolerance", default_value = "10")]
+    pub tolerance: f64,
+}
+
+impl OpenLoop {
+    pub fn exec(&self) -> Result<(), MjolnirError> {
+        for rate in &[self.rate, self.start_rate] {
+            if *rate <= 0.0 || !rate.is_finite() {
+                return Err(MjolnirError::InvalidRate(*rate));
+            }
+        }
+        let mut mix = WorkloadMix::new(self.transfers, self.delegations, self.vote_casts);
+        if mix.is_empty() {
+            return Err(MjolnirError::EmptyWorkload);
+        }
+        let baseline = match &self.baseline {
+            Some(path) => Some(LoadReport::from_json(&std::fs::read_to_string(path)?)?),
+            None => None,
+        };
+
+        let schedule = ArrivalSchedule::new(
+            Duration::from_secs(self.warmup),
+            Duration::from_secs(self.ramp),
+            Duration::from_secs(self.duration),
+            self.start_rate,
+            self.rate,
+        );
+        let vote_casts = mix.count(FragmentKind::VoteCast, schedule.clone().count());
+        let mut workload = self.prepare_workload(&mix, vote_plans(self.vote_plans, vote_casts))?;
+        let collector =
+            StatusCollector::start(self.endpoint, Duration::from_millis(self.poll_interval));
+
+        println!(
+            "open loop load at {} fragments/s for {}s...",
+            self.rate,
+            schedule.total().as_secs()
+        );
+        let mut sent = Vec::new();
+        let mut current_phase = None;
+        let start = Instant::now();
+        for (offset, phase) in schedule {
+            if current_phase != Some(phase) {
+                println!("{} phase", phase);
+                current_phase = Some(phase);
+            }
+            let scheduled_at = start + offset;
+            let now = Instant::now();
+            if scheduled_at > now {
+                std::thread::sleep(scheduled_at - now);
+            }
+            // the latency is counted from the scheduled time, a late send
+            // counts against the node
+            let id = workload.send(mix.next_kind()).ok();
+            if let Some(id) = &id {
+                collector.track(id.clone(), scheduled_at);
+            }
+            sent.push((phase, id));
+        }
+
+        println!("waiting for pending fragments...");
+        let outcomes = collector.finish(Duration::from_secs(self.drain));
+        let samples: Vec<Sample> = sent
+            .into_iter()
+            .map(|(phase, id)| Sample {
+                phase,
+                outcome: id
+                    .and_then(|id| outcomes.get(&id).copied())
+                    .unwrap_or(Outcome::NotSent),
+            })
+            .collect();
+
+        let report = LoadReport {
+            rate: self.rate,
+            phases: [
+                (Phase::Warmup, self.warmup),
+                (Phase::Ramp, self.ramp),
+                (Phase::Steady, self.duration),
+            ]
+            .iter()
+            .filter(|(_, duration)| *duration > 0)
+            .map(|(phase, duration)| {
+                PhaseReport::new(
+                    *phase,
+                    Duration::from_secs(*duration),
+                    samples.iter().filter(|sample| sample.phase == *phase),
+                )
+            })
+            .collect(),
+        };
+        print!("{}", report);
+
+        if let Some(path) = &self.report {
+            let content = match self.format {
+                ReportFormat::Json => report.to_json()?,
+                ReportFormat::Csv => report.to_csv(),
+            };
+            std::fs::write(path, content)?;
+        }
+
+        if let Some(baseline) = baseline {
+            let regressions = report.regressions(&baseline, self.tolerance);
+            if !regressions.is_empty() {
+                println!("worse than the baseline by more than {}%:", self.tolerance);
+                for regression in &regressions {
+                    println!("  - {}", regression);
+                }
+                return Err(MjolnirError::BaselineRegression(regressions.len()));
+            }
+            println!("within {}% of the baseline", self.tolerance);
+        }
+        Ok(())
+    }
+
+    /// fund the wallets of the transfers and register the stake pools and
+    /// vote plans of the delegations and vote casts
+    fn prepare_workload(
+        &self,
+        mix: &WorkloadMix,
+        vote_plans: usize,
+    ) -> Result<Workload<'static, DummySyncNode>, MjolnirError> {
+        let mut faucet = Wallet::import_account(
+            &self.faucet_key_file,
+            Some(self.faucet_spending_counter.into()),
+        );
+        let mut builder = RemoteJormungandrBuilder::new("node".to_owned());
+        builder.with_rest(self.endpoint);
+        let remote_jormungandr = builder.build();
+
+        let rest = remote_jormungandr.rest().clone();
+        let settings = rest.settings()?;
+        let block0_hash = Hash::from_str(&settings.block0_hash).unwrap();
+        let fees = settings.fees.clone();
+        let expiry_generator = self
+            .valid_until
+            .map(BlockDateGenerator::Fixed)
+            .unwrap_or_else(|| BlockDateGenerator::rolling(&settings, self.ttl.0, self.ttl.1));
+
+        let transfers = if mix.contains(FragmentKind::Transfer) {
+            println!("funding transfer wallets...");
+            let mut generator = TransactionGenerator::new(
+                FragmentSenderSetup::no_verify(),
+                remote_jormungandr.clone_with_rest(),
+                block0_hash,
+                fees.clone(),
+                expiry_generator.clone(),
+            );
+            generator.fill_from_faucet(&mut faucet);
+            Some(generator)
+        } else {
+            None
+        };
+
+        let others =
+            if mix.contains(FragmentKind::Delegation) || mix.contains(FragmentKind::VoteCast) {
+                println!("registering stake pools and vote plans...");
+                let fragment_sender = FragmentSender::new(
+                    block0_hash,
+                    fees,
+                    expiry_generator,
+                    FragmentSenderSetup::no_verify(),
+                );
+                let mut generator = FragmentGenerator::new(
+                    faucet,
+                    startup::create_new_account_address(),
+                    remote_jormungandr,
+                    settings.slots_per_epoch,
+                    STAKE_POOLS_COUNT,
+                    VOTE_PLANS_FOR_TALLY_COUNT,
+                    vote_plans,
+                    fragment_sender,
+                );
+
+                let current_date = jormungandr_lib::interfaces::BlockDate::from(
+                    BlockDate::from_str(
+                        rest.stats()?
+                            .stats
+                            .and_then(|stats| stats.last_block_date)
+                            .ok_or(MjolnirError::InternalClientError)?
+                            .as_ref(),
+                    )
+                    .unwrap(),
+                );
+                generator.prepare(current_date);
+                time::wait_for_date(
+                    current_date.shift_slot(
+                        VOTE_START_SLOTS,
+                        &current_date.time_era(settings.slots_per_epoch),
+                    ),
+                    rest,
+                );
+                Some(generator)
+            } else {
+                None
+            };
+
+        Ok(Workload { transfers, others })
+    }
+}
+
+/// vote plans to register for the vote casts of the run, the generator
+/// needs at least two
+fn vote_plans(min_vote_plans: usize, vote_casts: usize) -> usize {
+    let needed = (vote_casts + VOTE_CASTS_PER_VOTE_PLAN - 1) / VOTE_CASTS_PER_VOTE_PLAN;
+    min_vote_plans.max(needed).max(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vote_plans_sized_from_the_vote_casts() {
+        assert_eq!(vote_plans(0, 0), 2);
+        assert_eq!(vote_plans(4, 0), 4);
+        assert_eq!(vote_plans(4, 4 * VOTE_CASTS_PER_VOTE_PLAN), 4);
+        assert_eq!(vote_plans(4, 4 * VOTE_CASTS_PER_VOTE_PLAN + 1), 5);
+        assert_eq!(vote_plans(1, 10 * VOTE_CASTS_PER_VOTE_PLAN), 10);
+    }
+}
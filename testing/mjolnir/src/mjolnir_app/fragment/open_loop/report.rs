@@ -0,0 +1,340 @@
+use super::Phase;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Outcome of a fragment sent during the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// the node did not accept the fragment
+    NotSent,
+    /// the fragment was in a block after this time since it was scheduled
+    InABlock(Duration),
+    Rejected,
+    /// still pending at the end of the run
+    Pending,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub phase: Phase,
+    pub outcome: Outcome,
+}
+
+/// Statistics of the fragments sent in one phase of the run. Latencies are
+/// in milliseconds, from the time the fragment was scheduled to be sent to
+/// the time it was seen in a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseReport {
+    pub phase: Phase,
+    pub duration_secs: f64,
+    pub sent: usize,
+    pub not_sent: usize,
+    pub in_a_block: usize,
+    pub rejected: usize,
+    pub pending: usize,
+    /// fragments sent per second
+    pub offered_rate: f64,
+    /// fragments in a block per second
+    pub throughput: f64,
+    pub latency_p50: Option<u64>,
+    pub latency_p95: Option<u64>,
+    pub latency_p99: Option<u64>,
+    pub latency_max: Option<u64>,
+}
+
+impl PhaseReport {
+    pub fn new<'a, I: Iterator<Item = &'a Sample>>(
+        phase: Phase,
+        duration: Duration,
+        samples: I,
+    ) -> Self {
+        let mut latencies = Vec::new();
+        let (mut sent, mut not_sent, mut rejected, mut pending) = (0, 0, 0, 0);
+        for sample in samples {
+            match sample.outcome {
+                Outcome::NotSent => not_sent += 1,
+                Outcome::InABlock(latency) => latencies.push(latency.as_millis() as u64),
+                Outcome::Rejected => rejected += 1,
+                Outcome::Pending => pending += 1,
+            }
+            if sample.outcome != Outcome::NotSent {
+                sent += 1;
+            }
+        }
+        latencies.sort_unstable();
+
+        let secs = duration.as_secs_f64();
+        let per_second = |count: usize| if secs > 0.0 { count as f64 / secs } else { 0.0 };
+        Self {
+            phase,
+            duration_secs: secs,
+            sent,
+            not_sent,
+            in_a_block: latencies.len(),
+            rejected,
+            pending,
+            offered_rate: per_second(sent + not_sent),
+            throughput: per_second(latencies.len()),
+            latency_p50: percentile(&latencies, 50.0),
+            latency_p95: percentile(&latencies, 95.0),
+            latency_p99: percentile(&latencies, 99.0),
+            latency_max: latencies.last().copied(),
+        }
+    }
+}
+
+/// nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], percentile: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1) - 1).copied()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadReport {
+    /// fragments sent per second in the steady phase
+    pub rate: f64,
+    pub phases: Vec<PhaseReport>,
+}
+
+impl LoadReport {
+    pub fn phase(&self, phase: Phase) -> Option<&PhaseReport> {
+        self.phases.iter().find(|report| report.phase == phase)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "phase,duration_secs,sent,not_sent,in_a_block,rejected,pending,offered_rate,\
+             throughput,latency_p50,latency_p95,latency_p99,latency_max\n",
+        );
+        let optional = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for phase in &self.phases {
+            csv.push_str(&format!(
+                "{},{:.3},{},{},{},{},{},{:.3},{:.3},{},{},{},{}\n",
+                phase.phase,
+                phase.duration_secs,
+                phase.sent,
+                phase.not_sent,
+                phase.in_a_block,
+                phase.rejected,
+                phase.pending,
+                phase.offered_rate,
+                phase.throughput,
+                optional(phase.latency_p50),
+                optional(phase.latency_p95),
+                optional(phase.latency_p99),
+                optional(phase.latency_max),
+            ));
+        }
+        csv
+    }
+
+    /// compare the steady phase of this run with the one of the baseline,
+    /// returns the metrics worse than the baseline by more than `tolerance`
+    /// percent
+    pub fn regressions(&self, baseline: &LoadReport, tolerance: f64) -> Vec<Regression> {
+        let (current, baseline) = match (self.phase(Phase::Steady), baseline.phase(Phase::Steady)) {
+            (Some(current), Some(baseline)) => (current, baseline),
+            _ => return Vec::new(),
+        };
+        let factor = tolerance / 100.0;
+        let mut regressions = Vec::new();
+
+        let latencies = [
+            ("latency_p50", current.latency_p50, baseline.latency_p50),
+            ("latency_p95", current.latency_p95, baseline.latency_p95),
+            ("latency_p99", current.latency_p99, baseline.latency_p99),
+        ];
+        for (metric, current, baseline) in latencies.iter() {
+            if let (Some(current), Some(baseline)) = (current, baseline) {
+                if *current as f64 > *baseline as f64 * (1.0 + factor) {
+                    regressions.push(Regression {
+                        metric,
+                        baseline: *baseline as f64,
+                        current: *current as f64,
+                    });
+                }
+            }
+        }
+        if current.throughput < baseline.throughput * (1.0 - factor) {
+            regressions.push(Regression {
+                metric: "throughput",
+                baseline: baseline.throughput,
+                current: current.throughput,
+            });
+        }
+        regressions
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |value: Option<u64>| {
+            value
+                .map(|v| format!("{} ms", v))
+                .unwrap_or_else(|| "-".to_owned())
+        };
+        for phase in &self.phases {
+            writeln!(
+                f,
+                "{} ({:.0}s): sent {}, not sent {}, in a block {}, rejected {}, pending {}",
+                phase.phase,
+                phase.duration_secs,
+                phase.sent,
+                phase.not_sent,
+                phase.in_a_block,
+                phase.rejected,
+                phase.pending
+            )?;
+            writeln!(
+                f,
+                "  offered {:.2}/s, throughput {:.2}/s, latency p50 {}, p95 {}, p99 {}, max {}",
+                phase.offered_rate,
+                phase.throughput,
+                optional(phase.latency_p50),
+                optional(phase.latency_p95),
+                optional(phase.latency_p99),
+                optional(phase.latency_max)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.2} (baseline {:.2})",
+            self.metric, self.current, self.baseline
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// steady phase of one second with the given latencies in milliseconds
+    fn report(latencies: &[u64]) -> LoadReport {
+        let samples: Vec<Sample> = latencies
+            .iter()
+            .map(|latency| Sample {
+                phase: Phase::Steady,
+                outcome: Outcome::InABlock(Duration::from_millis(*latency)),
+            })
+            .collect();
+        LoadReport {
+            rate: latencies.len() as f64,
+            phases: vec![PhaseReport::new(
+                Phase::Steady,
+                Duration::from_secs(1),
+                samples.iter(),
+            )],
+        }
+    }
+
+    fn metrics(regressions: Vec<Regression>) -> Vec<&'static str> {
+        regressions
+            .into_iter()
+            .map(|regression| regression.metric)
+            .collect()
+    }
+
+    #[test]
+    fn nearest_rank_percentile() {
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(percentile(&[7], 0.0), Some(7));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+
+        let values: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&values, 0.0), Some(1));
+        assert_eq!(percentile(&values, 50.0), Some(50));
+        assert_eq!(percentile(&values, 95.0), Some(95));
+        assert_eq!(percentile(&values, 99.0), Some(99));
+        assert_eq!(percentile(&values, 100.0), Some(100));
+        assert_eq!(percentile(&[1, 2, 3], 50.0), Some(2));
+    }
+
+    #[test]
+    fn phase_report_counts_the_outcomes() {
+        let outcomes = [
+            Outcome::NotSent,
+            Outcome::InABlock(Duration::from_millis(10)),
+            Outcome::Rejected,
+            Outcome::Pending,
+        ];
+        let samples: Vec<Sample> = outcomes
+            .iter()
+            .map(|outcome| Sample {
+                phase: Phase::Steady,
+                outcome: *outcome,
+            })
+            .collect();
+        let report = PhaseReport::new(Phase::Steady, Duration::from_secs(2), samples.iter());
+        assert_eq!(
+            (
+                report.sent,
+                report.not_sent,
+                report.rejected,
+                report.pending
+            ),
+            (3, 1, 1, 1)
+        );
+        assert_eq!(report.in_a_block, 1);
+        assert!((report.offered_rate - 2.0).abs() < f64::EPSILON);
+        assert!((report.throughput - 0.5).abs() < f64::EPSILON);
+        assert_eq!(report.latency_max, Some(10));
+    }
+
+    #[test]
+    fn regressions_beyond_the_tolerance() {
+        let baseline = report(&[100; 10]);
+        assert!(report(&[100; 10]).regressions(&baseline, 10.0).is_empty());
+        assert!(report(&[105; 10]).regressions(&baseline, 10.0).is_empty());
+        // a faster run is not a regression
+        assert!(report(&[50; 12]).regressions(&baseline, 10.0).is_empty());
+
+        assert_eq!(
+            metrics(report(&[120; 10]).regressions(&baseline, 10.0)),
+            vec!["latency_p50", "latency_p95", "latency_p99"]
+        );
+        assert_eq!(
+            metrics(report(&[100; 8]).regressions(&baseline, 10.0)),
+            vec!["throughput"]
+        );
+        assert!(report(&[100; 8]).regressions(&baseline, 25.0).is_empty());
+
+        let mut tail = vec![100; 90];
+        tail.extend(vec![200; 10]);
+        assert_eq!(
+            metrics(report(&tail).regressions(&report(&[100; 100]), 10.0)),
+            vec!["latency_p95", "latency_p99"]
+        );
+    }
+
+    #[test]
+    fn no_regression_without_steady_phase() {
+        let mut baseline = report(&[100; 10]);
+        baseline.phases[0].phase = Phase::Warmup;
+        assert!(report(&[200; 1]).regressions(&baseline, 10.0).is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Part of the run a fragment is sent in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// sent at the start rate, the samples are not measured
+    Warmup,
+    /// the rate grows linearly from the start rate to the target rate
+    Ramp,
+    /// sent at the target rate
+    Steady,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Warmup => write!(f, "warmup"),
+            Phase::Ramp => write!(f, "ramp"),
+            Phase::Steady => write!(f, "steady"),
+        }
+    }
+}
+
+/// Times to send the fragments at, independently of how long the node takes
+/// to answer.
+#[derive(Clone)]
+pub struct ArrivalSchedule {
+    warmup: Duration,
+    ramp: Duration,
+    steady: Duration,
+    start_rate: f64,
+    rate: f64,
+    next: Duration,
+}
+
+impl ArrivalSchedule {
+    pub fn new(
+        warmup: Duration,
+        ramp: Duration,
+        steady: Duration,
+        start_rate: f64,
+        rate: f64,
+    ) -> Self {
+        Self {
+            warmup,
+            ramp,
+            steady,
+            start_rate,
+            rate,
+            next: Duration::from_secs(0),
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.warmup + self.ramp + self.steady
+    }
+
+    fn phase_at(&self, offset: Duration) -> Option<Phase> {
+        if offset < self.warmup {
+            Some(Phase::Warmup)
+        } else if offset < self.warmup + self.ramp {
+            Some(Phase::Ramp)
+        } else if offset < self.total() {
+            Some(Phase::Steady)
+        } else {
+            None
+        }
+    }
+
+    /// fragments per second at the given time since the start of the run
+    fn rate_at(&self, offset: Duration) -> f64 {
+        match self.phase_at(offset) {
+            Some(Phase::Warmup) => self.start_rate,
+            Some(Phase::Ramp) => {
+                let progress = (offset - self.warmup).as_secs_f64() / self.ramp.as_secs_f64();
+                self.start_rate + (self.rate - self.start_rate) * progress
+            }
+            _ => self.rate,
+        }
+    }
+}
+
+impl Iterator for ArrivalSchedule {
+    /// time since the start of the run to send the next fragment at
+    type Item = (Duration, Phase);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.next;
+        let phase = self.phase_at(offset)?;
+        self.next += Duration::from_secs_f64(1.0 / self.rate_at(offset));
+        Some((offset, phase))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    #[test]
+    fn constant_rate() {
+        let schedule = ArrivalSchedule::new(secs(0.0), secs(0.0), secs(2.0), 1.0, 10.0);
+        let arrivals: Vec<_> = schedule.collect();
+        assert_eq!(arrivals.len(), 20);
+        for (i, (offset, phase)) in arrivals.into_iter().enumerate() {
+            assert_eq!(offset, Duration::from_millis(100 * i as u64));
+            assert_eq!(phase, Phase::Steady);
+        }
+    }
+
+    #[test]
+    fn warmup_at_the_start_rate() {
+        let schedule = ArrivalSchedule::new(secs(2.0), secs(0.0), secs(1.0), 1.0, 2.0);
+        assert_eq!(schedule.total(), secs(3.0));
+        assert_eq!(
+            schedule.collect::<Vec<_>>(),
+            vec![
+                (secs(0.0), Phase::Warmup),
+                (secs(1.0), Phase::Warmup),
+                (secs(2.0), Phase::Steady),
+                (secs(2.5), Phase::Steady),
+            ]
+        );
+    }
+
+    #[test]
+    fn ramp_increases_the_rate() {
+        let schedule = ArrivalSchedule::new(secs(0.0), secs(4.0), secs(0.0), 1.0, 3.0);
+        let offsets: Vec<Duration> = schedule
+            .map(|(offset, phase)| {
+                assert_eq!(phase, Phase::Ramp);
+                offset
+            })
+            .collect();
+        // the average rate of the ramp is 2 fragments per second
+        assert!((6..=9).contains(&offsets.len()), "{}", offsets.len());
+        let intervals: Vec<Duration> = offsets.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(intervals.windows(2).all(|w| w[1] < w[0]));
+        assert!(*offsets.last().unwrap() < secs(4.0));
+    }
+}
@@ -0,0 +1,145 @@
+use jormungandr_testing_utils::testing::{FragmentGenerator, SyncNode, TransactionGenerator};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FragmentKind {
+    Transfer,
+    Delegation,
+    VoteCast,
+}
+
+impl fmt::Display for FragmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FragmentKind::Transfer => write!(f, "transfer"),
+            FragmentKind::Delegation => write!(f, "delegation"),
+            FragmentKind::VoteCast => write!(f, "vote_cast"),
+        }
+    }
+}
+
+/// Interleaves the kinds of fragments according to their weights, with a
+/// smooth weighted round robin so every window of the run has the same mix.
+#[derive(Clone)]
+pub struct WorkloadMix {
+    kinds: Vec<(FragmentKind, i64, i64)>,
+    total: i64,
+}
+
+impl WorkloadMix {
+    pub fn new(transfers: u32, delegations: u32, vote_casts: u32) -> Self {
+        let kinds: Vec<(FragmentKind, i64, i64)> = vec![
+            (FragmentKind::Transfer, transfers as i64, 0),
+            (FragmentKind::Delegation, delegations as i64, 0),
+            (FragmentKind::VoteCast, vote_casts as i64, 0),
+        ]
+        .into_iter()
+        .filter(|(_, weight, _)| *weight > 0)
+        .collect();
+        let total = kinds.iter().map(|(_, weight, _)| weight).sum();
+        Self { kinds, total }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    pub fn contains(&self, kind: FragmentKind) -> bool {
+        self.kinds.iter().any(|(k, _, _)| *k == kind)
+    }
+
+    /// number of fragments of the given kind among the next `fragments`
+    pub fn count(&self, kind: FragmentKind, fragments: usize) -> usize {
+        let mut mix = self.clone();
+        (0..fragments).filter(|_| mix.next_kind() == kind).count()
+    }
+
+    pub fn next_kind(&mut self) -> FragmentKind {
+        for (_, weight, current) in self.kinds.iter_mut() {
+            *current += *weight;
+        }
+        let (kind, _, current) = self
+            .kinds
+            .iter_mut()
+            .max_by_key(|(_, _, current)| *current)
+            .expect("empty workload mix");
+        *current -= self.total;
+        *kind
+    }
+}
+
+/// Generators sending the fragments of each kind of the mix.
+pub struct Workload<'a, S: SyncNode + Send> {
+    pub transfers: Option<TransactionGenerator<'a, S>>,
+    pub others: Option<FragmentGenerator<'a, S>>,
+}
+
+impl<'a, S: SyncNode + Send> Workload<'a, S> {
+    /// send a fragment of the given kind, returns its id
+    pub fn send(&mut self, kind: FragmentKind) -> Result<String, String> {
+        let check = match kind {
+            FragmentKind::Transfer => {
+                return self
+                    .transfers
+                    .as_mut()
+                    .ok_or_else(|| "no transfer generator".to_owned())?
+                    .send_transaction()
+                    .map(|id| id.to_string())
+                    .map_err(|e| format!("{:?}", e))
+            }
+            FragmentKind::Delegation => self.others()?.send_full_delegation(),
+            FragmentKind::VoteCast => self.others()?.send_vote_cast(),
+        };
+        check
+            .map(|check| check.fragment_id().to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    fn others(&mut self) -> Result<&mut FragmentGenerator<'a, S>, String> {
+        self.others
+            .as_mut()
+            .ok_or_else(|| "no delegation and vote cast generator".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_mix() {
+        let mix = WorkloadMix::new(0, 0, 0);
+        assert!(mix.is_empty());
+        assert!(!mix.contains(FragmentKind::Transfer));
+    }
+
+    #[test]
+    fn every_window_has_the_same_mix() {
+        let mut mix = WorkloadMix::new(3, 1, 0);
+        assert!(!mix.is_empty());
+        assert!(mix.contains(FragmentKind::Delegation));
+        assert!(!mix.contains(FragmentKind::VoteCast));
+
+        for _ in 0..5 {
+            let window: Vec<FragmentKind> = (0..4).map(|_| mix.next_kind()).collect();
+            let count = |kind| window.iter().filter(|k| **k == kind).count();
+            assert_eq!(count(FragmentKind::Transfer), 3);
+            assert_eq!(count(FragmentKind::Delegation), 1);
+        }
+    }
+
+    #[test]
+    fn count_does_not_advance_the_mix() {
+        let mut mix = WorkloadMix::new(1, 0, 2);
+        assert_eq!(mix.count(FragmentKind::VoteCast, 9), 6);
+        assert_eq!(mix.count(FragmentKind::Transfer, 9), 3);
+        assert_eq!(mix.count(FragmentKind::Delegation, 9), 0);
+
+        let mut fresh = WorkloadMix::new(1, 0, 2);
+        for _ in 0..6 {
+            assert_eq!(mix.next_kind(), fresh.next_kind());
+        }
+    }
+}